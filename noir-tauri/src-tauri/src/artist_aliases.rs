@@ -0,0 +1,113 @@
+//! Alias d'artistes : regroupe à l'affichage les variantes d'un même artiste
+//!
+//! Architecture :
+//! - Table alias (en minuscules) → nom canonique, persistée dans artist_aliases.json
+//! - Mapping purement à l'affichage : les tags des fichiers ne sont jamais réécrits,
+//!   la fusion survit donc aux rescans
+//! - Les variantes de casse du nom canonique pointent aussi vers lui ("THE BEATLES" → "The Beatles")
+//! - Pas de chaîne d'alias : fusionner vers un alias reprend sa cible, et les alias
+//!   d'un artiste fusionné suivent le nouveau nom canonique
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Fichier artist_aliases.json
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ArtistAliases {
+    pub aliases: HashMap<String, String>,
+}
+
+impl ArtistAliases {
+    /// Nom canonique d'un artiste (lui-même s'il n'a pas d'alias)
+    pub fn resolve<'a>(&'a self, artist: &'a str) -> &'a str {
+        self.aliases.get(&artist.to_lowercase()).map(String::as_str).unwrap_or(artist)
+    }
+
+    /// Fait pointer `aliases` vers `canonical`
+    pub fn merge(&mut self, canonical: &str, aliases: &[String]) {
+        let canonical = self.resolve(canonical.trim()).to_string();
+        let canonical_key = canonical.to_lowercase();
+        for alias in aliases {
+            let key = alias.trim().to_lowercase();
+            if key.is_empty() || key == canonical_key {
+                continue;
+            }
+            for target in self.aliases.values_mut() {
+                if target.to_lowercase() == key {
+                    *target = canonical.clone();
+                }
+            }
+            self.aliases.insert(key, canonical.clone());
+        }
+        self.aliases.insert(canonical_key, canonical);
+    }
+
+    /// Retire un alias. Retourne false s'il n'existait pas
+    pub fn remove(&mut self, alias: &str) -> bool {
+        self.aliases.remove(&alias.trim().to_lowercase()).is_some()
+    }
+}
+
+fn get_artist_aliases_path() -> PathBuf {
+    crate::get_data_dir().join("artist_aliases.json")
+}
+
+pub fn load_artist_aliases() -> ArtistAliases {
+    let path = get_artist_aliases_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        ArtistAliases::default()
+    }
+}
+
+pub fn save_artist_aliases(data: &ArtistAliases) {
+    let path = get_artist_aliases_path();
+    let content = serde_json::to_string_pretty(data).unwrap_or_default();
+    crate::save_file_secure(&path, &content);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_merge_resolves_case_insensitively() {
+        let mut aliases = ArtistAliases::default();
+        aliases.merge("The Beatles", &names(&["Beatles", "beatles "]));
+        assert_eq!(aliases.resolve("Beatles"), "The Beatles");
+        assert_eq!(aliases.resolve("BEATLES"), "The Beatles");
+        assert_eq!(aliases.resolve("the beatles"), "The Beatles");
+        assert_eq!(aliases.resolve("The Rolling Stones"), "The Rolling Stones");
+    }
+
+    #[test]
+    fn test_merge_never_creates_chains() {
+        let mut aliases = ArtistAliases::default();
+        aliases.merge("Beatles", &names(&["beatles!"]));
+        // "Beatles" devient à son tour un alias : ses propres alias suivent
+        aliases.merge("The Beatles", &names(&["Beatles"]));
+        assert_eq!(aliases.resolve("beatles!"), "The Beatles");
+        // Fusionner vers un alias reprend sa cible
+        aliases.merge("beatles", &names(&["Fab Four"]));
+        assert_eq!(aliases.resolve("Fab Four"), "The Beatles");
+        assert_eq!(aliases.resolve("Beatles"), "The Beatles");
+    }
+
+    #[test]
+    fn test_remove_alias() {
+        let mut aliases = ArtistAliases::default();
+        aliases.merge("The Beatles", &names(&["Beatles"]));
+        assert!(aliases.remove("BEATLES"));
+        assert!(!aliases.remove("Beatles"));
+        assert_eq!(aliases.resolve("Beatles"), "Beatles");
+    }
+}
@@ -0,0 +1,720 @@
+//! ALSA backend for Linux
+//!
+//! Reads the kernel's ALSA procfs (/proc/asound) to:
+//! - Enumerate playback PCMs (/proc/asound/pcm + /proc/asound/cards)
+//! - Read the supported rates of USB DACs (/proc/asound/cardN/streamM)
+//! - Read the running rate and owner of an open PCM (/proc/asound/cardN/pcmMp/sub0)
+//! - Follow the sound server's default sink unless a PCM is manually selected
+//!
+//! Differences with CoreAudio:
+//! - Playback always goes through the sound server (PipeWire/PulseAudio) on the
+//!   `default` PCM: the server picks the rate and the device, nothing to configure here.
+//! - Exclusive mode would mean opening the `hw:CARD,DEV` PCM directly. There is no
+//!   direct ALSA stream yet, so devices report `supports_exclusive: false` and
+//!   `set_exclusive_mode(Exclusive)` is refused.
+//! - The default device is the card behind the sound server's default sink (asked to
+//!   `pactl`, which pipewire-pulse also answers). Without a sound server, ALSA's own
+//!   default applies: card 0 unless ALSA_CARD names another one.
+//! - procfs only lists rates and formats for USB audio. Other cards (HDA, HDMI) report
+//!   the rate they are currently running at (or the two base rates when closed) and
+//!   the default bit depths.
+//! - No device change notifications (that would need udev): `set_device_event_callback`
+//!   returns `NotSupported`.
+//! - No library dependency (alsa-lib, libpulse): this backend is read-only on the
+//!   device side, no ioctl is issued.
+//!
+//! This file is only compiled on Linux via #[cfg(target_os = "linux")]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::audio::backend::AudioBackend;
+use crate::audio::error::{AudioBackendError, Result};
+use crate::audio::types::*;
+
+/// Root of the ALSA procfs
+const ASOUND_ROOT: &str = "/proc/asound";
+
+/// Rates assumed for a closed non-USB PCM (every ALSA codec accepts both)
+const BASE_RATES: [u32; 2] = [44100, 48000];
+
+/// How long a `pactl` answer is reused: device listing and status queries ask for the
+/// default device several times in a row, each query spawning two processes
+const SOUND_SERVER_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Last sound server default sink and when it was asked
+static SOUND_SERVER_CACHE: Mutex<Option<(Instant, Option<(u32, u32)>)>> = Mutex::new(None);
+
+/// A playback PCM parsed from /proc/asound/pcm
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PcmEntry {
+    card: u32,
+    device: u32,
+    name: String,
+}
+
+/// Playback section of a USB stream file (/proc/asound/cardN/streamM)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct UsbStreamInfo {
+    rates: Vec<u32>,
+    channels: u16,
+    bit_depths: Vec<u8>,
+}
+
+/// ALSA backend
+///
+/// Like the other backends, this follows the system default device. Playback is
+/// shared only: the selection is informative until a direct hw stream exists.
+pub struct AlsaBackend {
+    /// Manually selected PCM ID "hw:CARD,DEV" (None = follow system default)
+    manual_device_id: Option<String>,
+    /// Cached device info map (PCM ID -> DeviceInfo)
+    device_cache: HashMap<String, DeviceInfo>,
+    /// Last known PCM ID (to detect changes)
+    last_device_id: String,
+}
+
+impl AlsaBackend {
+    /// Create a new ALSA backend
+    pub fn new() -> Result<Self> {
+        println!("[ALSA] Initializing backend...");
+
+        if !Path::new(ASOUND_ROOT).exists() {
+            return Err(AudioBackendError::NotSupported(
+                "ALSA is not available (no /proc/asound)".to_string(),
+            ));
+        }
+
+        let default_device = Self::get_default_output_device()?;
+
+        let mut backend = Self {
+            manual_device_id: None, // Follow system default
+            device_cache: HashMap::new(),
+            last_device_id: default_device.clone(),
+        };
+
+        // Cache device info on startup
+        backend.refresh_device_cache()?;
+
+        let device_name = backend
+            .device_cache
+            .get(&default_device)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        println!("[ALSA] Backend initialized. Default device: {}", device_name);
+
+        Ok(backend)
+    }
+
+    /// Get the current active PCM ID
+    /// If manual_device_id is set, use that. Otherwise, follow system default.
+    fn get_active_device_id(&self) -> Result<String> {
+        match &self.manual_device_id {
+            Some(id) => Ok(id.clone()),
+            None => Self::get_default_output_device(),
+        }
+    }
+
+    /// Check if the active PCM has changed since last check
+    fn check_device_change(&mut self) -> Option<String> {
+        let current_id = self.get_active_device_id().ok()?;
+        if current_id == self.last_device_id {
+            return None;
+        }
+
+        let old_id = std::mem::replace(&mut self.last_device_id, current_id.clone());
+
+        // Refresh cache to include new device
+        let _ = self.refresh_device_cache();
+
+        // Reset manual device if it no longer exists
+        if let Some(manual_id) = &self.manual_device_id {
+            if !self.device_cache.contains_key(manual_id) {
+                println!("[ALSA] Manual device {} no longer exists, resetting to default", manual_id);
+                self.manual_device_id = None;
+            }
+        }
+
+        println!("[ALSA] Device changed: {} -> {}", old_id, current_id);
+        Some(current_id)
+    }
+
+    // === Private procfs Functions ===
+
+    fn device_id(card: u32, device: u32) -> String {
+        format!("hw:{},{}", card, device)
+    }
+
+    /// Parse "hw:CARD,DEV" back into (card, device)
+    fn parse_device_id(device_id: &str) -> Result<(u32, u32)> {
+        device_id
+            .strip_prefix("hw:")
+            .and_then(|rest| rest.split_once(','))
+            .and_then(|(card, device)| Some((card.parse().ok()?, device.parse().ok()?)))
+            .ok_or_else(|| AudioBackendError::DeviceNotFound(device_id.to_string()))
+    }
+
+    fn read_proc(path: impl AsRef<Path>) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    fn pcm_dir(card: u32, device: u32) -> PathBuf {
+        Path::new(ASOUND_ROOT)
+            .join(format!("card{}", card))
+            .join(format!("pcm{}p", device))
+    }
+
+    /// Playback PCMs from /proc/asound/pcm
+    /// Line format: "00-03: HDMI 0 : HDMI 0 : playback 1"
+    fn parse_pcm_list(content: &str) -> Vec<PcmEntry> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(" : ");
+                let head = fields.next()?;
+                let (ids, id_name) = head.split_once(':')?;
+                let (card, device) = ids.trim().split_once('-')?;
+                let name = fields.next().unwrap_or(id_name).trim().to_string();
+                let plays = fields.any(|f| f.trim().starts_with("playback"));
+                if !plays {
+                    return None;
+                }
+                Some(PcmEntry {
+                    card: card.parse().ok()?,
+                    device: device.parse().ok()?,
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    /// Card index -> (short id, long name) from /proc/asound/cards
+    /// Line format: " 0 [PCH            ]: HDA-Intel - HDA Intel PCH"
+    fn parse_card_list(content: &str) -> HashMap<u32, (String, String)> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let (index, rest) = line.trim_start().split_once(' ')?;
+                let index = index.parse().ok()?;
+                let (id, rest) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+                let name = rest
+                    .trim_start_matches(':')
+                    .split_once(" - ")
+                    .map(|(_, name)| name)
+                    .unwrap_or(rest)
+                    .trim();
+                Some((index, (id.trim().to_string(), name.to_string())))
+            })
+            .collect()
+    }
+
+    /// Playback section of a USB stream file
+    /// "Rates:" is either a list ("44100, 48000, 96000") or a range ("8000 - 192000 (continuous)")
+    fn parse_usb_stream(content: &str) -> Option<UsbStreamInfo> {
+        let playback = content
+            .split("\nCapture:")
+            .next()?
+            .split_once("Playback:")?
+            .1;
+
+        let mut info = UsbStreamInfo::default();
+        for line in playback.lines().map(str::trim) {
+            if let Some(format) = line.strip_prefix("Format:") {
+                // Signed integer formats only: "S16_LE", "S24_3LE", "S32_LE"
+                let bits = format.trim().strip_prefix('S').and_then(|f| f.get(..2)).and_then(|b| b.parse().ok());
+                if let Some(bits @ (16 | 24 | 32)) = bits {
+                    info.bit_depths.push(bits);
+                }
+            } else if let Some(channels) = line.strip_prefix("Channels:") {
+                let channels = channels.trim().parse().unwrap_or(0);
+                info.channels = info.channels.max(channels);
+            } else if let Some(rates) = line.strip_prefix("Rates:") {
+                let rates = rates.trim();
+                if let Some((min, max)) = rates.split_once(" - ") {
+                    let min: u32 = min.trim().parse().unwrap_or(0);
+                    let max: u32 = max.split_whitespace().next().and_then(|m| m.parse().ok()).unwrap_or(0);
+                    info.rates.extend(
+                        SampleRate::STANDARD_RATES.iter().copied().filter(|r| (min..=max).contains(r)),
+                    );
+                } else {
+                    info.rates.extend(rates.split(',').filter_map(|r| r.trim().parse::<u32>().ok()));
+                }
+            }
+        }
+
+        info.rates.sort_unstable();
+        info.rates.dedup();
+        info.bit_depths.sort_unstable();
+        info.bit_depths.dedup();
+        (!info.rates.is_empty()).then_some(info)
+    }
+
+    /// Field value in a sub0/status or sub0/hw_params file ("closed" when the PCM is not open)
+    /// Line format: "rate: 48000 (48000/1)" or "owner_pid   : 1234"
+    fn parse_proc_field(content: &str, field: &str) -> Option<u32> {
+        content.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key.trim() != field {
+                return None;
+            }
+            value.split_whitespace().next()?.parse().ok()
+        })
+    }
+
+    /// Rate the PCM is running at, None when closed
+    fn get_running_rate(card: u32, device: u32) -> Option<u32> {
+        let content = Self::read_proc(Self::pcm_dir(card, device).join("sub0/hw_params"))?;
+        Self::parse_proc_field(&content, "rate")
+    }
+
+    /// PID that has the PCM open, None when closed
+    fn get_owner_pid(card: u32, device: u32) -> Option<u32> {
+        let content = Self::read_proc(Self::pcm_dir(card, device).join("sub0/status"))?;
+        Self::parse_proc_field(&content, "owner_pid")
+    }
+
+    /// Get all playback PCMs
+    fn get_all_output_devices() -> Result<Vec<PcmEntry>> {
+        let content = Self::read_proc(Path::new(ASOUND_ROOT).join("pcm"))
+            .ok_or_else(|| AudioBackendError::DeviceEnumerationFailed("cannot read /proc/asound/pcm".to_string()))?;
+        Ok(Self::parse_pcm_list(&content))
+    }
+
+    /// (card, device) behind the sound server's default sink
+    /// `info` is `pactl info`, `sinks` is `pactl list sinks` (C locale). PipeWire sinks
+    /// carry `api.alsa.pcm.*` properties, PulseAudio sinks `alsa.*` ones.
+    fn parse_default_sink(info: &str, sinks: &str) -> Option<(u32, u32)> {
+        let default_sink = info
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Default Sink:"))?
+            .trim();
+        let block = sinks.split("Sink #").find(|block| {
+            block
+                .lines()
+                .any(|line| line.trim().strip_prefix("Name:").map(str::trim) == Some(default_sink))
+        })?;
+
+        let property = |keys: [&str; 2]| {
+            block.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                if !keys.contains(&key.trim()) {
+                    return None;
+                }
+                value.trim().trim_matches('"').parse::<u32>().ok()
+            })
+        };
+        Some((
+            property(["alsa.card", "api.alsa.pcm.card"])?,
+            property(["alsa.device", "api.alsa.pcm.device"])?,
+        ))
+    }
+
+    /// Default sink of the running sound server, None without one (or for a non-ALSA sink)
+    ///
+    /// Cached for SOUND_SERVER_CACHE_TTL.
+    fn sound_server_default() -> Option<(u32, u32)> {
+        let mut cache = SOUND_SERVER_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((asked_at, default)) = *cache {
+            if asked_at.elapsed() < SOUND_SERVER_CACHE_TTL {
+                return default;
+            }
+        }
+        let default = Self::query_sound_server_default();
+        *cache = Some((Instant::now(), default));
+        default
+    }
+
+    fn query_sound_server_default() -> Option<(u32, u32)> {
+        let pactl = |args: &[&str]| {
+            std::process::Command::new("pactl")
+                .args(args)
+                .env("LC_ALL", "C")
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        Self::parse_default_sink(&pactl(&["info"])?, &pactl(&["list", "sinks"])?)
+    }
+
+    /// Get the system default playback PCM ID
+    ///
+    /// The sound server's default sink wins (this is what playback actually uses).
+    /// Without a sound server, ALSA's default is card 0 unless ALSA_CARD names another
+    /// one (index or id).
+    fn get_default_output_device() -> Result<String> {
+        let pcms = Self::get_all_output_devices()?;
+
+        if let Some((card, device)) = Self::sound_server_default() {
+            if pcms.iter().any(|pcm| pcm.card == card && pcm.device == device) {
+                return Ok(Self::device_id(card, device));
+            }
+        }
+
+        let cards = Self::read_proc(Path::new(ASOUND_ROOT).join("cards"))
+            .map(|content| Self::parse_card_list(&content))
+            .unwrap_or_default();
+
+        let preferred_card = std::env::var("ALSA_CARD").ok().and_then(|card| {
+            card.parse::<u32>().ok().or_else(|| {
+                cards.iter().find(|(_, (id, _))| *id == card).map(|(index, _)| *index)
+            })
+        });
+
+        pcms.iter()
+            .filter(|pcm| preferred_card.is_none() || preferred_card == Some(pcm.card))
+            .min_by_key(|pcm| (pcm.card, pcm.device))
+            .or_else(|| pcms.iter().min_by_key(|pcm| (pcm.card, pcm.device)))
+            .map(|pcm| Self::device_id(pcm.card, pcm.device))
+            .ok_or_else(|| AudioBackendError::DeviceNotFound("No playback PCM".to_string()))
+    }
+
+    /// Refresh the device cache
+    fn refresh_device_cache(&mut self) -> Result<()> {
+        let pcms = Self::get_all_output_devices()?;
+        let default_id = Self::get_default_output_device().ok();
+        let cards = Self::read_proc(Path::new(ASOUND_ROOT).join("cards"))
+            .map(|content| Self::parse_card_list(&content))
+            .unwrap_or_default();
+
+        self.device_cache.clear();
+
+        for pcm in pcms {
+            let id = Self::device_id(pcm.card, pcm.device);
+            let card_name = cards.get(&pcm.card).map(|(_, name)| name.as_str());
+            let name = match card_name {
+                Some(card_name) if card_name != pcm.name => format!("{} - {}", card_name, pcm.name),
+                _ => pcm.name.clone(),
+            };
+
+            let usb_stream = Self::read_proc(
+                Path::new(ASOUND_ROOT)
+                    .join(format!("card{}", pcm.card))
+                    .join(format!("stream{}", pcm.device)),
+            )
+            .and_then(|content| Self::parse_usb_stream(&content));
+            let running_rate = Self::get_running_rate(pcm.card, pcm.device);
+
+            let mut supported_sample_rates = match &usb_stream {
+                Some(stream) => stream.rates.clone(),
+                None => BASE_RATES.to_vec(),
+            };
+            if let Some(rate) = running_rate {
+                if !supported_sample_rates.contains(&rate) {
+                    supported_sample_rates.push(rate);
+                    supported_sample_rates.sort_unstable();
+                }
+            }
+
+            // Only USB cards expose a stream file; HDMI/DisplayPort PCMs are named after the port
+            let transport = if usb_stream.is_some() {
+                Some("USB".to_string())
+            } else if pcm.name.contains("HDMI") {
+                Some("HDMI".to_string())
+            } else {
+                None
+            };
+
+            // USB stream files list the formats; other cards get the usual set
+            let supported_bit_depths = usb_stream
+                .as_ref()
+                .map(|s| s.bit_depths.clone())
+                .filter(|depths| !depths.is_empty())
+                .unwrap_or_else(|| DEFAULT_BIT_DEPTHS.to_vec());
+
+            let current_sample_rate = running_rate.unwrap_or(48000);
+            let max_channels = usb_stream.as_ref().map(|s| s.channels).filter(|&c| c > 0).unwrap_or(2);
+
+            let info = DeviceInfo {
+                id: id.clone(),
+                name,
+                manufacturer: None,
+                is_default: Some(&id) == default_id.as_ref(),
+                supported_sample_rates,
+                current_sample_rate,
+                supported_bit_depths,
+                max_channels,
+                supports_exclusive: false, // No direct hw stream yet
+                transport_type: 0, // CoreAudio-specific, no ALSA equivalent
+                transport,
+                is_airplay: false,
+            };
+
+            self.device_cache.insert(id, info);
+        }
+
+        Ok(())
+    }
+
+    fn cached_device(&self, device_id: &str) -> Result<&DeviceInfo> {
+        self.device_cache
+            .get(device_id)
+            .ok_or_else(|| AudioBackendError::DeviceNotFound(device_id.to_string()))
+    }
+}
+
+impl AudioBackend for AlsaBackend {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        Ok(self.device_cache.values().cloned().collect())
+    }
+
+    fn refresh_devices(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.refresh_device_cache()?;
+        Ok(self.device_cache.values().cloned().collect())
+    }
+
+    fn current_device(&self) -> Result<DeviceInfo> {
+        let device_id = match self.get_active_device_id() {
+            Ok(id) => id,
+            Err(e) => {
+                println!("[ALSA] Failed to get active device: {}", e);
+                if let Some(info) = self.device_cache.values().next() {
+                    return Ok(info.clone());
+                }
+                return Err(e);
+            }
+        };
+        self.cached_device(&device_id).cloned()
+    }
+
+    fn set_output_device(&mut self, device_id: &str) -> Result<()> {
+        // Verify device exists
+        if !self.device_cache.contains_key(device_id) {
+            self.refresh_device_cache()?;
+            self.cached_device(device_id)?;
+        }
+
+        // Set manual device (stops following system default)
+        // The sound server's default sink is left untouched.
+        self.manual_device_id = Some(device_id.to_string());
+        self.last_device_id = device_id.to_string();
+
+        println!("[ALSA] Switched to device {}", device_id);
+        Ok(())
+    }
+
+    fn follow_system_default(&mut self) {
+        self.manual_device_id = None;
+        println!("[ALSA] Following system default device");
+    }
+
+    fn get_device_info(&self, device_id: &str) -> Result<DeviceInfo> {
+        self.cached_device(device_id).cloned()
+    }
+
+    fn current_sample_rate(&self) -> Result<u32> {
+        let device_id = self.get_active_device_id()?;
+        let (card, device) = Self::parse_device_id(&device_id)?;
+        match Self::get_running_rate(card, device) {
+            Some(rate) => Ok(rate),
+            None => self.cached_device(&device_id).map(|info| info.current_sample_rate),
+        }
+    }
+
+    fn set_sample_rate(&mut self, rate: u32) -> Result<()> {
+        let device_id = self.get_active_device_id()?;
+        let info = self.cached_device(&device_id)?;
+
+        // Shared mode only: the sound server owns the card and picks the rate
+        if rate != info.current_sample_rate {
+            return Err(AudioBackendError::SampleRateChangeFailed {
+                requested: rate,
+                reason: format!("shared mode is fixed to the sound server rate ({} Hz)", info.current_sample_rate),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn is_sample_rate_supported(&self, rate: u32) -> bool {
+        self.get_active_device_id()
+            .ok()
+            .and_then(|id| self.device_cache.get(&id))
+            .map(|info| info.supports_sample_rate(rate))
+            .unwrap_or(false)
+    }
+
+    fn supported_sample_rates(&self) -> Result<Vec<u32>> {
+        let device_id = self.get_active_device_id()?;
+        self.cached_device(&device_id).map(|info| info.supported_sample_rates.clone())
+    }
+
+    fn integer_bit_depth(&self) -> Option<u16> {
+        // No ALSA stream yet: output stays 32-bit float
+        None
+    }
+
+    fn exclusive_mode(&self) -> ExclusiveMode {
+        ExclusiveMode::Shared
+    }
+
+    fn set_exclusive_mode(&mut self, mode: ExclusiveMode) -> Result<()> {
+        match mode {
+            ExclusiveMode::Shared => Ok(()),
+            ExclusiveMode::Exclusive => Err(AudioBackendError::ExclusiveModeFailed(
+                "Exclusive mode is not available with ALSA yet (playback goes through the sound server)".to_string(),
+            )),
+        }
+    }
+
+    fn hog_mode_status(&self) -> Result<HogModeStatus> {
+        let device_id = self.get_active_device_id()?;
+        let device_name = self.device_cache
+            .get(&device_id)
+            .map(|info| info.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        // The PCM owner is normally the sound server, never us (no direct hw stream)
+        let (card, device) = Self::parse_device_id(&device_id)?;
+        let owner_pid = Self::get_owner_pid(card, device).map(|pid| pid as i32).unwrap_or(-1);
+        let owned_by_us = owner_pid == std::process::id() as i32;
+
+        Ok(HogModeStatus {
+            enabled: false,
+            device_name,
+            device_id,
+            owner_pid,
+            owned_by_us,
+            message: "Shared mode (sound server)".to_string(),
+        })
+    }
+
+    fn set_device_event_callback(&mut self, _callback: Option<DeviceEventCallback>) -> Result<()> {
+        Err(AudioBackendError::NotSupported(
+            "ALSA device change notifications".to_string(),
+        ))
+    }
+
+    fn get_device_id(&self) -> Option<u32> {
+        // PCM IDs are strings ("hw:1,0"): no numeric ID to hand to a stream
+        None
+    }
+
+    fn prepare_for_streaming(&mut self, config: &StreamConfig) -> Result<u32> {
+        // Check if device has changed (default sink, ALSA_CARD, hotplug)
+        self.check_device_change();
+
+        // Refresh cache to get latest device info
+        let _ = self.refresh_device_cache();
+
+        let device_id = self.get_active_device_id()?;
+        let info = self.cached_device(&device_id)?;
+
+        // The sound server resamples to its own rate
+        println!(
+            "[ALSA] Shared mode on {} — {}Hz source at sound server rate {}Hz",
+            info.name, config.sample_rate, info.current_sample_rate
+        );
+        Ok(info.current_sample_rate)
+    }
+
+    fn restore_sample_rate(&mut self) -> Result<()> {
+        // The rate was never changed: the sound server keeps its own
+        Ok(())
+    }
+
+    fn release(&mut self) -> Result<()> {
+        // Nothing held: no direct hw stream, procfs is only read
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "ALSA"
+    }
+
+    fn system_default_device_id(&self) -> Option<String> {
+        Self::get_default_output_device().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pcm_list() {
+        let content = "00-00: ALC892 Analog : ALC892 Analog : playback 1 : capture 1\n\
+                       00-02: ALC892 Alt Analog : ALC892 Alt Analog : capture 1\n\
+                       01-00: USB Audio : USB Audio : playback 1\n";
+        let pcms = AlsaBackend::parse_pcm_list(content);
+        assert_eq!(pcms.len(), 2);
+        assert_eq!(pcms[0], PcmEntry { card: 0, device: 0, name: "ALC892 Analog".to_string() });
+        assert_eq!(pcms[1].card, 1);
+    }
+
+    #[test]
+    fn test_parse_card_list() {
+        let content = " 0 [PCH            ]: HDA-Intel - HDA Intel PCH\n\
+                       \x20                     HDA Intel PCH at 0xf7f10000 irq 32\n\
+                       \x201 [DAC            ]: USB-Audio - Topping D50s\n";
+        let cards = AlsaBackend::parse_card_list(content);
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[&0], ("PCH".to_string(), "HDA Intel PCH".to_string()));
+        assert_eq!(cards[&1].1, "Topping D50s");
+    }
+
+    #[test]
+    fn test_parse_usb_stream() {
+        let content = "Topping D50s at usb-0000:00:14.0-2, high speed : USB Audio\n\
+                       \n\
+                       Playback:\n\
+                       \x20 Status: Stop\n\
+                       \x20 Interface 1\n\
+                       \x20   Format: S32_LE\n\
+                       \x20   Channels: 2\n\
+                       \x20   Rates: 44100, 48000, 88200, 96000, 176400, 192000\n\
+                       Capture:\n\
+                       \x20   Channels: 8\n\
+                       \x20   Rates: 8000\n";
+        let info = AlsaBackend::parse_usb_stream(content).unwrap();
+        assert_eq!(info.rates, vec![44100, 48000, 88200, 96000, 176400, 192000]);
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.bit_depths, vec![32]);
+
+        let range = "Playback:\n    Channels: 2\n    Rates: 32000 - 96000 (continuous)\n";
+        let info = AlsaBackend::parse_usb_stream(range).unwrap();
+        assert_eq!(info.rates, vec![44100, 48000, 88200, 96000]);
+    }
+
+    #[test]
+    fn test_parse_proc_field() {
+        let hw_params = "access: RW_INTERLEAVED\nformat: S32_LE\nrate: 96000 (96000/1)\n";
+        assert_eq!(AlsaBackend::parse_proc_field(hw_params, "rate"), Some(96000));
+        assert_eq!(AlsaBackend::parse_proc_field("closed\n", "rate"), None);
+        let status = "state: RUNNING\nowner_pid   : 4242\n";
+        assert_eq!(AlsaBackend::parse_proc_field(status, "owner_pid"), Some(4242));
+    }
+
+    #[test]
+    fn test_parse_device_id() {
+        assert_eq!(AlsaBackend::parse_device_id("hw:1,0").unwrap(), (1, 0));
+        assert!(AlsaBackend::parse_device_id("default").is_err());
+    }
+
+    #[test]
+    fn test_parse_default_sink() {
+        let info = "Server Name: PulseAudio (on PipeWire 1.0.5)\n\
+                    Default Sink: alsa_output.usb-Topping_D50s-00.analog-stereo\n\
+                    Default Source: alsa_input.pci-0000_00_1f.3.analog-stereo\n";
+        let sinks = "Sink #46\n\
+                     \tName: alsa_output.pci-0000_00_1f.3.analog-stereo\n\
+                     \tProperties:\n\
+                     \t\talsa.card = \"0\"\n\
+                     \t\talsa.device = \"0\"\n\
+                     Sink #52\n\
+                     \tName: alsa_output.usb-Topping_D50s-00.analog-stereo\n\
+                     \tProperties:\n\
+                     \t\tapi.alsa.pcm.card = \"1\"\n\
+                     \t\tapi.alsa.pcm.device = \"0\"\n";
+        assert_eq!(AlsaBackend::parse_default_sink(info, sinks), Some((1, 0)));
+
+        // Bluetooth and network sinks have no ALSA card
+        let bluez = "Default Sink: bluez_output.AA_BB_CC_DD_EE_FF.1\n";
+        let sinks = "Sink #60\n\tName: bluez_output.AA_BB_CC_DD_EE_FF.1\n\tProperties:\n\t\tdevice.api = \"bluez5\"\n";
+        assert_eq!(AlsaBackend::parse_default_sink(bluez, sinks), None);
+    }
+}
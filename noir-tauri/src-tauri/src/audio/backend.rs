@@ -7,7 +7,8 @@
 //!
 //! Audio streaming is handled directly by platform-specific code:
 //! - macOS: CoreAudioStream using AudioUnit API
-//! - Windows: (future) WASAPI direct
+//! - Windows: WasapiBackend for device control, (future) WASAPI stream
+//! - Linux: AlsaBackend for device control (read-only), (future) ALSA stream
 //!
 //! # Important
 //!
@@ -65,6 +66,10 @@ pub trait AudioBackend: Send + Sync {
     /// Noir uses for output. The stream must be recreated after this call.
     fn set_output_device(&mut self, device_id: &str) -> Result<()>;
 
+    /// Drop the manual selection and follow the system default device again
+    /// (a device locked by exclusive mode stays selected until exclusive mode is released)
+    fn follow_system_default(&mut self);
+
     /// Get device info by ID
     fn get_device_info(&self, device_id: &str) -> Result<DeviceInfo>;
 
@@ -87,6 +92,10 @@ pub trait AudioBackend: Send + Sync {
     /// Get all supported sample rates for the current device
     fn supported_sample_rates(&self) -> Result<Vec<u32>>;
 
+    /// Highest signed integer bit depth (16/24/32) among the current device's
+    /// physical stream formats, or None if the device only offers float formats
+    fn integer_bit_depth(&self) -> Option<u16>;
+
     // === Exclusive Mode ===
 
     /// Get current exclusive mode state
@@ -112,7 +121,10 @@ pub trait AudioBackend: Send + Sync {
     /// - A device is added or removed
     /// - The default device changes
     /// - A device's sample rate changes
-    fn set_device_event_callback(&mut self, callback: Option<DeviceEventCallback>);
+    ///
+    /// Returns `NotSupported` on backends that cannot watch devices: the frontend
+    /// then only sees device changes when it refreshes the device list.
+    fn set_device_event_callback(&mut self, callback: Option<DeviceEventCallback>) -> Result<()>;
 
     // === Device ID for Streaming ===
 
@@ -138,6 +150,12 @@ pub trait AudioBackend: Send + Sync {
 
     // === Cleanup ===
 
+    /// Restore the sample rate each device had before Noir changed it
+    ///
+    /// Lighter than `release()`: exclusive mode and device selection are kept,
+    /// only the hardware rate is put back (called when playback stops).
+    fn restore_sample_rate(&mut self) -> Result<()>;
+
     /// Release exclusive mode and restore original sample rate
     ///
     /// Called when switching tracks, changing devices, or shutting down.
@@ -146,7 +164,7 @@ pub trait AudioBackend: Send + Sync {
 
     // === Info ===
 
-    /// Get the backend name (e.g., "CoreAudio", "WASAPI")
+    /// Get the backend name (e.g., "CoreAudio", "WASAPI", "ALSA")
     fn name(&self) -> &'static str;
 
     /// Get the OS-level system default output device ID (bypasses manual selection)
@@ -167,15 +185,17 @@ pub fn create_backend() -> Result<Box<dyn AudioBackend>> {
 
     #[cfg(target_os = "windows")]
     {
-        // Future: WASAPI backend
-        // use crate::audio::wasapi_backend::WasapiBackend;
-        // Ok(Box::new(WasapiBackend::new()?))
-        Err(crate::audio::error::AudioBackendError::NotSupported(
-            "Windows WASAPI backend not yet implemented".to_string(),
-        ))
+        use crate::audio::wasapi_backend::WasapiBackend;
+        Ok(Box::new(WasapiBackend::new()?))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::audio::alsa_backend::AlsaBackend;
+        Ok(Box::new(AlsaBackend::new()?))
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Err(crate::audio::error::AudioBackendError::NotSupported(
             "No native audio backend for this platform".to_string(),
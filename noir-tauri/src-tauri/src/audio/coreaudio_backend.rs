@@ -4,14 +4,17 @@
 //! - Enumerate audio devices
 //! - Change hardware sample rate (kAudioDevicePropertyNominalSampleRate)
 //! - Enable Hog Mode (kAudioDevicePropertyHogMode) for exclusive access
-//! - Listen for device changes
+//! - Listen for default output device changes (AudioObjectAddPropertyListener)
 //!
 //! This file is only compiled on macOS via #[cfg(target_os = "macos")]
 
 use std::collections::{HashMap, HashSet};
 use std::ffi::c_void;
+use std::sync::Arc;
 use std::time::Duration;
 
+use parking_lot::Mutex;
+
 use core_foundation::base::TCFType;
 use core_foundation::string::{CFString, CFStringRef};
 use coreaudio_sys::*;
@@ -21,6 +24,32 @@ use crate::audio::backend::AudioBackend;
 use crate::audio::error::{AudioBackendError, Result};
 use crate::audio::types::*;
 
+/// Device event callback shared with the HAL property listener
+/// (the listener runs on a CoreAudio notification thread, not on the engine thread)
+type SharedEventCallback = Arc<Mutex<Option<DeviceEventCallback>>>;
+
+/// HAL listener for kAudioHardwarePropertyDefaultOutputDevice
+///
+/// `client_data` points to the Mutex inside the backend's SharedEventCallback,
+/// kept alive until the listener is removed (set_device_event_callback(None) / Drop).
+unsafe extern "C" fn default_output_device_listener(
+    _object_id: AudioObjectID,
+    _number_addresses: u32,
+    _addresses: *const AudioObjectPropertyAddress,
+    client_data: *mut c_void,
+) -> OSStatus {
+    let callback = &*(client_data as *const Mutex<Option<DeviceEventCallback>>);
+
+    if let Ok(device_id) = CoreAudioBackend::get_default_output_device() {
+        let info = CoreAudioBackend::build_device_info(device_id, Some(device_id));
+        println!("[CoreAudio] Default output device changed: {} (ID: {})", info.name, device_id);
+        if let Some(ref cb) = *callback.lock() {
+            cb(DeviceEvent::DefaultDeviceChanged(info));
+        }
+    }
+    0
+}
+
 /// CoreAudio HAL backend
 ///
 /// IMPORTANT: This backend follows the system default output device.
@@ -35,8 +64,10 @@ pub struct CoreAudioBackend {
     exclusive_mode: ExclusiveMode,
     /// Original sample rate (to restore on release) - keyed by device ID
     original_sample_rates: HashMap<AudioObjectID, u32>,
-    /// Device event callback
-    event_callback: Option<DeviceEventCallback>,
+    /// Device event callback (shared with the HAL listener)
+    event_callback: SharedEventCallback,
+    /// Whether the default output device listener is registered
+    default_device_listener: bool,
     /// Last known device ID (to detect changes)
     last_device_id: AudioObjectID,
     /// Whether the device was locked by hog mode (to prevent device switching)
@@ -59,7 +90,8 @@ impl CoreAudioBackend {
             device_cache: HashMap::new(),
             exclusive_mode: ExclusiveMode::Shared,
             original_sample_rates: HashMap::new(),
-            event_callback: None,
+            event_callback: Arc::new(Mutex::new(None)),
+            default_device_listener: false,
             last_device_id: default_device,
             hog_locked_device: false,
             airplay_session_devices: HashMap::new(),
@@ -396,6 +428,38 @@ impl CoreAudioBackend {
         }
     }
 
+    /// Get device manufacturer (None if the driver doesn't report one)
+    fn get_device_manufacturer(device_id: AudioObjectID) -> Option<String> {
+        unsafe {
+            let property_address = AudioObjectPropertyAddress {
+                mSelector: kAudioObjectPropertyManufacturer,
+                mScope: kAudioObjectPropertyScopeGlobal,
+                mElement: kAudioObjectPropertyElementMain,
+            };
+
+            let mut cf_name: CFStringRef = std::ptr::null();
+            let mut size = std::mem::size_of::<CFStringRef>() as u32;
+
+            let status = AudioObjectGetPropertyData(
+                device_id,
+                &property_address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut cf_name as *mut _ as *mut c_void,
+            );
+
+            if status != 0 || cf_name.is_null() {
+                return None;
+            }
+
+            // The HAL hands over a +1 reference (copy rule)
+            let manufacturer = CFString::wrap_under_create_rule(cf_name).to_string();
+            let manufacturer = manufacturer.trim();
+            (!manufacturer.is_empty()).then(|| manufacturer.to_string())
+        }
+    }
+
     /// Get current sample rate of a device
     fn get_device_sample_rate(device_id: AudioObjectID) -> Result<u32> {
         unsafe {
@@ -552,6 +616,100 @@ impl CoreAudioBackend {
         }
     }
 
+    /// Signed integer bit depths (16/24/32) among the device's physical output formats
+    /// (kAudioStreamPropertyAvailablePhysicalFormats of each output stream), sorted
+    fn get_supported_bit_depths(device_id: AudioObjectID) -> Vec<u8> {
+        unsafe {
+            let streams_address = AudioObjectPropertyAddress {
+                mSelector: kAudioDevicePropertyStreams,
+                mScope: kAudioDevicePropertyScopeOutput,
+                mElement: kAudioObjectPropertyElementMain,
+            };
+
+            let mut size: u32 = 0;
+            let status = AudioObjectGetPropertyDataSize(
+                device_id,
+                &streams_address,
+                0,
+                std::ptr::null(),
+                &mut size,
+            );
+            if status != 0 || size == 0 {
+                return Vec::new();
+            }
+
+            let mut stream_ids: Vec<AudioStreamID> =
+                vec![0; size as usize / std::mem::size_of::<AudioStreamID>()];
+            let status = AudioObjectGetPropertyData(
+                device_id,
+                &streams_address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                stream_ids.as_mut_ptr() as *mut c_void,
+            );
+            if status != 0 {
+                return Vec::new();
+            }
+
+            let formats_address = AudioObjectPropertyAddress {
+                mSelector: kAudioStreamPropertyAvailablePhysicalFormats,
+                mScope: kAudioObjectPropertyScopeGlobal,
+                mElement: kAudioObjectPropertyElementMain,
+            };
+
+            let mut depths: Vec<u8> = Vec::new();
+            for stream_id in stream_ids {
+                let mut size: u32 = 0;
+                let status = AudioObjectGetPropertyDataSize(
+                    stream_id,
+                    &formats_address,
+                    0,
+                    std::ptr::null(),
+                    &mut size,
+                );
+                if status != 0 || size == 0 {
+                    continue;
+                }
+
+                let count = size as usize / std::mem::size_of::<AudioStreamRangedDescription>();
+                let mut formats: Vec<AudioStreamRangedDescription> =
+                    vec![std::mem::zeroed(); count];
+                let status = AudioObjectGetPropertyData(
+                    stream_id,
+                    &formats_address,
+                    0,
+                    std::ptr::null(),
+                    &mut size,
+                    formats.as_mut_ptr() as *mut c_void,
+                );
+                if status != 0 {
+                    continue;
+                }
+
+                for ranged in &formats {
+                    let format = &ranged.mFormat;
+                    let is_integer = format.mFormatID == kAudioFormatLinearPCM
+                        && format.mFormatFlags & kAudioFormatFlagIsSignedInteger != 0
+                        && format.mFormatFlags & kAudioFormatFlagIsFloat == 0;
+                    let bits = format.mBitsPerChannel as u8;
+                    if is_integer && matches!(bits, 16 | 24 | 32) {
+                        depths.push(bits);
+                    }
+                }
+            }
+
+            depths.sort_unstable();
+            depths.dedup();
+            depths
+        }
+    }
+
+    /// Highest signed integer bit depth among the device's physical output formats
+    fn get_integer_bit_depth(device_id: AudioObjectID) -> Option<u16> {
+        Self::get_supported_bit_depths(device_id).last().map(|&bits| bits as u16)
+    }
+
     /// Read which PID currently owns Hog Mode on a device
     /// Returns -1 if no process owns it, or the PID of the owning process
     fn get_hog_mode_pid(device_id: AudioObjectID) -> Result<i32> {
@@ -756,6 +914,90 @@ impl CoreAudioBackend {
         transport_type
     }
 
+    /// Human-readable label for a CoreAudio transport type (four-char code)
+    /// None for unknown transports (and 0, the failure default)
+    fn transport_label(transport_type: u32) -> Option<&'static str> {
+        let label = match &transport_type.to_be_bytes() {
+            b"bltn" => "Built-in",
+            b"usb " => "USB",
+            b"blue" | b"blea" => "Bluetooth",
+            b"hdmi" => "HDMI",
+            b"dprt" => "DisplayPort",
+            b"airp" => "AirPlay",
+            b"thun" => "Thunderbolt",
+            b"1394" => "FireWire",
+            b"pci " => "PCI",
+            b"eavb" => "AVB",
+            b"grup" => "Aggregate",
+            b"virt" => "Virtual",
+            _ => return None,
+        };
+        Some(label)
+    }
+
+    /// Property address of the system default output device
+    fn default_output_device_address() -> AudioObjectPropertyAddress {
+        AudioObjectPropertyAddress {
+            mSelector: kAudioHardwarePropertyDefaultOutputDevice,
+            mScope: kAudioObjectPropertyScopeGlobal,
+            mElement: kAudioObjectPropertyElementMain,
+        }
+    }
+
+    /// Register or remove the default output device listener
+    fn set_default_device_listener(&mut self, enabled: bool) {
+        if enabled == self.default_device_listener {
+            return;
+        }
+
+        let address = Self::default_output_device_address();
+        let client_data = Arc::as_ptr(&self.event_callback) as *mut c_void;
+        let status = unsafe {
+            if enabled {
+                AudioObjectAddPropertyListener(
+                    kAudioObjectSystemObject,
+                    &address,
+                    Some(default_output_device_listener),
+                    client_data,
+                )
+            } else {
+                AudioObjectRemovePropertyListener(
+                    kAudioObjectSystemObject,
+                    &address,
+                    Some(default_output_device_listener),
+                    client_data,
+                )
+            }
+        };
+
+        if status == 0 {
+            self.default_device_listener = enabled;
+        } else {
+            println!("[CoreAudio] Failed to update default device listener: CoreAudio error {}", status);
+        }
+    }
+
+    /// Query all device properties from the HAL
+    fn build_device_info(device_id: AudioObjectID, default_id: Option<AudioObjectID>) -> DeviceInfo {
+        let transport_type = Self::get_device_transport_type(device_id);
+
+        DeviceInfo {
+            id: device_id.to_string(),
+            name: Self::get_device_name(device_id).unwrap_or_else(|_| "Unknown".to_string()),
+            manufacturer: Self::get_device_manufacturer(device_id),
+            is_default: Some(device_id) == default_id,
+            supported_sample_rates: Self::get_supported_sample_rates(device_id).unwrap_or_default(),
+            current_sample_rate: Self::get_device_sample_rate(device_id).unwrap_or(44100),
+            supported_bit_depths: Self::get_supported_bit_depths(device_id),
+            max_channels: Self::get_max_channels(device_id),
+            supports_exclusive: true, // All macOS devices support Hog Mode
+            transport_type,
+            transport: Self::transport_label(transport_type).map(str::to_string),
+            // kAudioDeviceTransportTypeAirPlay = 'airp' = 0x61697270
+            is_airplay: transport_type == 0x61697270u32,
+        }
+    }
+
     /// Find the best supported sample rate for a given source rate
     /// Prioritizes: exact match > higher rate > highest available
     fn find_best_supported_rate(source_rate: u32, supported_rates: &[u32]) -> u32 {
@@ -792,27 +1034,9 @@ impl CoreAudioBackend {
         self.stale_airplay_ids.clear();
 
         for device_id in device_ids {
-            let name = Self::get_device_name(device_id).unwrap_or_else(|_| "Unknown".to_string());
-            let current_rate = Self::get_device_sample_rate(device_id).unwrap_or(44100);
-            let supported_rates = Self::get_supported_sample_rates(device_id).unwrap_or_default();
-            let max_channels = Self::get_max_channels(device_id);
-
-            let transport_type = Self::get_device_transport_type(device_id);
-            // kAudioDeviceTransportTypeAirPlay = 'airp' = 0x61697270
-            let is_airplay = transport_type == 0x61697270u32;
-
-            let info = DeviceInfo {
-                id: device_id.to_string(),
-                name,
-                manufacturer: None, // TODO: get manufacturer via kAudioObjectPropertyManufacturer
-                is_default: Some(device_id) == default_id,
-                supported_sample_rates: supported_rates,
-                current_sample_rate: current_rate,
-                max_channels,
-                supports_exclusive: true, // All macOS devices support Hog Mode
-                transport_type,
-                is_airplay,
-            };
+            let info = Self::build_device_info(device_id, default_id);
+            let transport_type = info.transport_type;
+            let is_airplay = info.is_airplay;
 
             // Save wireless devices (AirPlay + Bluetooth) to session cache so they
             // persist in Noir's list even when CoreAudio deactivates them.
@@ -1000,6 +1224,14 @@ impl AudioBackend for CoreAudioBackend {
         Ok(())
     }
 
+    fn follow_system_default(&mut self) {
+        if self.hog_locked_device {
+            return;
+        }
+        self.manual_device_id = None;
+        println!("[CoreAudio] Following system default device");
+    }
+
     fn get_device_info(&self, device_id: &str) -> Result<DeviceInfo> {
         self.device_cache
             .get(device_id)
@@ -1054,6 +1286,11 @@ impl AudioBackend for CoreAudioBackend {
             .ok_or_else(|| AudioBackendError::DeviceNotFound(id))
     }
 
+    fn integer_bit_depth(&self) -> Option<u16> {
+        let device_id = self.get_active_device_id().ok()?;
+        Self::get_integer_bit_depth(device_id)
+    }
+
     fn exclusive_mode(&self) -> ExclusiveMode {
         self.exclusive_mode
     }
@@ -1126,10 +1363,13 @@ impl AudioBackend for CoreAudioBackend {
         })
     }
 
-    fn set_device_event_callback(&mut self, callback: Option<DeviceEventCallback>) {
-        self.event_callback = callback;
-        // TODO: Register property listeners with CoreAudio for device changes
-        // AudioObjectAddPropertyListener(...)
+    fn set_device_event_callback(&mut self, callback: Option<DeviceEventCallback>) -> Result<()> {
+        let enabled = callback.is_some();
+        *self.event_callback.lock() = callback;
+        // Only the default output device is watched: it covers headphones/DAC hot-plug,
+        // which macOS reports as a default device change
+        self.set_default_device_listener(enabled);
+        Ok(())
     }
 
     fn get_device_id(&self) -> Option<u32> {
@@ -1244,6 +1484,21 @@ impl AudioBackend for CoreAudioBackend {
         Ok(target_rate)
     }
 
+    fn restore_sample_rate(&mut self) -> Result<()> {
+        for (device_id, original_rate) in self.original_sample_rates.drain() {
+            println!(
+                "[CoreAudio] Restoring device {} to original sample rate: {} Hz",
+                device_id, original_rate
+            );
+            let _ = Self::set_device_sample_rate_internal(device_id, original_rate);
+
+            if let Some(info) = self.device_cache.get_mut(&device_id.to_string()) {
+                info.current_sample_rate = original_rate;
+            }
+        }
+        Ok(())
+    }
+
     fn release(&mut self) -> Result<()> {
         println!("[CoreAudio] Releasing resources...");
 
@@ -1262,13 +1517,7 @@ impl AudioBackend for CoreAudioBackend {
         }
 
         // Restore original sample rates for all modified devices
-        for (device_id, original_rate) in self.original_sample_rates.drain() {
-            println!(
-                "[CoreAudio] Restoring device {} to original sample rate: {} Hz",
-                device_id, original_rate
-            );
-            let _ = Self::set_device_sample_rate_internal(device_id, original_rate);
-        }
+        let _ = self.restore_sample_rate();
 
         println!("[CoreAudio] Resources released");
         Ok(())
@@ -1285,6 +1534,9 @@ impl AudioBackend for CoreAudioBackend {
 
 impl Drop for CoreAudioBackend {
     fn drop(&mut self) {
+        // The listener holds a pointer into event_callback: remove it first
+        self.set_default_device_listener(false);
+
         // CRITICAL: Ensure cleanup on drop (even on panic/crash)
         if let Err(e) = self.release() {
             eprintln!("[CoreAudio] Error during drop cleanup: {}", e);
@@ -1302,6 +1554,14 @@ mod tests {
         assert!(backend.is_ok(), "Failed to create CoreAudio backend");
     }
 
+    #[test]
+    fn test_transport_label() {
+        assert_eq!(CoreAudioBackend::transport_label(0x75736220), Some("USB"));
+        assert_eq!(CoreAudioBackend::transport_label(0x626C746E), Some("Built-in"));
+        assert_eq!(CoreAudioBackend::transport_label(0x61697270), Some("AirPlay"));
+        assert_eq!(CoreAudioBackend::transport_label(0), None);
+    }
+
     #[test]
     fn test_device_enumeration() {
         let backend = CoreAudioBackend::new().unwrap();
@@ -19,24 +19,30 @@ use parking_lot::Mutex;
 use coreaudio_sys::{
     AudioComponentDescription, AudioComponentFindNext, AudioComponentInstanceNew,
     AudioComponentInstanceDispose, AudioOutputUnitStart, AudioOutputUnitStop,
-    AudioUnitInitialize, AudioUnitReset, AudioUnitSetProperty, AudioUnitUninitialize,
+    AudioUnitGetProperty, AudioUnitInitialize, AudioUnitReset, AudioUnitSetProperty,
+    AudioUnitUninitialize,
     AudioUnit as SysAudioUnit, AudioStreamBasicDescription, AudioObjectID,
     kAudioFormatFlagsNativeFloatPacked, kAudioFormatLinearPCM,
-    kAudioUnitProperty_SetRenderCallback, kAudioUnitProperty_StreamFormat,
+    kAudioFormatFlagIsSignedInteger, kAudioFormatFlagIsPacked,
+    kAudioUnitProperty_MaximumFramesPerSlice, kAudioUnitProperty_SetRenderCallback,
+    kAudioUnitProperty_StreamFormat,
     kAudioUnitScope_Global, kAudioUnitScope_Input, kAudioUnitType_Output,
     kAudioUnitSubType_HALOutput, kAudioUnitManufacturer_Apple,
     kAudioOutputUnitProperty_CurrentDevice,
     AURenderCallbackStruct, AudioUnitRenderActionFlags, AudioTimeStamp,
     AudioBufferList,
 };
+use crossbeam_channel::{bounded, Sender};
 use ringbuf::HeapCons;
-use ringbuf::traits::Consumer;
+use ringbuf::traits::{Consumer, Observer};
 use tauri::{AppHandle, Emitter};
 
 use crate::audio_decoder::StreamingState;
-use crate::audio_engine::PlaybackProgress;
+use crate::audio_engine::{AbLoopState, BufferHealthState, PlaybackProgress, SegmentState};
 use crate::eq::{EqProcessor, EqSharedState};
-use super::stream::{AudioOutputStream, AudioStreamConfig};
+use crate::output_stage::OutputStage;
+use crate::spectrum::SpectrumTap;
+use super::stream::{write_integer_samples, AudioOutputStream, AudioStreamConfig};
 
 /// CoreAudio-based audio output stream using raw coreaudio-sys
 pub struct CoreAudioStream {
@@ -90,10 +96,53 @@ struct CallbackData {
     // Chemin du prochain track préchargé (copié depuis audio_engine::next_path).
     // Lors de la transition gapless, son contenu est déplacé dans current_path.
     next_path: Arc<Mutex<Option<String>>>,
+    // Crossfade : durée configurée (ms, 0 = off) et flag partagé avec l'engine
+    crossfade_ms: Arc<AtomicU64>,
+    crossfade_active: Arc<AtomicBool>,
+    // Fondu en cours : nombre total de frames du fondu et frames déjà mixées
+    fade_total_frames: u64,
+    fade_done_frames: u64,
+    // Samples du prochain track déjà consommés pendant le fondu (position de départ après bascule)
+    fade_next_samples: u64,
+    // Fraction de sample non encore reportée sur playback_samples (vitesse != 1.0)
+    position_remainder: f64,
+    // Boucle A-B (le retour en A est fait par l'engine via un seek)
+    ab_loop: Arc<AbLoopState>,
+    // Piste CUE en cours : position et durée émises relativement au segment
+    segment: Arc<SegmentState>,
+    // Mode entier : profondeur du format natif du DAC (None = sortie float 32 bits)
+    integer_bits: Option<u16>,
+    // Ring de capture du visualiseur de spectre (écrit ici, analysé par get_spectrum)
+    spectrum: Arc<SpectrumTap>,
+    // Étage de sortie (canaux, balance, largeur, limiteur), appliqué après EQ et volume
+    output_stage: Arc<OutputStage>,
+    // Santé du ring ; `in_underrun` évite de compter / émettre à chaque callback d'une même coupure
+    buffer_health: Arc<BufferHealthState>,
+    in_underrun: bool,
+    // Buffer du track préchargé pendant un crossfade, alloué une fois à MaximumFramesPerSlice
+    crossfade_scratch: Vec<f32>,
+    // Consumer / état du track sortant après une transition gapless, libérés hors du callback
+    retired_tx: Sender<RetiredTrack>,
+}
+
+/// Ring et état d'un track remplacé par une transition gapless
+type RetiredTrack = (HeapCons<f32>, Arc<StreamingState>);
+
+/// Issue d'une tentative de transition gapless depuis le callback
+enum GaplessSwap {
+    /// Le track préchargé est devenu le track courant
+    Done,
+    /// Aucun preload compatible : la fin normale du track s'applique
+    Unavailable,
+    /// Un verrou est tenu par l'engine : nouvelle tentative au prochain callback
+    Retry,
 }
 
 const EMPTY_CALLBACKS_THRESHOLD: u32 = 3;
 
+/// Frames par callback si l'AudioUnit ne donne pas kAudioUnitProperty_MaximumFramesPerSlice
+const DEFAULT_MAX_FRAMES_PER_SLICE: u32 = 4096;
+
 impl CoreAudioStream {
     /// Create a new CoreAudio stream
     ///
@@ -118,6 +167,13 @@ impl CoreAudioStream {
         rms_energy: Arc<AtomicU64>,
         current_path: Arc<Mutex<Option<String>>>,
         next_path: Arc<Mutex<Option<String>>>,
+        crossfade_ms: Arc<AtomicU64>,
+        crossfade_active: Arc<AtomicBool>,
+        ab_loop: Arc<AbLoopState>,
+        segment: Arc<SegmentState>,
+        spectrum: Arc<SpectrumTap>,
+        output_stage: Arc<OutputStage>,
+        buffer_health: Arc<BufferHealthState>,
     ) -> Result<Self, String> {
         unsafe {
             // 1. Find the HAL output audio component (allows device selection)
@@ -168,16 +224,21 @@ impl CoreAudioStream {
                 println!("[CoreAudioStream] Using system default output device");
             }
 
-            // 3. Set the stream format
+            // 3. Set the stream format (float 32 bits, ou entier signé au format natif du DAC)
+            let (format_flags, bits_per_channel) = match config.integer_bits {
+                Some(bits) => (kAudioFormatFlagIsSignedInteger | kAudioFormatFlagIsPacked, bits as u32),
+                None => (kAudioFormatFlagsNativeFloatPacked, 32),
+            };
+            let bytes_per_frame = bits_per_channel / 8 * config.channels as u32;
             let asbd = AudioStreamBasicDescription {
                 mSampleRate: config.sample_rate as f64,
                 mFormatID: kAudioFormatLinearPCM,
-                mFormatFlags: kAudioFormatFlagsNativeFloatPacked,
-                mBytesPerPacket: 4 * config.channels as u32,
+                mFormatFlags: format_flags,
+                mBytesPerPacket: bytes_per_frame,
                 mFramesPerPacket: 1,
-                mBytesPerFrame: 4 * config.channels as u32,
+                mBytesPerFrame: bytes_per_frame,
                 mChannelsPerFrame: config.channels as u32,
-                mBitsPerChannel: 32,
+                mBitsPerChannel: bits_per_channel,
                 mReserved: 0,
             };
 
@@ -194,8 +255,24 @@ impl CoreAudioStream {
                 return Err(format!("Failed to set stream format: {}", status));
             }
 
-            println!("[CoreAudioStream] Configured: {}Hz, {} channels",
-                config.sample_rate, config.channels);
+            println!("[CoreAudioStream] Configured: {}Hz, {} channels, {}",
+                config.sample_rate, config.channels,
+                config.integer_bits.map(|b| format!("{}-bit integer", b)).unwrap_or_else(|| "32-bit float".to_string()));
+
+            // 3b. Taille maximale d'un callback (dimensionne le buffer de crossfade)
+            let mut max_frames: u32 = 0;
+            let mut max_frames_size = mem::size_of::<u32>() as u32;
+            let status = AudioUnitGetProperty(
+                audio_unit,
+                kAudioUnitProperty_MaximumFramesPerSlice,
+                kAudioUnitScope_Global,
+                0,
+                &mut max_frames as *mut _ as *mut c_void,
+                &mut max_frames_size,
+            );
+            if status != 0 || max_frames == 0 {
+                max_frames = DEFAULT_MAX_FRAMES_PER_SLICE;
+            }
 
             // 4. Prepare shared state
             let is_playing = Arc::new(AtomicBool::new(false));
@@ -209,9 +286,20 @@ impl CoreAudioStream {
             // 5. Create callback data
             let channels_count = config.channels as u64;
             let sample_rate_f64 = config.sample_rate as f64;
+            spectrum.set_sample_rate(config.sample_rate);
             let duration_samples = streaming_state.info.total_frames * channels_count;
             let emit_interval = config.sample_rate / 30;
 
+            // Thread de libération des tracks sortants (se termine avec le callback data)
+            let (retired_tx, retired_rx) = bounded::<RetiredTrack>(4);
+            std::thread::Builder::new()
+                .name("coreaudio-retire".to_string())
+                .spawn(move || for retired in retired_rx { drop(retired) })
+                .map_err(|e| {
+                    AudioComponentInstanceDispose(audio_unit);
+                    format!("Failed to spawn retire thread: {}", e)
+                })?;
+
             let callback_data = Box::new(CallbackData {
                 consumer,
                 streaming_state: Arc::clone(&streaming_state),
@@ -244,6 +332,21 @@ impl CoreAudioStream {
                 rms_energy,
                 current_path,
                 next_path,
+                crossfade_ms,
+                crossfade_active,
+                fade_total_frames: 0,
+                fade_done_frames: 0,
+                fade_next_samples: 0,
+                position_remainder: 0.0,
+                ab_loop,
+                segment,
+                integer_bits: config.integer_bits,
+                spectrum,
+                output_stage,
+                buffer_health,
+                in_underrun: false,
+                crossfade_scratch: vec![0.0f32; max_frames as usize * channels_count as usize],
+                retired_tx,
             });
 
             // 6. Set up the render callback
@@ -283,6 +386,197 @@ impl CoreAudioStream {
     }
 }
 
+impl CallbackData {
+    /// True si un track est préchargé et sort au même format que ce stream
+    /// (sample rate / canaux) — condition pour gapless comme pour crossfade.
+    fn preload_ready(&self) -> bool {
+        // Boucle A-B active : on reste sur le track courant
+        if !self.gapless_enabled.load(Ordering::Relaxed) || self.ab_loop.is_active() {
+            return false;
+        }
+        let (Some(cons_guard), Some(state_guard)) =
+            (self.next_consumer.try_lock(), self.next_streaming_state.try_lock())
+        else {
+            return false;
+        };
+        match (cons_guard.as_ref(), state_guard.as_ref()) {
+            (Some(_), Some(next_state)) => self.same_output_format(next_state),
+            _ => false,
+        }
+    }
+
+    fn same_output_format(&self, next_state: &StreamingState) -> bool {
+        next_state.info.output_sample_rate as f64 == self.sample_rate_f64
+            && next_state.info.channels as u64 == self.channels_count
+    }
+
+    /// Bascule sur le track préchargé (gapless) sans passer par la fin de stream.
+    ///
+    /// `Unavailable` si aucun preload n'est prêt, ou si son format de sortie
+    /// (sample rate / canaux) diffère de ce stream : le preload est alors laissé
+    /// en place, la fin normale est émise et le frontend relance un Play.
+    /// Aucun verrou n'est attendu (thread temps réel) : `Retry` si l'engine en tient un.
+    fn try_gapless_swap(&mut self) -> GaplessSwap {
+        if !self.gapless_enabled.load(Ordering::Relaxed) || self.ab_loop.is_active() {
+            return GaplessSwap::Unavailable;
+        }
+
+        // next_path et current_path sont pris sous les mêmes verrous : l'engine ne peut
+        // pas les modifier entre-temps
+        let (Some(mut next_cons_guard), Some(mut next_state_guard), Some(mut next_path_guard), Some(mut current_path_guard)) = (
+            self.next_consumer.try_lock(),
+            self.next_streaming_state.try_lock(),
+            self.next_path.try_lock(),
+            self.current_path.try_lock(),
+        ) else {
+            return GaplessSwap::Retry;
+        };
+        match next_state_guard.as_deref() {
+            Some(next_state) if next_cons_guard.is_some() && self.same_output_format(next_state) => {}
+            _ => return GaplessSwap::Unavailable,
+        }
+        let (Some(new_consumer), Some(new_state)) = (next_cons_guard.take(), next_state_guard.take()) else {
+            return GaplessSwap::Unavailable;
+        };
+        let new_path = next_path_guard.take();
+
+        // ── Mise à jour du chemin courant ────────────────────────────────
+        // CRITIQUE : current_path doit pointer sur le NOUVEAU fichier dès maintenant.
+        // Sans ça, un seek après transition gapless ferait re-probe l'ANCIEN fichier :
+        // mauvaise durée sur la progress bar + "out-of-range" si on cherche
+        // au-delà de la durée de l'ancien track.
+        *current_path_guard = new_path.clone();
+        drop((next_cons_guard, next_state_guard, next_path_guard, current_path_guard));
+
+        println!("[CoreAudioStream] GAPLESS TRANSITION at {:.3}s",
+            self.playback_samples as f64 / self.channels_count as f64 / self.sample_rate_f64);
+
+        // Swap consumer and streaming state ; l'ancien ring (et l'état s'il n'a plus
+        // d'autre référence) est libéré par le thread de libération, pas ici.
+        // Canal plein (transitions en rafale) : libéré sur place, comme avant.
+        let old_consumer = mem::replace(&mut self.consumer, new_consumer);
+        let old_state = mem::replace(&mut self.streaming_state, new_state);
+        let _ = self.retired_tx.try_send((old_consumer, old_state));
+
+        // Reset playback tracking for the new track
+        // (après un crossfade, le début du nouveau track a déjà été joué pendant le fondu)
+        self.playback_samples = (self.fade_next_samples as f64 * self.streaming_state.speed() as f64) as u64;
+        self.position_remainder = 0.0;
+        self.empty_callbacks = 0;
+        self.end_emitted = false;
+        self.emit_counter = 0;
+        self.duration_seconds = self.streaming_state.info.duration_seconds;
+        self.duration_samples = self.streaming_state.info.total_frames * self.channels_count;
+        self.position_state.store(0, Ordering::Relaxed);
+        // Les pistes CUE ne sont jamais préchargées : le nouveau track est un fichier entier
+        self.segment.clear();
+        self.end_crossfade();
+
+        // Notifie le frontend (transition + chemin du nouveau track pour l'UI)
+        if let Some(ref app) = self.app_handle {
+            let _ = app.emit("playback_gapless_transition", ());
+            if let Some(path) = new_path {
+                let _ = app.emit("playback_track_switched", path);
+            }
+        }
+
+        GaplessSwap::Done
+    }
+
+    /// Démarre un crossfade si on entre dans les `crossfade_ms` dernières ms du track
+    /// et qu'un preload compatible est prêt. Le fondu dure ce qu'il reste du track.
+    fn maybe_start_crossfade(&mut self) {
+        if self.fade_total_frames > 0 {
+            return;
+        }
+        let crossfade_ms = self.crossfade_ms.load(Ordering::Relaxed);
+        if crossfade_ms == 0 || self.duration_samples == 0 {
+            return;
+        }
+        let fade_frames = crossfade_ms * self.sample_rate_f64 as u64 / 1000;
+        // Positions en temps "fichier" : à vitesse != 1.0, il reste moins (ou plus) de frames de sortie
+        let remaining_media_frames = self.duration_samples.saturating_sub(self.playback_samples) / self.channels_count;
+        let remaining_frames = (remaining_media_frames as f64 / self.streaming_state.speed() as f64) as u64;
+        if remaining_frames == 0 || remaining_frames > fade_frames || !self.preload_ready() {
+            return;
+        }
+
+        println!("[CoreAudioStream] CROSSFADE START ({} frames)", remaining_frames);
+        self.fade_total_frames = remaining_frames;
+        self.fade_done_frames = 0;
+        self.fade_next_samples = 0;
+        self.crossfade_active.store(true, Ordering::Relaxed);
+    }
+
+    /// Avance la position de `samples` samples de sortie, convertis en samples "fichier"
+    /// selon la vitesse de lecture pour que position/progress bar restent exactes.
+    fn advance_position(&mut self, samples: usize) {
+        let speed = self.streaming_state.speed();
+        if speed == 1.0 {
+            self.playback_samples += samples as u64;
+            return;
+        }
+        self.position_remainder += samples as f64 * speed as f64;
+        let whole = self.position_remainder.floor();
+        self.playback_samples += whole as u64;
+        self.position_remainder -= whole;
+    }
+
+    /// Abandonne / termine le fondu en cours
+    fn end_crossfade(&mut self) {
+        self.fade_total_frames = 0;
+        self.fade_done_frames = 0;
+        self.fade_next_samples = 0;
+        self.crossfade_active.store(false, Ordering::Relaxed);
+    }
+
+    /// Mixe le début du track préchargé dans `buf` (déjà rempli avec `read` samples
+    /// du track courant) : gain linéaire 1→0 pour l'ancien, 0→1 pour le nouveau.
+    /// Retourne le nombre de samples valides dans `buf` après mixage.
+    fn mix_crossfade(&mut self, buf: &mut [f32], read: usize) -> usize {
+        // Pas d'allocation dans le callback : buffer pré-alloué à la taille max d'un callback
+        let scratch_len = buf.len().min(self.crossfade_scratch.len());
+        let next_buf = &mut self.crossfade_scratch[..scratch_len];
+        next_buf.fill(0.0);
+        let next_read = match self.next_consumer.try_lock() {
+            Some(mut guard) => guard.as_mut().map(|next| next.pop_slice(next_buf)),
+            // Verrou tenu brièvement par l'engine : on saute le nouveau track pour ce buffer
+            None => Some(0),
+        };
+        let Some(next_read) = next_read else {
+            // Preload annulé (seek, Play, gapless off) → fondu abandonné
+            self.end_crossfade();
+            return read;
+        };
+        self.fade_next_samples += next_read as u64;
+
+        let channels = self.channels_count as usize;
+        let frames = buf.len() / channels;
+        let total = self.fade_total_frames as f32;
+        for frame in 0..frames {
+            let progress = ((self.fade_done_frames + frame as u64) as f32 / total).min(1.0);
+            for ch in 0..channels {
+                let idx = frame * channels + ch;
+                let old = if idx < read { buf[idx] } else { 0.0 };
+                let new = if idx < next_read { next_buf[idx] } else { 0.0 };
+                buf[idx] = old * (1.0 - progress) + new * progress;
+            }
+        }
+        self.fade_done_frames += frames as u64;
+
+        read.max(next_read)
+    }
+}
+
+/// Zeroes every output buffer byte by byte: valid for float and integer formats alike,
+/// and covers packed 24-bit buffers whose size is not a multiple of 4
+unsafe fn write_silence(buffer_list: &mut AudioBufferList, num_buffers: usize) {
+    for i in 0..num_buffers {
+        let buffer = &mut *buffer_list.mBuffers.as_mut_ptr().add(i);
+        ptr::write_bytes(buffer.mData as *mut u8, 0, buffer.mDataByteSize as usize);
+    }
+}
+
 /// The render callback function called by CoreAudio
 unsafe extern "C" fn render_callback(
     in_ref_con: *mut c_void,
@@ -304,31 +598,13 @@ unsafe extern "C" fn render_callback(
     // Check if we're paused or not playing
     if data.is_paused.load(Ordering::Relaxed) || !data.is_playing_local.load(Ordering::Relaxed) {
         // Output silence
-        for i in 0..num_buffers {
-            let buffer = &mut *buffer_list.mBuffers.as_mut_ptr().add(i);
-            let samples = std::slice::from_raw_parts_mut(
-                buffer.mData as *mut f32,
-                buffer.mDataByteSize as usize / 4,
-            );
-            for sample in samples.iter_mut() {
-                *sample = 0.0;
-            }
-        }
+        write_silence(buffer_list, num_buffers);
         return 0;
     }
 
     // If track ended, output silence
     if data.end_emitted {
-        for i in 0..num_buffers {
-            let buffer = &mut *buffer_list.mBuffers.as_mut_ptr().add(i);
-            let samples = std::slice::from_raw_parts_mut(
-                buffer.mData as *mut f32,
-                buffer.mDataByteSize as usize / 4,
-            );
-            for sample in samples.iter_mut() {
-                *sample = 0.0;
-            }
-        }
+        write_silence(buffer_list, num_buffers);
         return 0;
     }
 
@@ -364,32 +640,17 @@ unsafe extern "C" fn render_callback(
         data.playback_samples = data.streaming_state.seek_position.load(Ordering::Relaxed);
         data.empty_callbacks = 0;
 
+        // Un seek coupe net un éventuel crossfade (l'engine recharge le preload)
+        data.end_crossfade();
+
         // Output silence for this callback
-        for i in 0..num_buffers {
-            let buffer = &mut *buffer_list.mBuffers.as_mut_ptr().add(i);
-            let samples = std::slice::from_raw_parts_mut(
-                buffer.mData as *mut f32,
-                buffer.mDataByteSize as usize / 4,
-            );
-            for sample in samples.iter_mut() {
-                *sample = 0.0;
-            }
-        }
+        write_silence(buffer_list, num_buffers);
         return 0;
     }
 
     // Check if seek is in progress (pre-fill not complete)
     if data.streaming_state.seeking.load(Ordering::Acquire) {
-        for i in 0..num_buffers {
-            let buffer = &mut *buffer_list.mBuffers.as_mut_ptr().add(i);
-            let samples = std::slice::from_raw_parts_mut(
-                buffer.mData as *mut f32,
-                buffer.mDataByteSize as usize / 4,
-            );
-            for sample in samples.iter_mut() {
-                *sample = 0.0;
-            }
-        }
+        write_silence(buffer_list, num_buffers);
         data.playback_samples = data.streaming_state.seek_position.load(Ordering::Relaxed);
         data.empty_callbacks = 0;
         return 0;
@@ -399,7 +660,49 @@ unsafe extern "C" fn render_callback(
     // Read from RingBuffer (which has interleaved samples)
     let total_samples = in_number_frames as usize * data.channels_count as usize;
     let mut interleaved_buf = vec![0.0f32; total_samples];
-    let read = data.consumer.pop_slice(&mut interleaved_buf);
+    // Lu AVANT pop_slice : si le décodage était déjà terminé, un read incomplet
+    // signifie que le ring est réellement vide (aucun sample encore en transit)
+    let decoding_done = data.streaming_state.decoding_complete.load(Ordering::Acquire);
+    let mut read = data.consumer.pop_slice(&mut interleaved_buf);
+    // Samples qui font avancer la position (ceux du nouveau track après une transition)
+    let mut advanced = read;
+
+    // Underrun : le décodeur n'a pas suivi (le ring est vide avant la fin du fichier)
+    data.buffer_health.record_fill(data.consumer.occupied_len(), data.consumer.capacity().get());
+    let underrun = !decoding_done && read < total_samples;
+    if underrun && !data.in_underrun {
+        data.buffer_health.record_underrun();
+        if let Some(ref app) = data.app_handle {
+            let _ = app.emit("buffer_underrun", data.buffer_health.snapshot());
+        }
+    }
+    data.in_underrun = underrun;
+
+    // === CROSSFADE: mixe le début du track préchargé sur la fin du track courant ===
+    data.maybe_start_crossfade();
+    if data.fade_total_frames > 0 {
+        let old_read = read;
+        read = data.mix_crossfade(&mut interleaved_buf, old_read);
+        let fade_finished = data.fade_done_frames >= data.fade_total_frames
+            || (decoding_done && old_read < total_samples);
+        if fade_finished && data.fade_total_frames > 0 {
+            match data.try_gapless_swap() {
+                // La position repart de ce que le nouveau track a déjà joué pendant le fondu
+                GaplessSwap::Done => advanced = 0,
+                GaplessSwap::Unavailable => data.end_crossfade(),
+                // Fondu maintenu (nouveau track seul à gain 1) jusqu'au prochain callback
+                GaplessSwap::Retry => {}
+            }
+        }
+    } else if decoding_done && read < total_samples
+        && matches!(data.try_gapless_swap(), GaplessSwap::Done)
+    {
+        // === GAPLESS: le track courant est épuisé → complète CE buffer avec le track préchargé ===
+        // (Retry : le read reste incomplet, la transition est retentée au prochain callback)
+        let new_read = data.consumer.pop_slice(&mut interleaved_buf[read..]);
+        read += new_read;
+        advanced = new_read;
+    }
 
     // Debug logging after seek
     if data.first_read_after_seek && read > 0 {
@@ -435,11 +738,33 @@ unsafe extern "C" fn render_callback(
         }
         let rms = (sum_sq / read as f64).sqrt();
         data.rms_energy.store(rms.to_bits(), Ordering::Relaxed);
+        data.spectrum.capture(&interleaved_buf[..read], data.channels_count as usize);
+    }
+
+    // Volume puis étage de sortie, en place (ni l'un ni l'autre ne touche aux samples
+    // à volume 100 % et limiteur désactivé)
+    if volume < 1.0 {
+        for sample in interleaved_buf[..read].iter_mut() {
+            *sample *= volume;
+        }
     }
+    data.output_stage.process(&mut interleaved_buf[..read], data.channels_count as usize);
 
-    // Write to output buffers with volume applied
-    // CoreAudio on macOS typically uses interleaved stereo in a single buffer
-    if num_buffers == 1 && data.channels_count == 2 {
+    // Write to output buffers
+    // (write_silence remet les buffers à zéro octet par octet : valide pour float comme pour entier)
+    if let Some(bits) = data.integer_bits {
+        // Mode entier : format interleaved dans un seul buffer, converti depuis le f32
+        for sample in interleaved_buf[read..].iter_mut() {
+            *sample = 0.0;
+        }
+        let buffer = &mut *buffer_list.mBuffers.as_mut_ptr();
+        let out_bytes = std::slice::from_raw_parts_mut(
+            buffer.mData as *mut u8,
+            buffer.mDataByteSize as usize,
+        );
+        write_integer_samples(&interleaved_buf, out_bytes, bits);
+    } else if num_buffers == 1 && data.channels_count == 2 {
+        // CoreAudio on macOS typically uses interleaved stereo in a single buffer
         // Single interleaved buffer
         let buffer = &mut *buffer_list.mBuffers.as_mut_ptr();
         let out_samples = std::slice::from_raw_parts_mut(
@@ -447,17 +772,8 @@ unsafe extern "C" fn render_callback(
             buffer.mDataByteSize as usize / 4,
         );
 
-        if volume < 1.0 {
-            for (i, sample) in interleaved_buf[..read].iter().enumerate() {
-                if i < out_samples.len() {
-                    out_samples[i] = sample * volume;
-                }
-            }
-        } else {
-            // Bit-perfect bypass: copy samples without modification
-            let copy_len = read.min(out_samples.len());
-            out_samples[..copy_len].copy_from_slice(&interleaved_buf[..copy_len]);
-        }
+        let copy_len = read.min(out_samples.len());
+        out_samples[..copy_len].copy_from_slice(&interleaved_buf[..copy_len]);
         // Fill remaining with silence
         for sample in out_samples[read..].iter_mut() {
             *sample = 0.0;
@@ -475,7 +791,7 @@ unsafe extern "C" fn render_callback(
             for frame in 0..in_number_frames as usize {
                 let idx = frame * data.channels_count as usize + ch;
                 if frame < frames_read && idx < read {
-                    out_samples[frame] = if volume < 1.0 { interleaved_buf[idx] * volume } else { interleaved_buf[idx] };
+                    out_samples[frame] = interleaved_buf[idx];
                 } else {
                     out_samples[frame] = 0.0;
                 }
@@ -485,7 +801,7 @@ unsafe extern "C" fn render_callback(
 
     // Update playback position
     if read > 0 {
-        data.playback_samples += read as u64;
+        data.advance_position(advanced);
         if data.playback_samples > data.duration_samples {
             data.playback_samples = data.duration_samples;
         }
@@ -494,62 +810,35 @@ unsafe extern "C" fn render_callback(
         data.empty_callbacks += 1;
     }
 
+    // Boucle A-B : point B atteint → l'engine fait le seek vers A.
+    // Pas pendant un seek (la position n'est pas encore celle du nouveau point).
+    if data.ab_loop.is_active() && !data.streaming_state.seeking.load(Ordering::Relaxed) {
+        let position_ms = (data.playback_samples as f64 / data.channels_count as f64
+            / data.sample_rate_f64 * 1000.0) as u64;
+        if position_ms >= data.ab_loop.end_ms() {
+            data.ab_loop.request_loop();
+        }
+    }
+
     // Detect end of track
     if data.streaming_state.decoding_complete.load(Ordering::Relaxed)
         && data.empty_callbacks >= EMPTY_CALLBACKS_THRESHOLD
         && !data.end_emitted
     {
-        // === GAPLESS: try to swap to next consumer ===
-        if data.gapless_enabled.load(Ordering::Relaxed) {
-            let mut next_cons_guard = data.next_consumer.lock();
-            let mut next_state_guard = data.next_streaming_state.lock();
-
-            if let (Some(new_consumer), Some(new_state)) = (next_cons_guard.take(), next_state_guard.take()) {
-                println!("[CoreAudioStream] GAPLESS TRANSITION at {:.3}s",
-                    data.playback_samples as f64 / data.channels_count as f64 / data.sample_rate_f64);
-
-                // Swap consumer and streaming state
-                data.consumer = new_consumer;
-                data.streaming_state = new_state;
-
-                // ── Mise à jour du chemin courant ────────────────────────────────
-                // CRITIQUE : current_path doit pointer sur le NOUVEAU fichier dès maintenant.
-                // Sans ça, un seek après transition gapless ferait re-probe l'ANCIEN fichier :
-                // mauvaise durée sur la progress bar + "out-of-range" si on cherche
-                // au-delà de la durée de l'ancien track.
-                *data.current_path.lock() = data.next_path.lock().take();
-
-                // Reset playback tracking for the new track
-                data.playback_samples = 0;
-                data.empty_callbacks = 0;
-                data.end_emitted = false;
-                data.emit_counter = 0;
-                data.duration_seconds = data.streaming_state.info.duration_seconds;
-                data.duration_samples = data.streaming_state.info.total_frames * data.channels_count;
-
-                // Emit gapless transition event to frontend
-                if let Some(ref app) = data.app_handle {
-                    let _ = app.emit("playback_gapless_transition", ());
-                }
-
-                // Drop the guards
-                drop(next_cons_guard);
-                drop(next_state_guard);
-
-                // Don't return 0 — continue to output from new consumer in THIS callback
-                // The next emit cycle will send progress from the new track
-                return 0;
+        if data.ab_loop.is_active() {
+            // B = fin du fichier : on reboucle au lieu de terminer le track
+            data.ab_loop.request_loop();
+        } else {
+            // La transition gapless a déjà été tentée lors du read incomplet :
+            // arriver ici signifie qu'aucun track compatible n'est préchargé.
+            data.end_emitted = true;
+            data.is_playing_global.store(false, Ordering::Relaxed);
+            println!("[CoreAudioStream] Track finished at {:.3}s",
+                data.playback_samples as f64 / data.channels_count as f64 / data.sample_rate_f64);
+            if let Some(ref app) = data.app_handle {
+                let _ = app.emit("playback_ended", ());
             }
         }
-
-        // No gapless next available — normal end
-        data.end_emitted = true;
-        data.is_playing_global.store(false, Ordering::Relaxed);
-        println!("[CoreAudioStream] Track finished at {:.3}s",
-            data.playback_samples as f64 / data.channels_count as f64 / data.sample_rate_f64);
-        if let Some(ref app) = data.app_handle {
-            let _ = app.emit("playback_ended", ());
-        }
     }
 
     // Emit progress (~30 FPS)
@@ -571,8 +860,8 @@ unsafe extern "C" fn render_callback(
         if let Some(ref app) = data.app_handle {
             let rms = f64::from_bits(data.rms_energy.load(Ordering::Relaxed));
             let _ = app.emit("playback_progress", PlaybackProgress {
-                position: clamped_position,
-                duration: data.duration_seconds,
+                position: data.segment.relative_seconds(clamped_position),
+                duration: data.segment.duration_seconds(data.duration_seconds),
                 rms,
             });
         }
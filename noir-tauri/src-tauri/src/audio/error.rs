@@ -50,3 +50,143 @@ impl std::error::Error for AudioBackendError {}
 
 /// Result type alias for audio backend operations
 pub type Result<T> = std::result::Result<T, AudioBackendError>;
+
+/// Error returned by the audio engine Tauri commands
+///
+/// Serialized as `{ "code": "device_unavailable", "message": "..." }` so the frontend
+/// can branch on `code` (e.g. open the device picker) instead of parsing the message.
+/// Codes match the ones emitted with the `playback_error` event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioError {
+    /// Audio engine not created yet (or failed to start)
+    NotInitialized,
+    /// Audio thread exited: commands can no longer be delivered
+    EngineStopped,
+    /// Output device missing, unplugged or not enumerable
+    DeviceUnavailable(String),
+    /// Sample rate not supported by the output device
+    UnsupportedSampleRate(u32),
+    /// File to play does not exist
+    FileNotFound(String),
+    /// No decoder for this codec or container
+    UnsupportedCodec(String),
+    /// Seek target rejected
+    SeekFailed(String),
+    /// Operation needs a loaded track (seek, A-B loop)
+    NoTrackLoaded,
+    /// Network source (SMB) unreachable or missing credentials
+    NetworkUnavailable(String),
+    /// Argument out of range or unknown name
+    InvalidArgument(String),
+    /// Operation not supported by this device or platform
+    NotSupported(String),
+    /// Other backend failure (exclusive mode, sample rate change, stream creation)
+    Backend(String),
+    /// Generic error
+    Other(String),
+}
+
+impl AudioError {
+    /// Stable identifier sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotInitialized => "not_initialized",
+            Self::EngineStopped => "engine_stopped",
+            Self::DeviceUnavailable(_) => "device_unavailable",
+            Self::UnsupportedSampleRate(_) => "unsupported_sample_rate",
+            Self::FileNotFound(_) => "file_not_found",
+            Self::UnsupportedCodec(_) => "unsupported_codec",
+            Self::SeekFailed(_) => "seek_failed",
+            Self::NoTrackLoaded => "no_track_loaded",
+            Self::NetworkUnavailable(_) => "network_unavailable",
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::NotSupported(_) => "not_supported",
+            Self::Backend(_) => "backend_error",
+            Self::Other(_) => "error",
+        }
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInitialized => write!(f, "Audio engine not initialized"),
+            Self::EngineStopped => write!(f, "Audio engine stopped"),
+            Self::DeviceUnavailable(e) => write!(f, "Audio device unavailable: {}", e),
+            Self::UnsupportedSampleRate(rate) => write!(f, "Unsupported sample rate: {} Hz", rate),
+            Self::FileNotFound(path) => write!(f, "File not found: {}", path),
+            Self::UnsupportedCodec(e) => write!(f, "{}", e),
+            Self::SeekFailed(e) => write!(f, "Seek failed: {}", e),
+            Self::NoTrackLoaded => write!(f, "No track loaded"),
+            Self::NetworkUnavailable(e) => write!(f, "{}", e),
+            Self::InvalidArgument(e) => write!(f, "{}", e),
+            Self::NotSupported(e) => write!(f, "Not supported: {}", e),
+            Self::Backend(e) => write!(f, "{}", e),
+            Self::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl serde::Serialize for AudioError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AudioError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<AudioBackendError> for AudioError {
+    fn from(e: AudioBackendError) -> Self {
+        match e {
+            AudioBackendError::DeviceNotFound(_) | AudioBackendError::DeviceEnumerationFailed(_) => {
+                Self::DeviceUnavailable(e.to_string())
+            }
+            AudioBackendError::UnsupportedSampleRate(rate) => Self::UnsupportedSampleRate(rate),
+            AudioBackendError::NotSupported(op) => Self::NotSupported(op),
+            other => Self::Backend(other.to_string()),
+        }
+    }
+}
+
+/// Errors still reported as plain strings (decoder, network helpers)
+impl From<String> for AudioError {
+    fn from(e: String) -> Self {
+        if e.starts_with(crate::audio_decoder::UNSUPPORTED_CODEC_ERROR) {
+            Self::UnsupportedCodec(e)
+        } else {
+            Self::Other(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_error_serializes_code_and_message() {
+        let json = serde_json::to_value(AudioError::FileNotFound("/music/a.flac".to_string())).unwrap();
+        assert_eq!(json["code"], "file_not_found");
+        assert_eq!(json["message"], "File not found: /music/a.flac");
+        assert_eq!(serde_json::to_value(AudioError::NotInitialized).unwrap()["code"], "not_initialized");
+    }
+
+    #[test]
+    fn test_backend_errors_map_to_audio_errors() {
+        assert_eq!(
+            AudioError::from(AudioBackendError::DeviceNotFound("DAC".to_string())).code(),
+            "device_unavailable"
+        );
+        assert_eq!(AudioError::from(AudioBackendError::UnsupportedSampleRate(705600)), AudioError::UnsupportedSampleRate(705600));
+        assert_eq!(
+            AudioError::from(AudioBackendError::ExclusiveModeFailed("locked by PID 42".to_string())).to_string(),
+            "Exclusive mode failed: locked by PID 42"
+        );
+        let decoder_error = format!("{}: Opus", crate::audio_decoder::UNSUPPORTED_CODEC_ERROR);
+        assert_eq!(AudioError::from(decoder_error).code(), "unsupported_codec");
+    }
+}
@@ -25,8 +25,19 @@
 //! │  CoreAudio (macOS)           │
 //! │  - coreaudio_backend.rs      │
 //! │  - coreaudio_stream.rs       │
+//! ├──────────────────────────────┤
+//! │  WASAPI (Windows)            │
+//! │  - wasapi_backend.rs         │
+//! │  - (future) wasapi_stream.rs │
+//! ├──────────────────────────────┤
+//! │  ALSA (Linux)                │
+//! │  - alsa_backend.rs           │
 //! └──────────────────────────────┘
 //! ```
+//!
+//! Only macOS has an output stream: `create_audio_stream` is compiled for macOS only.
+//! The WASAPI and ALSA backends are enumeration-only (devices, rates, default
+//! device, status) until their streams exist.
 
 pub mod backend;
 pub mod error;
@@ -39,13 +50,18 @@ pub mod coreaudio_backend;
 #[cfg(target_os = "macos")]
 pub mod coreaudio_stream;
 
-// Future: Windows WASAPI backend
+#[cfg(target_os = "windows")]
+pub mod wasapi_backend;
+
+#[cfg(target_os = "linux")]
+pub mod alsa_backend;
+
+// Future: Windows WASAPI stream
 // #[cfg(target_os = "windows")]
-// pub mod wasapi_backend;
 // pub mod wasapi_stream;
 
 // Re-exports for convenience
 pub use backend::{create_backend, AudioBackend};
-pub use error::{AudioBackendError, Result};
+pub use error::{AudioBackendError, AudioError, Result};
 pub use types::*;
 pub use stream::{AudioOutputStream, AudioStreamConfig, create_audio_stream};
@@ -50,16 +50,39 @@ pub trait AudioOutputStream: Send {
 pub struct AudioStreamConfig {
     pub sample_rate: u32,
     pub channels: u16,
+    /// Integer output bit depth (16/24/32). None = 32-bit float output
+    pub integer_bits: Option<u16>,
 }
 
 impl AudioStreamConfig {
     pub fn new(sample_rate: u32, channels: u16) -> Self {
-        Self { sample_rate, channels }
+        Self { sample_rate, channels, integer_bits: None }
     }
 
     pub fn stereo(sample_rate: u32) -> Self {
         Self::new(sample_rate, 2)
     }
+
+    /// Emit signed integer samples at the given bit depth instead of float
+    pub fn with_integer_bits(mut self, bits: Option<u16>) -> Self {
+        self.integer_bits = bits;
+        self
+    }
+}
+
+/// Converts f32 samples to packed little-endian signed integers (16, 24 or 32 bits)
+///
+/// `out` must hold `samples.len() * bits / 8` bytes. Samples are clamped to [-1.0, 1.0].
+pub fn write_integer_samples(samples: &[f32], out: &mut [u8], bits: u16) {
+    let bytes_per_sample = bits as usize / 8;
+    for (sample, dst) in samples.iter().zip(out.chunks_exact_mut(bytes_per_sample)) {
+        let s = sample.clamp(-1.0, 1.0) as f64;
+        match bits {
+            16 => dst.copy_from_slice(&((s * i16::MAX as f64).round() as i16).to_le_bytes()),
+            24 => dst.copy_from_slice(&((s * 8_388_607.0).round() as i32).to_le_bytes()[..3]),
+            _ => dst.copy_from_slice(&((s * i32::MAX as f64).round() as i32).to_le_bytes()),
+        }
+    }
 }
 
 /// Factory function to create the platform-appropriate audio stream
@@ -88,6 +111,19 @@ pub fn create_audio_stream(
     // → empêche le seek post-transition de re-probe l'ancien fichier
     current_path: Arc<Mutex<Option<String>>>,
     next_path: Arc<Mutex<Option<String>>>,
+    // Crossfade : durée en ms (0 = désactivé) + flag "fondu en cours" lu par l'engine
+    crossfade_ms: Arc<std::sync::atomic::AtomicU64>,
+    crossfade_active: Arc<AtomicBool>,
+    // Boucle A-B : le callback signale le passage du point B
+    ab_loop: Arc<crate::audio_engine::AbLoopState>,
+    // Piste CUE : bornes du segment, la progression est émise relativement à son début
+    segment: Arc<crate::audio_engine::SegmentState>,
+    // Spectre : capture des samples pour le visualiseur
+    spectrum: Arc<crate::spectrum::SpectrumTap>,
+    // Étage de sortie (canaux, balance, largeur, limiteur) appliqué après EQ et volume
+    output_stage: Arc<crate::output_stage::OutputStage>,
+    // Remplissage du ring et underruns, relevés à chaque callback
+    buffer_health: Arc<crate::audio_engine::BufferHealthState>,
 ) -> Result<Box<dyn AudioOutputStream>, String> {
     use super::coreaudio_stream::CoreAudioStream;
     CoreAudioStream::new(
@@ -107,6 +143,13 @@ pub fn create_audio_stream(
         rms_energy,
         current_path,
         next_path,
+        crossfade_ms,
+        crossfade_active,
+        ab_loop,
+        segment,
+        spectrum,
+        output_stage,
+        buffer_health,
     ).map(|s| Box::new(s) as Box<dyn AudioOutputStream>)
 }
 
@@ -116,3 +159,25 @@ pub fn create_audio_stream(
 //     use super::wasapi_stream::WasapiStream;
 //     WasapiStream::new(...).map(|s| Box::new(s) as Box<dyn AudioOutputStream>)
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_integer_samples() {
+        let samples = [0.0f32, 1.0, -1.0, 2.0];
+
+        let mut out16 = [0u8; 8];
+        write_integer_samples(&samples, &mut out16, 16);
+        assert_eq!(out16, [0, 0, 0xFF, 0x7F, 0x01, 0x80, 0xFF, 0x7F]);
+
+        let mut out24 = [0u8; 12];
+        write_integer_samples(&samples, &mut out24, 24);
+        assert_eq!(&out24[3..9], &[0xFF, 0xFF, 0x7F, 0x01, 0x00, 0x80]);
+
+        let mut out32 = [0u8; 16];
+        write_integer_samples(&samples[..2], &mut out32, 32);
+        assert_eq!(i32::from_le_bytes(out32[4..8].try_into().unwrap()), i32::MAX);
+    }
+}
@@ -20,6 +20,10 @@ pub struct DeviceInfo {
     pub supported_sample_rates: Vec<u32>,
     /// Current device sample rate
     pub current_sample_rate: u32,
+    /// Signed integer bit depths the device accepts (e.g., [16, 24, 32])
+    /// Backends that cannot query stream formats report DEFAULT_BIT_DEPTHS;
+    /// empty on CoreAudio means the device only offers float formats
+    pub supported_bit_depths: Vec<u8>,
     /// Maximum number of channels
     pub max_channels: u16,
     /// Whether exclusive mode is supported
@@ -27,6 +31,8 @@ pub struct DeviceInfo {
     /// Raw CoreAudio transport type (kAudioDevicePropertyTransportType)
     /// 0x61697270 = AirPlay, 0x626C746E = Built-in, 0x75736220 = USB, 0x626C7565 = Bluetooth
     pub transport_type: u32,
+    /// Human-readable transport ("USB", "Built-in", "Bluetooth", "HDMI"...), None if unknown
+    pub transport: Option<String>,
     /// True if the device is an AirPlay receiver (transport_type == 0x61697270)
     /// AirPlay devices do not support hog mode and introduce ~2s latency
     pub is_airplay: bool,
@@ -39,6 +45,10 @@ impl DeviceInfo {
     }
 }
 
+/// Bit depths assumed when the backend cannot query the device's stream formats
+/// (every PCM DAC accepts 16-bit; 24 and 32 are standard on anything hi-res)
+pub const DEFAULT_BIT_DEPTHS: [u8; 3] = [16, 24, 32];
+
 /// Standard audiophile sample rates
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SampleRate(pub u32);
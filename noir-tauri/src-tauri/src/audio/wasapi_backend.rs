@@ -0,0 +1,654 @@
+//! WASAPI backend for Windows
+//!
+//! Uses the `windows` crate for direct Core Audio (MMDevice + IAudioClient) access to:
+//! - Enumerate render endpoints (IMMDeviceEnumerator)
+//! - Probe supported exclusive-mode formats (IAudioClient::IsFormatSupported)
+//! - Follow the system default endpoint unless one is manually selected
+//!
+//! Differences with CoreAudio:
+//! - There is no device-wide "nominal sample rate" to change. In exclusive mode the
+//!   stream format IS the hardware rate, so `set_sample_rate` records the rate that
+//!   the exclusive (event-driven) stream will open with. In shared mode the Windows
+//!   mixer format is imposed and only that rate is accepted.
+//! - Exclusive mode is per-stream, not a device lock like Hog Mode: nothing to
+//!   release on the device itself, the endpoint is freed when the stream closes.
+//! - Endpoint IDs are strings. COM objects are never stored (they are not Send),
+//!   only their IDs; interfaces are re-acquired on demand.
+//! - No IMMNotificationClient is registered: `set_device_event_callback` returns
+//!   `NotSupported`.
+//!
+//! This file is only compiled on Windows via #[cfg(target_os = "windows")]
+
+use std::collections::HashMap;
+
+use windows::core::{HSTRING, PWSTR};
+use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+use windows::Win32::Foundation::{RPC_E_CHANGED_MODE, S_OK};
+use windows::Win32::Media::Audio::{
+    eConsole, eRender, IAudioClient, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator,
+    AUDCLNT_SHAREMODE_EXCLUSIVE, DEVICE_STATE_ACTIVE, WAVEFORMATEX, WAVEFORMATEXTENSIBLE,
+    WAVEFORMATEXTENSIBLE_0,
+};
+use windows::Win32::Media::KernelStreaming::{
+    KSAUDIO_SPEAKER_STEREO, KSDATAFORMAT_SUBTYPE_PCM, WAVE_FORMAT_EXTENSIBLE,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+};
+
+use crate::audio::backend::AudioBackend;
+use crate::audio::error::{AudioBackendError, Result};
+use crate::audio::types::*;
+
+/// Bit depths probed for exclusive-mode support (container bits, valid bits)
+const PROBE_FORMATS: [(u16, u16); 3] = [(32, 24), (24, 24), (16, 16)];
+
+/// WASAPI backend
+///
+/// IMPORTANT: Like the CoreAudio backend, this follows the system default output
+/// endpoint. When the user switches the default device in Windows Sound settings
+/// (or plugs in a DAC), the next `prepare_for_streaming` picks it up.
+pub struct WasapiBackend {
+    /// Manually selected endpoint ID (None = follow system default)
+    manual_device_id: Option<String>,
+    /// Cached device info map (endpoint ID -> DeviceInfo)
+    device_cache: HashMap<String, DeviceInfo>,
+    /// Current exclusive mode state
+    exclusive_mode: ExclusiveMode,
+    /// Rate the exclusive stream will open with (set by set_sample_rate)
+    requested_sample_rate: Option<u32>,
+    /// Last known endpoint ID (to detect changes)
+    last_device_id: String,
+    /// Whether the device was locked by exclusive mode (to prevent device switching)
+    exclusive_locked_device: bool,
+}
+
+impl WasapiBackend {
+    /// Create a new WASAPI backend
+    pub fn new() -> Result<Self> {
+        println!("[WASAPI] Initializing backend...");
+
+        let default_device = Self::get_default_output_device()?;
+
+        let mut backend = Self {
+            manual_device_id: None, // Follow system default
+            device_cache: HashMap::new(),
+            exclusive_mode: ExclusiveMode::Shared,
+            requested_sample_rate: None,
+            last_device_id: default_device.clone(),
+            exclusive_locked_device: false,
+        };
+
+        // Cache device info on startup
+        backend.refresh_device_cache()?;
+
+        let device_name = backend
+            .device_cache
+            .get(&default_device)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        println!("[WASAPI] Backend initialized. Default device: {}", device_name);
+
+        Ok(backend)
+    }
+
+    /// Get the current active endpoint ID
+    /// If manual_device_id is set, use that. Otherwise, follow system default.
+    fn get_active_device_id(&self) -> Result<String> {
+        match &self.manual_device_id {
+            Some(id) => Ok(id.clone()),
+            None => Self::get_default_output_device(),
+        }
+    }
+
+    /// Endpoint ID the stream should open (None = system default)
+    ///
+    /// `AudioBackend::get_device_id` returns a u32 (AudioObjectID on macOS), which
+    /// cannot carry a WASAPI endpoint ID: the stream uses this instead.
+    pub fn selected_endpoint_id(&self) -> Option<String> {
+        self.manual_device_id.clone()
+    }
+
+    /// Check if the active endpoint has changed since last check
+    fn check_device_change(&mut self) -> Option<String> {
+        let current_id = self.get_active_device_id().ok()?;
+        if current_id == self.last_device_id {
+            return None;
+        }
+
+        let old_id = std::mem::replace(&mut self.last_device_id, current_id.clone());
+
+        // Refresh cache to include new device
+        let _ = self.refresh_device_cache();
+
+        // Reset manual device if it no longer exists
+        if let Some(manual_id) = &self.manual_device_id {
+            if !self.device_cache.contains_key(manual_id) {
+                println!("[WASAPI] Manual device {} no longer exists, resetting to default", manual_id);
+                self.manual_device_id = None;
+                self.exclusive_locked_device = false;
+            }
+        }
+
+        println!("[WASAPI] Device changed: {} -> {}", old_id, current_id);
+        Some(current_id)
+    }
+
+    // === Private COM Functions ===
+
+    /// Initialize COM on the calling thread and create a device enumerator
+    fn enumerator() -> Result<IMMDeviceEnumerator> {
+        unsafe {
+            // S_FALSE (already initialized) is fine; RPC_E_CHANGED_MODE means the thread
+            // is already STA (e.g. the Tauri main thread), which MMDevice also supports
+            let hr = CoInitializeEx(None, COINIT_MULTITHREADED);
+            if hr.is_err() && hr != RPC_E_CHANGED_MODE {
+                return Err(Self::system_error(hr.0, "CoInitializeEx failed"));
+            }
+
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| Self::system_error(e.code().0, &e.message()))
+        }
+    }
+
+    fn system_error(code: i32, message: &str) -> AudioBackendError {
+        AudioBackendError::SystemError { code, message: message.to_string() }
+    }
+
+    /// Get the system default render endpoint ID
+    fn get_default_output_device() -> Result<String> {
+        let enumerator = Self::enumerator()?;
+        unsafe {
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(|e| AudioBackendError::DeviceNotFound(format!("No default output device: {}", e)))?;
+            Self::device_id_of(&device)
+        }
+    }
+
+    /// Get all active render endpoints
+    fn get_all_output_devices() -> Result<Vec<IMMDevice>> {
+        let enumerator = Self::enumerator()?;
+        unsafe {
+            let collection = enumerator
+                .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+                .map_err(|e| AudioBackendError::DeviceEnumerationFailed(e.to_string()))?;
+            let count = collection
+                .GetCount()
+                .map_err(|e| AudioBackendError::DeviceEnumerationFailed(e.to_string()))?;
+
+            Ok((0..count).filter_map(|i| collection.Item(i).ok()).collect())
+        }
+    }
+
+    fn get_device(device_id: &str) -> Result<IMMDevice> {
+        let enumerator = Self::enumerator()?;
+        unsafe {
+            enumerator
+                .GetDevice(&HSTRING::from(device_id))
+                .map_err(|_| AudioBackendError::DeviceNotFound(device_id.to_string()))
+        }
+    }
+
+    fn device_id_of(device: &IMMDevice) -> Result<String> {
+        unsafe {
+            let raw: PWSTR = device
+                .GetId()
+                .map_err(|e| Self::system_error(e.code().0, "IMMDevice::GetId failed"))?;
+            let id = raw.to_string().unwrap_or_default();
+            CoTaskMemFree(Some(raw.0 as *const _));
+            Ok(id)
+        }
+    }
+
+    fn get_device_name(device: &IMMDevice) -> Result<String> {
+        unsafe {
+            let store = device
+                .OpenPropertyStore(STGM_READ)
+                .map_err(|e| Self::system_error(e.code().0, "OpenPropertyStore failed"))?;
+            let value = store
+                .GetValue(&PKEY_Device_FriendlyName)
+                .map_err(|e| Self::system_error(e.code().0, "PKEY_Device_FriendlyName missing"))?;
+            Ok(value.to_string())
+        }
+    }
+
+    fn audio_client(device: &IMMDevice) -> Result<IAudioClient> {
+        unsafe {
+            device
+                .Activate::<IAudioClient>(CLSCTX_ALL, None)
+                .map_err(|e| Self::system_error(e.code().0, "IMMDevice::Activate(IAudioClient) failed"))
+        }
+    }
+
+    /// Shared-mode mixer format: (sample rate, channels)
+    fn get_mix_format(client: &IAudioClient) -> Result<(u32, u16)> {
+        unsafe {
+            let format_ptr = client
+                .GetMixFormat()
+                .map_err(|e| Self::system_error(e.code().0, "GetMixFormat failed"))?;
+            // WAVEFORMATEX is packed: copy it out before reading fields
+            let format: WAVEFORMATEX = std::ptr::read_unaligned(format_ptr);
+            CoTaskMemFree(Some(format_ptr as *const _));
+            Ok((format.nSamplesPerSec, format.nChannels))
+        }
+    }
+
+    /// Stereo PCM format used to probe exclusive-mode support
+    fn pcm_format(rate: u32, container_bits: u16, valid_bits: u16) -> WAVEFORMATEXTENSIBLE {
+        let channels: u16 = 2;
+        let block_align = channels * container_bits / 8;
+        WAVEFORMATEXTENSIBLE {
+            Format: WAVEFORMATEX {
+                wFormatTag: WAVE_FORMAT_EXTENSIBLE as u16,
+                nChannels: channels,
+                nSamplesPerSec: rate,
+                nAvgBytesPerSec: rate * block_align as u32,
+                nBlockAlign: block_align,
+                wBitsPerSample: container_bits,
+                cbSize: (std::mem::size_of::<WAVEFORMATEXTENSIBLE>() - std::mem::size_of::<WAVEFORMATEX>()) as u16,
+            },
+            Samples: WAVEFORMATEXTENSIBLE_0 { wValidBitsPerSample: valid_bits },
+            dwChannelMask: KSAUDIO_SPEAKER_STEREO,
+            SubFormat: KSDATAFORMAT_SUBTYPE_PCM,
+        }
+    }
+
+    /// Whether the endpoint accepts this rate in exclusive mode (any probed bit depth)
+    fn is_exclusive_rate_supported(client: &IAudioClient, rate: u32) -> bool {
+        PROBE_FORMATS.iter().any(|&(container_bits, valid_bits)| {
+            let format = Self::pcm_format(rate, container_bits, valid_bits);
+            let hr = unsafe {
+                client.IsFormatSupported(
+                    AUDCLNT_SHAREMODE_EXCLUSIVE,
+                    &format as *const WAVEFORMATEXTENSIBLE as *const WAVEFORMATEX,
+                    None,
+                )
+            };
+            hr == S_OK
+        })
+    }
+
+    /// Get the exclusive-mode sample rates of an endpoint
+    /// Empty if exclusive mode is disabled for the device in Windows Sound settings
+    fn get_supported_sample_rates(client: &IAudioClient) -> Vec<u32> {
+        SampleRate::STANDARD_RATES
+            .iter()
+            .copied()
+            .filter(|&rate| Self::is_exclusive_rate_supported(client, rate))
+            .collect()
+    }
+
+    /// Find the best supported sample rate for a given source rate
+    /// Prioritizes: exact match > higher rate > highest available
+    fn find_best_supported_rate(source_rate: u32, supported_rates: &[u32]) -> Option<u32> {
+        if supported_rates.contains(&source_rate) {
+            return Some(source_rate);
+        }
+        supported_rates
+            .iter()
+            .copied()
+            .filter(|&r| r >= source_rate)
+            .min()
+            .or_else(|| supported_rates.iter().copied().max())
+    }
+
+    /// Refresh the device cache
+    fn refresh_device_cache(&mut self) -> Result<()> {
+        let devices = Self::get_all_output_devices()?;
+        let default_id = Self::get_default_output_device().ok();
+
+        self.device_cache.clear();
+
+        for device in devices {
+            let Ok(id) = Self::device_id_of(&device) else { continue };
+            let name = Self::get_device_name(&device).unwrap_or_else(|_| "Unknown".to_string());
+
+            let (mix_rate, channels, exclusive_rates) = match Self::audio_client(&device) {
+                Ok(client) => {
+                    let (rate, channels) = Self::get_mix_format(&client).unwrap_or((48000, 2));
+                    (rate, channels, Self::get_supported_sample_rates(&client))
+                }
+                Err(_) => (48000, 2, Vec::new()),
+            };
+
+            let supports_exclusive = !exclusive_rates.is_empty();
+            let mut supported_sample_rates = exclusive_rates;
+            if !supported_sample_rates.contains(&mix_rate) {
+                supported_sample_rates.push(mix_rate);
+                supported_sample_rates.sort_unstable();
+            }
+
+            let info = DeviceInfo {
+                id: id.clone(),
+                name,
+                manufacturer: None,
+                is_default: Some(&id) == default_id.as_ref(),
+                supported_sample_rates,
+                current_sample_rate: mix_rate,
+                // Formats are only probed per rate, not reported per depth: assume the usual set
+                supported_bit_depths: DEFAULT_BIT_DEPTHS.to_vec(),
+                max_channels: channels,
+                supports_exclusive,
+                transport_type: 0, // CoreAudio-specific, no WASAPI equivalent
+                transport: None,
+                is_airplay: false,
+            };
+
+            self.device_cache.insert(id, info);
+        }
+
+        Ok(())
+    }
+
+    fn cached_device(&self, device_id: &str) -> Result<&DeviceInfo> {
+        self.device_cache
+            .get(device_id)
+            .ok_or_else(|| AudioBackendError::DeviceNotFound(device_id.to_string()))
+    }
+}
+
+impl AudioBackend for WasapiBackend {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        Ok(self.device_cache.values().cloned().collect())
+    }
+
+    fn refresh_devices(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.refresh_device_cache()?;
+        Ok(self.device_cache.values().cloned().collect())
+    }
+
+    fn current_device(&self) -> Result<DeviceInfo> {
+        // Always get the CURRENT default device (follows system changes)
+        let device_id = match self.get_active_device_id() {
+            Ok(id) => id,
+            Err(e) => {
+                println!("[WASAPI] Failed to get active device: {}", e);
+                if let Some(info) = self.device_cache.values().next() {
+                    return Ok(info.clone());
+                }
+                return Err(e);
+            }
+        };
+        self.cached_device(&device_id).cloned()
+    }
+
+    fn set_output_device(&mut self, device_id: &str) -> Result<()> {
+        // Verify device exists
+        if !self.device_cache.contains_key(device_id) {
+            self.refresh_device_cache()?;
+            self.cached_device(device_id)?;
+        }
+
+        // Exclusive mode is per-stream: the new stream re-opens exclusively on the new
+        // device, unless it has exclusive mode disabled in Windows Sound settings
+        if self.exclusive_mode == ExclusiveMode::Exclusive && !self.cached_device(device_id)?.supports_exclusive {
+            self.exclusive_mode = ExclusiveMode::Shared;
+            println!("[WASAPI] Exclusive mode auto-disabled: not allowed on {}", device_id);
+        }
+
+        // Set manual device (stops following system default)
+        // Unlike CoreAudio, the Windows default endpoint is left untouched:
+        // there is no public API to change it.
+        self.manual_device_id = Some(device_id.to_string());
+        self.last_device_id = device_id.to_string();
+        self.exclusive_locked_device = false;
+        self.requested_sample_rate = None;
+
+        println!("[WASAPI] Switched to device {}", device_id);
+        Ok(())
+    }
+
+    fn follow_system_default(&mut self) {
+        if self.exclusive_locked_device {
+            return;
+        }
+        self.manual_device_id = None;
+        println!("[WASAPI] Following system default device");
+    }
+
+    fn get_device_info(&self, device_id: &str) -> Result<DeviceInfo> {
+        self.cached_device(device_id).cloned()
+    }
+
+    fn current_sample_rate(&self) -> Result<u32> {
+        let device_id = self.get_active_device_id()?;
+
+        if self.exclusive_mode == ExclusiveMode::Exclusive {
+            if let Some(rate) = self.requested_sample_rate {
+                return Ok(rate);
+            }
+        }
+
+        let client = Self::audio_client(&Self::get_device(&device_id)?)?;
+        Self::get_mix_format(&client).map(|(rate, _)| rate)
+    }
+
+    fn set_sample_rate(&mut self, rate: u32) -> Result<()> {
+        let device_id = self.get_active_device_id()?;
+        let client = Self::audio_client(&Self::get_device(&device_id)?)?;
+
+        if self.exclusive_mode == ExclusiveMode::Shared {
+            // Shared mode: the Windows mixer format is imposed
+            let (mix_rate, _) = Self::get_mix_format(&client)?;
+            if rate != mix_rate {
+                return Err(AudioBackendError::SampleRateChangeFailed {
+                    requested: rate,
+                    reason: format!("shared mode is fixed to the Windows mixer rate ({} Hz)", mix_rate),
+                });
+            }
+        } else if !Self::is_exclusive_rate_supported(&client, rate) {
+            return Err(AudioBackendError::UnsupportedSampleRate(rate));
+        }
+
+        // Applied when the exclusive stream is opened (the stream format sets the DAC rate)
+        self.requested_sample_rate = Some(rate);
+        if let Some(info) = self.device_cache.get_mut(&device_id) {
+            info.current_sample_rate = rate;
+        }
+
+        Ok(())
+    }
+
+    fn is_sample_rate_supported(&self, rate: u32) -> bool {
+        self.get_active_device_id()
+            .ok()
+            .and_then(|id| self.device_cache.get(&id))
+            .map(|info| info.supports_sample_rate(rate))
+            .unwrap_or(false)
+    }
+
+    fn supported_sample_rates(&self) -> Result<Vec<u32>> {
+        let device_id = self.get_active_device_id()?;
+        self.cached_device(&device_id).map(|info| info.supported_sample_rates.clone())
+    }
+
+    fn integer_bit_depth(&self) -> Option<u16> {
+        // No WASAPI stream yet: output stays 32-bit float
+        None
+    }
+
+    fn exclusive_mode(&self) -> ExclusiveMode {
+        self.exclusive_mode
+    }
+
+    fn set_exclusive_mode(&mut self, mode: ExclusiveMode) -> Result<()> {
+        let device_id = self.get_active_device_id()?;
+
+        if mode == ExclusiveMode::Exclusive {
+            let supports_exclusive = self.device_cache
+                .get(&device_id)
+                .map(|info| info.supports_exclusive)
+                .unwrap_or(false);
+            if !supports_exclusive {
+                return Err(AudioBackendError::ExclusiveModeFailed(
+                    "Exclusive mode is not allowed on this device (Windows Sound settings)".to_string(),
+                ));
+            }
+        }
+
+        if mode == self.exclusive_mode {
+            return Ok(());
+        }
+
+        match mode {
+            ExclusiveMode::Exclusive => {
+                // Lock on this device so a default-device change doesn't steal the stream
+                self.manual_device_id = Some(device_id);
+                self.exclusive_locked_device = true;
+            }
+            ExclusiveMode::Shared => {
+                // Release the lock if it was set by exclusive mode
+                if self.exclusive_locked_device {
+                    self.manual_device_id = None;
+                    self.exclusive_locked_device = false;
+                }
+                self.requested_sample_rate = None;
+            }
+        }
+
+        self.exclusive_mode = mode;
+        Ok(())
+    }
+
+    fn hog_mode_status(&self) -> Result<HogModeStatus> {
+        let device_id = self.get_active_device_id()?;
+        let device_name = self.device_cache
+            .get(&device_id)
+            .map(|info| info.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let enabled = self.exclusive_mode == ExclusiveMode::Exclusive;
+
+        // WASAPI doesn't expose which process holds an endpoint exclusively
+        let message = if enabled {
+            format!("Exclusive mode active on {}", device_name)
+        } else {
+            "Shared mode".to_string()
+        };
+
+        Ok(HogModeStatus {
+            enabled,
+            device_name,
+            device_id,
+            owner_pid: if enabled { std::process::id() as i32 } else { -1 },
+            owned_by_us: enabled,
+            message,
+        })
+    }
+
+    fn set_device_event_callback(&mut self, _callback: Option<DeviceEventCallback>) -> Result<()> {
+        Err(AudioBackendError::NotSupported(
+            "WASAPI endpoint change notifications".to_string(),
+        ))
+    }
+
+    fn get_device_id(&self) -> Option<u32> {
+        // Endpoint IDs are strings: the stream reads selected_endpoint_id() instead
+        None
+    }
+
+    fn prepare_for_streaming(&mut self, config: &StreamConfig) -> Result<u32> {
+        // Check if device has changed (user switched the default output)
+        self.check_device_change();
+
+        // Refresh cache to get latest device info
+        let _ = self.refresh_device_cache();
+
+        let device_id = self.get_active_device_id()?;
+        let info = self.cached_device(&device_id)?.clone();
+
+        if self.exclusive_mode == ExclusiveMode::Shared {
+            // The stream resamples to the mixer rate
+            println!(
+                "[WASAPI] Shared mode on {} — using mixer rate {}Hz",
+                info.name, info.current_sample_rate
+            );
+            return Ok(info.current_sample_rate);
+        }
+
+        if !info.supports_exclusive {
+            return Err(AudioBackendError::ExclusiveModeFailed(format!(
+                "Exclusive mode is not allowed on {}",
+                info.name
+            )));
+        }
+
+        println!(
+            "[WASAPI] Preparing exclusive (event-driven) stream at {} Hz on {}...",
+            config.sample_rate, info.name
+        );
+
+        let target_rate = Self::find_best_supported_rate(config.sample_rate, &info.supported_sample_rates)
+            .ok_or(AudioBackendError::UnsupportedSampleRate(config.sample_rate))?;
+        self.set_sample_rate(target_rate)?;
+
+        Ok(target_rate)
+    }
+
+    fn restore_sample_rate(&mut self) -> Result<()> {
+        // The exclusive stream's rate only lasts as long as the stream: once it is
+        // closed, Windows is back on the mixer format. Forget the requested rate.
+        self.requested_sample_rate = None;
+        Ok(())
+    }
+
+    fn release(&mut self) -> Result<()> {
+        println!("[WASAPI] Releasing resources...");
+
+        // Exclusive access ends with the stream; only the backend state is reset
+        self.exclusive_mode = ExclusiveMode::Shared;
+        self.requested_sample_rate = None;
+
+        if self.exclusive_locked_device {
+            self.manual_device_id = None;
+            self.exclusive_locked_device = false;
+        }
+
+        println!("[WASAPI] Resources released");
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "WASAPI"
+    }
+
+    fn system_default_device_id(&self) -> Option<String> {
+        Self::get_default_output_device().ok()
+    }
+}
+
+impl Drop for WasapiBackend {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            eprintln!("[WASAPI] Error during drop cleanup: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_creation() {
+        let backend = WasapiBackend::new();
+        assert!(backend.is_ok(), "Failed to create WASAPI backend");
+    }
+
+    #[test]
+    fn test_device_enumeration() {
+        let backend = WasapiBackend::new().unwrap();
+        let devices = backend.list_devices().unwrap();
+        assert!(!devices.is_empty(), "No audio devices found");
+    }
+
+    #[test]
+    fn test_find_best_supported_rate() {
+        let rates = [44100, 48000, 96000];
+        assert_eq!(WasapiBackend::find_best_supported_rate(48000, &rates), Some(48000));
+        assert_eq!(WasapiBackend::find_best_supported_rate(88200, &rates), Some(96000));
+        assert_eq!(WasapiBackend::find_best_supported_rate(192000, &rates), Some(96000));
+        assert_eq!(WasapiBackend::find_best_supported_rate(44100, &[]), None);
+    }
+}
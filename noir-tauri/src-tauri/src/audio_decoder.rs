@@ -4,24 +4,31 @@
 // Supporte le RESAMPLING : conversion de sample rate via rubato
 // Architecture : [Thread Décodeur] ←→ [Resampler?] → [RingBuffer] → [Callback cpal]
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
 
 use crossbeam_channel::{bounded, Receiver, Sender};
 use ringbuf::{HeapRb, HeapCons, HeapProd};
 use ringbuf::traits::{Consumer, Producer, Split};
-use symphonia::core::audio::{AudioBufferRef, Signal};
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::{FormatOptions, SeekMode, SeekTo};
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, Signal};
+use symphonia::core::codecs::{CodecType, DecoderOptions, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
+use symphonia::core::sample::Sample;
 use symphonia::core::units::Time;
 
+use crate::dsd::DsdFormatReader;
+use crate::wavpack::WavPackFormatReader;
 use crate::resampler::AudioResampler;
 
 /// Taille du RingBuffer en secondes
@@ -33,10 +40,53 @@ const RING_BUFFER_SECONDS: f64 = 5.0;
 /// Avec 5s de buffer, 10% = 500ms de préchargement avant lecture
 const PRE_ROLL_PERCENT: f64 = 0.10; // 10% = 500ms pour un buffer de 5s
 
+/// Bornes de la taille du RingBuffer configurable (secondes)
+pub const MIN_BUFFER_SECONDS: f64 = 1.0;
+pub const MAX_BUFFER_SECONDS: f64 = 30.0;
+
+/// Bornes du pré-remplissage configurable (% du RingBuffer)
+pub const MIN_PRE_ROLL_PERCENT: f64 = 5.0;
+pub const MAX_PRE_ROLL_PERCENT: f64 = 50.0;
+
+/// Taille du RingBuffer et pré-remplissage avant lecture, fixés à la création du stream.
+/// Un NAS lent gagne à un buffer plus grand (moins d'underruns, démarrage plus lent)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferConfig {
+    pub seconds: f64,
+    /// Fraction du RingBuffer (0.05–0.50)
+    pub pre_roll: f64,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self { seconds: RING_BUFFER_SECONDS, pre_roll: PRE_ROLL_PERCENT }
+    }
+}
+
+impl BufferConfig {
+    /// `preroll_percent` en pourcents (10.0 = 10 %)
+    pub fn new(seconds: f64, preroll_percent: f64) -> Result<Self, String> {
+        if !(MIN_BUFFER_SECONDS..=MAX_BUFFER_SECONDS).contains(&seconds) {
+            return Err(format!("Buffer size must be between {} and {} seconds", MIN_BUFFER_SECONDS, MAX_BUFFER_SECONDS));
+        }
+        if !(MIN_PRE_ROLL_PERCENT..=MAX_PRE_ROLL_PERCENT).contains(&preroll_percent) {
+            return Err(format!("Pre-roll must be between {}% and {}%", MIN_PRE_ROLL_PERCENT, MAX_PRE_ROLL_PERCENT));
+        }
+        Ok(Self { seconds, pre_roll: preroll_percent / 100.0 })
+    }
+}
+
 /// Nombre minimum de samples à pré-remplir après un seek (environ 300ms)
 /// Cela permet de reprendre la lecture rapidement sans attendre le pre-roll complet
 const SEEK_PREFILL_MS: u64 = 300;
 
+/// Erreurs consécutives tolérées avant d'abandonner le fichier (~0.5s de retries en I/O)
+const MAX_CONSECUTIVE_READ_ERRORS: u32 = 50;
+const READ_ERROR_RETRY_MS: u64 = 10;
+
+/// Canaux en sortie du décodeur : les fichiers multicanaux sont downmixés en stéréo
+const MAX_OUTPUT_CHANNELS: usize = 2;
+
 /// Informations sur le fichier audio
 #[derive(Debug, Clone)]
 pub struct AudioInfo {
@@ -86,6 +136,10 @@ pub struct StreamingState {
     pub info: AudioInfo,
     /// Taille du ring buffer
     pub ring_capacity: usize,
+    /// Vitesse de lecture (f32 bits) — appliquée par le resampler du décodeur
+    pub speed: AtomicU64,
+    /// Le fichier est devenu illisible en cours de lecture (disque débranché, partage perdu)
+    pub read_error: AtomicBool,
 }
 
 impl StreamingState {
@@ -101,9 +155,22 @@ impl StreamingState {
             samples_since_seek: AtomicUsize::new(0),
             info,
             ring_capacity,
+            speed: AtomicU64::new(1.0f32.to_bits() as u64),
+            read_error: AtomicBool::new(false),
         }
     }
 
+    /// Vitesse de lecture courante (1.0 = normale)
+    pub fn speed(&self) -> f32 {
+        f32::from_bits(self.speed.load(Ordering::Relaxed) as u32)
+    }
+
+    /// Change la vitesse de lecture. Le décodeur ne reconstruit son resampler
+    /// qu'au prochain seek : l'appelant doit en envoyer un pour appliquer le changement.
+    pub fn set_speed(&self, speed: f32) {
+        self.speed.store(speed.to_bits() as u64, Ordering::Relaxed);
+    }
+
     /// Durée réelle basée sur les métadonnées (précision au sample)
     pub fn duration_seconds(&self) -> f64 {
         self.info.duration_seconds
@@ -268,9 +335,185 @@ fn open_media_source(path: &Path) -> Option<Box<dyn MediaSource>> {
 
 // =====================================================================
 
-/// Probe un fichier audio pour obtenir ses métadonnées sans décoder
-/// Utilise Symphonia d'abord, puis lofty en fallback pour les M4A/AAC
+/// Préfixe des erreurs "aucun décodeur pour ce codec" (l'engine émet alors `unsupported_codec`)
+pub const UNSUPPORTED_CODEC_ERROR: &str = "Unsupported codec";
+
+/// Formats lus par lofty (tags, propriétés) mais sans décodeur : Symphonia 0.5 ne connaît
+/// pas Monkey's Audio. Ils sont indexés avec leurs métadonnées, la lecture échoue avec
+/// UNSUPPORTED_CODEC_ERROR (WavPack est décodé par wavpack.rs)
+pub fn metadata_only_codec(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "ape" => Some("Monkey's Audio"),
+        _ => None,
+    }
+}
+
+/// Nom lisible d'un codec sans décodeur enregistré
+fn codec_name(codec: CodecType) -> String {
+    if codec == CODEC_TYPE_OPUS {
+        "Opus".to_string()
+    } else {
+        format!("codec {}", codec)
+    }
+}
+
+/// Résultat de can_play : le fichier peut-il être décodé ?
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlayabilitySummary {
+    pub supported: bool,
+    /// Codec détecté ("FLAC", "ALAC", "DSD"…), ou l'extension si le conteneur n'est pas reconnu
+    pub codec: String,
+    /// Pourquoi le fichier ne peut pas être lu (None s'il est lisible)
+    pub reason: Option<String>,
+}
+
+impl PlayabilitySummary {
+    fn unsupported(codec: String, reason: String) -> Self {
+        Self { supported: false, codec, reason: Some(reason) }
+    }
+}
+
+/// Vérifie sans décoder qu'un fichier local est lisible : conteneur reconnu, piste audio,
+/// décodeur disponible (Opus, DSD illisible…). Même chemin que start_streaming_with_speed
+pub fn check_playability(path: &str) -> PlayabilitySummary {
+    let path_buf = Path::new(path).to_path_buf();
+    let extension = path_buf.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_uppercase();
+
+    if crate::dsd::is_dsd_path(&path_buf) {
+        return match crate::dsd::probe_dsd(path) {
+            Ok(_) => PlayabilitySummary { supported: true, codec: "DSD".to_string(), reason: None },
+            Err(e) => PlayabilitySummary::unsupported("DSD".to_string(), e),
+        };
+    }
+    if crate::wavpack::is_wavpack_path(&path_buf) {
+        let codec = "WAVPACK".to_string();
+        return match crate::wavpack::read_wavpack_info(path) {
+            Ok(info) => match info.unsupported_variant() {
+                None => PlayabilitySummary { supported: true, codec, reason: None },
+                Some(variant) => {
+                    let reason = format!("{}: WavPack {}", UNSUPPORTED_CODEC_ERROR, variant);
+                    PlayabilitySummary::unsupported(codec, reason)
+                }
+            },
+            Err(e) => PlayabilitySummary::unsupported(codec, e),
+        };
+    }
+    if let Some(codec) = metadata_only_codec(&path_buf) {
+        let reason = format!("{}: {}", UNSUPPORTED_CODEC_ERROR, codec);
+        return PlayabilitySummary::unsupported(codec.to_string(), reason);
+    }
+
+    let Some(media_source) = open_media_source(&path_buf) else {
+        return PlayabilitySummary::unsupported(extension, format!("Cannot open file: {}", path));
+    };
+    let mss = MediaSourceStream::new(media_source, Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(&extension.to_lowercase());
+
+    let probed = match symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+    {
+        Ok(probed) => probed,
+        Err(e) => return PlayabilitySummary::unsupported(extension, format!("Unrecognized format: {}", e)),
+    };
+    let Some(track) = probed.format.tracks().iter().find(|t| t.codec_params.codec != CODEC_TYPE_NULL) else {
+        return PlayabilitySummary::unsupported(extension, "No audio track found".to_string());
+    };
+
+    let codecs = symphonia::default::get_codecs();
+    let codec = codecs.get_codec(track.codec_params.codec)
+        .map(|descriptor| descriptor.short_name.to_uppercase())
+        .unwrap_or_else(|| codec_name(track.codec_params.codec));
+    match codecs.make(&track.codec_params, &DecoderOptions::default()) {
+        Ok(_) => PlayabilitySummary { supported: true, codec, reason: None },
+        Err(symphonia::core::errors::Error::Unsupported(_)) => {
+            let reason = format!("{}: {}", UNSUPPORTED_CODEC_ERROR, codec);
+            PlayabilitySummary::unsupported(codec, reason)
+        }
+        Err(e) => PlayabilitySummary::unsupported(codec, format!("Failed to create decoder: {}", e)),
+    }
+}
+
+/// Nombre de fichiers dont le probe reste en mémoire (file d'attente + tracks récents)
+const PROBE_CACHE_CAPACITY: usize = 64;
+
+/// Résultat d'un probe, valable tant que le mtime du fichier ne change pas
+struct CachedProbe {
+    mtime: SystemTime,
+    info: AudioInfo,
+    /// Ordre d'insertion : le plus ancien est évincé quand le cache est plein
+    stamp: u64,
+}
+
+#[derive(Default)]
+struct ProbeCache {
+    entries: HashMap<String, CachedProbe>,
+    next_stamp: u64,
+}
+
+/// Probes déjà faits : audio_play, le redémarrage après seek et le préchargement gapless
+/// reprennent le même fichier plusieurs fois
+static PROBE_CACHE: Lazy<Mutex<ProbeCache>> = Lazy::new(|| Mutex::new(ProbeCache::default()));
+
+/// Probe un fichier audio pour obtenir ses métadonnées sans décoder.
+/// Le résultat est mis en cache par chemin + mtime ; un download SMB progressif en cours
+/// (fichier incomplet) n'est jamais mis en cache
 pub fn probe_audio_file(path: &str) -> Result<AudioInfo, String> {
+    let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let downloading = crate::PROGRESSIVE_DOWNLOADS.lock()
+        .map(|registry| registry.contains_key(Path::new(path)))
+        .unwrap_or(true);
+    let cacheable_mtime = mtime.filter(|_| !downloading);
+
+    if let Some(mtime) = cacheable_mtime {
+        if let Ok(cache) = PROBE_CACHE.lock() {
+            if let Some(cached) = cache.entries.get(path).filter(|c| c.mtime == mtime) {
+                return Ok(cached.info.clone());
+            }
+        }
+    }
+
+    let info = probe_audio_file_uncached(path)?;
+
+    if let Some(mtime) = cacheable_mtime {
+        if let Ok(mut cache) = PROBE_CACHE.lock() {
+            if cache.entries.len() >= PROBE_CACHE_CAPACITY && !cache.entries.contains_key(path) {
+                let oldest = cache.entries.iter()
+                    .min_by_key(|(_, c)| c.stamp)
+                    .map(|(p, _)| p.clone());
+                if let Some(oldest) = oldest {
+                    cache.entries.remove(&oldest);
+                }
+            }
+            let stamp = cache.next_stamp;
+            cache.next_stamp += 1;
+            cache.entries.insert(path.to_string(), CachedProbe { mtime, info: info.clone(), stamp });
+        }
+    }
+    Ok(info)
+}
+
+/// Probe sans cache. Utilise Symphonia d'abord, puis lofty en fallback pour les M4A/AAC
+fn probe_audio_file_uncached(path: &str) -> Result<AudioInfo, String> {
+    // 0. DSF/DFF : ni Symphonia ni lofty ne les lisent
+    if crate::dsd::is_dsd_path(Path::new(path)) {
+        return crate::dsd::probe_dsd(path);
+    }
+
+    // WavPack : en-têtes de blocs lus par wavpack.rs
+    if crate::wavpack::is_wavpack_path(Path::new(path)) {
+        return crate::wavpack::probe_wavpack(path);
+    }
+
+    // APE : Symphonia ne le reconnaît pas, inutile de l'essayer
+    if metadata_only_codec(Path::new(path)).is_some() {
+        return probe_with_lofty(path);
+    }
+
     // 1. Essaie avec Symphonia (rapide, fonctionne bien pour WAV/FLAC/MP3)
     if let Some(info) = try_probe_with_symphonia(path) {
         // Vérifie que le sample_rate est plausible (pas un fallback)
@@ -407,9 +650,30 @@ pub fn start_streaming_with_config(
     start_time: f64,
     source_sample_rate: u32,  // NOUVEAU: passé depuis probe_audio_file()
     target_sample_rate: Option<u32>,
+) -> Result<StreamingSession, String> {
+    start_streaming_with_speed(path, start_time, source_sample_rate, target_sample_rate, 1.0, BufferConfig::default())
+}
+
+/// Comme start_streaming_with_config(), avec une vitesse de lecture (0.5x–2.0x)
+/// et une taille de buffer choisie par l'utilisateur
+///
+/// La vitesse est obtenue en resamplant depuis un sample rate source virtuel
+/// `source_rate × speed` vers le rate de sortie : le pitch varie donc avec la vitesse.
+pub fn start_streaming_with_speed(
+    path: &str,
+    start_time: f64,
+    source_sample_rate: u32,
+    target_sample_rate: Option<u32>,
+    speed: f32,
+    buffer: BufferConfig,
 ) -> Result<StreamingSession, String> {
     let path_buf = Path::new(path).to_path_buf();
 
+    // APE : métadonnées seulement, l'engine émet `unsupported_codec`
+    if let Some(codec) = metadata_only_codec(&path_buf) {
+        return Err(format!("{}: {}", UNSUPPORTED_CODEC_ERROR, codec));
+    }
+
     // open_media_source retourne SmbProgressiveFile (blocking) si download en cours, File sinon.
     // Cela permet à Symphonia de seeker même si le fichier FLAC n'est pas entièrement téléchargé.
     let media_source = open_media_source(&path_buf)
@@ -422,20 +686,29 @@ pub fn start_streaming_with_config(
         hint.with_extension(ext);
     }
 
-    // Probe le format
-    let probed = symphonia::default::get_probe()
-        .format(
-            &hint,
-            mss,
-            &FormatOptions {
-                enable_gapless: true,
-                ..Default::default()
-            },
-            &MetadataOptions::default(),
-        )
-        .map_err(|e| format!("Failed to probe format: {}", e))?;
-
-    let mut format = probed.format;
+    // Probe le format (DSD, WavPack : readers maison qui produisent du PCM f32,
+    // avec la profondeur du flux source)
+    let (mut format, native_bit_depth): (Box<dyn FormatReader>, Option<u8>) = if crate::dsd::is_dsd_path(&path_buf) {
+        (Box::new(DsdFormatReader::open(mss)?), Some(1))
+    } else if crate::wavpack::is_wavpack_path(&path_buf) {
+        let reader = WavPackFormatReader::open(mss)?;
+        let bits = reader.bits_per_sample();
+        (Box::new(reader), Some(bits))
+    } else {
+        let format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                mss,
+                &FormatOptions {
+                    enable_gapless: true,
+                    ..Default::default()
+                },
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| format!("Failed to probe format: {}", e))?
+            .format;
+        (format, None)
+    };
 
     // Trouve la piste audio
     let track = format
@@ -460,11 +733,12 @@ pub fn start_streaming_with_config(
         }
     }
 
-    let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(2);
+    // Canaux produits par convert_to_f32_interleaved (RingBuffer, resampler, stream)
+    let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(2).min(MAX_OUTPUT_CHANNELS);
     let total_frames = track.codec_params.n_frames.unwrap_or(0);
 
-    // Pour AAC, bit_depth est souvent None - on met 24 pour hi-res
-    let bit_depth = symphonia_bit_depth.unwrap_or(24) as u8;
+    // Pour AAC, bit_depth est souvent None - on met 24 pour hi-res (DSD : flux source 1-bit)
+    let bit_depth = native_bit_depth.unwrap_or_else(|| symphonia_bit_depth.unwrap_or(24) as u8);
 
     // Détermine le sample rate de sortie (bit-perfect si target_sample_rate est None)
     let output_sample_rate = target_sample_rate.unwrap_or(source_sample_rate);
@@ -498,15 +772,15 @@ pub fn start_streaming_with_config(
 
     // Calcule la taille du RingBuffer basée sur le OUTPUT rate
     // (le RingBuffer contiendra des samples au sample rate de sortie)
-    let ring_capacity = (RING_BUFFER_SECONDS * output_sample_rate as f64 * channels as f64) as usize;
-    let pre_roll_samples = (ring_capacity as f64 * PRE_ROLL_PERCENT) as usize;
+    let ring_capacity = (buffer.seconds * output_sample_rate as f64 * channels as f64) as usize;
+    let pre_roll_samples = (ring_capacity as f64 * buffer.pre_roll) as usize;
 
     #[cfg(debug_assertions)]
     println!(
         "=== Audio File Info ===\n  source_rate: {}Hz\n  output_rate: {}Hz (resampling: {})\n  bit_depth: {}bit\n  channels: {}\n  total_frames: {}\n  duration: {:.3}s\n  RingBuffer: {} samples ({:.1}s)\n  pre-roll: {:.0}ms",
         source_sample_rate, output_sample_rate, needs_resampling,
         bit_depth, channels, total_frames, duration_seconds,
-        ring_capacity, RING_BUFFER_SECONDS,
+        ring_capacity, buffer.seconds,
         (pre_roll_samples / channels) as f64 / output_sample_rate as f64 * 1000.0
     );
 
@@ -519,25 +793,22 @@ pub fn start_streaming_with_config(
 
     // État partagé
     let state = Arc::new(StreamingState::new(info.clone(), ring_capacity));
+    state.set_speed(speed);
     let state_clone = Arc::clone(&state);
 
-    // Crée le décodeur
+    // Crée le décodeur (Symphonia 0.5 n'a pas de décodeur Opus : le demuxer Ogg
+    // reconnaît le flux mais aucun codec n'est enregistré pour lui)
     let decoder = symphonia::default::get_codecs()
         .make(&track.codec_params, &DecoderOptions::default())
-        .map_err(|e| format!("Failed to create decoder: {}", e))?;
-
-    // Crée le resampler si nécessaire
-    let resampler = if needs_resampling {
-        match AudioResampler::new(source_sample_rate, output_sample_rate, channels) {
-            Ok(r) => Some(r),
-            Err(e) => {
-                eprintln!("Failed to create resampler: {}, falling back to native rate", e);
-                None
+        .map_err(|e| match e {
+            symphonia::core::errors::Error::Unsupported(_) => {
+                format!("{}: {}", UNSUPPORTED_CODEC_ERROR, codec_name(track.codec_params.codec))
             }
-        }
-    } else {
-        None
-    };
+            e => format!("Failed to create decoder: {}", e),
+        })?;
+
+    // Crée le resampler si nécessaire (changement de rate ou vitesse != 1.0)
+    let resampler = create_resampler(source_sample_rate, output_sample_rate, channels, speed);
 
     // Si on démarre à une position non-zero, effectue un seek initial
     if start_time > 0.0 {
@@ -596,6 +867,31 @@ pub fn start_streaming_with_config(
     })
 }
 
+/// Crée le resampler pour une conversion source → output à la vitesse donnée
+///
+/// Le ratio effectif est `output_rate / (source_rate × speed)` : on ment au resampler
+/// sur le rate source, ce qui accélère/ralentit la lecture mais décale aussi le pitch.
+/// TODO: remplacer par un vrai time-stretcher (WSOLA / phase vocoder) pour garder le pitch.
+fn create_resampler(
+    source_sample_rate: u32,
+    output_sample_rate: u32,
+    channels: usize,
+    speed: f32,
+) -> Option<AudioResampler> {
+    let effective_source_rate = (source_sample_rate as f64 * speed as f64).round() as u32;
+    if effective_source_rate == output_sample_rate {
+        return None;
+    }
+
+    match AudioResampler::new(effective_source_rate, output_sample_rate, channels) {
+        Ok(r) => Some(r),
+        Err(e) => {
+            eprintln!("Failed to create resampler: {}, falling back to native rate", e);
+            None
+        }
+    }
+}
+
 /// Thread de décodage avec support du seeking et resampling
 fn decoder_thread(
     mut format: Box<dyn symphonia::core::formats::FormatReader>,
@@ -618,12 +914,21 @@ fn decoder_thread(
     // Le sample rate utilisé pour calculer les positions dépend du resampling
     let position_sample_rate = output_sample_rate;
 
+    // Vitesse avec laquelle le resampler actuel a été construit
+    let mut resampler_speed = state.speed();
+
     // Variables pour tracer le seek (debug only)
     let mut last_seek_target: f64 = 0.0;
     let mut first_packet_after_seek = false;
     #[allow(unused_variables)]
     let mut prefill_start_logged = false;
 
+    // Erreurs consécutives (lecture ou décodage) : au-delà du seuil, le fichier est abandonné
+    let mut consecutive_errors = 0u32;
+
+    // Temps de décodage par packet (get_engine_stats), hors lecture du fichier
+    let mut decode_timer = crate::engine_stats::BatchTimer::new(crate::engine_stats::Stage::Decode);
+
     #[cfg(debug_assertions)]
     println!("[DEBUG-D] No intermediate queue found — decoder writes directly to RingBuffer");
 
@@ -666,6 +971,16 @@ fn decoder_thread(
                         // Reset le décodeur après le seek
                         decoder.reset();
 
+                        // Un changement de vitesse passe par un seek : reconstruit le resampler
+                        // (le buffer vient d'être vidé, aucun sample à l'ancienne vitesse ne reste)
+                        let speed = state.speed();
+                        if speed != resampler_speed {
+                            resampler = create_resampler(source_sample_rate, output_sample_rate, channels, speed);
+                            resampler_speed = speed;
+                            #[cfg(debug_assertions)]
+                            println!("Decoder: Resampler rebuilt for speed {:.2}x", speed);
+                        }
+
                         #[cfg(debug_assertions)]
                         {
                             let decoder_position_ts = seeked_to.actual_ts as f64 / source_sample_rate as f64;
@@ -721,8 +1036,25 @@ fn decoder_thread(
                 decoder.reset();
                 continue;
             }
+            Err(symphonia::core::errors::Error::IoError(e)) => {
+                // Erreur d'I/O hors EOF : court délai avant de réessayer (SMB lent),
+                // abandon si elle persiste (fichier disparu)
+                consecutive_errors += 1;
+                if consecutive_errors >= MAX_CONSECUTIVE_READ_ERRORS {
+                    eprintln!("Read failed {} times in a row, giving up: {}", consecutive_errors, e);
+                    state.read_error.store(true, Ordering::Release);
+                    break;
+                }
+                thread::sleep(std::time::Duration::from_millis(READ_ERROR_RETRY_MS));
+                continue;
+            }
             Err(e) => {
                 eprintln!("Decode warning: {}", e);
+                consecutive_errors += 1;
+                if consecutive_errors >= MAX_CONSECUTIVE_READ_ERRORS {
+                    state.read_error.store(true, Ordering::Release);
+                    break;
+                }
                 continue;
             }
         };
@@ -743,17 +1075,25 @@ fn decoder_thread(
         }
 
         // Décode le packet
+        let decode_start = std::time::Instant::now();
         let decoded = match decoder.decode(&packet) {
             Ok(decoded) => decoded,
             Err(e) => {
                 eprintln!("Decode error: {}", e);
+                consecutive_errors += 1;
+                if consecutive_errors >= MAX_CONSECUTIVE_READ_ERRORS {
+                    state.read_error.store(true, Ordering::Release);
+                    break;
+                }
                 continue;
             }
         };
+        consecutive_errors = 0;
 
         // Convertit en f32 interleaved
         temp_buffer.clear();
         convert_to_f32_interleaved(&decoded, &mut temp_buffer);
+        decode_timer.record(decode_start.elapsed());
 
         // Applique le resampling si nécessaire
         let output_samples = if let Some(ref mut r) = resampler {
@@ -851,58 +1191,81 @@ fn push_to_ring(
 /// Convertit un AudioBufferRef en samples f32 interleaved
 fn convert_to_f32_interleaved(decoded: &AudioBufferRef, output: &mut Vec<f32>) {
     match decoded {
-        AudioBufferRef::F32(buf) => {
-            let channels = buf.spec().channels.count();
-            let frames = buf.frames();
-            output.reserve(frames * channels);
-            for frame in 0..frames {
-                for ch in 0..channels {
-                    output.push(buf.chan(ch)[frame]);
-                }
-            }
-        }
-        AudioBufferRef::S16(buf) => {
-            let channels = buf.spec().channels.count();
-            let frames = buf.frames();
-            output.reserve(frames * channels);
-            for frame in 0..frames {
-                for ch in 0..channels {
-                    output.push(buf.chan(ch)[frame] as f32 / 32768.0);
-                }
-            }
+        AudioBufferRef::F32(buf) => interleave(buf, |s| s, output),
+        AudioBufferRef::S16(buf) => interleave(buf, |s| s as f32 / 32768.0, output),
+        AudioBufferRef::S24(buf) => interleave(buf, |s| s.0 as f32 / 8388608.0, output),
+        AudioBufferRef::S32(buf) => interleave(buf, |s| s as f32 / 2147483648.0, output),
+        AudioBufferRef::U8(buf) => interleave(buf, |s| (s as f32 - 128.0) / 128.0, output),
+        _ => {
+            eprintln!("Unsupported audio format");
         }
-        AudioBufferRef::S24(buf) => {
-            let channels = buf.spec().channels.count();
-            let frames = buf.frames();
-            output.reserve(frames * channels);
-            for frame in 0..frames {
-                for ch in 0..channels {
-                    output.push(buf.chan(ch)[frame].0 as f32 / 8388608.0);
-                }
+    }
+}
+
+/// Entrelace les canaux du buffer ; au-delà de 2 canaux, downmix stéréo
+fn interleave<S: Sample>(buf: &AudioBuffer<S>, to_f32: impl Fn(S) -> f32, output: &mut Vec<f32>) {
+    let channels = buf.spec().channels.count();
+    let frames = buf.frames();
+    if channels > MAX_OUTPUT_CHANNELS {
+        let coefficients = downmix_coefficients(buf.spec().channels);
+        output.reserve(frames * MAX_OUTPUT_CHANNELS);
+        for frame in 0..frames {
+            let (mut left, mut right) = (0.0, 0.0);
+            for (ch, (to_left, to_right)) in coefficients.iter().enumerate() {
+                let sample = to_f32(buf.chan(ch)[frame]);
+                left += sample * to_left;
+                right += sample * to_right;
             }
+            output.push(left);
+            output.push(right);
         }
-        AudioBufferRef::S32(buf) => {
-            let channels = buf.spec().channels.count();
-            let frames = buf.frames();
-            output.reserve(frames * channels);
-            for frame in 0..frames {
-                for ch in 0..channels {
-                    output.push(buf.chan(ch)[frame] as f32 / 2147483648.0);
-                }
-            }
+        return;
+    }
+    output.reserve(frames * channels);
+    for frame in 0..frames {
+        for ch in 0..channels {
+            output.push(to_f32(buf.chan(ch)[frame]));
         }
-        AudioBufferRef::U8(buf) => {
-            let channels = buf.spec().channels.count();
-            let frames = buf.frames();
-            output.reserve(frames * channels);
-            for frame in 0..frames {
-                for ch in 0..channels {
-                    output.push((buf.chan(ch)[frame] as f32 - 128.0) / 128.0);
-                }
-            }
+    }
+}
+
+/// Gains (gauche, droite) de chaque canal source, dans l'ordre des canaux du buffer.
+/// Coefficients ITU-R BS.775 : centre et surrounds à -3 dB, LFE ignoré ; l'ensemble est
+/// ramené pour qu'un signal pleine échelle sur tous les canaux ne dépasse pas 1.0
+fn downmix_coefficients(channels: Channels) -> Vec<(f32, f32)> {
+    const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    let left = Channels::FRONT_LEFT | Channels::REAR_LEFT | Channels::SIDE_LEFT
+        | Channels::FRONT_LEFT_CENTRE | Channels::REAR_LEFT_CENTRE | Channels::FRONT_LEFT_WIDE
+        | Channels::FRONT_LEFT_HIGH | Channels::TOP_FRONT_LEFT | Channels::TOP_REAR_LEFT;
+    let right = Channels::FRONT_RIGHT | Channels::REAR_RIGHT | Channels::SIDE_RIGHT
+        | Channels::FRONT_RIGHT_CENTRE | Channels::REAR_RIGHT_CENTRE | Channels::FRONT_RIGHT_WIDE
+        | Channels::FRONT_RIGHT_HIGH | Channels::TOP_FRONT_RIGHT | Channels::TOP_REAR_RIGHT;
+
+    let mut coefficients: Vec<(f32, f32)> = channels.iter().map(|ch| {
+        if ch == Channels::FRONT_LEFT {
+            (1.0, 0.0)
+        } else if ch == Channels::FRONT_RIGHT {
+            (0.0, 1.0)
+        } else if ch == Channels::LFE1 || ch == Channels::LFE2 {
+            (0.0, 0.0)
+        } else if left.contains(ch) {
+            (MINUS_3DB, 0.0)
+        } else if right.contains(ch) {
+            (0.0, MINUS_3DB)
+        } else {
+            // Canaux centraux (centre, arrière centre, zénith) : répartis à -3 dB
+            (MINUS_3DB, MINUS_3DB)
         }
-        _ => {
-            eprintln!("Unsupported audio format");
+    }).collect();
+
+    let sum_left: f32 = coefficients.iter().map(|c| c.0).sum();
+    let sum_right: f32 = coefficients.iter().map(|c| c.1).sum();
+    let peak = sum_left.max(sum_right);
+    if peak > 1.0 {
+        for (to_left, to_right) in coefficients.iter_mut() {
+            *to_left /= peak;
+            *to_right /= peak;
         }
     }
+    coefficients
 }
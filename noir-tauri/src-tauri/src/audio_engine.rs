@@ -6,18 +6,23 @@
 // PURE COREAUDIO - No CPAL dependency!
 // Device management and streaming handled entirely via CoreAudio HAL.
 
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::thread;
 
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
 use parking_lot::Mutex;
 use tauri::{AppHandle, Emitter};
 
-use crate::audio_decoder::{start_streaming_with_config, StreamingState};
-use crate::audio::{AudioBackend, create_backend, ExclusiveMode, StreamConfig};
+use crate::audio_decoder::{start_streaming_with_speed, AudioInfo, BufferConfig, StreamingState};
+use crate::audio::{AudioBackend, AudioError, create_backend, DeviceEvent, ExclusiveMode, StreamConfig};
 use crate::audio::{AudioOutputStream, AudioStreamConfig, create_audio_stream};
+use crate::engine_stats::{EngineStats, Stage, PROCESSING_STATS};
 use crate::eq::EqSharedState;
+use crate::spectrum::SpectrumTap;
+use crate::output_stage::{ChannelMode, OutputStage, VolumeCurve};
+use crate::queue::RepeatMode;
 
 // NOTE: Device capabilities are now obtained directly from the backend
 // via backend.current_device() which returns DeviceInfo with all necessary info.
@@ -26,6 +31,15 @@ use crate::eq::EqSharedState;
 #[allow(dead_code)]
 const STANDARD_SAMPLE_RATES: [u32; 8] = [44100, 48000, 88200, 96000, 176400, 192000, 352800, 384000];
 
+/// Profondeur entière du stream à créer : None (float) si le mode entier est off
+/// ou si le device courant n'offre pas de format entier (ex. après un changement de device)
+fn integer_output_bits(state: &PlaybackState, backend: &Mutex<Box<dyn AudioBackend>>) -> Option<u16> {
+    if !state.integer_mode.load(Ordering::Relaxed) {
+        return None;
+    }
+    backend.lock().integer_bit_depth()
+}
+
 /// Trouve le meilleur sample rate de sortie pour une source donnée
 /// Utilise le backend CoreAudio directement (pas CPAL)
 fn find_best_output_rate_from_backend(
@@ -83,8 +97,9 @@ fn find_best_output_rate_from_backend(
 /// Commandes envoyées au thread audio
 #[derive(Debug)]
 pub enum AudioCommand {
-    /// Joue un fichier (chemin, position de départ optionnelle)
-    Play(String, Option<f64>),
+    /// Joue un fichier (chemin, position de départ optionnelle, segment optionnel
+    /// (début, fin) pour les pistes CUE — la dernière piste va jusqu'à la fin du fichier)
+    Play(String, Option<f64>, Option<(f64, Option<f64>)>),
     Pause,
     Resume,
     Stop,
@@ -95,6 +110,207 @@ pub enum AudioCommand {
     PreloadNext(String),
     /// Active/désactive le gapless
     SetGapless(bool),
+    /// Durée du crossfade en ms (0 = désactivé)
+    SetCrossfade(u32),
+    /// Vitesse de lecture (0.5x–2.0x), suivie d'un Seek pour l'appliquer
+    SetPlaybackSpeed(f32),
+}
+
+/// Durée maximale du crossfade (ms)
+pub const MAX_CROSSFADE_MS: u32 = 12_000;
+
+/// Intervalle de sauvegarde de la position des tracks longs (secondes)
+const POSITION_SAVE_INTERVAL_SECS: u64 = 10;
+
+/// Intervalle de recalage de la position du Now Playing macOS (secondes).
+/// Entre deux recalages, le système extrapole la position depuis le dernier envoi.
+const NOW_PLAYING_SYNC_SECS: u64 = 5;
+
+/// Bornes de la vitesse de lecture
+pub const MIN_PLAYBACK_SPEED: f32 = 0.5;
+pub const MAX_PLAYBACK_SPEED: f32 = 2.0;
+
+/// Minuterie de mise en veille : durée du fondu avant Stop, pas du fondu et
+/// intervalle auquel le thread de la minuterie vérifie qu'il n'a pas été annulé
+const SLEEP_FADE_MS: u64 = 5_000;
+const SLEEP_FADE_STEP_MS: u64 = 50;
+const SLEEP_TIMER_POLL_MS: u64 = 250;
+
+/// Temps restant sur le track en cours en dessous duquel le suivant de la file est préchargé
+const QUEUE_PRELOAD_THRESHOLD_MS: u64 = 10_000;
+
+/// Pause (ou fin de lecture sans Stop) au-delà de laquelle on considère que Noir a perdu
+/// le focus audio : le stream est arrêté et le DAC rendu à son sample rate d'origine
+const IDLE_RATE_RESTORE_SECS: u64 = 120;
+
+/// Boucle A-B : positions en ms, `end_ms == 0` → pas de boucle.
+/// Le callback détecte le passage du point B et lève `pending` ;
+/// l'engine le transforme en Seek vers A (même chemin qu'un seek utilisateur).
+pub struct AbLoopState {
+    start_ms: AtomicU64,
+    end_ms: AtomicU64,
+    pending: AtomicBool,
+}
+
+impl AbLoopState {
+    pub fn new() -> Self {
+        Self {
+            start_ms: AtomicU64::new(0),
+            end_ms: AtomicU64::new(0),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    pub fn set(&self, start: f64, end: f64) {
+        // end d'abord à 0 : le callback ne voit jamais une boucle à moitié écrite
+        self.end_ms.store(0, Ordering::Release);
+        self.pending.store(false, Ordering::Relaxed);
+        self.start_ms.store((start * 1000.0) as u64, Ordering::Relaxed);
+        self.end_ms.store(((end * 1000.0) as u64).max(1), Ordering::Release);
+    }
+
+    pub fn clear(&self) {
+        self.end_ms.store(0, Ordering::Release);
+        self.pending.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.end_ms.load(Ordering::Acquire) > 0
+    }
+
+    pub fn start_seconds(&self) -> f64 {
+        self.start_ms.load(Ordering::Relaxed) as f64 / 1000.0
+    }
+
+    pub fn end_ms(&self) -> u64 {
+        self.end_ms.load(Ordering::Acquire)
+    }
+
+    /// Appelé par le callback audio quand la position atteint B
+    pub fn request_loop(&self) {
+        self.pending.store(true, Ordering::Release);
+    }
+
+    /// Appelé par l'engine : true si un retour en A est demandé
+    pub fn take_pending(&self) -> bool {
+        self.pending.swap(false, Ordering::AcqRel) && self.is_active()
+    }
+}
+
+/// Piste virtuelle (CUE) en cours : bornes du segment dans le fichier, en ms.
+/// En interne (engine, callback, boucle A-B) les positions restent celles du fichier ;
+/// tout ce qui sort vers le frontend (playback_progress, get_position, seeks reçus) est
+/// relatif au segment. `end_ms == 0` → jusqu'à la fin du fichier.
+pub struct SegmentState {
+    start_ms: AtomicU64,
+    end_ms: AtomicU64,
+}
+
+impl SegmentState {
+    pub fn new() -> Self {
+        Self {
+            start_ms: AtomicU64::new(0),
+            end_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set(&self, start: f64, end: Option<f64>) {
+        self.start_ms.store((start.max(0.0) * 1000.0) as u64, Ordering::Relaxed);
+        self.end_ms.store(end.map_or(0, |end| (end.max(0.0) * 1000.0) as u64), Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        self.set(0.0, None);
+    }
+
+    pub fn end_ms(&self) -> u64 {
+        self.end_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn start_seconds(&self) -> f64 {
+        self.start_ms.load(Ordering::Relaxed) as f64 / 1000.0
+    }
+
+    /// Position dans le fichier → position dans le segment
+    pub fn relative_seconds(&self, position: f64) -> f64 {
+        (position - self.start_seconds()).max(0.0)
+    }
+
+    /// Position dans le segment → position dans le fichier
+    pub fn absolute_seconds(&self, position: f64) -> f64 {
+        self.start_seconds() + position.max(0.0)
+    }
+
+    /// Durée du segment (le fichier entier hors CUE)
+    pub fn duration_seconds(&self, file_duration: f64) -> f64 {
+        let end = match self.end_ms() {
+            0 => file_duration,
+            end_ms => end_ms as f64 / 1000.0,
+        };
+        (end - self.start_seconds()).max(0.0)
+    }
+}
+
+/// Santé du RingBuffer du stream courant, écrite par le callback audio.
+/// Un underrun = le callback trouve le ring vide alors que le décodage n'est pas terminé
+/// (NAS / réseau trop lent) ; compté une fois par coupure, pas par callback.
+pub struct BufferHealthState {
+    /// Remplissage après le dernier callback, en pour mille
+    fill_permille: AtomicU64,
+    underrun_count: AtomicU64,
+    /// Heure du dernier underrun (ms depuis l'epoch, 0 = aucun)
+    last_underrun_ms: AtomicU64,
+}
+
+impl BufferHealthState {
+    pub fn new() -> Self {
+        Self {
+            fill_permille: AtomicU64::new(0),
+            underrun_count: AtomicU64::new(0),
+            last_underrun_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Nouveau Play : compteurs remis à zéro
+    pub fn reset(&self) {
+        self.fill_permille.store(0, Ordering::Relaxed);
+        self.underrun_count.store(0, Ordering::Relaxed);
+        self.last_underrun_ms.store(0, Ordering::Relaxed);
+    }
+
+    /// Appelé par le callback audio après chaque lecture du ring
+    pub fn record_fill(&self, occupied: usize, capacity: usize) {
+        let permille = (occupied as u64 * 1000) / capacity.max(1) as u64;
+        self.fill_permille.store(permille, Ordering::Relaxed);
+    }
+
+    /// Appelé par le callback audio au début d'une coupure
+    pub fn record_underrun(&self) {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        self.last_underrun_ms.store(now_ms, Ordering::Relaxed);
+        self.underrun_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> BufferHealth {
+        let last_underrun_ms = self.last_underrun_ms.load(Ordering::Relaxed);
+        BufferHealth {
+            fill_percent: self.fill_permille.load(Ordering::Relaxed) as f64 / 10.0,
+            underrun_count: self.underrun_count.load(Ordering::Relaxed),
+            last_underrun_ms: (last_underrun_ms > 0).then_some(last_underrun_ms),
+        }
+    }
+}
+
+/// État du RingBuffer envoyé au frontend (get_buffer_health, événement `buffer_underrun`)
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BufferHealth {
+    pub fill_percent: f64,
+    pub underrun_count: u64,
+    /// Heure du dernier underrun (ms depuis l'epoch)
+    pub last_underrun_ms: Option<u64>,
 }
 
 /// État de lecture partagé avec le frontend
@@ -105,10 +321,44 @@ pub struct PlaybackState {
     pub channels: Arc<AtomicU64>,
     pub duration: Arc<AtomicU64>,  // Durée en millisecondes (précision)
     pub position: Arc<AtomicU64>,  // Position en millisecondes (précision)
-    pub volume: Arc<AtomicU64>,    // f32 as bits
+    pub volume: Arc<AtomicU64>,    // f32 as bits — gain appliqué par le callback
+    /// Position du curseur de volume (f32 bits) avant la courbe de volume
+    pub volume_level: AtomicU64,
+    /// VolumeCurve (discriminant)
+    pub volume_curve: AtomicU8,
     pub is_seeking: Arc<AtomicBool>,
     /// RMS energy (f64 bits) — written by audio callback, read by frontend for visualisation
     pub rms_energy: Arc<AtomicU64>,
+    /// Durée du crossfade en ms (0 = désactivé)
+    pub crossfade_ms: Arc<AtomicU64>,
+    /// True pendant qu'un crossfade est en cours dans le callback
+    pub crossfade_active: Arc<AtomicBool>,
+    /// Vitesse de lecture (f32 bits), appliquée aux nouvelles sessions de décodage
+    pub playback_speed: Arc<AtomicU64>,
+    /// Boucle A-B du track courant (partagée avec le callback)
+    pub ab_loop: Arc<AbLoopState>,
+    /// Remet le DAC à son sample rate d'origine sur Stop
+    pub restore_rate_on_stop: Arc<AtomicBool>,
+    /// Format source → sortie du stream courant (None = pas de stream)
+    pub stream_format: Mutex<Option<StreamFormat>>,
+    /// Sortie en entiers au format natif du DAC (au lieu du float 32 bits)
+    pub integer_mode: Arc<AtomicBool>,
+    /// Sample rate de sortie imposé par l'utilisateur (0 = automatique, bit-perfect si possible)
+    pub locked_sample_rate: AtomicU64,
+    /// Bornes de la piste virtuelle (CUE) en cours (partagées avec le callback)
+    pub segment: Arc<SegmentState>,
+    /// Capture pour le visualiseur de spectre (désactivée par défaut)
+    pub spectrum: Arc<SpectrumTap>,
+    /// Remplissage du RingBuffer et underruns depuis le dernier Play
+    pub buffer_health: Arc<BufferHealthState>,
+    /// Traitement de sortie après EQ et volume (canaux, balance, largeur, limiteur), contourné s'il est inactif
+    pub output_stage: Arc<OutputStage>,
+    /// Taille du RingBuffer et pré-remplissage des prochaines sessions de décodage
+    pub buffer_config: Mutex<BufferConfig>,
+    /// Modes shuffle / repeat (réglages de lecture), sauvegardés avec la session
+    pub shuffle: AtomicBool,
+    /// RepeatMode (discriminant)
+    pub repeat_mode: AtomicU8,
 }
 
 impl PlaybackState {
@@ -121,17 +371,69 @@ impl PlaybackState {
             duration: Arc::new(AtomicU64::new(0)),
             position: Arc::new(AtomicU64::new(0)),
             volume: Arc::new(AtomicU64::new(f32::to_bits(1.0) as u64)),
+            volume_level: AtomicU64::new(f32::to_bits(1.0) as u64),
+            volume_curve: AtomicU8::new(VolumeCurve::default() as u8),
             is_seeking: Arc::new(AtomicBool::new(false)),
             rms_energy: Arc::new(AtomicU64::new(0)),
+            crossfade_ms: Arc::new(AtomicU64::new(0)),
+            crossfade_active: Arc::new(AtomicBool::new(false)),
+            playback_speed: Arc::new(AtomicU64::new(f32::to_bits(1.0) as u64)),
+            ab_loop: Arc::new(AbLoopState::new()),
+            restore_rate_on_stop: Arc::new(AtomicBool::new(true)),
+            stream_format: Mutex::new(None),
+            integer_mode: Arc::new(AtomicBool::new(false)),
+            locked_sample_rate: AtomicU64::new(0),
+            segment: Arc::new(SegmentState::new()),
+            spectrum: Arc::new(SpectrumTap::new()),
+            buffer_health: Arc::new(BufferHealthState::new()),
+            output_stage: Arc::new(OutputStage::new()),
+            buffer_config: Mutex::new(BufferConfig::default()),
+            shuffle: AtomicBool::new(false),
+            repeat_mode: AtomicU8::new(RepeatMode::default() as u8),
         }
     }
 
+    pub fn set_queue_modes(&self, shuffle: bool, repeat: RepeatMode) {
+        self.shuffle.store(shuffle, Ordering::Relaxed);
+        self.repeat_mode.store(repeat as u8, Ordering::Relaxed);
+    }
+
+    pub fn queue_modes(&self) -> (bool, RepeatMode) {
+        (
+            self.shuffle.load(Ordering::Relaxed),
+            RepeatMode::from_u8(self.repeat_mode.load(Ordering::Relaxed)),
+        )
+    }
+
+    /// Position du curseur (0.0–1.0) ; le gain du callback passe par la courbe de volume
     pub fn set_volume(&self, vol: f32) {
-        self.volume.store(f32::to_bits(vol.clamp(0.0, 1.0)) as u64, Ordering::Relaxed);
+        let level = vol.clamp(0.0, 1.0);
+        self.volume_level.store(f32::to_bits(level) as u64, Ordering::Relaxed);
+        let gain = self.get_volume_curve().gain(level);
+        self.volume.store(f32::to_bits(gain) as u64, Ordering::Relaxed);
     }
 
+    /// Position du curseur (pas le gain appliqué)
     pub fn get_volume(&self) -> f32 {
-        f32::from_bits(self.volume.load(Ordering::Relaxed) as u32)
+        f32::from_bits(self.volume_level.load(Ordering::Relaxed) as u32)
+    }
+
+    /// Change la courbe et recalcule le gain pour la position courante du curseur
+    pub fn set_volume_curve(&self, curve: VolumeCurve) {
+        self.volume_curve.store(curve as u8, Ordering::Relaxed);
+        self.set_volume(self.get_volume());
+    }
+
+    pub fn get_volume_curve(&self) -> VolumeCurve {
+        VolumeCurve::from_u8(self.volume_curve.load(Ordering::Relaxed))
+    }
+
+    /// Rate à demander au backend pour une source : le rate verrouillé s'il y en a un
+    pub fn requested_output_rate(&self, source_rate: u32) -> u32 {
+        match self.locked_sample_rate.load(Ordering::Relaxed) {
+            0 => source_rate,
+            locked => locked as u32,
+        }
     }
 
     pub fn get_duration_seconds(&self) -> f64 {
@@ -142,6 +444,16 @@ impl PlaybackState {
         self.position.load(Ordering::Relaxed) as f64 / 1000.0
     }
 
+    /// Position dans le track tel que le voit l'utilisateur (relative au segment CUE)
+    pub fn track_position_seconds(&self) -> f64 {
+        self.segment.relative_seconds(self.get_position_seconds())
+    }
+
+    /// Durée du track tel que le voit l'utilisateur (celle du segment CUE)
+    pub fn track_duration_seconds(&self) -> f64 {
+        self.segment.duration_seconds(self.get_duration_seconds())
+    }
+
     pub fn set_position_ms(&self, ms: u64) {
         self.position.store(ms, Ordering::Relaxed);
     }
@@ -149,6 +461,40 @@ impl PlaybackState {
     pub fn set_duration_ms(&self, ms: u64) {
         self.duration.store(ms, Ordering::Relaxed);
     }
+
+    pub fn get_crossfade_ms(&self) -> u32 {
+        self.crossfade_ms.load(Ordering::Relaxed) as u32
+    }
+
+    /// Arrondie au pas de 0.05 : garde des ratios simples pour le resampler FFT
+    pub fn set_playback_speed(&self, speed: f32) {
+        let speed = ((speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED) * 20.0).round() / 20.0).max(MIN_PLAYBACK_SPEED);
+        self.playback_speed.store(f32::to_bits(speed) as u64, Ordering::Relaxed);
+    }
+
+    pub fn get_playback_speed(&self) -> f32 {
+        f32::from_bits(self.playback_speed.load(Ordering::Relaxed) as u32)
+    }
+
+    /// Mémorise le format du track courant : appelé à chaque création de stream
+    /// (play, restart après seek) et à chaque transition gapless
+    pub fn set_stream_format(&self, info: &AudioInfo) {
+        *self.stream_format.lock() = Some(StreamFormat {
+            source_rate: info.sample_rate,
+            output_rate: info.output_sample_rate,
+            source_bit_depth: info.bit_depth,
+            resampling: info.is_resampled || info.sample_rate != info.output_sample_rate,
+        });
+    }
+}
+
+/// Format du stream courant, figé à la création du stream
+#[derive(Clone, Copy, Debug)]
+pub struct StreamFormat {
+    pub source_rate: u32,
+    pub output_rate: u32,
+    pub source_bit_depth: u8,
+    pub resampling: bool,
 }
 
 /// Moteur audio principal
@@ -160,25 +506,42 @@ pub struct AudioEngine {
     backend: Arc<Mutex<Box<dyn AudioBackend>>>,
     /// EQ shared state (gains atomiques partagés avec le callback audio)
     pub eq_state: EqSharedState,
+    app_handle: Option<AppHandle>,
+    /// Génération de la minuterie de mise en veille : incrémentée à chaque armement ou
+    /// annulation, un thread de minuterie dont la génération est périmée s'arrête
+    sleep_timer_generation: Arc<AtomicU64>,
 }
 
 impl AudioEngine {
-    pub fn new(app_handle: Option<AppHandle>) -> Result<Self, String> {
+    pub fn new(app_handle: Option<AppHandle>) -> Result<Self, AudioError> {
         let (command_tx, command_rx) = bounded::<AudioCommand>(32);
         let state = Arc::new(PlaybackState::new());
         let state_clone = Arc::clone(&state);
 
         // Create audio backend for device control
-        let backend: Box<dyn AudioBackend> = match create_backend() {
+        let mut backend: Box<dyn AudioBackend> = match create_backend() {
             Ok(b) => {
                 #[cfg(debug_assertions)]
                 println!("Audio backend created: {}", b.name());
                 b
             }
             Err(e) => {
-                return Err(format!("Audio backend required: {}", e));
+                return Err(e.into());
             }
         };
+
+        // Casque/DAC branché ou débranché → le frontend met à jour la sortie courante sans polling
+        if let Some(ref app) = app_handle {
+            let app = app.clone();
+            let watched = backend.set_device_event_callback(Some(Box::new(move |event| {
+                if let DeviceEvent::DefaultDeviceChanged(device) = event {
+                    let _ = app.emit("audio_device_changed", &device);
+                }
+            })));
+            if let Err(e) = watched {
+                println!("Device change events unavailable: {}", e);
+            }
+        }
         let backend = Arc::new(Mutex::new(backend));
         let backend_clone = Arc::clone(&backend);
 
@@ -186,6 +549,7 @@ impl AudioEngine {
         let eq_state = EqSharedState::new();
         let eq_state_clone = eq_state.clone();
 
+        let engine_app_handle = app_handle.clone();
         let audio_thread = thread::spawn(move || {
             Self::audio_thread_main(command_rx, state_clone, app_handle, backend_clone, eq_state_clone);
         });
@@ -196,33 +560,35 @@ impl AudioEngine {
             _audio_thread: audio_thread,
             backend,
             eq_state,
+            app_handle: engine_app_handle,
+            sleep_timer_generation: Arc::new(AtomicU64::new(0)),
         })
     }
 
     // === Public API for device control ===
 
     /// List all available audio output devices (from cache)
-    pub fn list_devices(&self) -> Result<Vec<crate::audio::DeviceInfo>, String> {
+    pub fn list_devices(&self) -> Result<Vec<crate::audio::DeviceInfo>, AudioError> {
         self.backend
             .lock()
             .list_devices()
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     /// Refresh device cache from OS and return updated list
-    pub fn refresh_devices(&self) -> Result<Vec<crate::audio::DeviceInfo>, String> {
+    pub fn refresh_devices(&self) -> Result<Vec<crate::audio::DeviceInfo>, AudioError> {
         self.backend
             .lock()
             .refresh_devices()
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     /// Get the current output device
-    pub fn current_device(&self) -> Result<crate::audio::DeviceInfo, String> {
+    pub fn current_device(&self) -> Result<crate::audio::DeviceInfo, AudioError> {
         self.backend
             .lock()
             .current_device()
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     /// Get the OS-level system default output device ID (bypasses manual selection)
@@ -232,31 +598,36 @@ impl AudioEngine {
     }
 
     /// Set the output device by ID
-    pub fn set_output_device(&self, device_id: &str) -> Result<(), String> {
+    pub fn set_output_device(&self, device_id: &str) -> Result<(), AudioError> {
         self.backend
             .lock()
             .set_output_device(device_id)
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
+    }
+
+    /// Drop the manual device selection and follow the system default again
+    pub fn follow_system_default(&self) {
+        self.backend.lock().follow_system_default();
     }
 
     /// Set the sample rate manually
-    pub fn set_sample_rate(&self, rate: u32) -> Result<(), String> {
+    pub fn set_sample_rate(&self, rate: u32) -> Result<(), AudioError> {
         self.backend
             .lock()
             .set_sample_rate(rate)
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     /// Get current sample rate
-    pub fn current_sample_rate(&self) -> Result<u32, String> {
+    pub fn current_sample_rate(&self) -> Result<u32, AudioError> {
         self.backend
             .lock()
             .current_sample_rate()
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     /// Enable/disable exclusive mode (Hog Mode on macOS)
-    pub fn set_exclusive_mode(&self, enabled: bool) -> Result<(), String> {
+    pub fn set_exclusive_mode(&self, enabled: bool) -> Result<(), AudioError> {
         let mode = if enabled {
             ExclusiveMode::Exclusive
         } else {
@@ -265,7 +636,10 @@ impl AudioEngine {
         self.backend
             .lock()
             .set_exclusive_mode(mode)
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)?;
+        // Balance et largeur stéréo ne s'appliquent pas en Hog Mode
+        self.state.output_stage.set_exclusive(enabled);
+        Ok(())
     }
 
     /// Check if exclusive mode is enabled
@@ -274,11 +648,11 @@ impl AudioEngine {
     }
 
     /// Get detailed Hog Mode status
-    pub fn hog_mode_status(&self) -> Result<crate::audio::HogModeStatus, String> {
+    pub fn hog_mode_status(&self) -> Result<crate::audio::HogModeStatus, AudioError> {
         self.backend
             .lock()
             .hog_mode_status()
-            .map_err(|e| e.to_string())
+            .map_err(AudioError::from)
     }
 
     fn audio_thread_main(
@@ -322,19 +696,102 @@ impl AudioEngine {
         use ringbuf::HeapCons;
         let next_consumer: Arc<Mutex<Option<HeapCons<f32>>>> = Arc::new(Mutex::new(None));
         let next_streaming_state: Arc<Mutex<Option<Arc<StreamingState>>>> = Arc::new(Mutex::new(None));
-        let next_session_cmd: Arc<Mutex<Option<Sender<crate::audio_decoder::DecoderCommand>>>> = Arc::new(Mutex::new(None));
+        // Session préchargée côté engine (canal décodeur + état), promue en session
+        // courante quand le callback a effectué la transition gapless
+        let mut next_session: Option<(Sender<crate::audio_decoder::DecoderCommand>, Arc<StreamingState>)> = None;
         let next_path: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
         let gapless_enabled = Arc::new(AtomicBool::new(true));
+        // Preload à relancer après un seek qui a interrompu un crossfade
+        let mut preload_to_reload: Option<String> = None;
 
-        // Rate-limiting pour les seeks (évite le flood)
+        // Seeks : les seeks en attente sont fusionnés (scrub), seul le redémarrage du décodeur
+        // (seek après fin de décodage, coûteux) est limité par un cooldown
         let mut last_seek_time = std::time::Instant::now();
         let mut last_seek_position: f64 = -1.0;  // Dernière position de seek (pour éviter les doublons)
         const SEEK_COOLDOWN_MS: u64 = 50;
-        const SEEK_POSITION_THRESHOLD: f64 = 0.1;  // Ignore les seeks à moins de 100ms de différence
+        const SEEK_POSITION_THRESHOLD: f64 = 0.01;  // Ignore les seeks à moins de 10ms de différence
+        // Seek de redémarrage reporté par le cooldown, rejoué dès qu'il expire : la position
+        // finale d'un scrub est toujours appliquée
+        let mut pending_seek: Option<f64> = None;
+
+        // Position mémorisée des tracks longs : track suivi + dernière sauvegarde
+        let mut position_track: Option<String> = None;
+        let mut last_position_save = std::time::Instant::now();
+        // Dernier recalage du Now Playing (None = à envoyer au prochain tour, ex. après un seek)
+        let mut now_playing_synced: Option<std::time::Instant> = None;
+        // Track pour lequel la file d'attente a déjà été sollicitée (un preload par track)
+        let mut queue_preload_for: Option<String> = None;
+        // Commandes lues pendant la fusion des Play, rejouées avant de relire le canal
+        let mut deferred_commands: VecDeque<AudioCommand> = VecDeque::new();
+        // Début de la pause / inactivité en cours, et DAC rendu après IDLE_RATE_RESTORE_SECS
+        // (stream arrêté, Resume doit le reprendre au rate du track)
+        let mut idle_since: Option<std::time::Instant> = None;
+        let mut dac_released = false;
 
         loop {
-            match command_rx.recv() {
-                Ok(AudioCommand::Play(path, start_position)) => {
+            // === GAPLESS: promotion de la session préchargée ===
+            // Le callback a consommé next_consumer/next_streaming_state → le décodeur du
+            // track préchargé devient la session courante (seek/stop doivent le cibler).
+            if next_session.is_some() && next_streaming_state.lock().is_none() {
+                if let Some(session) = next_session.take() {
+                    Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
+                }
+            }
+
+            if let Some(path) = preload_to_reload.take() {
+                Self::start_preload(&path, &state, &current_stream, &next_consumer, &next_streaming_state, &mut next_session, &next_path);
+            }
+
+            Self::track_playback_position(&state, &current_path, &mut position_track, &mut last_position_save);
+            Self::sync_now_playing_position(&state, &mut now_playing_synced);
+            Self::check_segment_end(&state, &current_stream, &app_handle);
+            Self::check_queue_preload(&state, &current_path, &next_path, &mut queue_preload_for);
+            Self::check_idle_rate_restore(&state, &current_stream, &backend, &mut idle_since, &mut dac_released);
+
+            // Boucle A-B : le callback a atteint B → Seek vers A. last_seek_position et le
+            // cooldown sont remis à zéro : une boucle courte (ou un seek utilisateur juste
+            // avant) ne doit ni filtrer ni reporter le retour en A.
+            let received = if state.ab_loop.take_pending() {
+                last_seek_position = -1.0;
+                last_seek_time = std::time::Instant::now()
+                    .checked_sub(std::time::Duration::from_millis(SEEK_COOLDOWN_MS))
+                    .unwrap_or(last_seek_time);
+                Ok(AudioCommand::Seek(state.ab_loop.start_seconds()))
+            } else if Self::check_read_error(&current_streaming_state, &current_path, &app_handle) {
+                // Fichier devenu illisible : même nettoyage qu'un Stop (stream, session, preload)
+                Ok(AudioCommand::Stop)
+            } else if let Some(time_seconds) = pending_seek
+                .filter(|_| last_seek_time.elapsed().as_millis() as u64 >= SEEK_COOLDOWN_MS)
+            {
+                pending_seek = None;
+                Ok(AudioCommand::Seek(time_seconds))
+            } else if let Some(command) = deferred_commands.pop_front() {
+                Ok(command)
+            } else {
+                // Timeout court : permet la promotion gapless même sans commande entrante
+                // (encore plus court si une boucle A-B est active, pour limiter le dépassement de B,
+                // si une piste CUE doit s'arrêter avant la fin du fichier, ou si un seek est reporté)
+                let precise = state.ab_loop.is_active()
+                    || state.segment.end_ms() > 0
+                    || pending_seek.is_some();
+                let timeout_ms = if precise { 5 } else { 100 };
+                command_rx.recv_timeout(std::time::Duration::from_millis(timeout_ms))
+            };
+
+            // Double-clic : seul le Play le plus récent est joué (pas de stream zombie)
+            // Scrub : seul le Seek le plus récent d'une rafale est appliqué
+            let received = match received {
+                Ok(play @ AudioCommand::Play(..)) => {
+                    Ok(Self::coalesce_play(play, &command_rx, &mut deferred_commands))
+                }
+                Ok(AudioCommand::Seek(time_seconds)) => {
+                    Ok(AudioCommand::Seek(Self::coalesce_seek(time_seconds, &command_rx, &mut deferred_commands)))
+                }
+                other => other,
+            };
+
+            match received {
+                Ok(AudioCommand::Play(path, start_position, segment)) => {
                     let start_time = std::time::Instant::now();
                     // ── [TIMING ENG-0] Commande Play reçue par le thread audio ──
                     println!("[SMB TIMING] ENG+0ms   — AudioCommand::Play received: {}",
@@ -342,11 +799,25 @@ impl AudioEngine {
                     #[cfg(debug_assertions)]
                     println!("=== Starting playback: {} at {:?}s ===", path, start_position);
 
+                    // Nouveau track : la boucle A-B et un seek reporté de l'ancien ne s'appliquent plus
+                    state.ab_loop.clear();
+                    pending_seek = None;
+                    dac_released = false;
+                    state.buffer_health.reset();
+                    match segment {
+                        Some((start, end)) => state.segment.set(start, end),
+                        None => state.segment.clear(),
+                    }
+
+                    // Mémorise où on en était dans le track précédent
+                    if let Some(previous) = position_track.take() {
+                        crate::remember_playback_position(&previous, state.get_position_seconds(), state.get_duration_seconds());
+                    }
+
                     // Clear gapless preload (manual play cancels it)
-                    *next_consumer.lock() = None;
-                    *next_streaming_state.lock() = None;
-                    *next_session_cmd.lock() = None;
-                    *next_path.lock() = None;
+                    if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                        Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
+                    }
 
                     // Reset de l'état de lecture AVANT tout
                     state.is_playing.store(false, Ordering::Relaxed);
@@ -381,6 +852,7 @@ impl AudioEngine {
 
                     // Sauvegarde le chemin
                     *current_path.lock() = Some(path.clone());
+                    queue_preload_for = None;
 
                     // Émet loading
                     if let Some(ref app) = app_handle {
@@ -396,7 +868,12 @@ impl AudioEngine {
                         Err(e) => {
                             eprintln!("Failed to probe file: {}", e);
                             if let Some(ref app) = app_handle {
-                                emit_error(app, "file_probe_failed", "Unreadable or corrupted audio file", &e);
+                                if crate::dsd::is_dsd_path(std::path::Path::new(&path)) {
+                                    // Variantes DSD non gérées (DST, DSD > 6 canaux, rate exotique)
+                                    emit_error(app, "unsupported_codec", "Unsupported DSD format", &e);
+                                } else {
+                                    emit_error(app, "file_probe_failed", "Unreadable or corrupted audio file", &e);
+                                }
                                 let _ = app.emit("playback_loading", false);
                             }
                             continue;
@@ -423,7 +900,9 @@ impl AudioEngine {
                     println!("[SMB TIMING] ENG+{}ms — prepare_for_streaming START ({}Hz)",
                         start_time.elapsed().as_millis(), source_info.sample_rate);
                     // 3. Use backend to prepare device for streaming (changes sample rate if possible)
-                    let stream_config = StreamConfig::stereo(source_info.sample_rate);
+                    // Un rate verrouillé remplace le rate source : tout est resamplé vers lui
+                    let requested_rate = state.requested_output_rate(source_info.sample_rate);
+                    let stream_config = StreamConfig::stereo(requested_rate);
                     let (optimal_rate, is_bit_perfect) = {
                         let mut backend_guard = backend.lock();
                         match backend_guard.prepare_for_streaming(&stream_config) {
@@ -431,13 +910,14 @@ impl AudioEngine {
                                 let bit_perfect = actual_rate == source_info.sample_rate;
                                 #[cfg(debug_assertions)]
                                 println!("[Backend] Device prepared: {} Hz (requested: {} Hz, bit-perfect: {})",
-                                    actual_rate, source_info.sample_rate, bit_perfect);
+                                    actual_rate, requested_rate, bit_perfect);
                                 (actual_rate, bit_perfect)
                             }
                             Err(e) => {
                                 eprintln!("[Backend] Failed to prepare device: {}. Using fallback.", e);
                                 // Fallback: use backend's info
-                                find_best_output_rate_from_backend(source_info.sample_rate, &mut *backend_guard)
+                                let (rate, _) = find_best_output_rate_from_backend(requested_rate, &mut *backend_guard);
+                                (rate, rate == source_info.sample_rate)
                             }
                         }
                     };
@@ -454,21 +934,23 @@ impl AudioEngine {
                         source_info.sample_rate, optimal_rate, is_bit_perfect, needs_resampling);
 
                     // ── [TIMING ENG-5] Démarrage streaming + pre-roll ────────
-                    println!("[SMB TIMING] ENG+{}ms — start_streaming_with_config START (pre-roll wait…)",
+                    println!("[SMB TIMING] ENG+{}ms — start_streaming_with_speed START (pre-roll wait…)",
                         start_time.elapsed().as_millis());
                     // 3. Démarre le streaming avec le source rate ET le target rate
-                    let session_result = start_streaming_with_config(
+                    let session_result = start_streaming_with_speed(
                         &path,
                         start_position.unwrap_or(0.0),
                         source_info.sample_rate,  // sample rate source (de probe_audio_file)
                         target_rate,               // sample rate cible (None = bit-perfect)
+                        state.get_playback_speed(),
+                        *state.buffer_config.lock(),
                     );
 
                     match session_result {
                         Ok(mut session) => {
                             let init_time = start_time.elapsed();
                             // ── [TIMING ENG-6] Pre-roll atteint, session prête ──
-                            println!("[SMB TIMING] ENG+{}ms — start_streaming_with_config DONE (pre-roll ready in {:?})",
+                            println!("[SMB TIMING] ENG+{}ms — start_streaming_with_speed DONE (pre-roll ready in {:?})",
                                 start_time.elapsed().as_millis(), init_time);
                             #[cfg(debug_assertions)]
                             println!("Streaming session ready in {:?}", init_time);
@@ -496,7 +978,8 @@ impl AudioEngine {
                                 *current_streaming_state.lock() = Some(Arc::clone(&session.state));
 
                                 // Crée le stream de sortie CoreAudio (PURE COREAUDIO - no CPAL!)
-                                let stream_config = AudioStreamConfig::new(output_sample_rate, channels as u16);
+                                let stream_config = AudioStreamConfig::new(output_sample_rate, channels as u16)
+                                    .with_integer_bits(integer_output_bits(&state, &backend));
                                 let stream_result = create_audio_stream(
                                     device_id,  // Pass device ID for direct CoreAudio routing
                                     stream_config,
@@ -514,6 +997,13 @@ impl AudioEngine {
                                     Arc::clone(&state.rms_energy),
                                     Arc::clone(&current_path),
                                     Arc::clone(&next_path),
+                                    Arc::clone(&state.crossfade_ms),
+                                    Arc::clone(&state.crossfade_active),
+                                    Arc::clone(&state.ab_loop),
+                                    Arc::clone(&state.segment),
+                                    Arc::clone(&state.spectrum),
+                                    Arc::clone(&state.output_stage),
+                                    Arc::clone(&state.buffer_health),
                                 );
 
                                 match stream_result {
@@ -526,6 +1016,7 @@ impl AudioEngine {
                                         } else {
                                             state.is_playing.store(true, Ordering::Relaxed);
                                             state.is_paused.store(false, Ordering::Relaxed);
+                                            state.set_stream_format(&session.state.info);
                                             *current_stream.lock() = Some(s);
                                         // ── [TIMING ENG-7] SON DÉMARRÉ ───────────────────────
                                         println!("[SMB TIMING] ENG+{}ms — ✅ AUDIO STARTED (stream.start() OK) ← total engine: {}ms",
@@ -572,7 +1063,11 @@ impl AudioEngine {
                         Err(e) => {
                             eprintln!("Failed to start streaming: {}", e);
                             if let Some(ref app) = app_handle {
-                                emit_error(app, "decode_failed", "File decoding error", &e);
+                                if e.starts_with(crate::audio_decoder::UNSUPPORTED_CODEC_ERROR) {
+                                    emit_error(app, "unsupported_codec", "Unsupported audio codec", &e);
+                                } else {
+                                    emit_error(app, "decode_failed", "File decoding error", &e);
+                                }
                                 let _ = app.emit("playback_loading", false);
                             }
                         }
@@ -580,6 +1075,13 @@ impl AudioEngine {
                 }
 
                 Ok(AudioCommand::Pause) => {
+                    if let Some(ref path) = position_track {
+                        crate::remember_playback_position(path, state.get_position_seconds(), state.get_duration_seconds());
+                    }
+                    if let Some(path) = current_path.lock().clone() {
+                        let (shuffle, repeat) = state.queue_modes();
+                        crate::remember_session(&path, state.get_position_seconds(), shuffle, repeat);
+                    }
                     if let Some(ref mut stream) = *current_stream.lock() {
                         let _ = stream.pause();
                         state.is_paused.store(true, Ordering::Relaxed);
@@ -592,6 +1094,15 @@ impl AudioEngine {
 
                 Ok(AudioCommand::Resume) => {
                     if let Some(ref mut stream) = *current_stream.lock() {
+                        // DAC rendu pendant une longue pause : on le reprend au rate du stream
+                        if std::mem::take(&mut dac_released) {
+                            if let Err(e) = backend.lock().prepare_for_streaming(&StreamConfig::stereo(stream.sample_rate())) {
+                                eprintln!("[AudioEngine] Resume: Failed to prepare device: {}", e);
+                            }
+                            if let Err(e) = stream.start() {
+                                eprintln!("[AudioEngine] Resume: Failed to restart stream: {}", e);
+                            }
+                        }
                         let _ = stream.resume();
                         state.is_paused.store(false, Ordering::Relaxed);
                         // Notifie le frontend
@@ -602,6 +1113,20 @@ impl AudioEngine {
                 }
 
                 Ok(AudioCommand::Stop) => {
+                    state.ab_loop.clear();
+                    pending_seek = None;
+                    dac_released = false;
+                    state.segment.clear();
+                    if let Some(path) = position_track.take() {
+                        crate::remember_playback_position(&path, state.get_position_seconds(), state.get_duration_seconds());
+                    }
+                    if let Some(path) = current_path.lock().clone() {
+                        let (shuffle, repeat) = state.queue_modes();
+                        crate::remember_session(&path, state.get_position_seconds(), shuffle, repeat);
+                    }
+                    if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                        Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
+                    }
                     {
                         let mut stream_guard = current_stream.lock();
                         if let Some(mut stream) = stream_guard.take() {
@@ -623,15 +1148,21 @@ impl AudioEngine {
                     state.is_playing.store(false, Ordering::Relaxed);
                     state.is_paused.store(false, Ordering::Relaxed);
                     state.position.store(0, Ordering::Relaxed);
+                    *state.stream_format.lock() = None;
+
+                    // Rend le DAC aux autres apps à son rate d'origine (évite que les sons
+                    // système soient upsamplés à 192kHz). Pause garde le rate pour un resume instantané,
+                    // sauf au-delà de IDLE_RATE_RESTORE_SECS (check_idle_rate_restore).
+                    if state.restore_rate_on_stop.load(Ordering::Relaxed) {
+                        if let Err(e) = backend.lock().restore_sample_rate() {
+                            eprintln!("[AudioEngine] Stop: Failed to restore sample rate: {}", e);
+                        }
+                    }
                 }
 
                 Ok(AudioCommand::Seek(time_seconds)) => {
-                    // Rate-limiting : ignore les seeks trop rapprochés (< 50ms)
-                    let elapsed = last_seek_time.elapsed().as_millis() as u64;
-                    if elapsed < SEEK_COOLDOWN_MS {
-                        println!("Engine: Seek rate-limited ({} ms since last, ignoring)", elapsed);
-                        continue;
-                    }
+                    // Un seek plus récent remplace celui qui attendait la fin du cooldown
+                    pending_seek = None;
 
                     // Ignore les seeks à la même position (évite les doublons)
                     if (time_seconds - last_seek_position).abs() < SEEK_POSITION_THRESHOLD {
@@ -639,11 +1170,17 @@ impl AudioEngine {
                         continue;
                     }
 
-                    last_seek_time = std::time::Instant::now();
-                    last_seek_position = time_seconds;
-
                     println!("Engine: Seek request to {:.2}s", time_seconds);
 
+                    // Un seek reste instantané : il coupe le crossfade en cours. Le début du
+                    // track préchargé a déjà été consommé par le fondu → on le recharge après le seek.
+                    if state.crossfade_active.load(Ordering::Relaxed) {
+                        preload_to_reload = next_path.lock().clone();
+                        if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                            Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
+                        }
+                    }
+
                     // Vérifie si le décodage est terminé - si oui, on doit relancer la lecture
                     let decoding_complete = current_streaming_state.lock()
                         .as_ref()
@@ -651,6 +1188,17 @@ impl AudioEngine {
                         .unwrap_or(true);
 
                     if decoding_complete {
+                        // Redémarrage coûteux : un seek trop rapproché (< 50ms) est reporté,
+                        // pas ignoré, pour que la dernière position d'un scrub soit appliquée
+                        let elapsed = last_seek_time.elapsed().as_millis() as u64;
+                        if elapsed < SEEK_COOLDOWN_MS {
+                            println!("Engine: Restart seek deferred ({} ms since last)", elapsed);
+                            pending_seek = Some(time_seconds);
+                            continue;
+                        }
+                        last_seek_time = std::time::Instant::now();
+                        last_seek_position = time_seconds;
+
                         // Le décodeur est terminé, on doit relancer la lecture à cette position
                         if let Some(path) = current_path.lock().clone() {
                             println!("Engine: Decoder finished, restarting at {:.2}s", time_seconds);
@@ -698,7 +1246,8 @@ impl AudioEngine {
                             let device_id = backend.lock().get_device_id();
 
                             // Use backend to prepare device (sample rate already set, just verify)
-                            let stream_config = StreamConfig::stereo(source_info.sample_rate);
+                            let requested_rate = state.requested_output_rate(source_info.sample_rate);
+                            let stream_config = StreamConfig::stereo(requested_rate);
                             let (optimal_rate, is_bit_perfect) = {
                                 let mut backend_guard = backend.lock();
                                 match backend_guard.prepare_for_streaming(&stream_config) {
@@ -707,13 +1256,14 @@ impl AudioEngine {
                                         (actual_rate, bit_perfect)
                                     }
                                     Err(_) => {
-                                        find_best_output_rate_from_backend(source_info.sample_rate, &mut *backend_guard)
+                                        let (rate, _) = find_best_output_rate_from_backend(requested_rate, &mut *backend_guard);
+                                        (rate, rate == source_info.sample_rate)
                                     }
                                 }
                             };
                             let target_rate = if !is_bit_perfect { Some(optimal_rate) } else { None };
 
-                            match start_streaming_with_config(&path, time_seconds, source_info.sample_rate, target_rate) {
+                            match start_streaming_with_speed(&path, time_seconds, source_info.sample_rate, target_rate, state.get_playback_speed(), *state.buffer_config.lock()) {
                                 Ok(mut session) => {
                                     let output_sample_rate = session.state.info.output_sample_rate;
                                     let source_sample_rate = session.state.info.sample_rate;
@@ -730,7 +1280,8 @@ impl AudioEngine {
                                         *current_streaming_state.lock() = Some(Arc::clone(&session.state));
 
                                         // Crée le stream CoreAudio (PURE COREAUDIO - no CPAL)
-                                        let stream_config = AudioStreamConfig::new(output_sample_rate, channels as u16);
+                                        let stream_config = AudioStreamConfig::new(output_sample_rate, channels as u16)
+                                            .with_integer_bits(integer_output_bits(&state, &backend));
                                         match create_audio_stream(
                                             device_id,  // Pass device ID for direct CoreAudio routing
                                             stream_config,
@@ -748,6 +1299,13 @@ impl AudioEngine {
                                             Arc::clone(&state.rms_energy),
                                             Arc::clone(&current_path),
                                             Arc::clone(&next_path),
+                                            Arc::clone(&state.crossfade_ms),
+                                            Arc::clone(&state.crossfade_active),
+                                            Arc::clone(&state.ab_loop),
+                                            Arc::clone(&state.segment),
+                                            Arc::clone(&state.spectrum),
+                                            Arc::clone(&state.output_stage),
+                                            Arc::clone(&state.buffer_health),
                                         ) {
                                             Ok(mut s) => {
                                                 if let Err(e) = s.start() {
@@ -758,7 +1316,9 @@ impl AudioEngine {
                                                 } else {
                                                     state.is_playing.store(true, Ordering::Relaxed);
                                                     state.is_paused.store(false, Ordering::Relaxed);
+                                                    state.set_stream_format(&session.state.info);
                                                     *current_stream.lock() = Some(s);
+                                                    dac_released = false;
 
                                                     // Émet les specs audio après seek/restart
                                                     if let Some(ref app) = app_handle {
@@ -799,7 +1359,8 @@ impl AudioEngine {
                         }
                     }
 
-                    // Cas normal : le décodeur tourne encore
+                    // Cas normal : le décodeur tourne encore (seek peu coûteux, pas de cooldown)
+                    last_seek_position = time_seconds;
                     state.is_seeking.store(true, Ordering::Relaxed);
 
                     // Met à jour la position immédiatement pour le frontend
@@ -871,16 +1432,17 @@ impl AudioEngine {
                     }
 
                     // Maintenant le seek est vraiment terminé
-                    let duration_seconds = state.get_duration_seconds();
+                    let duration_seconds = state.track_duration_seconds();
                     let rms = f64::from_bits(state.rms_energy.load(Ordering::Relaxed));
                     if let Some(ref app) = app_handle {
                         let _ = app.emit("playback_progress", PlaybackProgress {
-                            position: time_seconds,
+                            position: state.segment.relative_seconds(time_seconds),
                             duration: duration_seconds,
                             rms,
                         });
                         println!("Engine: Seek complete, emitted progress: pos={:.2}s", time_seconds);
                     }
+                    now_playing_synced = None;
 
                     state.is_seeking.store(false, Ordering::Relaxed);
                 }
@@ -896,107 +1458,764 @@ impl AudioEngine {
                     println!("[Gapless] Preloading next: {}", path);
 
                     // Clear previous preload
-                    *next_consumer.lock() = None;
-                    *next_streaming_state.lock() = None;
-                    *next_session_cmd.lock() = None;
+                    if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                        Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
+                    }
 
-                    // Probe the file
-                    let source_info = match crate::audio_decoder::probe_audio_file(&path) {
-                        Ok(info) => info,
-                        Err(e) => {
-                            eprintln!("[Gapless] Failed to probe next file: {}", e);
-                            continue;
+                    Self::start_preload(&path, &state, &current_stream, &next_consumer, &next_streaming_state, &mut next_session, &next_path);
+                }
+
+                Ok(AudioCommand::SetGapless(enabled)) => {
+                    gapless_enabled.store(enabled, Ordering::Relaxed);
+                    if !enabled {
+                        // Clear preloaded data
+                        if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                            Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
                         }
-                    };
+                    }
+                    println!("[Gapless] {}", if enabled { "Enabled" } else { "Disabled" });
+                }
 
-                    // Use the CURRENT stream's output rate for the next track
-                    // to avoid sample rate mismatch during gapless transition
-                    let current_output_rate = state.sample_rate.load(Ordering::Relaxed) as u32;
-                    let target_rate = if source_info.sample_rate != current_output_rate {
-                        Some(current_output_rate)
+                Ok(AudioCommand::SetCrossfade(duration_ms)) => {
+                    let duration_ms = duration_ms.min(MAX_CROSSFADE_MS);
+                    state.crossfade_ms.store(duration_ms as u64, Ordering::Relaxed);
+                    println!("[Crossfade] {}", if duration_ms > 0 {
+                        format!("{} ms", duration_ms)
                     } else {
-                        None
-                    };
+                        "Disabled".to_string()
+                    });
+                }
 
-                    match start_streaming_with_config(&path, 0.0, source_info.sample_rate, target_rate) {
-                        Ok(mut session) => {
-                            if let Some(consumer) = session.take_consumer() {
-                                *next_consumer.lock() = Some(consumer);
-                                *next_streaming_state.lock() = Some(Arc::clone(&session.state));
-                                *next_session_cmd.lock() = Some(session.command_tx.clone());
-                                *next_path.lock() = Some(path.clone());
-                                println!("[Gapless] Next track preloaded: {} ({}Hz → {}Hz)",
-                                    path, source_info.sample_rate, session.state.info.output_sample_rate);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("[Gapless] Failed to preload: {}", e);
+                Ok(AudioCommand::SetPlaybackSpeed(speed)) => {
+                    state.set_playback_speed(speed);
+                    let speed = state.get_playback_speed();
+                    if let Some(ref streaming_state) = *current_streaming_state.lock() {
+                        streaming_state.set_speed(speed);
+                    }
+
+                    // Le preload a été décodé à l'ancienne vitesse → on le recharge
+                    let preloaded = next_path.lock().clone();
+                    if let Some(path) = preloaded {
+                        if let Some(session) = Self::clear_preload(&next_consumer, &next_streaming_state, &mut next_session, &next_path) {
+                            Self::promote_gapless_session(session, &state, &current_session_cmd, &current_streaming_state, &app_handle);
                         }
+                        preload_to_reload = Some(path);
                     }
+
+                    // Le Seek envoyé juste après (même position) vide le buffer et fait
+                    // reconstruire le resampler : il ne doit être ni filtré ni reporté
+                    last_seek_position = -1.0;
+                    last_seek_time = std::time::Instant::now()
+                        .checked_sub(std::time::Duration::from_millis(SEEK_COOLDOWN_MS))
+                        .unwrap_or(last_seek_time);
+                    println!("[Speed] {:.2}x", speed);
                 }
 
-                Ok(AudioCommand::SetGapless(enabled)) => {
-                    gapless_enabled.store(enabled, Ordering::Relaxed);
-                    if !enabled {
-                        // Clear preloaded data
-                        *next_consumer.lock() = None;
-                        *next_streaming_state.lock() = None;
-                        *next_session_cmd.lock() = None;
-                        *next_path.lock() = None;
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// Vide le canal et ne garde que le dernier Play en attente. Ce qui le précède
+    /// (Play, Seek, Pause/Stop, preload) visait un track abandonné et est jeté, sauf
+    /// les réglages (volume, gapless, crossfade, vitesse) ; ce qui le suit est rejoué
+    /// dans l'ordre via `deferred`.
+    fn coalesce_play(
+        play: AudioCommand,
+        command_rx: &Receiver<AudioCommand>,
+        deferred: &mut VecDeque<AudioCommand>,
+    ) -> AudioCommand {
+        let mut latest = play;
+        let mut discarded = 0;
+
+        while let Ok(command) = command_rx.try_recv() {
+            match command {
+                AudioCommand::Play(..) => {
+                    latest = command;
+                    discarded += 1;
+                    deferred.retain(|c| matches!(
+                        c,
+                        AudioCommand::SetVolume(_)
+                            | AudioCommand::SetGapless(_)
+                            | AudioCommand::SetCrossfade(_)
+                            | AudioCommand::SetPlaybackSpeed(_)
+                    ));
+                }
+                other => deferred.push_back(other),
+            }
+        }
+
+        if discarded > 0 {
+            println!("[AudioEngine] Coalesced Play: {} superseded command(s) skipped", discarded);
+        }
+        latest
+    }
+
+    /// Fusionne les Seek consécutifs en attente (drag de la barre de progression) : seule
+    /// la dernière position est appliquée. S'arrête à la première autre commande, rejouée
+    /// via `deferred` : un Seek qui suit un Play vise le nouveau track.
+    fn coalesce_seek(
+        time_seconds: f64,
+        command_rx: &Receiver<AudioCommand>,
+        deferred: &mut VecDeque<AudioCommand>,
+    ) -> f64 {
+        let mut latest = time_seconds;
+        let mut skipped = 0;
+
+        while let Some(&AudioCommand::Seek(next)) = deferred.front() {
+            deferred.pop_front();
+            latest = next;
+            skipped += 1;
+        }
+        if deferred.is_empty() {
+            while let Ok(command) = command_rx.try_recv() {
+                match command {
+                    AudioCommand::Seek(next) => {
+                        latest = next;
+                        skipped += 1;
+                    }
+                    other => {
+                        deferred.push_back(other);
+                        break;
                     }
-                    println!("[Gapless] {}", if enabled { "Enabled" } else { "Disabled" });
                 }
+            }
+        }
+
+        if skipped > 0 {
+            println!("[AudioEngine] Coalesced Seek: {} intermediate position(s) skipped", skipped);
+        }
+        latest
+    }
+
+    /// Démarre le décodage du prochain track et le range dans les slots gapless
+    /// (consumer/state lus par le callback, session gardée côté engine).
+    fn start_preload(
+        path: &str,
+        state: &PlaybackState,
+        current_stream: &Mutex<Option<Box<dyn AudioOutputStream>>>,
+        next_consumer: &Mutex<Option<ringbuf::HeapCons<f32>>>,
+        next_streaming_state: &Mutex<Option<Arc<StreamingState>>>,
+        next_session: &mut Option<(Sender<crate::audio_decoder::DecoderCommand>, Arc<StreamingState>)>,
+        next_path: &Mutex<Option<String>>,
+    ) {
+        // Probe the file
+        let source_info = match crate::audio_decoder::probe_audio_file(path) {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("[Gapless] Failed to probe next file: {}", e);
+                return;
+            }
+        };
+
+        // Use the CURRENT stream's output rate for the next track
+        // to avoid sample rate mismatch during gapless transition
+        let current_output_rate = state.sample_rate.load(Ordering::Relaxed) as u32;
+        let target_rate = if source_info.sample_rate != current_output_rate {
+            Some(current_output_rate)
+        } else {
+            None
+        };
+
+        match start_streaming_with_speed(path, 0.0, source_info.sample_rate, target_rate, state.get_playback_speed(), *state.buffer_config.lock()) {
+            Ok(mut session) => {
+                // La transition n'est possible que si le track préchargé sort au même
+                // format que le stream courant. Sinon : pas de preload, le frontend
+                // enchaînera avec un Play normal sur playback_ended.
+                let stream_format = current_stream.lock()
+                    .as_ref()
+                    .map(|s| (s.sample_rate(), s.channels()));
+                let next_format = (
+                    session.state.info.output_sample_rate,
+                    session.state.info.channels as u16,
+                );
+                if stream_format != Some(next_format) {
+                    println!("[Gapless] Format mismatch (stream {:?}, next {:?}) — preload skipped",
+                        stream_format, next_format);
+                    session.stop();
+                    return;
+                }
+
+                if let Some(consumer) = session.take_consumer() {
+                    *next_consumer.lock() = Some(consumer);
+                    *next_streaming_state.lock() = Some(Arc::clone(&session.state));
+                    *next_session = Some((session.command_tx.clone(), Arc::clone(&session.state)));
+                    *next_path.lock() = Some(path.to_string());
+                    println!("[Gapless] Next track preloaded: {} ({}Hz → {}Hz)",
+                        path, source_info.sample_rate, session.state.info.output_sample_rate);
+                }
+            }
+            Err(e) => {
+                eprintln!("[Gapless] Failed to preload: {}", e);
+            }
+        }
+    }
 
-                Err(_) => break,
+    /// Annule le preload gapless : vide les slots partagés avec le callback
+    /// et arrête le décodeur du track préchargé (sinon il reste bloqué sur un ring plein).
+    ///
+    /// Les slots sont verrouillés dans le même ordre que le callback. Si celui-ci a
+    /// déjà basculé sur le preload, la session est retournée pour être promue
+    /// au lieu d'être arrêtée.
+    fn clear_preload(
+        next_consumer: &Mutex<Option<ringbuf::HeapCons<f32>>>,
+        next_streaming_state: &Mutex<Option<Arc<StreamingState>>>,
+        next_session: &mut Option<(Sender<crate::audio_decoder::DecoderCommand>, Arc<StreamingState>)>,
+        next_path: &Mutex<Option<String>>,
+    ) -> Option<(Sender<crate::audio_decoder::DecoderCommand>, Arc<StreamingState>)> {
+        let pending = {
+            let mut cons_guard = next_consumer.lock();
+            let mut state_guard = next_streaming_state.lock();
+            let pending = state_guard.take().is_some();
+            *cons_guard = None;
+            *next_path.lock() = None;
+            pending
+        };
+        match next_session.take() {
+            Some((tx, _)) if pending => {
+                let _ = tx.send(crate::audio_decoder::DecoderCommand::Stop);
+                None
             }
+            switched => switched,
+        }
+    }
+
+    /// Recale la position du Now Playing (Centre de contrôle, lock screen) sur celle
+    /// du PlaybackState toutes les NOW_PLAYING_SYNC_SECS, et tout de suite après un seek.
+    /// En pause, c'est le JS qui publie l'état via update_media_playback_state.
+    fn sync_now_playing_position(state: &PlaybackState, last_sync: &mut Option<std::time::Instant>) {
+        if !state.is_playing.load(Ordering::Relaxed) || state.is_paused.load(Ordering::Relaxed) {
+            *last_sync = None;
+            return;
+        }
+        if last_sync.is_some_and(|t| t.elapsed().as_secs() < NOW_PLAYING_SYNC_SECS) {
+            return;
         }
+        crate::media_controls::update_playback_state(true, Some(state.track_position_seconds()));
+        *last_sync = Some(std::time::Instant::now());
+    }
+
+    /// Suit le track en cours pour le cache de positions (tracks longs) et la session :
+    /// sauvegarde toutes les POSITION_SAVE_INTERVAL_SECS, oubli quand le track est lu jusqu'au bout.
+    /// Play/Stop retirent le suivi avant de toucher à l'état : un arrêt vu ici est une fin de track.
+    fn track_playback_position(
+        state: &PlaybackState,
+        current_path: &Mutex<Option<String>>,
+        tracked: &mut Option<String>,
+        last_save: &mut std::time::Instant,
+    ) {
+        let playing_path = current_path.lock().clone();
+        let is_playing = state.is_playing.load(Ordering::Relaxed);
+
+        match (tracked.as_deref(), playing_path.as_deref()) {
+            (Some(old), Some(new)) if old != new => {
+                // Transition gapless : l'ancien track est terminé
+                crate::forget_playback_position(old);
+                *tracked = playing_path;
+                *last_save = std::time::Instant::now();
+            }
+            (Some(old), _) if !is_playing => {
+                // playback_ended
+                crate::forget_playback_position(old);
+                *tracked = None;
+            }
+            (None, Some(_)) if is_playing => {
+                *tracked = playing_path;
+                *last_save = std::time::Instant::now();
+            }
+            _ => {}
+        }
+
+        if let Some(ref path) = *tracked {
+            if !state.is_paused.load(Ordering::Relaxed)
+                && last_save.elapsed().as_secs() >= POSITION_SAVE_INTERVAL_SECS
+            {
+                crate::remember_playback_position(path, state.get_position_seconds(), state.get_duration_seconds());
+                let (shuffle, repeat) = state.queue_modes();
+                crate::remember_session(path, state.get_position_seconds(), shuffle, repeat);
+                *last_save = std::time::Instant::now();
+            }
+        }
+    }
+
+    /// Fait de la session préchargée la session courante après une transition gapless.
+    fn promote_gapless_session(
+        session: (Sender<crate::audio_decoder::DecoderCommand>, Arc<StreamingState>),
+        state: &PlaybackState,
+        current_session_cmd: &Mutex<Option<Sender<crate::audio_decoder::DecoderCommand>>>,
+        current_streaming_state: &Mutex<Option<Arc<StreamingState>>>,
+        app_handle: &Option<AppHandle>,
+    ) {
+        let (tx, new_state) = session;
+        #[cfg(debug_assertions)]
+        println!("[Gapless] Promoting preloaded session to current");
+
+        state.duration.store((new_state.info.duration_seconds * 1000.0) as u64, Ordering::Relaxed);
+        state.set_stream_format(&new_state.info);
+        *current_session_cmd.lock() = Some(tx);
+        *current_streaming_state.lock() = Some(Arc::clone(&new_state));
+
+        if let Some(app) = app_handle {
+            let info = &new_state.info;
+            let _ = app.emit("playback_audio_specs", AudioSpecs {
+                source_sample_rate: info.sample_rate,
+                source_bit_depth: info.bit_depth,
+                source_channels: info.channels as u16,
+                output_sample_rate: info.output_sample_rate,
+                output_channels: info.channels as u16,
+                is_mismatch: info.sample_rate != info.output_sample_rate,
+            });
+        }
+
+        crate::queue_gapless_transition();
+    }
+
+    /// Fin du track proche et rien de préchargé : la file d'attente précharge son track suivant
+    fn check_queue_preload(
+        state: &PlaybackState,
+        current_path: &Mutex<Option<String>>,
+        next_path: &Mutex<Option<String>>,
+        requested_for: &mut Option<String>,
+    ) {
+        if !state.is_playing.load(Ordering::Relaxed) || state.is_seeking.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(path) = current_path.lock().clone() else {
+            return;
+        };
+        if requested_for.as_deref() == Some(path.as_str()) || next_path.lock().is_some() {
+            return;
+        }
+        // Fin du segment pour une piste CUE
+        let duration_ms = (state.track_duration_seconds() * 1000.0) as u64;
+        let remaining_ms = duration_ms.saturating_sub((state.track_position_seconds() * 1000.0) as u64);
+        if duration_ms == 0 || remaining_ms > QUEUE_PRELOAD_THRESHOLD_MS {
+            return;
+        }
+        crate::preload_upcoming_queue_track();
+        *requested_for = Some(path);
+    }
+
+    /// Perte du focus audio : CoreAudio ne signale pas qu'une autre app veut le DAC, et
+    /// l'AudioUnit tourne encore pendant une pause. Une pause (ou une fin de lecture sans Stop)
+    /// qui dure plus de IDLE_RATE_RESTORE_SECS en tient lieu : le stream est arrêté et le DAC
+    /// rendu à son rate d'origine, comme sur Stop. Le stream et la session restent en place
+    /// pour que Resume reparte à la même position.
+    fn check_idle_rate_restore(
+        state: &PlaybackState,
+        current_stream: &Mutex<Option<Box<dyn AudioOutputStream>>>,
+        backend: &Mutex<Box<dyn AudioBackend>>,
+        idle_since: &mut Option<std::time::Instant>,
+        dac_released: &mut bool,
+    ) {
+        let idle = state.is_paused.load(Ordering::Relaxed) || !state.is_playing.load(Ordering::Relaxed);
+        let mut stream_guard = current_stream.lock();
+        let Some(stream) = stream_guard.as_mut().filter(|_| idle && !*dac_released) else {
+            *idle_since = None;
+            return;
+        };
+        if !state.restore_rate_on_stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let since = *idle_since.get_or_insert_with(std::time::Instant::now);
+        if since.elapsed() < std::time::Duration::from_secs(IDLE_RATE_RESTORE_SECS) {
+            return;
+        }
+
+        println!("[AudioEngine] Idle for {}s: releasing the DAC", IDLE_RATE_RESTORE_SECS);
+        // stop() remet is_playing à false : l'état affiché (en pause) ne change pas
+        let was_playing = state.is_playing.load(Ordering::Relaxed);
+        let _ = stream.stop();
+        state.is_playing.store(was_playing, Ordering::Relaxed);
+        if let Err(e) = backend.lock().restore_sample_rate() {
+            eprintln!("[AudioEngine] Idle: Failed to restore sample rate: {}", e);
+        }
+        *idle_since = None;
+        *dac_released = true;
+    }
+
+    /// Piste virtuelle (CUE) : la position a atteint la fin du segment → même issue
+    /// qu'une fin de fichier (stream en pause, playback_ended, le frontend enchaîne)
+    fn check_segment_end(
+        state: &PlaybackState,
+        current_stream: &Mutex<Option<Box<dyn AudioOutputStream>>>,
+        app_handle: &Option<AppHandle>,
+    ) {
+        let end_ms = state.segment.end_ms();
+        if end_ms == 0
+            || !state.is_playing.load(Ordering::Relaxed)
+            || state.is_seeking.load(Ordering::Relaxed)
+            || state.position.load(Ordering::Relaxed) < end_ms
+        {
+            return;
+        }
+        // Le segment reste en place (position et durée relatives) jusqu'au prochain Play/Stop ;
+        // is_playing à false évite de signaler la fin deux fois
+        if let Some(ref mut stream) = *current_stream.lock() {
+            let _ = stream.pause();
+        }
+        state.is_playing.store(false, Ordering::Relaxed);
+        if let Some(ref app) = app_handle {
+            let _ = app.emit("playback_ended", ());
+        }
+    }
+
+    /// Le décodeur a abandonné le track courant sur des erreurs de lecture répétées
+    /// (clé USB retirée, NAS déconnecté) : erreur structurée + playback_interrupted, sans
+    /// attendre que le buffer se vide (playback_ended ferait enchaîner la suite)
+    fn check_read_error(
+        current_streaming_state: &Mutex<Option<Arc<StreamingState>>>,
+        current_path: &Mutex<Option<String>>,
+        app_handle: &Option<AppHandle>,
+    ) -> bool {
+        let failed = current_streaming_state.lock().as_ref()
+            .is_some_and(|s| s.read_error.swap(false, Ordering::AcqRel));
+        if !failed {
+            return false;
+        }
+        let path = current_path.lock().clone().unwrap_or_default();
+        if let Some(ref app) = app_handle {
+            emit_error(app, "file_read_error", "Playback interrupted — device disconnected?", &path);
+            let _ = app.emit("playback_interrupted", &path);
+        }
+        true
     }
 
     // === API Publique ===
 
-    pub fn play(&self, path: &str) -> Result<(), String> {
-        self.command_tx.send(AudioCommand::Play(path.to_string(), None))
-            .map_err(|e| e.to_string())
+    pub fn play(&self, path: &str) -> Result<(), AudioError> {
+        self.command_tx.send(AudioCommand::Play(path.to_string(), None, None))
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn play_at(&self, path: &str, position: f64) -> Result<(), String> {
-        self.command_tx.send(AudioCommand::Play(path.to_string(), Some(position)))
-            .map_err(|e| e.to_string())
+    /// Joue à partir de `position` secondes (None = début du fichier)
+    pub fn play_at(&self, path: &str, position: Option<f64>) -> Result<(), AudioError> {
+        self.command_tx.send(AudioCommand::Play(path.to_string(), position, None))
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn pause(&self) -> Result<(), String> {
+    /// Joue `[start, end)` d'un fichier (pistes virtuelles d'un CUE sheet), en démarrant
+    /// `offset` secondes après le début du segment
+    pub fn play_segment(&self, path: &str, start: f64, end: Option<f64>, offset: f64) -> Result<(), AudioError> {
+        let position = start + offset.max(0.0);
+        self.command_tx.send(AudioCommand::Play(path.to_string(), Some(position), Some((start, end))))
+            .map_err(|_| AudioError::EngineStopped)
+    }
+
+    pub fn pause(&self) -> Result<(), AudioError> {
         self.command_tx.send(AudioCommand::Pause)
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn resume(&self) -> Result<(), String> {
+    pub fn resume(&self) -> Result<(), AudioError> {
         self.command_tx.send(AudioCommand::Resume)
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn stop(&self) -> Result<(), String> {
+    pub fn stop(&self) -> Result<(), AudioError> {
+        // Arrêt manuel : la minuterie n'a plus rien à arrêter
+        self.cancel_sleep_timer();
         self.command_tx.send(AudioCommand::Stop)
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
+    }
+
+    /// Arme (ou réarme) la minuterie de mise en veille. À l'échéance, le volume descend
+    /// à zéro sur SLEEP_FADE_MS puis la lecture s'arrête (`sleep_timer_fired`).
+    /// Avec `finish_current_track`, le fondu attend les dernières secondes du track en cours.
+    pub fn set_sleep_timer(&self, delay: std::time::Duration, finish_current_track: bool) {
+        let generation = self.sleep_timer_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current_generation = Arc::clone(&self.sleep_timer_generation);
+        let state = Arc::clone(&self.state);
+        let command_tx = self.command_tx.clone();
+        let app_handle = self.app_handle.clone();
+
+        thread::spawn(move || {
+            let armed = || current_generation.load(Ordering::SeqCst) == generation;
+            let poll = std::time::Duration::from_millis(SLEEP_TIMER_POLL_MS);
+
+            let deadline = std::time::Instant::now() + delay;
+            while std::time::Instant::now() < deadline {
+                if !armed() {
+                    return;
+                }
+                thread::sleep(poll.min(deadline.saturating_duration_since(std::time::Instant::now())));
+            }
+
+            if finish_current_track {
+                while armed() && state.is_playing.load(Ordering::Relaxed) {
+                    // Fin du segment pour une piste CUE
+                    let remaining_ms = ((state.track_duration_seconds() - state.track_position_seconds()) * 1000.0) as u64;
+                    if remaining_ms <= SLEEP_FADE_MS {
+                        break;
+                    }
+                    thread::sleep(poll);
+                }
+            }
+            if !armed() {
+                return;
+            }
+
+            #[cfg(debug_assertions)]
+            println!("[SleepTimer] Fired, fading out");
+
+            let original_volume = state.get_volume();
+            if state.is_playing.load(Ordering::Relaxed) && !state.is_paused.load(Ordering::Relaxed) {
+                let steps = SLEEP_FADE_MS / SLEEP_FADE_STEP_MS;
+                for step in 1..=steps {
+                    if !armed() {
+                        state.set_volume(original_volume);
+                        return;
+                    }
+                    state.set_volume(original_volume * (1.0 - step as f32 / steps as f32));
+                    thread::sleep(std::time::Duration::from_millis(SLEEP_FADE_STEP_MS));
+                }
+            }
+
+            // Le volume d'origine est rétabli après le Stop (canal ordonné) : la prochaine lecture
+            // repart au niveau choisi par l'utilisateur
+            let _ = command_tx.send(AudioCommand::Stop);
+            let _ = command_tx.send(AudioCommand::SetVolume(original_volume));
+            if let Some(ref app) = app_handle {
+                let _ = app.emit("sleep_timer_fired", ());
+            }
+        });
+    }
+
+    /// Annule la minuterie de mise en veille (sans effet si aucune n'est armée).
+    /// Un fondu en cours est interrompu et le volume rétabli
+    pub fn cancel_sleep_timer(&self) {
+        self.sleep_timer_generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Seek à une position du track (relative au segment pour une piste CUE)
+    pub fn seek(&self, time: f64) -> Result<(), AudioError> {
+        if !time.is_finite() {
+            return Err(AudioError::SeekFailed(format!("invalid position {}", time)));
+        }
+        self.command_tx.send(AudioCommand::Seek(self.state.segment.absolute_seconds(time)))
+            .map_err(|_| AudioError::EngineStopped)
+    }
+
+    /// Seek à un pourcentage (0-100) de la durée du track courant
+    pub fn seek_percent(&self, percent: f64) -> Result<(), AudioError> {
+        if !percent.is_finite() {
+            return Err(AudioError::SeekFailed(format!("invalid percentage {}", percent)));
+        }
+        let duration = self.loaded_duration()?;
+        self.seek((percent / 100.0 * duration).clamp(0.0, duration))
     }
 
-    pub fn seek(&self, time: f64) -> Result<(), String> {
-        self.command_tx.send(AudioCommand::Seek(time))
-            .map_err(|e| e.to_string())
+    /// Seek relatif à la position courante (-10s / +30s) ; passe par le même
+    /// rate-limiting que les seeks absolus
+    pub fn seek_relative(&self, delta_seconds: f64) -> Result<(), AudioError> {
+        if !delta_seconds.is_finite() {
+            return Err(AudioError::SeekFailed(format!("invalid offset {}", delta_seconds)));
+        }
+        let duration = self.loaded_duration()?;
+        let target = self.state.track_position_seconds() + delta_seconds;
+        self.seek(target.clamp(0.0, duration))
     }
 
-    pub fn set_volume(&self, vol: f32) -> Result<(), String> {
+    fn loaded_duration(&self) -> Result<f64, AudioError> {
+        let duration = self.state.track_duration_seconds();
+        if duration <= 0.0 {
+            return Err(AudioError::NoTrackLoaded);
+        }
+        Ok(duration)
+    }
+
+    pub fn set_volume(&self, vol: f32) -> Result<(), AudioError> {
         self.command_tx.send(AudioCommand::SetVolume(vol))
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn preload_next(&self, path: &str) -> Result<(), String> {
+    pub fn preload_next(&self, path: &str) -> Result<(), AudioError> {
         self.command_tx.send(AudioCommand::PreloadNext(path.to_string()))
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
     }
 
-    pub fn set_gapless(&self, enabled: bool) -> Result<(), String> {
+    pub fn set_gapless(&self, enabled: bool) -> Result<(), AudioError> {
         self.command_tx.send(AudioCommand::SetGapless(enabled))
-            .map_err(|e| e.to_string())
+            .map_err(|_| AudioError::EngineStopped)
+    }
+
+    pub fn set_crossfade(&self, duration_ms: u32) -> Result<(), AudioError> {
+        self.command_tx.send(AudioCommand::SetCrossfade(duration_ms))
+            .map_err(|_| AudioError::EngineStopped)
+    }
+
+    pub fn get_crossfade_ms(&self) -> u32 {
+        self.state.get_crossfade_ms()
+    }
+
+    /// Active/désactive la sortie en entiers (16/24/32 bits selon le DAC).
+    /// Erreur si le device courant n'offre aucun format entier ; pris en compte au prochain stream.
+    pub fn set_integer_mode(&self, enabled: bool) -> Result<(), AudioError> {
+        if enabled && self.backend.lock().integer_bit_depth().is_none() {
+            return Err(AudioError::NotSupported("output device does not offer an integer format".to_string()));
+        }
+        self.state.integer_mode.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Verrouille le sample rate de sortie (Some) ou revient au bit-perfect automatique (None).
+    /// Erreur si le device courant ne supporte pas ce rate ; pris en compte au prochain stream.
+    pub fn lock_sample_rate(&self, rate: Option<u32>) -> Result<(), AudioError> {
+        if let Some(rate) = rate {
+            if !self.backend.lock().is_sample_rate_supported(rate) {
+                return Err(AudioError::UnsupportedSampleRate(rate));
+            }
+        }
+        self.state.locked_sample_rate.store(rate.unwrap_or(0) as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn get_locked_sample_rate(&self) -> Option<u32> {
+        match self.state.locked_sample_rate.load(Ordering::Relaxed) {
+            0 => None,
+            rate => Some(rate as u32),
+        }
+    }
+
+    /// Modes shuffle / repeat courants (enregistrés dans la session par le thread audio)
+    pub fn set_queue_modes(&self, shuffle: bool, repeat: RepeatMode) {
+        self.state.set_queue_modes(shuffle, repeat);
+    }
+
+    /// Active/désactive la restauration du sample rate d'origine sur Stop
+    pub fn set_restore_sample_rate_on_stop(&self, enabled: bool) {
+        self.state.restore_rate_on_stop.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Change la vitesse de lecture. Le changement passe par un seek à la position
+    /// courante pour vider les samples déjà resamplés à l'ancienne vitesse.
+    pub fn set_playback_speed(&self, speed: f32) -> Result<(), AudioError> {
+        self.command_tx.send(AudioCommand::SetPlaybackSpeed(speed))
+            .map_err(|_| AudioError::EngineStopped)?;
+        if self.state.is_playing.load(Ordering::Relaxed) {
+            self.seek(self.get_position())?;
+        }
+        Ok(())
+    }
+
+    pub fn get_playback_speed(&self) -> f32 {
+        self.state.get_playback_speed()
+    }
+
+    /// Taille du buffer pour les prochains streams (le track en cours garde la sienne)
+    pub fn set_buffer_config(&self, config: BufferConfig) {
+        *self.state.buffer_config.lock() = config;
+    }
+
+    pub fn get_buffer_config(&self) -> BufferConfig {
+        *self.state.buffer_config.lock()
+    }
+
+    /// Active une boucle A-B sur le track courant (secondes, start < end <= durée)
+    pub fn set_ab_loop(&self, start: f64, end: f64) -> Result<(), AudioError> {
+        let duration = self.state.track_duration_seconds();
+        if duration <= 0.0 {
+            return Err(AudioError::NoTrackLoaded);
+        }
+        if !(start >= 0.0 && start < end && end <= duration) {
+            return Err(AudioError::InvalidArgument(format!(
+                "Invalid A-B loop {:.3}s → {:.3}s (track duration {:.3}s)",
+                start, end, duration
+            )));
+        }
+        // Le callback compare la boucle aux positions du fichier
+        let segment = &self.state.segment;
+        self.state.ab_loop.set(segment.absolute_seconds(start), segment.absolute_seconds(end));
+        Ok(())
+    }
+
+    /// Désactive la boucle A-B : la lecture continue depuis la position courante
+    pub fn clear_ab_loop(&self) {
+        self.state.ab_loop.clear();
+    }
+
+    /// Active/désactive la capture du visualiseur de spectre
+    pub fn set_spectrum_enabled(&self, enabled: bool) {
+        self.state.spectrum.set_enabled(enabled);
+    }
+
+    /// Spectre lissé de la dernière fenêtre audio (FFT calculée sur le thread appelant)
+    pub fn get_spectrum(&self, bins: usize) -> Vec<f32> {
+        self.state.spectrum.spectrum(bins)
+    }
+
+    pub fn get_buffer_health(&self) -> BufferHealth {
+        self.state.buffer_health.snapshot()
+    }
+
+    /// Temps moyen de décodage / resampling par buffer et underruns du stream courant.
+    /// Le resampler tourne aussi à vitesse ≠ 1.0, même quand source et sortie ont le même rate
+    pub fn get_engine_stats(&self) -> EngineStats {
+        let resampling_active = match *self.state.stream_format.lock() {
+            Some(format) => format.resampling || self.state.get_playback_speed() != 1.0,
+            None => false,
+        };
+        EngineStats {
+            avg_decode_us: PROCESSING_STATS.average_us(Stage::Decode),
+            avg_resample_us: if resampling_active { PROCESSING_STATS.average_us(Stage::Resample) } else { 0.0 },
+            resampling_active,
+            underruns: self.state.buffer_health.snapshot().underrun_count,
+        }
+    }
+
+    /// Mode de canaux en sortie (stéréo, mono, gauche ou droite dupliqué), appliqué dès le prochain callback
+    pub fn set_channel_mode(&self, mode: ChannelMode) {
+        self.state.output_stage.set_channel_mode(mode);
+    }
+
+    pub fn get_channel_mode(&self) -> ChannelMode {
+        self.state.output_stage.channel_mode()
+    }
+
+    /// Courbe de volume (linéaire ou logarithmique), appliquée immédiatement au volume courant
+    pub fn set_volume_curve(&self, curve: VolumeCurve) {
+        self.state.set_volume_curve(curve);
+    }
+
+    pub fn get_volume_curve(&self) -> VolumeCurve {
+        self.state.get_volume_curve()
+    }
+
+    /// Balance -1.0 (gauche) … +1.0 (droite) ; retourne la valeur appliquée (bornée)
+    pub fn set_balance(&self, balance: f32) -> Result<f32, AudioError> {
+        self.state.output_stage.set_balance(balance).map_err(AudioError::InvalidArgument)
+    }
+
+    pub fn get_balance(&self) -> f32 {
+        self.state.output_stage.balance()
+    }
+
+    /// Largeur stéréo (0 = mono, 1 = inchangée, jusqu'à 2 = élargie) ; retourne la valeur appliquée
+    pub fn set_stereo_width(&self, width: f32) -> Result<f32, AudioError> {
+        self.state.output_stage.set_stereo_width(width).map_err(AudioError::InvalidArgument)
+    }
+
+    pub fn get_stereo_width(&self) -> f32 {
+        self.state.output_stage.stereo_width()
+    }
+
+    /// Limiteur doux en sortie (après EQ et volume), appliqué dès le prochain callback
+    pub fn set_limiter_enabled(&self, enabled: bool) {
+        self.state.output_stage.set_limiter_enabled(enabled);
+    }
+
+    pub fn is_limiter_enabled(&self) -> bool {
+        self.state.output_stage.is_limiter_enabled()
+    }
+
+    /// Retourne le seuil effectivement appliqué (borné)
+    pub fn set_limiter_threshold_db(&self, threshold_db: f32) -> Result<f32, AudioError> {
+        self.state.output_stage.set_limiter_threshold_db(threshold_db).map_err(AudioError::InvalidArgument)
+    }
+
+    pub fn get_limiter_threshold_db(&self) -> f32 {
+        self.state.output_stage.limiter_threshold_db()
     }
 
     pub fn is_playing(&self) -> bool {
@@ -1005,12 +2224,55 @@ impl AudioEngine {
     }
 
     pub fn get_position(&self) -> f64 {
-        self.state.get_position_seconds()
+        self.state.track_position_seconds()
     }
 
     pub fn get_duration(&self) -> f64 {
-        self.state.get_duration_seconds()
+        self.state.track_duration_seconds()
     }
+
+    /// Qualité de la chaîne de lecture du stream courant.
+    /// Le format est figé à la création du stream ; volume, EQ et Hog Mode sont lus
+    /// à l'appel car ils peuvent changer pendant la lecture.
+    pub fn get_playback_quality(&self) -> Option<PlaybackQuality> {
+        let format = (*self.state.stream_format.lock())?;
+
+        let exclusive_active = self.backend
+            .lock()
+            .hog_mode_status()
+            .map(|status| status.enabled && status.owned_by_us)
+            .unwrap_or(false);
+
+        // Le callback ne touche pas aux samples : volume à 100 %, EQ neutre, étage de sortie inactif
+        // (stéréo non modifiée, balance centrée, largeur 1.0, pas de limiteur)
+        let unity_volume = (self.state.get_volume() - 1.0).abs() < f32::EPSILON;
+        let eq_transparent = !self.eq_state.is_enabled()
+            || (self.eq_state.get_preamp().abs() <= 0.01
+                && self.eq_state.get_all_gains().iter().all(|g| g.abs() <= 0.01));
+        let output_transparent = !self.state.output_stage.is_active();
+
+        Some(PlaybackQuality {
+            bit_perfect: !format.resampling && unity_volume && eq_transparent && output_transparent,
+            resampling: format.resampling,
+            exclusive_active,
+            source_rate: format.source_rate,
+            output_rate: format.output_rate,
+            source_bit_depth: format.source_bit_depth,
+        })
+    }
+}
+
+/// Bit-perfect atteint ou non, et pourquoi (resampling, Hog Mode)
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PlaybackQuality {
+    /// Le DAC reçoit le flux source intact (pas de resampling, volume 100 %, EQ neutre, pas de limiteur)
+    pub bit_perfect: bool,
+    pub resampling: bool,
+    /// Hog Mode réellement détenu par Noir (pas seulement demandé)
+    pub exclusive_active: bool,
+    pub source_rate: u32,
+    pub output_rate: u32,
+    pub source_bit_depth: u8,
 }
 
 #[derive(Clone, serde::Serialize)]
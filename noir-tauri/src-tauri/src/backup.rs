@@ -0,0 +1,91 @@
+//! Sauvegarde / restauration des données utilisateur (archive zip)
+//!
+//! Architecture :
+//! - Écriture et lecture via le crate `zip` : entrées compressées en deflate
+//! - Lecture de toute archive deflate ou stockée, y compris recompressée par un autre outil
+//! - Les CRC32 sont vérifiés à la lecture ; le contenu JSON est validé par l'appelant
+
+use std::io::{Cursor, Read, Write};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+#[derive(Debug)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+pub fn write_archive(entries: &[ArchiveEntry]) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for entry in entries {
+        writer.start_file(entry.name.as_str(), options)
+            .map_err(|e| format!("{}: {}", entry.name, e))?;
+        writer.write_all(&entry.data)
+            .map_err(|e| format!("{}: {}", entry.name, e))?;
+    }
+    writer.finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| format!("Failed to finalize archive: {}", e))
+}
+
+pub fn read_archive(data: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    let mut archive = ZipArchive::new(Cursor::new(data))
+        .map_err(|e| format!("Not a zip archive: {}", e))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| format!("Corrupted archive: {}", e))?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut content = Vec::new();
+        // Le CRC32 est vérifié par le lecteur en fin d'entrée
+        file.read_to_end(&mut content).map_err(|e| format!("{}: {}", name, e))?;
+        entries.push(ArchiveEntry { name, data: content });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let entries = vec![
+            ArchiveEntry { name: "config.json".to_string(), data: b"{\"library_paths\":[]}".to_vec() },
+            ArchiveEntry { name: "playlists.json".to_string(), data: Vec::new() },
+        ];
+        let archive = write_archive(&entries).unwrap();
+        let read = read_archive(&archive).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].name, "config.json");
+        assert_eq!(read[0].data, entries[0].data);
+        assert!(read[1].data.is_empty());
+        assert!(read_archive(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn test_read_stored_archive() {
+        // Archive non compressée avec dossier (anciennes sauvegardes, zip du Finder)
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        writer.add_directory("Noir Backup/", options).unwrap();
+        writer.start_file("Noir Backup/ratings.json", options).unwrap();
+        writer.write_all(b"{}").unwrap();
+        let mut archive = writer.finish().unwrap().into_inner();
+
+        let read = read_archive(&archive).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, "Noir Backup/ratings.json");
+        assert_eq!(read[0].data, b"{}");
+
+        // Contenu altéré : CRC invalide
+        let at = archive.windows(2).position(|w| w == b"{}").unwrap();
+        archive[at] = b'[';
+        assert!(read_archive(&archive).is_err());
+    }
+}
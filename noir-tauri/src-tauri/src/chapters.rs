@@ -0,0 +1,336 @@
+//! Chapitres embarqués (livres audio, mixes DJ)
+//!
+//! Architecture :
+//! - MP3 : frames ID3v2 CHAP, conservées en binaire par lofty ; titre = sous-frame TIT2
+//! - MP4 / M4A / M4B : atome Nero moov/udta/chpl (débuts en unités de 100 ns), durée via mvhd
+//! - Fin d'un chapitre absente → début du suivant, ou fin du fichier pour le dernier
+//! - Formats sans chapitres lisibles (pistes de chapitres QuickTime, FLAC…) : liste vide
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use lofty::id3::v2::{FrameValue, Id3v2Version};
+use lofty::mpeg::MpegFile;
+use lofty::{AudioFile, ParseOptions};
+use serde::Serialize;
+
+/// Atome moov lu en mémoire au-delà de cette taille : fichier suspect, on abandonne
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Un chapitre, bornes en secondes
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+/// Chapitre lu avant résolution des fins manquantes
+struct RawChapter {
+    title: Option<String>,
+    start: f64,
+    end: Option<f64>,
+}
+
+/// Chapitres d'un fichier (vide si le format n'en expose pas)
+pub fn get_chapters(path: &str) -> Vec<Chapter> {
+    if path.starts_with("smb://") {
+        return Vec::new();
+    }
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    let (raw, duration) = match extension.as_str() {
+        "mp3" => read_id3_chapters(path),
+        "m4a" | "m4b" | "mp4" => read_mp4_chapters(path),
+        _ => None,
+    }
+    .unwrap_or_default();
+
+    resolve_chapters(raw, duration)
+}
+
+/// Trie par début et complète les fins manquantes
+fn resolve_chapters(mut raw: Vec<RawChapter>, duration: Option<f64>) -> Vec<Chapter> {
+    raw.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let starts: Vec<f64> = raw.iter().map(|c| c.start).collect();
+
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let next_start = starts.get(i + 1).copied().or(duration);
+            let end = chapter
+                .end
+                .filter(|end| *end > chapter.start)
+                .or(next_start)
+                .unwrap_or(chapter.start);
+            Chapter {
+                title: chapter
+                    .title
+                    .filter(|t| !t.trim().is_empty())
+                    .unwrap_or_else(|| format!("Chapter {}", i + 1)),
+                start_seconds: chapter.start,
+                end_seconds: end,
+            }
+        })
+        .collect()
+}
+
+// === ID3v2 CHAP ===
+
+fn read_id3_chapters(path: &str) -> Option<(Vec<RawChapter>, Option<f64>)> {
+    let mut file = File::open(path).ok()?;
+    let mpeg = MpegFile::read_from(&mut file, ParseOptions::new()).ok()?;
+    let duration = Some(mpeg.properties().duration().as_secs_f64()).filter(|d| *d > 0.0);
+    let tag = mpeg.id3v2()?;
+    let version = tag.original_version();
+
+    let chapters = tag
+        .into_iter()
+        .filter(|frame| frame.id_str() == "CHAP")
+        .filter_map(|frame| match frame.content() {
+            FrameValue::Binary(content) => parse_chap_frame(content, version),
+            _ => None,
+        })
+        .collect();
+    Some((chapters, duration))
+}
+
+/// Contenu d'une frame CHAP : element ID (terminé par \0), début/fin en ms,
+/// offsets en octets (ignorés), puis sous-frames (TIT2 = titre)
+fn parse_chap_frame(content: &[u8], version: Id3v2Version) -> Option<RawChapter> {
+    let id_end = content.iter().position(|&b| b == 0)?;
+    let body = content.get(id_end + 1..)?;
+    if body.len() < 16 {
+        return None;
+    }
+    let start_ms = u32::from_be_bytes(body[0..4].try_into().ok()?);
+    let end_ms = u32::from_be_bytes(body[4..8].try_into().ok()?);
+
+    let mut title = None;
+    let mut sub_frames = &body[16..];
+    while sub_frames.len() >= 10 {
+        let size_bytes: [u8; 4] = sub_frames[4..8].try_into().ok()?;
+        let size = match version {
+            Id3v2Version::V4 => size_bytes.iter().fold(0usize, |acc, b| (acc << 7) | (*b & 0x7F) as usize),
+            _ => u32::from_be_bytes(size_bytes) as usize,
+        };
+        let frame_body = sub_frames.get(10..10 + size)?;
+        if &sub_frames[0..4] == b"TIT2" {
+            title = decode_id3_text(frame_body);
+        }
+        sub_frames = &sub_frames[10 + size..];
+    }
+
+    Some(RawChapter {
+        title,
+        start: start_ms as f64 / 1000.0,
+        // 0xFFFFFFFF = fin non renseignée
+        end: (end_ms != u32::MAX).then_some(end_ms as f64 / 1000.0),
+    })
+}
+
+/// Frame texte ID3 : octet d'encodage puis texte (Latin-1, UTF-16 BOM, UTF-16BE, UTF-8)
+fn decode_id3_text(data: &[u8]) -> Option<String> {
+    let (&encoding, text) = data.split_first()?;
+    let decoded = match encoding {
+        0 => text.iter().map(|&b| b as char).collect(),
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xFF, 0xFE, rest @ ..] => (false, rest),
+                [0xFE, 0xFF, rest @ ..] => (true, rest),
+                _ => (encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).to_string(),
+    };
+    Some(decoded.trim_end_matches('\0').to_string())
+}
+
+// === MP4 Nero chpl ===
+
+fn read_mp4_chapters(path: &str) -> Option<(Vec<RawChapter>, Option<f64>)> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+    let moov = read_top_level_box(&mut file, file_len, b"moov")?;
+
+    let duration = find_child_box(&moov, b"mvhd").and_then(parse_mvhd_duration);
+    let chpl = find_child_box(&moov, b"udta").and_then(|udta| find_child_box(udta, b"chpl"))?;
+    Some((parse_chpl(chpl), duration))
+}
+
+/// En-tête d'atome : (taille du contenu, taille de l'en-tête) ; size 0 = jusqu'à `remaining`
+fn box_header(header: &[u8], remaining: u64) -> Option<(u64, u64)> {
+    let size = u32::from_be_bytes(header.get(0..4)?.try_into().ok()?) as u64;
+    match size {
+        0 => Some((remaining.checked_sub(8)?, 8)),
+        1 => {
+            let large = u64::from_be_bytes(header.get(8..16)?.try_into().ok()?);
+            Some((large.checked_sub(16)?, 16))
+        }
+        _ => Some((size.checked_sub(8)?, 8)),
+    }
+}
+
+/// Contenu d'un atome de premier niveau (parcours par seek, sans lire mdat)
+fn read_top_level_box(file: &mut File, file_len: u64, kind: &[u8; 4]) -> Option<Vec<u8>> {
+    let mut offset = 0u64;
+    while offset + 8 <= file_len {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut header = [0u8; 16];
+        let read = file.read(&mut header).ok()?;
+        let (content_len, header_len) = box_header(&header[..read], file_len - offset)?;
+
+        if &header[4..8] == kind {
+            if content_len > MAX_MOOV_SIZE {
+                return None;
+            }
+            file.seek(SeekFrom::Start(offset + header_len)).ok()?;
+            let mut content = vec![0u8; content_len as usize];
+            file.read_exact(&mut content).ok()?;
+            return Some(content);
+        }
+        offset += header_len + content_len;
+    }
+    None
+}
+
+/// Contenu d'un atome enfant dans un conteneur déjà en mémoire
+fn find_child_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 0usize;
+    while offset + 8 <= data.len() {
+        let (content_len, header_len) = box_header(&data[offset..], (data.len() - offset) as u64)?;
+        let start = offset + header_len as usize;
+        let end = start.checked_add(content_len as usize)?;
+        if &data[offset + 4..offset + 8] == kind {
+            return data.get(start..end);
+        }
+        offset = end;
+    }
+    None
+}
+
+/// mvhd : version/flags, dates, timescale, durée (32 ou 64 bits selon la version)
+fn parse_mvhd_duration(mvhd: &[u8]) -> Option<f64> {
+    let (timescale, duration) = match *mvhd.first()? {
+        1 => (
+            u32::from_be_bytes(mvhd.get(20..24)?.try_into().ok()?),
+            u64::from_be_bytes(mvhd.get(24..32)?.try_into().ok()?),
+        ),
+        _ => (
+            u32::from_be_bytes(mvhd.get(12..16)?.try_into().ok()?),
+            u32::from_be_bytes(mvhd.get(16..20)?.try_into().ok()?) as u64,
+        ),
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+/// chpl : version/flags, (4 octets réservés si version 1), nombre de chapitres,
+/// puis pour chacun début (u64, 100 ns), longueur du titre (u8), titre UTF-8
+fn parse_chpl(chpl: &[u8]) -> Vec<RawChapter> {
+    let mut chapters = Vec::new();
+    let Some(&version) = chpl.first() else {
+        return chapters;
+    };
+    let mut pos = if version == 0 { 4 } else { 8 };
+    let Some(&count) = chpl.get(pos) else {
+        return chapters;
+    };
+    pos += 1;
+
+    for _ in 0..count {
+        let Some(start) = chpl.get(pos..pos + 8).and_then(|b| b.try_into().ok()).map(u64::from_be_bytes) else {
+            break;
+        };
+        let Some(&title_len) = chpl.get(pos + 8) else {
+            break;
+        };
+        let title_start = pos + 9;
+        let Some(title) = chpl.get(title_start..title_start + title_len as usize) else {
+            break;
+        };
+        chapters.push(RawChapter {
+            title: Some(String::from_utf8_lossy(title).to_string()),
+            start: start as f64 / 10_000_000.0,
+            end: None,
+        });
+        pos = title_start + title_len as usize;
+    }
+    chapters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chap_frame(id: &str, start_ms: u32, end_ms: u32, title: Option<&str>) -> Vec<u8> {
+        let mut frame = id.as_bytes().to_vec();
+        frame.push(0);
+        frame.extend(start_ms.to_be_bytes());
+        frame.extend(end_ms.to_be_bytes());
+        frame.extend([0xFF; 8]);
+        if let Some(title) = title {
+            // TIT2 UTF-8, taille synchsafe (ID3v2.4)
+            let body_len = title.len() as u8 + 1;
+            frame.extend(b"TIT2");
+            frame.extend([0, 0, 0, body_len, 0, 0, 3]);
+            frame.extend(title.as_bytes());
+        }
+        frame
+    }
+
+    #[test]
+    fn test_parse_chap_frame() {
+        let chapter = parse_chap_frame(&chap_frame("chp0", 90_500, 180_000, Some("Intro")), Id3v2Version::V4).unwrap();
+        assert_eq!(chapter.title.as_deref(), Some("Intro"));
+        assert_eq!(chapter.start, 90.5);
+        assert_eq!(chapter.end, Some(180.0));
+
+        let untitled = parse_chap_frame(&chap_frame("chp1", 0, u32::MAX, None), Id3v2Version::V3).unwrap();
+        assert_eq!(untitled.title, None);
+        assert_eq!(untitled.end, None);
+    }
+
+    #[test]
+    fn test_decode_id3_text_utf16() {
+        assert_eq!(decode_id3_text(&[1, 0xFF, 0xFE, b'H', 0, b'i', 0, 0, 0]).as_deref(), Some("Hi"));
+        assert_eq!(decode_id3_text(&[0, 0xE9, b't', 0xE9]).as_deref(), Some("été"));
+    }
+
+    #[test]
+    fn test_parse_chpl_and_resolve() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "Part One"), (600_000_000u64, "Part Two")] {
+            chpl.extend(start.to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend(title.as_bytes());
+        }
+
+        let chapters = resolve_chapters(parse_chpl(&chpl), Some(1500.0));
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter { title: "Part One".into(), start_seconds: 0.0, end_seconds: 60.0 },
+                Chapter { title: "Part Two".into(), start_seconds: 60.0, end_seconds: 1500.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_child_box() {
+        // udta { chpl { 01 02 } }
+        let data = [0, 0, 0, 18, b'u', b'd', b't', b'a', 0, 0, 0, 10, b'c', b'h', b'p', b'l', 1, 2];
+        let udta = find_child_box(&data, b"udta").unwrap();
+        assert_eq!(find_child_box(udta, b"chpl"), Some(&[1u8, 2][..]));
+        assert_eq!(find_child_box(&data, b"moov"), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Coalescence de requêtes concurrentes (extraction de pochettes)
+//!
+//! Architecture :
+//! - Le premier appel pour une clé exécute le travail ; les appels concurrents sur la même
+//!   clé attendent son résultat au lieu de refaire la même lecture disque
+//! - L'entrée disparaît dès le résultat publié : un appel ultérieur refait le travail
+//!   (en pratique il trouve alors le cache rempli par le premier)
+//! - Si le travail panique, les appels en attente reprennent le travail eux-mêmes
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex};
+
+struct Slot<T> {
+    /// (terminé, résultat) — terminé sans résultat = le premier appel a paniqué
+    state: Mutex<(bool, Option<T>)>,
+    ready: Condvar,
+}
+
+pub struct InFlight<T> {
+    pending: Mutex<HashMap<String, Arc<Slot<T>>>>,
+}
+
+impl<T: Clone> Default for InFlight<T> {
+    fn default() -> Self {
+        Self { pending: Mutex::new(HashMap::new()) }
+    }
+}
+
+/// Publie la fin du travail même en cas de panique
+struct Publish<'a, T> {
+    in_flight: &'a InFlight<T>,
+    key: &'a str,
+    slot: Arc<Slot<T>>,
+}
+
+impl<T> Drop for Publish<'_, T> {
+    fn drop(&mut self) {
+        self.in_flight.pending.lock().remove(self.key);
+        self.slot.state.lock().0 = true;
+        self.slot.ready.notify_all();
+    }
+}
+
+impl<T: Clone> InFlight<T> {
+    pub fn run(&self, key: &str, work: impl FnOnce() -> T) -> T {
+        let (slot, leader) = {
+            let mut pending = self.pending.lock();
+            match pending.get(key) {
+                Some(slot) => (Arc::clone(slot), false),
+                None => {
+                    let slot = Arc::new(Slot { state: Mutex::new((false, None)), ready: Condvar::new() });
+                    pending.insert(key.to_string(), Arc::clone(&slot));
+                    (slot, true)
+                }
+            }
+        };
+
+        if leader {
+            let publish = Publish { in_flight: self, key, slot };
+            let result = work();
+            publish.slot.state.lock().1 = Some(result.clone());
+            return result;
+        }
+
+        let mut state = slot.state.lock();
+        while !state.0 {
+            slot.ready.wait(&mut state);
+        }
+        match state.1.clone() {
+            Some(result) => result,
+            None => {
+                drop(state);
+                work()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_concurrent_calls_share_one_run() {
+        let in_flight = InFlight::<String>::default();
+        let runs = AtomicUsize::new(0);
+        let results: Vec<String> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| {
+                in_flight.run("/music/album/01.flac", || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(100));
+                    "cover.jpg".to_string()
+                })
+            })).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|r| r == "cover.jpg"));
+
+        // Terminé : l'appel suivant refait le travail
+        in_flight.run("/music/album/01.flac", || runs.fetch_add(1, Ordering::SeqCst).to_string());
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
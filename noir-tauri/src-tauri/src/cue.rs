@@ -0,0 +1,226 @@
+//! CUE sheets : albums stockés en un seul fichier audio accompagné d'un `.cue`
+//!
+//! Architecture :
+//! - Parsing : TITLE / PERFORMER (album puis par piste), FILE, TRACK nn AUDIO, INDEX 01 mm:ss:ff
+//! - Le scanner remplace le fichier par une piste virtuelle par TRACK : `album.flac#track=3`
+//! - Lecture : le chemin virtuel est résolu en (fichier, début, fin) et joué via play_at
+//! - Fin d'une piste = INDEX 01 de la suivante dans le même fichier (None = fin du fichier)
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Séparateur entre le fichier réel et le numéro de piste d'un chemin virtuel
+const TRACK_FRAGMENT: &str = "#track=";
+
+/// Une piste d'un CUE sheet
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: String,
+    /// Interprète de la piste (celui de l'album à défaut)
+    pub performer: String,
+    /// Fichier audio contenant la piste (chemin absolu)
+    pub file: String,
+    /// Début (INDEX 01) en secondes
+    pub start: f64,
+    /// Fin en secondes (None = jusqu'à la fin du fichier)
+    pub end: Option<f64>,
+}
+
+/// CUE sheet complet (les champs album servent aux pistes virtuelles du scanner)
+#[derive(Clone, Debug, Default)]
+pub struct CueSheet {
+    pub title: String,
+    pub performer: String,
+    pub tracks: Vec<CueTrack>,
+}
+
+/// Lit et parse un fichier .cue (encodage inconnu : UTF-8 avec remplacement)
+pub fn read_cue_sheet(cue_path: &Path) -> Option<CueSheet> {
+    let bytes = std::fs::read(cue_path).ok()?;
+    let dir = cue_path.parent().unwrap_or_else(|| Path::new(""));
+    let sheet = parse_cue(&String::from_utf8_lossy(&bytes), dir);
+    if sheet.tracks.is_empty() {
+        None
+    } else {
+        Some(sheet)
+    }
+}
+
+/// Parse le contenu d'un CUE sheet ; les chemins FILE sont relatifs à `cue_dir`
+pub fn parse_cue(content: &str, cue_dir: &Path) -> CueSheet {
+    let mut sheet = CueSheet::default();
+    let mut current_file = String::new();
+    // Piste en cours : (numéro, titre, interprète, fichier, INDEX 01)
+    let mut pending: Vec<(u32, String, String, String, Option<f64>)> = Vec::new();
+
+    for raw_line in content.trim_start_matches('\u{feff}').lines() {
+        let line = raw_line.trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let in_track = !pending.is_empty();
+
+        match keyword.to_ascii_uppercase().as_str() {
+            "FILE" => {
+                // FILE "nom.flac" WAVE : le type est le dernier mot
+                let name = rest.rsplit_once(char::is_whitespace).map_or(rest, |(name, _)| name);
+                current_file = cue_dir.join(unquote(name)).to_string_lossy().to_string();
+            }
+            "TRACK" => {
+                let number = rest.split_whitespace().next().and_then(|n| n.parse().ok()).unwrap_or(0);
+                pending.push((number, String::new(), String::new(), current_file.clone(), None));
+            }
+            "TITLE" if in_track => pending.last_mut().unwrap().1 = unquote(rest),
+            "TITLE" => sheet.title = unquote(rest),
+            "PERFORMER" if in_track => pending.last_mut().unwrap().2 = unquote(rest),
+            "PERFORMER" => sheet.performer = unquote(rest),
+            "INDEX" if in_track => {
+                let mut parts = rest.split_whitespace();
+                if parts.next() == Some("01") {
+                    pending.last_mut().unwrap().4 = parts.next().and_then(parse_cue_time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Pistes sans INDEX 01 ignorées (CUE incomplet)
+    let pending: Vec<_> = pending
+        .into_iter()
+        .filter_map(|(number, title, performer, file, start)| start.map(|s| (number, title, performer, file, s)))
+        .collect();
+
+    for (i, (number, title, performer, file, start)) in pending.iter().enumerate() {
+        let end = pending
+            .get(i + 1)
+            .filter(|next| next.3 == *file)
+            .map(|next| next.4);
+        sheet.tracks.push(CueTrack {
+            number: *number,
+            title: if title.is_empty() { format!("Track {:02}", number) } else { title.clone() },
+            performer: if performer.is_empty() { sheet.performer.clone() } else { performer.clone() },
+            file: file.clone(),
+            start: *start,
+            end,
+        });
+    }
+    sheet
+}
+
+/// `"texte entre guillemets"` → `texte entre guillemets`
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// `mm:ss:ff` (ff = frames CD, 75 par seconde) → secondes
+fn parse_cue_time(value: &str) -> Option<f64> {
+    let mut parts = value.split(':');
+    let minutes: u32 = parts.next()?.parse().ok()?;
+    let seconds: u32 = parts.next()?.parse().ok()?;
+    let frames: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || seconds >= 60 || frames >= 75 {
+        return None;
+    }
+    Some(minutes as f64 * 60.0 + seconds as f64 + frames as f64 / 75.0)
+}
+
+/// CUE sheet voisin d'un fichier audio : `album.cue` ou `album.flac.cue`
+pub fn find_cue_sheet(audio_path: &Path) -> Option<PathBuf> {
+    let with_ext = audio_path.with_extension("cue");
+    if with_ext.is_file() {
+        return Some(with_ext);
+    }
+    let mut appended = audio_path.as_os_str().to_os_string();
+    appended.push(".cue");
+    let appended = PathBuf::from(appended);
+    appended.is_file().then_some(appended)
+}
+
+/// Pistes du CUE sheet contenues dans `audio_path`.
+/// Un CUE à fichier unique qui référence un nom absent (ex. `.wav` converti en `.flac`)
+/// est rattaché au fichier audio voisin.
+pub fn tracks_for_file(sheet: &CueSheet, audio_path: &str) -> Vec<CueTrack> {
+    let matching: Vec<CueTrack> = sheet.tracks.iter().filter(|t| t.file == audio_path).cloned().collect();
+    if !matching.is_empty() {
+        return matching;
+    }
+    let single_file = sheet.tracks.windows(2).all(|w| w[0].file == w[1].file);
+    if single_file && !sheet.tracks.is_empty() && !Path::new(&sheet.tracks[0].file).exists() {
+        return sheet
+            .tracks
+            .iter()
+            .map(|t| CueTrack { file: audio_path.to_string(), ..t.clone() })
+            .collect();
+    }
+    Vec::new()
+}
+
+/// `album.flac` + 3 → `album.flac#track=3`
+pub fn virtual_path(audio_path: &str, number: u32) -> String {
+    format!("{}{}{}", audio_path, TRACK_FRAGMENT, number)
+}
+
+/// `album.flac#track=3` → (`album.flac`, 3) ; None pour un chemin ordinaire
+pub fn split_virtual_path(path: &str) -> Option<(&str, u32)> {
+    let (file, number) = path.rsplit_once(TRACK_FRAGMENT)?;
+    Some((file, number.parse().ok()?))
+}
+
+/// Résout un chemin virtuel en piste jouable (fichier réel + bornes)
+pub fn resolve_virtual_track(path: &str) -> Option<CueTrack> {
+    let (file, number) = split_virtual_path(path)?;
+    let sheet = read_cue_sheet(&find_cue_sheet(Path::new(file))?)?;
+    tracks_for_file(&sheet, file).into_iter().find(|t| t.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUE: &str = "\u{feff}PERFORMER \"Miles Davis\"\nTITLE \"Kind of Blue\"\nFILE \"Kind of Blue.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"So What\"\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    TITLE \"Freddie Freeloader\"\n    PERFORMER \"Miles Davis Sextet\"\n    INDEX 00 09:20:50\n    INDEX 01 09:22:37\n  TRACK 03 AUDIO\n    INDEX 01 19:00:00\n";
+
+    #[test]
+    fn test_parse_cue() {
+        let sheet = parse_cue(CUE, Path::new("/music"));
+
+        assert_eq!(sheet.title, "Kind of Blue");
+        assert_eq!(sheet.tracks.len(), 3);
+
+        let first = &sheet.tracks[0];
+        assert_eq!(first.file, "/music/Kind of Blue.flac");
+        assert_eq!((first.number, first.title.as_str(), first.performer.as_str()), (1, "So What", "Miles Davis"));
+        assert_eq!(first.start, 0.0);
+
+        // INDEX 01 (pas le pregap INDEX 00), frames à 1/75 s
+        let second = &sheet.tracks[1];
+        assert_eq!(second.performer, "Miles Davis Sextet");
+        assert!((second.start - (562.0 + 37.0 / 75.0)).abs() < 1e-9);
+        assert_eq!(first.end, Some(second.start));
+
+        assert_eq!(sheet.tracks[2].title, "Track 03");
+        assert_eq!(sheet.tracks[2].end, None);
+    }
+
+    #[test]
+    fn test_virtual_path_round_trip() {
+        let path = virtual_path("/music/Kind of Blue.flac", 3);
+        assert_eq!(path, "/music/Kind of Blue.flac#track=3");
+        assert_eq!(split_virtual_path(&path), Some(("/music/Kind of Blue.flac", 3)));
+        assert_eq!(split_virtual_path("/music/So What.flac"), None);
+    }
+
+    #[test]
+    fn test_tracks_for_renamed_single_file() {
+        let sheet = parse_cue(&CUE.replace("Kind of Blue.flac", "Kind of Blue.wav"), Path::new("/nonexistent"));
+        let tracks = tracks_for_file(&sheet, "/music/album.flac");
+
+        assert_eq!(tracks.len(), 3);
+        assert!(tracks.iter().all(|t| t.file == "/music/album.flac"));
+    }
+}
@@ -0,0 +1,597 @@
+// === DSD (DSF / DFF) ===
+// Lecture des fichiers DSD 1-bit et conversion en PCM pour le pipeline existant
+// Architecture : [DSF/DFF] → DsdFormatReader (décimation FIR) → paquets PCM f32 → décodeur PCM Symphonia
+//
+// Symphonia ne décode pas le DSD : on expose un FormatReader qui produit directement
+// des paquets PCM_F32LE, le reste de la chaîne (seek, resampler, RingBuffer) est inchangé.
+// TODO: DoP / DSD natif bit-perfect quand le DAC le supporte (passthrough CoreAudio)
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use symphonia::core::audio::Channels;
+use symphonia::core::codecs::{CodecParameters, CODEC_TYPE_PCM_F32LE};
+use symphonia::core::errors::{unsupported_error, Error as SymphoniaError, Result as SymphoniaResult};
+use symphonia::core::formats::{Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{Metadata, MetadataLog};
+use symphonia::core::units::TimeBase;
+
+use crate::audio_decoder::AudioInfo;
+
+/// Sample rate PCM produit par la conversion (DSD64 / 16, DSD128 / 32, ...)
+/// 176.4kHz suffit : le bruit de mise en forme du DSD est filtré bien avant 88kHz
+pub const DSD_PCM_RATE: u32 = 176_400;
+
+/// Sample rate DSD64 (référence pour dimensionner le filtre)
+const DSD64_RATE: u32 = 2_822_400;
+
+/// Nombre de taps du FIR à DSD64 (doublé à chaque multiple de DSD64)
+const FILTER_TAPS_DSD64: usize = 512;
+
+/// Fréquence de coupure du filtre passe-bas (Hz)
+const FILTER_CUTOFF_HZ: f64 = 35_000.0;
+
+/// Gain du filtre en continu : le 0 dB DSD correspond à une modulation de 50 % (SACD),
+/// un flux saturé (que des 1) sort donc à -6 dBFS au lieu d'écrêter
+const DSD_OUTPUT_GAIN: f64 = 0.5;
+
+/// Octets DSD lus par canal et par paquet pour les DFF (les DSF utilisent leur taille de bloc)
+const DFF_PACKET_BYTES: usize = 4096;
+
+/// Motif de silence DSD (autant de 1 que de 0)
+const DSD_SILENCE: u8 = 0x69;
+
+/// Extensions traitées par ce module
+pub fn is_dsd_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "dsf" | "dff" | "dsd"))
+        .unwrap_or(false)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DsdLayout {
+    /// DSF : blocs de `block_size` octets par canal, bits LSB en premier (sauf bits_per_sample = 8)
+    Dsf { block_size: usize, lsb_first: bool },
+    /// DFF : octets entrelacés par canal, bits MSB en premier
+    Dff,
+}
+
+/// En-tête d'un fichier DSD (DSF ou DFF non compressé)
+#[derive(Clone, Debug)]
+pub struct DsdHeader {
+    pub channels: usize,
+    pub dsd_rate: u32,
+    /// Octets DSD utiles par canal
+    pub bytes_per_channel: u64,
+    data_offset: u64,
+    layout: DsdLayout,
+}
+
+impl DsdHeader {
+    pub fn duration_seconds(&self) -> f64 {
+        self.bytes_per_channel as f64 * 8.0 / self.dsd_rate as f64
+    }
+
+    /// Octets DSD (par canal) consommés pour produire une frame PCM
+    fn bytes_per_frame(&self) -> usize {
+        (self.dsd_rate / 8 / DSD_PCM_RATE) as usize
+    }
+
+    pub fn pcm_frames(&self) -> u64 {
+        self.bytes_per_channel / self.bytes_per_frame() as u64
+    }
+
+    fn packet_bytes_per_channel(&self) -> usize {
+        match self.layout {
+            DsdLayout::Dsf { block_size, .. } => block_size,
+            DsdLayout::Dff => DFF_PACKET_BYTES,
+        }
+    }
+}
+
+/// Lit l'en-tête DSF ou DFF (détecté par le magic, pas par l'extension)
+fn read_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader, String> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(|e| format!("Cannot read DSD header: {}", e))?;
+    reader.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+
+    let header = match &magic {
+        b"DSD " => read_dsf_header(reader),
+        b"FRM8" => read_dff_header(reader),
+        _ => Err("Not a DSF/DFF file".to_string()),
+    }?;
+
+    if header.channels == 0 || header.channels > 6 {
+        return Err(format!("Unsupported DSD channel count: {}", header.channels));
+    }
+    if header.dsd_rate < DSD64_RATE || header.dsd_rate % (DSD_PCM_RATE * 8) != 0 {
+        return Err(format!("Unsupported DSD sample rate: {}Hz", header.dsd_rate));
+    }
+    Ok(header)
+}
+
+fn read_id<R: Read>(reader: &mut R) -> io::Result<[u8; 4]> {
+    let mut id = [0u8; 4];
+    reader.read_exact(&mut id)?;
+    Ok(id)
+}
+
+fn read_u16_be<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut b = [0u8; 2];
+    reader.read_exact(&mut b)?;
+    Ok(u16::from_be_bytes(b))
+}
+
+fn read_u32_le<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    reader.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u32_be<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    reader.read_exact(&mut b)?;
+    Ok(u32::from_be_bytes(b))
+}
+
+fn read_u64_le<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut b = [0u8; 8];
+    reader.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+fn read_u64_be<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut b = [0u8; 8];
+    reader.read_exact(&mut b)?;
+    Ok(u64::from_be_bytes(b))
+}
+
+/// DSF (Sony) : chunks "DSD " (28 octets), "fmt " puis "data", little-endian
+fn read_dsf_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader, String> {
+    let io_err = |e: io::Error| format!("Invalid DSF header: {}", e);
+
+    read_id(reader).map_err(io_err)?;
+    let dsd_chunk_size = read_u64_le(reader).map_err(io_err)?;
+    reader.seek(SeekFrom::Start(dsd_chunk_size)).map_err(io_err)?;
+
+    if &read_id(reader).map_err(io_err)? != b"fmt " {
+        return Err("Invalid DSF file: missing fmt chunk".to_string());
+    }
+    let fmt_chunk_size = read_u64_le(reader).map_err(io_err)?;
+    let _format_version = read_u32_le(reader).map_err(io_err)?;
+    let format_id = read_u32_le(reader).map_err(io_err)?;
+    let _channel_type = read_u32_le(reader).map_err(io_err)?;
+    let channels = read_u32_le(reader).map_err(io_err)? as usize;
+    let dsd_rate = read_u32_le(reader).map_err(io_err)?;
+    let bits_per_sample = read_u32_le(reader).map_err(io_err)?;
+    let sample_count = read_u64_le(reader).map_err(io_err)?;
+    let block_size = read_u32_le(reader).map_err(io_err)? as usize;
+
+    if format_id != 0 {
+        return Err(format!("Unsupported DSF format id: {}", format_id));
+    }
+    if block_size == 0 {
+        return Err("Invalid DSF block size".to_string());
+    }
+
+    let data_chunk_start = dsd_chunk_size + fmt_chunk_size;
+    reader.seek(SeekFrom::Start(data_chunk_start)).map_err(io_err)?;
+    if &read_id(reader).map_err(io_err)? != b"data" {
+        return Err("Invalid DSF file: missing data chunk".to_string());
+    }
+
+    Ok(DsdHeader {
+        channels,
+        dsd_rate,
+        bytes_per_channel: sample_count.div_ceil(8),
+        data_offset: data_chunk_start + 12,
+        layout: DsdLayout::Dsf { block_size, lsb_first: bits_per_sample != 8 },
+    })
+}
+
+/// DFF / DSDIFF (Philips) : FRM8 > PROP (FS, CHNL, CMPR) > "DSD ", big-endian
+fn read_dff_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader, String> {
+    let io_err = |e: io::Error| format!("Invalid DFF header: {}", e);
+
+    read_id(reader).map_err(io_err)?;
+    let _form_size = read_u64_be(reader).map_err(io_err)?;
+    if &read_id(reader).map_err(io_err)? != b"DSD " {
+        return Err("Invalid DFF file: not a DSD form".to_string());
+    }
+
+    let mut dsd_rate = 0u32;
+    let mut channels = 0usize;
+
+    loop {
+        let id = read_id(reader).map_err(io_err)?;
+        let size = read_u64_be(reader).map_err(io_err)?;
+        let chunk_start = reader.stream_position().map_err(io_err)?;
+        // Les chunks sont alignés sur 2 octets
+        let chunk_end = chunk_start + size + (size & 1);
+
+        match &id {
+            b"PROP" => {
+                if &read_id(reader).map_err(io_err)? != b"SND " {
+                    return Err("Invalid DFF file: unexpected PROP type".to_string());
+                }
+                while reader.stream_position().map_err(io_err)? + 12 <= chunk_start + size {
+                    let sub_id = read_id(reader).map_err(io_err)?;
+                    let sub_size = read_u64_be(reader).map_err(io_err)?;
+                    let sub_start = reader.stream_position().map_err(io_err)?;
+                    match &sub_id {
+                        b"FS  " => dsd_rate = read_u32_be(reader).map_err(io_err)?,
+                        b"CHNL" => channels = read_u16_be(reader).map_err(io_err)? as usize,
+                        b"CMPR" if &read_id(reader).map_err(io_err)? != b"DSD " => {
+                            return Err("DST-compressed DFF files are not supported".to_string());
+                        }
+                        _ => {}
+                    }
+                    reader.seek(SeekFrom::Start(sub_start + sub_size + (sub_size & 1))).map_err(io_err)?;
+                }
+            }
+            b"DSD " => {
+                if channels == 0 || dsd_rate == 0 {
+                    return Err("Invalid DFF file: DSD data before PROP chunk".to_string());
+                }
+                return Ok(DsdHeader {
+                    channels,
+                    dsd_rate,
+                    bytes_per_channel: size / channels as u64,
+                    data_offset: chunk_start,
+                    layout: DsdLayout::Dff,
+                });
+            }
+            b"DST " => return Err("DST-compressed DFF files are not supported".to_string()),
+            _ => {}
+        }
+        reader.seek(SeekFrom::Start(chunk_end)).map_err(io_err)?;
+    }
+}
+
+/// Lit l'en-tête d'un fichier DSD sur disque
+pub fn read_dsd_header(path: &str) -> Result<DsdHeader, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Cannot open file: {}", e))?;
+    read_header(&mut file)
+}
+
+/// Infos audio d'un fichier DSD (équivalent de probe_audio_file pour DSF/DFF)
+pub fn probe_dsd(path: &str) -> Result<AudioInfo, String> {
+    let header = read_dsd_header(path)?;
+
+    #[cfg(debug_assertions)]
+    println!("DEBUG PROBE (DSD): DSD{} ({}Hz), {}ch, {:.1}s → PCM {}Hz",
+        header.dsd_rate / 44_100, header.dsd_rate, header.channels,
+        header.duration_seconds(), DSD_PCM_RATE);
+
+    Ok(AudioInfo {
+        sample_rate: DSD_PCM_RATE,
+        output_sample_rate: DSD_PCM_RATE,
+        channels: header.channels,
+        duration_seconds: header.duration_seconds(),
+        total_frames: header.pcm_frames(),
+        bit_depth: 1,
+        is_resampled: false,
+    })
+}
+
+/// FIR passe-bas de décimation, précalculé en tables : une table de 256 valeurs
+/// par octet de l'historique (somme des 8 taps couverts par l'octet, bit à 1 → +h, à 0 → -h)
+struct DsdFilter {
+    tables: Vec<[f32; 256]>,
+}
+
+impl DsdFilter {
+    fn new(dsd_rate: u32) -> Self {
+        let taps = FILTER_TAPS_DSD64 * (dsd_rate / DSD64_RATE).max(1) as usize;
+        let cutoff = FILTER_CUTOFF_HZ / dsd_rate as f64;
+        let m = (taps - 1) as f64;
+
+        // Sinc fenêtré (Blackman), normalisé pour un gain DSD_OUTPUT_GAIN en continu
+        let mut coeffs: Vec<f64> = (0..taps)
+            .map(|n| {
+                let x = n as f64 - m / 2.0;
+                let sinc = if x == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * std::f64::consts::PI * cutoff * x).sin() / (std::f64::consts::PI * x)
+                };
+                let phase = 2.0 * std::f64::consts::PI * n as f64 / m;
+                sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+            })
+            .collect();
+        let sum: f64 = coeffs.iter().sum();
+        coeffs.iter_mut().for_each(|c| *c *= DSD_OUTPUT_GAIN / sum);
+
+        let tables = coeffs
+            .chunks(8)
+            .map(|group| {
+                let mut table = [0.0f32; 256];
+                for (byte, slot) in table.iter_mut().enumerate() {
+                    *slot = group.iter().enumerate()
+                        .map(|(bit, c)| if byte & (0x80 >> bit) != 0 { *c } else { -*c })
+                        .sum::<f64>() as f32;
+                }
+                table
+            })
+            .collect();
+
+        Self { tables }
+    }
+
+    fn history_len(&self) -> usize {
+        self.tables.len()
+    }
+}
+
+/// Historique d'un canal (octets DSD, MSB = bit le plus ancien)
+struct ChannelHistory {
+    bytes: Vec<u8>,
+    pos: usize,
+}
+
+impl ChannelHistory {
+    fn new(len: usize) -> Self {
+        Self { bytes: vec![DSD_SILENCE; len], pos: 0 }
+    }
+
+    fn reset(&mut self) {
+        self.bytes.fill(DSD_SILENCE);
+        self.pos = 0;
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.bytes[self.pos] = byte;
+        self.pos = (self.pos + 1) % self.bytes.len();
+    }
+
+    /// Sortie du filtre pour l'historique courant (pos = octet le plus ancien)
+    fn output(&self, filter: &DsdFilter) -> f32 {
+        let len = self.bytes.len();
+        filter.tables.iter().enumerate()
+            .map(|(k, table)| table[self.bytes[(self.pos + k) % len] as usize])
+            .sum()
+    }
+}
+
+/// FormatReader DSF/DFF : lit le flux 1-bit et émet des paquets PCM f32 entrelacés
+pub struct DsdFormatReader {
+    source: MediaSourceStream,
+    header: DsdHeader,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    filter: DsdFilter,
+    history: Vec<ChannelHistory>,
+    /// Octets DSD déjà lus par canal
+    byte_pos: u64,
+    raw: Vec<u8>,
+}
+
+impl DsdFormatReader {
+    pub fn open(mut source: MediaSourceStream) -> Result<Self, String> {
+        let header = read_header(&mut source)?;
+        source.seek(SeekFrom::Start(header.data_offset)).map_err(|e| e.to_string())?;
+
+        let max_frames = (header.packet_bytes_per_channel() / header.bytes_per_frame()) as u64;
+        let mut codec_params = CodecParameters::new();
+        codec_params
+            .for_codec(CODEC_TYPE_PCM_F32LE)
+            .with_sample_rate(DSD_PCM_RATE)
+            .with_channels(Channels::from_bits_truncate((1u32 << header.channels) - 1))
+            .with_n_frames(header.pcm_frames())
+            .with_time_base(TimeBase::new(1, DSD_PCM_RATE))
+            .with_max_frames_per_packet(max_frames);
+
+        let filter = DsdFilter::new(header.dsd_rate);
+        let history = (0..header.channels).map(|_| ChannelHistory::new(filter.history_len())).collect();
+        let raw = vec![0u8; header.packet_bytes_per_channel() * header.channels];
+
+        Ok(Self {
+            source,
+            tracks: vec![Track::new(0, codec_params)],
+            cues: Vec::new(),
+            metadata: MetadataLog::default(),
+            filter,
+            history,
+            byte_pos: 0,
+            raw,
+            header,
+        })
+    }
+
+    /// Décime `valid` octets par canal de `self.raw` en frames PCM entrelacées
+    fn convert(&mut self, valid: usize) -> Vec<f32> {
+        let channels = self.header.channels;
+        let per_frame = self.header.bytes_per_frame();
+        let frames = valid / per_frame;
+        let mut pcm = vec![0.0f32; frames * channels];
+
+        for ch in 0..channels {
+            let history = &mut self.history[ch];
+            for frame in 0..frames {
+                for i in 0..per_frame {
+                    let idx = frame * per_frame + i;
+                    let byte = match self.header.layout {
+                        DsdLayout::Dsf { block_size, lsb_first } => {
+                            let b = self.raw[ch * block_size + idx];
+                            if lsb_first { b.reverse_bits() } else { b }
+                        }
+                        DsdLayout::Dff => self.raw[idx * channels + ch],
+                    };
+                    history.push(byte);
+                }
+                pcm[frame * channels + ch] = history.output(&self.filter);
+            }
+        }
+        pcm
+    }
+}
+
+impl FormatReader for DsdFormatReader {
+    fn try_new(source: MediaSourceStream, _options: &FormatOptions) -> SymphoniaResult<Self> {
+        match Self::open(source) {
+            Ok(reader) => Ok(reader),
+            Err(_) => unsupported_error("dsd: unsupported or invalid DSD stream"),
+        }
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> SymphoniaResult<SeekedTo> {
+        let per_frame = self.header.bytes_per_frame() as u64;
+        let required_ts = match to {
+            SeekTo::Time { time, .. } => {
+                ((time.seconds as f64 + time.frac) * DSD_PCM_RATE as f64) as u64
+            }
+            SeekTo::TimeStamp { ts, .. } => ts,
+        };
+
+        // DSF : on ne peut reprendre qu'au début d'un bloc
+        let target = (required_ts * per_frame).min(self.header.bytes_per_channel);
+        let aligned = match self.header.layout {
+            DsdLayout::Dsf { block_size, .. } => target / block_size as u64 * block_size as u64,
+            DsdLayout::Dff => target,
+        };
+
+        let offset = self.header.data_offset + aligned * self.header.channels as u64;
+        self.source.seek(SeekFrom::Start(offset)).map_err(SymphoniaError::IoError)?;
+        self.byte_pos = aligned;
+        self.history.iter_mut().for_each(ChannelHistory::reset);
+
+        Ok(SeekedTo { track_id: 0, required_ts, actual_ts: aligned / per_frame })
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn next_packet(&mut self) -> SymphoniaResult<Packet> {
+        let per_frame = self.header.bytes_per_frame();
+        let remaining = self.header.bytes_per_channel.saturating_sub(self.byte_pos);
+        if remaining < per_frame as u64 {
+            return Err(SymphoniaError::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "end of DSD stream",
+            )));
+        }
+
+        let packet_bytes = self.header.packet_bytes_per_channel();
+        let to_read = match self.header.layout {
+            // Le dernier bloc DSF est complété par du padding : toujours un bloc entier par canal
+            DsdLayout::Dsf { .. } => self.raw.len(),
+            DsdLayout::Dff => (remaining as usize).min(packet_bytes) * self.header.channels,
+        };
+        self.source.read_exact(&mut self.raw[..to_read]).map_err(SymphoniaError::IoError)?;
+
+        let consumed = (remaining as usize).min(packet_bytes);
+        let valid = consumed / per_frame * per_frame;
+        let pcm = self.convert(valid);
+
+        let ts = self.byte_pos / per_frame as u64;
+        let frames = (valid / per_frame) as u64;
+        self.byte_pos += consumed as u64;
+
+        let data: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+        Ok(Packet::new_from_boxed_slice(0, ts, frames, data.into_boxed_slice()))
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// DSF stéréo DSD64 minimal : un bloc de 4096 octets par canal
+    fn make_dsf(fill: u8, sample_count: u64) -> Vec<u8> {
+        let block = 4096usize;
+        let mut f = Vec::new();
+        f.extend_from_slice(b"DSD ");
+        f.extend_from_slice(&28u64.to_le_bytes());
+        f.extend_from_slice(&0u64.to_le_bytes());
+        f.extend_from_slice(&0u64.to_le_bytes());
+        f.extend_from_slice(b"fmt ");
+        f.extend_from_slice(&52u64.to_le_bytes());
+        f.extend_from_slice(&1u32.to_le_bytes());
+        f.extend_from_slice(&0u32.to_le_bytes());
+        f.extend_from_slice(&2u32.to_le_bytes());
+        f.extend_from_slice(&2u32.to_le_bytes());
+        f.extend_from_slice(&DSD64_RATE.to_le_bytes());
+        f.extend_from_slice(&1u32.to_le_bytes());
+        f.extend_from_slice(&sample_count.to_le_bytes());
+        f.extend_from_slice(&(block as u32).to_le_bytes());
+        f.extend_from_slice(&0u32.to_le_bytes());
+        f.extend_from_slice(b"data");
+        f.extend_from_slice(&(12 + 2 * block as u64).to_le_bytes());
+        f.extend(std::iter::repeat_n(fill, 2 * block));
+        f
+    }
+
+    #[test]
+    fn test_dsf_header() {
+        let header = read_header(&mut Cursor::new(make_dsf(DSD_SILENCE, 4096 * 8))).unwrap();
+        assert_eq!(header.channels, 2);
+        assert_eq!(header.dsd_rate, DSD64_RATE);
+        assert_eq!(header.bytes_per_channel, 4096);
+        assert_eq!(header.data_offset, 92);
+        assert_eq!(header.pcm_frames(), 2048);
+    }
+
+    #[test]
+    fn test_filter_dc_gain() {
+        let filter = DsdFilter::new(DSD64_RATE);
+        let mut history = ChannelHistory::new(filter.history_len());
+        for _ in 0..filter.history_len() {
+            history.push(0xFF);
+        }
+        assert!((history.output(&filter) - 0.5).abs() < 1e-3);
+
+        history.reset();
+        assert!(history.output(&filter).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dsf_packets() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(make_dsf(0xFF, 4096 * 8))), Default::default());
+        let mut reader = DsdFormatReader::open(mss).unwrap();
+
+        let packet = reader.next_packet().unwrap();
+        assert_eq!(packet.dur, 2048);
+        assert_eq!(packet.buf().len(), 2048 * 2 * 4);
+        assert!(reader.next_packet().is_err());
+
+        let seeked = reader.seek(SeekMode::Coarse, SeekTo::TimeStamp { ts: 0, track_id: 0 }).unwrap();
+        assert_eq!(seeked.actual_ts, 0);
+        assert!(reader.next_packet().is_ok());
+    }
+
+    #[test]
+    fn test_full_scale_dsd_stays_at_half_amplitude() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(make_dsf(0xFF, 4096 * 8))), Default::default());
+        let mut reader = DsdFormatReader::open(mss).unwrap();
+        // Frames stéréo couvrant l'historique initial (silence) : transitoire du filtre
+        let warmup = reader.filter.history_len() * 2;
+
+        let packet = reader.next_packet().unwrap();
+        let samples: Vec<f32> = packet.buf()
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        assert!(samples.len() > warmup);
+        for sample in &samples[warmup..] {
+            assert!(sample.abs() <= 0.5 + 1e-3, "sample {}", sample);
+            assert!((sample - 0.5).abs() < 1e-3);
+        }
+    }
+}
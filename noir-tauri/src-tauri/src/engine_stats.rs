@@ -0,0 +1,136 @@
+//! Charge CPU du décodage et du resampling (get_engine_stats)
+//!
+//! Architecture :
+//! - Le thread de décodage et le resampler chronomètrent chaque buffer qu'ils traitent
+//! - Les durées sont moyennées par lots de STATS_BATCH buffers dans le thread qui mesure, puis
+//!   lissées (moyenne mobile exponentielle) dans des atomiques : une seule écriture par lot
+//! - Mesure globale au process : le décodeur d'un preload gapless y contribue aussi,
+//!   c'est bien la charge réelle qui est reportée
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Nombre de buffers moyennés avant publication
+const STATS_BATCH: u32 = 32;
+
+/// Poids d'un nouveau lot dans la moyenne mobile
+const EMA_ALPHA: f64 = 0.2;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Stage {
+    Decode,
+    Resample,
+}
+
+/// Temps moyen par buffer (µs, f64 bits ; 0 = pas encore mesuré)
+pub struct ProcessingStats {
+    decode_us: AtomicU64,
+    resample_us: AtomicU64,
+}
+
+pub static PROCESSING_STATS: ProcessingStats = ProcessingStats::new();
+
+impl ProcessingStats {
+    pub const fn new() -> Self {
+        Self {
+            decode_us: AtomicU64::new(0),
+            resample_us: AtomicU64::new(0),
+        }
+    }
+
+    fn slot(&self, stage: Stage) -> &AtomicU64 {
+        match stage {
+            Stage::Decode => &self.decode_us,
+            Stage::Resample => &self.resample_us,
+        }
+    }
+
+    pub fn average_us(&self, stage: Stage) -> f64 {
+        f64::from_bits(self.slot(stage).load(Ordering::Relaxed))
+    }
+
+    /// Intègre la moyenne d'un lot dans la moyenne mobile
+    fn publish(&self, stage: Stage, batch_avg_us: f64) {
+        let smoothed = ema(self.average_us(stage), batch_avg_us);
+        self.slot(stage).store(smoothed.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Moyenne mobile exponentielle ; la première mesure sert de valeur initiale
+fn ema(previous: f64, sample: f64) -> f64 {
+    if previous <= 0.0 {
+        sample
+    } else {
+        previous + EMA_ALPHA * (sample - previous)
+    }
+}
+
+/// Chronométrage par lot, propre au thread qui mesure (aucun atomique par buffer)
+pub struct BatchTimer {
+    stage: Stage,
+    total: Duration,
+    count: u32,
+}
+
+impl BatchTimer {
+    pub fn new(stage: Stage) -> Self {
+        Self { stage, total: Duration::ZERO, count: 0 }
+    }
+
+    pub fn record(&mut self, elapsed: Duration) {
+        self.record_into(&PROCESSING_STATS, elapsed);
+    }
+
+    fn record_into(&mut self, stats: &ProcessingStats, elapsed: Duration) {
+        self.total += elapsed;
+        self.count += 1;
+        if self.count >= STATS_BATCH {
+            let batch_avg_us = self.total.as_secs_f64() * 1_000_000.0 / self.count as f64;
+            stats.publish(self.stage, batch_avg_us);
+            self.total = Duration::ZERO;
+            self.count = 0;
+        }
+    }
+}
+
+/// Statistiques renvoyées au frontend (get_engine_stats)
+#[derive(Clone, Debug, Serialize)]
+pub struct EngineStats {
+    /// Temps moyen de décodage d'un packet (µs)
+    pub avg_decode_us: f64,
+    /// Temps moyen de resampling d'un packet décodé (µs, 0 sans resampling)
+    pub avg_resample_us: f64,
+    pub resampling_active: bool,
+    /// Underruns depuis le dernier Play (voir BufferHealth)
+    pub underruns: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_starts_at_first_sample_and_converges() {
+        assert_eq!(ema(0.0, 120.0), 120.0);
+        let mut value = 100.0;
+        for _ in 0..50 {
+            value = ema(value, 200.0);
+        }
+        assert!((value - 200.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_batch_timer_publishes_once_per_batch() {
+        let stats = ProcessingStats::new();
+        let mut timer = BatchTimer::new(Stage::Decode);
+        for _ in 0..STATS_BATCH - 1 {
+            timer.record_into(&stats, Duration::from_micros(50));
+        }
+        assert_eq!(stats.average_us(Stage::Decode), 0.0);
+        timer.record_into(&stats, Duration::from_micros(50));
+        assert!((stats.average_us(Stage::Decode) - 50.0).abs() < 1e-6);
+        assert_eq!(stats.average_us(Stage::Resample), 0.0);
+    }
+}
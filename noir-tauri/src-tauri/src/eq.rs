@@ -3,17 +3,20 @@
 //! Architecture :
 //! - Les gains (f32 encodés en u32 bits) sont partagés via Arc<AtomicU32>
 //! - Les filtres biquad vivent dans le callback audio (pas thread-safe)
-//! - Les coefficients sont recalculés dans le callback quand un gain change
+//! - Les fréquences centrales sont partagées de la même façon (configurables, 20 Hz – 20 kHz)
+//! - Les coefficients sont recalculés dans le callback quand un gain ou une fréquence change
+//! - Préampli global appliqué avant les filtres (marge anti-clipping), même si les bandes sont à plat
 //! - 0 dB gain = filtre bypassé (pas de traitement, bit-perfect)
 
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type, Q_BUTTERWORTH_F32};
+use serde::Serialize;
 
 /// Nombre de bandes de l'égaliseur
 pub const EQ_BAND_COUNT: usize = 8;
 
-/// Fréquences centrales des 8 bandes (Hz)
+/// Fréquences centrales par défaut des 8 bandes (Hz)
 pub const EQ_FREQUENCIES: [f32; EQ_BAND_COUNT] = [
     32.0, 64.0, 250.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
 ];
@@ -23,18 +26,48 @@ pub const EQ_LABELS: [&str; EQ_BAND_COUNT] = [
     "32", "64", "250", "1k", "2k", "4k", "8k", "16k",
 ];
 
+/// Presets intégrés (non supprimables), gains en dB par bande
+pub const EQ_BUILTIN_PRESETS: [(&str, [f32; EQ_BAND_COUNT]); 11] = [
+    ("Flat",         [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+    ("Bass Boost",   [6.0, 5.0, 3.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+    ("Treble Boost", [0.0, 0.0, 0.0, 0.0, 1.0, 3.0, 5.0, 6.0]),
+    ("Loudness",     [4.0, 3.0, 0.0, -1.0, -1.0, 0.0, 3.0, 4.0]),
+    ("Vocal",        [-2.0, -1.0, 0.0, 3.0, 4.0, 2.0, 0.0, -1.0]),
+    ("Rock",         [4.0, 3.0, 1.0, 0.0, -1.0, 1.0, 3.0, 4.0]),
+    ("Jazz",         [3.0, 2.0, 0.0, 1.0, -1.0, -1.0, 1.0, 3.0]),
+    ("Classical",    [0.0, 0.0, 0.0, 0.0, 0.0, -1.0, -2.0, -3.0]),
+    ("Electronic",   [5.0, 4.0, 1.0, 0.0, 0.0, 1.0, 3.0, 5.0]),
+    ("Hip-Hop",      [5.0, 4.0, 2.0, 0.0, -1.0, 1.0, 0.0, 2.0]),
+    ("Late Night",   [3.0, 2.0, 0.0, -2.0, -2.0, 0.0, 1.0, 2.0]),
+];
+
 /// Gain minimum et maximum en dB
 pub const EQ_MIN_DB: f32 = -12.0;
 pub const EQ_MAX_DB: f32 = 12.0;
 
+/// Plage de fréquences autorisée pour une bande (Hz)
+pub const EQ_MIN_FREQUENCY: f32 = 20.0;
+pub const EQ_MAX_FREQUENCY: f32 = 20000.0;
+
 /// Q factor pour les filtres peaking EQ (Butterworth par défaut)
 const EQ_Q: f32 = Q_BUTTERWORTH_F32;
 
+/// Définition d'une bande exposée au frontend
+#[derive(Serialize, Clone, Debug)]
+pub struct EqBand {
+    pub frequency: f32,
+    pub gain: f32,
+    pub q: f32,
+}
+
 /// État partagé de l'EQ (thread-safe, passé via Arc)
-/// Les gains sont stockés comme f32::to_bits() dans des AtomicU32
+/// Les gains et fréquences sont stockés comme f32::to_bits() dans des AtomicU32
 pub struct EqSharedState {
     pub enabled: Arc<AtomicBool>,
     pub gains: [Arc<AtomicU32>; EQ_BAND_COUNT],
+    pub frequencies: [Arc<AtomicU32>; EQ_BAND_COUNT],
+    /// Préampli en dB (f32 bits)
+    pub preamp: Arc<AtomicU32>,
 }
 
 impl EqSharedState {
@@ -43,6 +76,8 @@ impl EqSharedState {
         Self {
             enabled: Arc::new(AtomicBool::new(false)),
             gains: std::array::from_fn(|_| Arc::new(AtomicU32::new(zero_bits))),
+            frequencies: std::array::from_fn(|i| Arc::new(AtomicU32::new(f32::to_bits(EQ_FREQUENCIES[i])))),
+            preamp: Arc::new(AtomicU32::new(zero_bits)),
         }
     }
 
@@ -75,6 +110,51 @@ impl EqSharedState {
         std::array::from_fn(|i| self.get_gain(i))
     }
 
+    /// Change la fréquence centrale d'une bande (Hz, 20 Hz – 20 kHz)
+    pub fn set_frequency(&self, band: usize, frequency: f32) -> Result<(), String> {
+        if band >= EQ_BAND_COUNT {
+            return Err(format!("Invalid EQ band index: {}", band));
+        }
+        if !(EQ_MIN_FREQUENCY..=EQ_MAX_FREQUENCY).contains(&frequency) {
+            return Err(format!(
+                "EQ frequency must be between {} and {} Hz",
+                EQ_MIN_FREQUENCY, EQ_MAX_FREQUENCY
+            ));
+        }
+        self.frequencies[band].store(f32::to_bits(frequency), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Lit la fréquence centrale d'une bande
+    pub fn get_frequency(&self, band: usize) -> f32 {
+        if band < EQ_BAND_COUNT {
+            f32::from_bits(self.frequencies[band].load(Ordering::Relaxed))
+        } else {
+            0.0
+        }
+    }
+
+    /// Définition complète des bandes (fréquence, gain, Q)
+    pub fn get_bands(&self) -> Vec<EqBand> {
+        (0..EQ_BAND_COUNT)
+            .map(|i| EqBand {
+                frequency: self.get_frequency(i),
+                gain: self.get_gain(i),
+                q: EQ_Q,
+            })
+            .collect()
+    }
+
+    /// Met à jour le préampli (en dB, clampé à [-12, +12])
+    pub fn set_preamp(&self, gain_db: f32) {
+        let clamped = gain_db.clamp(EQ_MIN_DB, EQ_MAX_DB);
+        self.preamp.store(f32::to_bits(clamped), Ordering::Relaxed);
+    }
+
+    pub fn get_preamp(&self) -> f32 {
+        f32::from_bits(self.preamp.load(Ordering::Relaxed))
+    }
+
     /// Active/désactive l'EQ
     pub fn set_enabled(&self, enabled: bool) {
         self.enabled.store(enabled, Ordering::Relaxed);
@@ -90,6 +170,8 @@ impl Clone for EqSharedState {
         Self {
             enabled: Arc::clone(&self.enabled),
             gains: std::array::from_fn(|i| Arc::clone(&self.gains[i])),
+            frequencies: std::array::from_fn(|i| Arc::clone(&self.frequencies[i])),
+            preamp: Arc::clone(&self.preamp),
         }
     }
 }
@@ -135,13 +217,14 @@ impl EqBandFilter {
         })
     }
 
-    /// Met à jour les coefficients si le gain a changé
+    /// Met à jour les coefficients si le gain ou la fréquence a changé
     /// Retourne true si les coefficients ont été recalculés
-    fn update_if_needed(&mut self, new_gain_db: f32, sample_rate: f32) -> bool {
-        // Seuil de 0.01 dB pour éviter les recalculs inutiles
-        if (new_gain_db - self.current_gain_db).abs() > 0.01 {
+    fn update_if_needed(&mut self, new_gain_db: f32, new_freq: f32, sample_rate: f32) -> bool {
+        // Seuils de 0.01 dB / 0.01 Hz pour éviter les recalculs inutiles
+        if (new_gain_db - self.current_gain_db).abs() > 0.01 || (new_freq - self.freq).abs() > 0.01 {
             self.current_gain_db = new_gain_db;
-            let coeffs = Self::make_coeffs(self.freq, new_gain_db, sample_rate);
+            self.freq = new_freq;
+            let coeffs = Self::make_coeffs(new_freq, new_gain_db, sample_rate);
             self.filter_l = DirectForm1::<f32>::new(coeffs);
             self.filter_r = DirectForm1::<f32>::new(coeffs);
             true
@@ -203,13 +286,22 @@ impl EqProcessor {
             return;
         }
 
-        // Lit les gains et met à jour les coefficients si nécessaire
+        // Lit gains et fréquences, met à jour les coefficients si nécessaire
         for (i, band) in self.bands.iter_mut().enumerate() {
             let new_gain = f32::from_bits(shared.gains[i].load(Ordering::Relaxed));
-            band.update_if_needed(new_gain, self.sample_rate);
+            let new_freq = f32::from_bits(shared.frequencies[i].load(Ordering::Relaxed));
+            band.update_if_needed(new_gain, new_freq, self.sample_rate);
         }
 
-        // Applique les filtres sur chaque frame stéréo
+        // Préampli : 0 dB = aucun traitement
+        let preamp_db = shared.get_preamp();
+        let preamp = if preamp_db.abs() > 0.01 {
+            Some(10.0f32.powf(preamp_db / 20.0))
+        } else {
+            None
+        };
+
+        // Applique le préampli puis les filtres sur chaque frame stéréo
         for frame in 0..frames {
             let l_idx = frame * 2;
             let r_idx = frame * 2 + 1;
@@ -218,6 +310,11 @@ impl EqProcessor {
             let mut l = samples[l_idx];
             let mut r = samples[r_idx];
 
+            if let Some(gain) = preamp {
+                l *= gain;
+                r *= gain;
+            }
+
             for band in &mut self.bands {
                 // Bypass les bandes à 0 dB (bit-perfect quand flat)
                 if band.current_gain_db.abs() > 0.01 {
@@ -0,0 +1,248 @@
+//! Import d'une bibliothèque iTunes / Musique (`iTunes Library.xml`)
+//!
+//! Architecture :
+//! - Lecture du plist XML par le crate `plist` (désérialisation serde des seules clés utiles)
+//! - Tracks : `Location` (URL file:// décodée en chemin), `Play Count`, `Play Date UTC`, `Rating`
+//!   (les notes déduites de celle de l'album, `Rating Computed`, sont ignorées)
+//! - Playlists : `Playlist Items` → Track IDs ; la bibliothèque maître, les playlists
+//!   spéciales (Distinguished Kind) et les dossiers sont ignorés
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+
+/// Track de la bibliothèque iTunes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItunesTrack {
+    pub id: i64,
+    pub name: String,
+    pub artist: String,
+    /// Chemin local décodé (None pour les tracks en streaming / sans fichier)
+    pub path: Option<String>,
+    pub play_count: u32,
+    /// Dernière écoute (timestamp Unix)
+    pub last_played: Option<u64>,
+    /// Note en étoiles (0 = pas de note propre au track)
+    pub stars: u8,
+}
+
+/// Playlist utilisateur
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItunesPlaylist {
+    pub name: String,
+    pub track_ids: Vec<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct ItunesLibrary {
+    pub tracks: HashMap<i64, ItunesTrack>,
+    pub playlists: Vec<ItunesPlaylist>,
+}
+
+// Clés lues dans le plist ; les autres sont ignorées par serde
+#[derive(Deserialize)]
+struct RawLibrary {
+    #[serde(rename = "Tracks", default)]
+    tracks: HashMap<String, RawTrack>,
+    #[serde(rename = "Playlists", default)]
+    playlists: Vec<RawPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct RawTrack {
+    #[serde(rename = "Track ID")]
+    id: Option<i64>,
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Artist", default)]
+    artist: String,
+    #[serde(rename = "Location")]
+    location: Option<String>,
+    #[serde(rename = "Play Count", default)]
+    play_count: i64,
+    #[serde(rename = "Play Date UTC")]
+    play_date: Option<plist::Date>,
+    /// 0–100, 20 par étoile
+    #[serde(rename = "Rating", default)]
+    rating: i64,
+    #[serde(rename = "Rating Computed", default)]
+    rating_computed: bool,
+}
+
+#[derive(Deserialize)]
+struct RawPlaylist {
+    #[serde(rename = "Name")]
+    name: Option<String>,
+    #[serde(rename = "Master", default)]
+    master: bool,
+    #[serde(rename = "Folder", default)]
+    folder: bool,
+    #[serde(rename = "Distinguished Kind")]
+    distinguished_kind: Option<i64>,
+    #[serde(rename = "Playlist Items", default)]
+    items: Vec<RawPlaylistItem>,
+}
+
+#[derive(Deserialize)]
+struct RawPlaylistItem {
+    #[serde(rename = "Track ID")]
+    id: Option<i64>,
+}
+
+pub fn read_library(path: &str) -> Result<ItunesLibrary, String> {
+    let raw: RawLibrary = plist::from_file(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    Ok(convert_library(raw))
+}
+
+pub fn parse_library(xml: &str) -> Result<ItunesLibrary, String> {
+    let raw: RawLibrary = plist::from_bytes(xml.as_bytes()).map_err(|e| format!("Invalid iTunes library: {}", e))?;
+    Ok(convert_library(raw))
+}
+
+fn convert_library(raw: RawLibrary) -> ItunesLibrary {
+    let mut library = ItunesLibrary::default();
+
+    for track in raw.tracks.into_values() {
+        let Some(id) = track.id else { continue };
+        library.tracks.insert(id, ItunesTrack {
+            id,
+            name: track.name,
+            artist: track.artist,
+            path: track.location.as_deref().and_then(file_url_to_path),
+            play_count: track.play_count.clamp(0, u32::MAX as i64) as u32,
+            last_played: track.play_date
+                .and_then(|date| SystemTime::from(date).duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            stars: if track.rating_computed { 0 } else { rating_to_stars(track.rating) },
+        });
+    }
+
+    for playlist in raw.playlists {
+        if playlist.master || playlist.folder || playlist.distinguished_kind.is_some() {
+            continue;
+        }
+        library.playlists.push(ItunesPlaylist {
+            name: playlist.name.unwrap_or_else(|| "iTunes Playlist".to_string()),
+            track_ids: playlist.items.iter().filter_map(|item| item.id).collect(),
+        });
+    }
+
+    library
+}
+
+/// Note iTunes 0–100 → étoiles, arrondie à l'étoile la plus proche (les demi-étoiles
+/// de certaines versions donnent des multiples de 10)
+fn rating_to_stars(rating: i64) -> u8 {
+    ((rating.clamp(0, 100) + 10) / 20) as u8
+}
+
+/// `file://localhost/Users/me/Music/A%20B.m4a` → `/Users/me/Music/A B.m4a`
+pub fn file_url_to_path(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !rest.starts_with('/') {
+        return None;
+    }
+    let path = percent_decode_str(rest).decode_utf8_lossy().to_string();
+    // iTunes termine les dossiers par "/" ; un fichier ne doit pas en avoir
+    Some(path.trim_end_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Major Version</key><integer>1</integer>
+	<key>Show Content Ratings</key><true/>
+	<key>Tracks</key>
+	<dict>
+		<key>101</key>
+		<dict>
+			<key>Track ID</key><integer>101</integer>
+			<key>Name</key><string>So What</string>
+			<key>Artist</key><string>Miles Davis</string>
+			<key>Play Count</key><integer>12</integer>
+			<key>Play Date UTC</key><date>2021-03-04T05:06:07Z</date>
+			<key>Rating</key><integer>80</integer>
+			<key>Location</key><string>file:///Users/me/Music/Miles%20Davis/Kind%20of%20Blue/01%20So%20What.m4a</string>
+		</dict>
+		<key>102</key>
+		<dict>
+			<key>Track ID</key><integer>102</integer>
+			<key>Name</key><string>Rock &amp; Roll</string>
+			<key>Artwork Count</key><integer>1</integer>
+			<key>Rating</key><integer>60</integer>
+			<key>Rating Computed</key><true/>
+			<key>Location</key><string>file://localhost/Volumes/NAS/Led%20Zeppelin/Rock%20&amp;%20Roll.flac</string>
+		</dict>
+	</dict>
+	<key>Playlists</key>
+	<array>
+		<dict>
+			<key>Name</key><string>Library</string>
+			<key>Master</key><true/>
+			<key>Playlist Items</key><array><dict><key>Track ID</key><integer>101</integer></dict></array>
+		</dict>
+		<dict>
+			<key>Name</key><string>Music</string>
+			<key>Distinguished Kind</key><integer>4</integer>
+		</dict>
+		<dict>
+			<key>Name</key><string>Late Night</string>
+			<key>Playlist Items</key>
+			<array>
+				<dict><key>Track ID</key><integer>102</integer></dict>
+				<dict><key>Track ID</key><integer>101</integer></dict>
+			</array>
+		</dict>
+		<dict>
+			<key>Name</key><string>Empty</string>
+			<key>Playlist Items</key><array/>
+		</dict>
+	</array>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn test_parse_itunes_library() {
+        let library = parse_library(LIBRARY).unwrap();
+
+        let so_what = &library.tracks[&101];
+        assert_eq!(so_what.path.as_deref(), Some("/Users/me/Music/Miles Davis/Kind of Blue/01 So What.m4a"));
+        assert_eq!((so_what.play_count, so_what.stars), (12, 4));
+        assert_eq!(so_what.last_played, Some(1_614_834_367));
+
+        let rock = &library.tracks[&102];
+        assert_eq!(rock.name, "Rock & Roll");
+        assert_eq!(rock.path.as_deref(), Some("/Volumes/NAS/Led Zeppelin/Rock & Roll.flac"));
+        assert_eq!(rock.play_count, 0);
+        // Note déduite de l'album : pas importée
+        assert_eq!(rock.stars, 0);
+
+        // Bibliothèque maître et playlists spéciales ignorées
+        let names: Vec<&str> = library.playlists.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Late Night", "Empty"]);
+        assert_eq!(library.playlists[0].track_ids, vec![102, 101]);
+    }
+
+    #[test]
+    fn test_file_url_to_path() {
+        assert_eq!(file_url_to_path("file:///Users/me/A%C3%A9.mp3").as_deref(), Some("/Users/me/Aé.mp3"));
+        assert_eq!(file_url_to_path("http://example.com/a.mp3"), None);
+    }
+
+    #[test]
+    fn test_rating_to_stars() {
+        assert_eq!(rating_to_stars(0), 0);
+        assert_eq!(rating_to_stars(20), 1);
+        assert_eq!(rating_to_stars(50), 3);
+        assert_eq!(rating_to_stars(100), 5);
+        assert_eq!(rating_to_stars(140), 5);
+    }
+}
@@ -23,8 +23,30 @@ mod audio;
 pub mod audio_decoder;
 mod audio_engine;
 mod resampler;
+mod dsd;
+mod wavpack;
+mod smart_playlists;
+mod artist_aliases;
+mod ratings;
+mod session;
+mod lyrics;
+mod search;
+mod cue;
+mod chapters;
+mod waveform;
+mod spectrum;
+mod output_stage;
+mod mixes;
+mod library_export;
+mod itunes;
+mod queue;
+mod library_paths;
+mod backup;
+mod coalesce;
+mod engine_stats;
 mod eq;
 use audio_engine::AudioEngine;
+use audio::AudioError;
 
 // === MEDIA CONTROLS (MPRemoteCommandCenter — media keys macOS) ===
 mod media_controls;
@@ -47,7 +69,7 @@ struct AudioTrack {
 }
 
 // Structure pour un fichier audio avec métadonnées (scan rapide)
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub(crate) struct TrackWithMetadata {
     path: String,
     name: String,
@@ -56,7 +78,7 @@ pub(crate) struct TrackWithMetadata {
 }
 
 // Structure pour les métadonnées
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub(crate) struct Metadata {
     title: String,
     artist: String,
@@ -68,6 +90,9 @@ pub(crate) struct Metadata {
     genre: Option<String>,
     #[serde(default)]
     genre_enriched: bool,
+    /// Genre choisi manuellement (set_album_genre) : jamais modifié par l'enrichissement
+    #[serde(default)]
+    genre_locked: bool,
     duration: f64,
     #[serde(rename = "bitDepth")]
     bit_depth: Option<u8>,
@@ -77,6 +102,46 @@ pub(crate) struct Metadata {
     codec: Option<String>,
     #[serde(rename = "fileSize", default)]
     file_size: Option<u64>,
+    /// Tag ALBUMARTIST / TPE2 (regroupe les compilations sous "Various Artists")
+    #[serde(rename = "albumArtist", default)]
+    album_artist: Option<String>,
+    /// `artist` séparé en interprètes individuels (`artist` reste la chaîne affichée)
+    #[serde(default)]
+    artists: Vec<String>,
+    /// Tag COMPOSER / TCOM (axe de navigation pour la musique classique)
+    #[serde(default)]
+    composer: Option<String>,
+    /// Tag COMPILATION / TCMP / cpil
+    #[serde(default)]
+    compilation: bool,
+    /// Tempo (TBPM / BPM / tmpo)
+    #[serde(default)]
+    bpm: Option<f32>,
+    /// Tonalité (TKEY / INITIALKEY), notation libre : "Am", "8A"…
+    #[serde(rename = "initialKey", default)]
+    initial_key: Option<String>,
+}
+
+// Track de test partagé par les tests des modules : FLAC 16 bits / 44.1 kHz de 200 s,
+// titre = chemin ; chaque test ajuste les champs qu'il vérifie
+#[cfg(test)]
+pub(crate) fn test_track(path: &str) -> TrackWithMetadata {
+    TrackWithMetadata {
+        path: path.to_string(),
+        name: path.to_string(),
+        folder: "/music".to_string(),
+        metadata: Metadata {
+            title: path.to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            track: 1,
+            duration: 200.0,
+            bit_depth: Some(16),
+            sample_rate: Some(44100),
+            codec: Some("FLAC".to_string()),
+            ..Default::default()
+        },
+    }
 }
 
 // Configuration de la bibliothèque
@@ -90,6 +155,71 @@ struct Config {
     /// L'utilisateur peut désactiver dans Settings → Privacy.
     #[serde(default)]
     sentry_enabled: Option<bool>,
+    /// Kill switch des appels Internet (pochettes, photos d'artistes, genres). None = activé.
+    #[serde(default)]
+    online_enabled: Option<bool>,
+    /// Fournisseurs de métadonnées en ligne, activables individuellement
+    #[serde(default)]
+    metadata_providers: MetadataProviders,
+    /// Suivre la sortie par défaut du système. None = oui ; false = utiliser audio_device
+    #[serde(default)]
+    follow_system_default: Option<bool>,
+    /// Dernière sortie audio choisie manuellement
+    #[serde(default)]
+    audio_device: Option<SavedAudioDevice>,
+    /// Ancien format : liste complète des extensions scannées, migrée vers extension_overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scanned_extensions: Option<Vec<String>>,
+    /// Extensions ajoutées / retirées par l'utilisateur. None = DEFAULT_AUDIO_EXTENSIONS
+    #[serde(default)]
+    extension_overrides: Option<ExtensionOverrides>,
+    /// Retire de la bibliothèque les fichiers sans audio (durée ou sample rate nuls) au scan.
+    /// Pas ajoutés à excluded_paths : un fichier réparé revient au scan suivant
+    #[serde(default)]
+    exclude_problem_tracks: bool,
+    /// Profondeur et suivi des liens symboliques au parcours des dossiers
+    #[serde(default)]
+    scan_options: ScanOptions,
+    /// Nombre d'écoutes conservées dans l'historique. None = DEFAULT_HISTORY_LIMIT
+    #[serde(default)]
+    history_limit: Option<usize>,
+}
+
+// Parcours des dossiers de la bibliothèque (profondeur bornée par MAX_SCAN_DEPTH)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase", default)]
+struct ScanOptions {
+    max_depth: usize,
+    follow_symlinks: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self { max_depth: DEFAULT_SCAN_DEPTH, follow_symlinks: true }
+    }
+}
+
+// Sortie audio mémorisée. L'ID CoreAudio d'un device peut changer d'un démarrage à
+// l'autre (AirPlay, DAC rebranché) : le nom sert de second critère
+#[derive(Serialize, Deserialize, Clone)]
+struct SavedAudioDevice {
+    id: String,
+    name: String,
+}
+
+// Fournisseurs de métadonnées en ligne (tous activés par défaut)
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase", default)]
+struct MetadataProviders {
+    deezer: bool,
+    musicbrainz: bool,
+    cover_art_archive: bool,
+}
+
+impl Default for MetadataProviders {
+    fn default() -> Self {
+        Self { deezer: true, musicbrainz: true, cover_art_archive: true }
+    }
 }
 
 // Cache des métadonnées
@@ -121,6 +251,18 @@ struct Playlist {
 #[derive(Serialize, Deserialize, Default, Clone)]
 struct PlaylistsData {
     playlists: Vec<Playlist>,
+    /// Albums et artistes favoris, stockés par clé (sans énumérer leurs tracks)
+    #[serde(rename = "favoriteAlbums", default)]
+    favorite_albums: Vec<FavoriteAlbum>,
+    #[serde(rename = "favoriteArtists", default)]
+    favorite_artists: Vec<String>,
+}
+
+// Album favori : même clé que la vue albums (album artist ou artiste, album)
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct FavoriteAlbum {
+    artist: String,
+    album: String,
 }
 
 // Cache pour les pochettes "not found" sur Internet (évite les requêtes répétées)
@@ -133,6 +275,24 @@ struct InternetCoverNotFoundCache {
 
 const INTERNET_NOT_FOUND_TTL_SECS: u64 = 30 * 24 * 3600; // 30 jours
 
+// Index des photos d'artistes : artist_key -> fichier téléchargé ou "not found" horodaté.
+// Évite de réinterroger Deezer/MusicBrainz à chaque ouverture de la vue artistes.
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct ArtistImageCache {
+    entries: HashMap<String, ArtistImageEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ArtistImageEntry {
+    filename: Option<String>, // None = aucune image trouvée
+    checked_at: u64,          // Timestamp Unix (secs) de la dernière recherche
+}
+
+const ARTIST_IMAGE_NOT_FOUND_TTL_SECS: u64 = 7 * 24 * 3600; // 7 jours
+
+// Téléchargements de photos d'artistes simultanés pendant un prefetch (rate limit Deezer)
+const ARTIST_PREFETCH_CONCURRENCY: usize = 4;
+
 // === HISTORIQUE D'ÉCOUTE ===
 // Structure pour une entrée d'écoute
 #[derive(Serialize, Deserialize, Clone)]
@@ -151,6 +311,64 @@ struct ListeningHistory {
     last_played: Option<ListeningEntry>,    // Dernière track jouée
     #[serde(default)]
     played_paths: std::collections::HashSet<String>,  // Tous les paths jamais écoutés (non tronqué)
+    #[serde(default)]
+    imported_plays: HashMap<String, ImportedPlays>,   // Écoutes importées (iTunes) par path
+    #[serde(skip)]
+    play_counts: PlayCounts,                // Index dérivé de entries (reconstruit au chargement)
+}
+
+// Compteur d'écoutes importé d'une autre application : remplacé (pas cumulé) à chaque
+// import, pour qu'importer deux fois la même bibliothèque ne double pas les compteurs
+#[derive(Serialize, Deserialize, Clone)]
+struct ImportedPlays {
+    artist: String,
+    album: String,
+    count: u32,
+    last_played: Option<u64>,
+}
+
+// Nombre d'écoutes par track et par album, maintenu en phase avec entries
+// pour éviter de ré-agréger l'historique à chaque appel du frontend
+#[derive(Default, Clone)]
+struct PlayCounts {
+    tracks: HashMap<String, u32>,
+    albums: HashMap<(String, String), u32>,  // (artist, album) -> écoutes
+}
+
+impl PlayCounts {
+    fn from_history(entries: &[ListeningEntry], imported: &HashMap<String, ImportedPlays>) -> Self {
+        let mut counts = PlayCounts::default();
+        for entry in entries {
+            counts.add(entry);
+        }
+        for (path, plays) in imported {
+            *counts.tracks.entry(path.clone()).or_insert(0) += plays.count;
+            *counts.albums.entry((plays.artist.clone(), plays.album.clone())).or_insert(0) += plays.count;
+        }
+        counts
+    }
+
+    fn add(&mut self, entry: &ListeningEntry) {
+        *self.tracks.entry(entry.path.clone()).or_insert(0) += 1;
+        *self.albums.entry((entry.artist.clone(), entry.album.clone())).or_insert(0) += 1;
+    }
+
+    // Entrée sortie de l'historique (troncature)
+    fn remove(&mut self, entry: &ListeningEntry) {
+        if let Some(count) = self.tracks.get_mut(&entry.path) {
+            *count -= 1;
+            if *count == 0 {
+                self.tracks.remove(&entry.path);
+            }
+        }
+        let album_key = (entry.artist.clone(), entry.album.clone());
+        if let Some(count) = self.albums.get_mut(&album_key) {
+            *count -= 1;
+            if *count == 0 {
+                self.albums.remove(&album_key);
+            }
+        }
+    }
 }
 
 // === DATE D'AJOUT DES TRACKS ===
@@ -160,6 +378,20 @@ struct AddedDatesCache {
     entries: HashMap<String, u64>, // path -> timestamp d'ajout
 }
 
+// === DATES DE MODIFICATION DES FICHIERS (rescan incrémental) ===
+// mtime de chaque fichier lors de sa dernière lecture de métadonnées
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct MtimeCache {
+    entries: HashMap<String, u64>, // path -> mtime (secondes Unix)
+}
+
+// === POSITIONS DE LECTURE (live sets, livres audio) ===
+// Dernière position connue des tracks longs, pour reprendre où on s'était arrêté
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct PlaybackPositionCache {
+    entries: HashMap<String, f64>, // path -> position en secondes
+}
+
 // === CACHE DES TRACKS (pour démarrage instantané) ===
 #[derive(Serialize, Deserialize, Default, Clone)]
 struct TracksCache {
@@ -177,6 +409,12 @@ struct LibraryStats {
     flac_16bit_count: usize,
     flac_24bit_count: usize,
     other_count: usize,
+    /// Par qualité réelle (codec, profondeur, fréquence), tous formats confondus
+    hires_count: usize,          // Sans perte, > 16 bits ou > 48 kHz
+    lossless_cd_count: usize,    // Sans perte, qualité CD ou moins
+    lossy_count: usize,
+    /// Fichiers sans audio (durée ou sample rate nuls), hors de tous les autres compteurs
+    problem_tracks: usize,
 }
 
 // === ÉVÉNEMENTS DE SCAN ===
@@ -195,6 +433,56 @@ struct ScanComplete {
     removed_tracks: usize,
 }
 
+// Fichier à problème rencontré pendant le scan (émis dans scan_issues)
+// reason : "unreadable", "tag_parse_failed", "zero_duration" ou "zero_sample_rate"
+#[derive(Serialize, Clone)]
+struct ScanIssue {
+    path: String,
+    reason: &'static str,
+    details: String,
+}
+
+impl ScanIssue {
+    fn new(path: &str, reason: &'static str, details: impl ToString) -> Self {
+        Self { path: path.to_string(), reason, details: details.to_string() }
+    }
+
+    // Fichier non relu (inchangé depuis le dernier scan) : diagnostic depuis les métadonnées
+    // en cache, sans la cause exacte (codec absent = la lecture des propriétés a échoué)
+    fn from_metadata(path: &str, metadata: &Metadata) -> Option<Self> {
+        if metadata.codec.is_none() {
+            Some(Self::new(path, "unreadable", "no audio properties"))
+        } else {
+            Self::from_audio_properties(path, metadata)
+        }
+    }
+
+    // Fichier vide ou téléchargement tronqué : lu sans erreur mais sans audio exploitable
+    fn from_audio_properties(path: &str, metadata: &Metadata) -> Option<Self> {
+        if metadata.duration <= 0.0 {
+            Some(Self::new(path, "zero_duration", "duration is 0"))
+        } else if metadata.sample_rate.unwrap_or(0) == 0 {
+            Some(Self::new(path, "zero_sample_rate", "sample rate is 0"))
+        } else {
+            None
+        }
+    }
+}
+
+// Track qui ne peut pas être joué : durée ou sample rate nuls (vide, tronqué, illisible).
+// Exclu des statistiques, listé par get_problem_tracks
+fn is_problem_track(metadata: &Metadata) -> bool {
+    metadata.duration <= 0.0 || metadata.sample_rate.unwrap_or(0) == 0
+}
+
+#[derive(Serialize, Clone)]
+struct ScanCancelled {
+    stats: LibraryStats,  // Stats partielles : tracks lus avant l'annulation (non persistés)
+}
+
+// Demande d'annulation du scan en cours (remis à false au début de chaque scan)
+static SCAN_CANCELLED: AtomicBool = AtomicBool::new(false);
+
 // Structures pour l'API MusicBrainz
 #[derive(Deserialize)]
 struct MusicBrainzSearchResponse {
@@ -265,6 +553,9 @@ static COVER_CACHE: Lazy<Mutex<CoverCache>> = Lazy::new(|| {
     Mutex::new(load_cover_cache_from_file())
 });
 
+// Extractions de pochettes en cours (coalescence des get_cover simultanés sur un même fichier)
+static COVER_EXTRACTIONS: Lazy<coalesce::InFlight<Option<String>>> = Lazy::new(coalesce::InFlight::default);
+
 // Flag pour savoir si le cache a été modifié
 static CACHE_DIRTY: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
 
@@ -273,6 +564,11 @@ static INTERNET_NOT_FOUND_CACHE: Lazy<Mutex<InternetCoverNotFoundCache>> = Lazy:
     Mutex::new(load_internet_not_found_cache())
 });
 
+// Index des photos d'artistes (trouvées / non trouvées)
+static ARTIST_IMAGE_CACHE: Lazy<Mutex<ArtistImageCache>> = Lazy::new(|| {
+    Mutex::new(load_artist_image_cache())
+});
+
 // Cache de l'historique d'écoute
 static LISTENING_HISTORY: Lazy<Mutex<ListeningHistory>> = Lazy::new(|| {
     Mutex::new(load_listening_history())
@@ -283,6 +579,37 @@ static ADDED_DATES_CACHE: Lazy<Mutex<AddedDatesCache>> = Lazy::new(|| {
     Mutex::new(load_added_dates_cache())
 });
 
+// Cache des mtimes (détection des fichiers modifiés au rescan)
+static MTIME_CACHE: Lazy<Mutex<MtimeCache>> = Lazy::new(|| {
+    Mutex::new(load_mtime_cache())
+});
+
+// Rescan complet demandé (ignore les mtimes, relit tous les fichiers)
+static FORCE_FULL_RESCAN: AtomicBool = AtomicBool::new(false);
+
+// Cache des positions de lecture des tracks longs
+static PLAYBACK_POSITIONS: Lazy<Mutex<PlaybackPositionCache>> = Lazy::new(|| {
+    Mutex::new(load_playback_positions())
+});
+
+// Alias d'artistes appliqués à l'affichage (artist_aliases.json)
+static ARTIST_ALIASES: Lazy<Mutex<artist_aliases::ArtistAliases>> = Lazy::new(|| {
+    Mutex::new(artist_aliases::load_artist_aliases())
+});
+
+// Notes des tracks en étoiles (ratings.json)
+static RATINGS: Lazy<Mutex<ratings::RatingsCache>> = Lazy::new(|| {
+    Mutex::new(ratings::load_ratings())
+});
+
+// File d'attente de lecture (restaurée au démarrage)
+static PLAY_QUEUE: Lazy<Mutex<queue::PlayQueue>> = Lazy::new(|| {
+    Mutex::new(load_queue())
+});
+
+// Tracks à venir dans la file du frontend (pour la session, voir remember_session)
+static SESSION_QUEUE: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
 // Cache des tracks (pour démarrage instantané)
 static TRACKS_CACHE: Lazy<Mutex<TracksCache>> = Lazy::new(|| {
     Mutex::new(load_tracks_cache())
@@ -310,6 +637,73 @@ static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
         .unwrap_or_else(|_| Client::new())
 });
 
+// Taille de l'historique d'écoute (config.json), bornée par MIN/MAX_HISTORY_LIMIT
+const DEFAULT_HISTORY_LIMIT: usize = 1000;
+const MIN_HISTORY_LIMIT: usize = 100;
+const MAX_HISTORY_LIMIT: usize = 50_000;
+
+static HISTORY_LIMIT: Lazy<std::sync::atomic::AtomicUsize> = Lazy::new(|| {
+    std::sync::atomic::AtomicUsize::new(
+        load_config().history_limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(MIN_HISTORY_LIMIT, MAX_HISTORY_LIMIT)
+    )
+});
+
+// Réglages réseau en mémoire (consultés avant chaque requête, persistés dans config.json)
+static ONLINE_ENABLED: Lazy<AtomicBool> = Lazy::new(|| {
+    AtomicBool::new(load_config().online_enabled.unwrap_or(true))
+});
+static METADATA_PROVIDERS: Lazy<Mutex<MetadataProviders>> = Lazy::new(|| {
+    Mutex::new(load_config().metadata_providers)
+});
+
+fn is_online_enabled() -> bool {
+    ONLINE_ENABLED.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+// true si le mode en ligne est actif ET que `pick` accepte les fournisseurs configurés
+fn provider_enabled(pick: impl Fn(&MetadataProviders) -> bool) -> bool {
+    is_online_enabled() && METADATA_PROVIDERS.lock().map(|providers| pick(&providers)).unwrap_or(true)
+}
+
+// Limiteur de débit partagé entre tâches concurrentes (seau d'un jeton) :
+// chaque appel réserve le prochain créneau libre puis attend hors verrou
+struct RateLimiter {
+    interval: std::time::Duration,
+    next_slot: Mutex<std::time::Instant>,
+}
+
+impl RateLimiter {
+    fn new(interval: std::time::Duration) -> Self {
+        Self { interval, next_slot: Mutex::new(std::time::Instant::now()) }
+    }
+
+    async fn acquire(&self) {
+        let wait = match self.next_slot.lock() {
+            Ok(mut next_slot) => {
+                let now = std::time::Instant::now();
+                let slot = (*next_slot).max(now);
+                *next_slot = slot + self.interval;
+                slot - now
+            }
+            Err(_) => self.interval,
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+// Deezer tolère ~50 requêtes / 5 s ; MusicBrainz demande 1 requête / s
+static DEEZER_RATE_LIMITER: Lazy<RateLimiter> = Lazy::new(|| {
+    RateLimiter::new(std::time::Duration::from_millis(100))
+});
+static MUSICBRAINZ_RATE_LIMITER: Lazy<RateLimiter> = Lazy::new(|| {
+    RateLimiter::new(std::time::Duration::from_millis(1000))
+});
+
+// Albums interrogés simultanément pendant l'enrichissement des genres
+const GENRE_ENRICHMENT_CONCURRENCY: usize = 5;
+
 // === CHEMINS DES FICHIERS ===
 pub(crate) fn get_data_dir() -> PathBuf {
     let home = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -348,6 +742,18 @@ fn get_tracks_cache_path() -> PathBuf {
     get_data_dir().join("tracks_cache.json")
 }
 
+fn get_mtime_cache_path() -> PathBuf {
+    get_data_dir().join("mtime_cache.json")
+}
+
+fn get_playback_positions_path() -> PathBuf {
+    get_data_dir().join("playback_positions.json")
+}
+
+fn get_queue_path() -> PathBuf {
+    get_data_dir().join("queue.json")
+}
+
 // === FONCTIONS DE LECTURE/ÉCRITURE FICHIER ===
 fn load_config() -> Config {
     let config_path = get_config_path();
@@ -440,6 +846,27 @@ fn save_internet_not_found_cache(cache: &InternetCoverNotFoundCache) {
     save_file_secure(&cache_path, &content);
 }
 
+fn load_artist_image_cache() -> ArtistImageCache {
+    let cache_path = get_data_dir().join("artist_image_cache.json");
+    let content = fs::read_to_string(&cache_path).unwrap_or_default();
+    let mut cache: ArtistImageCache = serde_json::from_str(&content).unwrap_or_default();
+    // Purge les "not found" expirés ; les images trouvées restent indexées
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    cache.entries.retain(|_, entry| {
+        entry.filename.is_some() || now.saturating_sub(entry.checked_at) < ARTIST_IMAGE_NOT_FOUND_TTL_SECS
+    });
+    cache
+}
+
+fn save_artist_image_cache(cache: &ArtistImageCache) {
+    let cache_path = get_data_dir().join("artist_image_cache.json");
+    let content = serde_json::to_string(cache).unwrap_or_default();
+    save_file_secure(&cache_path, &content);
+}
+
 // === FONCTIONS HISTORIQUE D'ÉCOUTE ===
 fn load_listening_history() -> ListeningHistory {
     let path = get_listening_history_path();
@@ -457,6 +884,7 @@ fn load_listening_history() -> ListeningHistory {
             println!("[ListeningHistory] Backfilled {} played paths from entries", history.played_paths.len());
         }
 
+        history.play_counts = PlayCounts::from_history(&history.entries, &history.imported_plays);
         history
     } else {
         ListeningHistory::default()
@@ -486,6 +914,64 @@ fn save_added_dates_cache(cache: &AddedDatesCache) {
     save_file_secure(&path, &content);
 }
 
+// === DATES DE MODIFICATION DES FICHIERS ===
+fn load_mtime_cache() -> MtimeCache {
+    let path = get_mtime_cache_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        MtimeCache::default()
+    }
+}
+
+fn save_mtime_cache(cache: &MtimeCache) {
+    let path = get_mtime_cache_path();
+    let content = serde_json::to_string(cache).unwrap_or_default();
+    save_file_secure(&path, &content);
+}
+
+fn file_mtime(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok()?
+        .modified().ok()?
+        .duration_since(UNIX_EPOCH).ok()
+        .map(|d| d.as_secs())
+}
+
+// === POSITIONS DE LECTURE ===
+fn load_playback_positions() -> PlaybackPositionCache {
+    let path = get_playback_positions_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        PlaybackPositionCache::default()
+    }
+}
+
+fn save_playback_positions(cache: &PlaybackPositionCache) {
+    let path = get_playback_positions_path();
+    let content = serde_json::to_string(cache).unwrap_or_default();
+    save_file_secure(&path, &content);
+}
+
+// === FILE D'ATTENTE ===
+fn load_queue() -> queue::PlayQueue {
+    let path = get_queue_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        queue::PlayQueue::default()
+    }
+}
+
+fn save_queue(queue: &queue::PlayQueue) {
+    let path = get_queue_path();
+    let content = serde_json::to_string(queue).unwrap_or_default();
+    save_file_secure(&path, &content);
+}
+
 // === TRACKS CACHE (pour démarrage instantané) ===
 fn load_tracks_cache() -> TracksCache {
     let path = get_tracks_cache_path();
@@ -503,54 +989,94 @@ fn save_tracks_cache(cache: &TracksCache) {
     save_file_secure(&path, &content);
 }
 
+// Codec d'un track : celui lu à l'indexation, sinon déduit de l'extension (anciens caches,
+// ou "Other" quand lofty n'a pas reconnu le type de fichier)
+fn track_codec(track: &TrackWithMetadata) -> String {
+    if let Some(codec) = track.metadata.codec.as_deref().filter(|c| !c.eq_ignore_ascii_case("other")) {
+        return codec.to_uppercase();
+    }
+    codec_from_extension(&track.path, track.metadata.bit_depth).to_string()
+}
+
+// Codec déduit de l'extension (même nommage que codec_name) ; M4A : ALAC si une profondeur est connue
+fn codec_from_extension(path: &str, bit_depth: Option<u8>) -> &'static str {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "mp3" => "MP3",
+        "flac" => "FLAC",
+        "m4a" => if bit_depth.is_some() { "ALAC" } else { "AAC" },
+        "alac" => "ALAC",
+        "aac" => "AAC",
+        "wav" => "WAV",
+        "aiff" | "aif" => "AIFF",
+        "wv" => "WAVPACK",
+        "ape" => "APE",
+        "ogg" => "VORBIS",
+        "opus" => "OPUS",
+        "dsf" | "dff" | "dsd" => "DSD",
+        _ => "OTHER",
+    }
+}
+
+// Copie des alias d'artistes, pour les résoudre sans garder le verrou pendant une agrégation
+fn artist_aliases_snapshot() -> artist_aliases::ArtistAliases {
+    ARTIST_ALIASES.lock().map(|aliases| aliases.clone()).unwrap_or_default()
+}
+
 // Calcule les statistiques de la bibliothèque
 fn calculate_library_stats(tracks: &[TrackWithMetadata]) -> LibraryStats {
     use std::collections::HashSet;
 
+    let aliases = artist_aliases_snapshot();
     let mut artists: HashSet<String> = HashSet::new();
     let mut albums: HashSet<String> = HashSet::new();
-    let mut mp3_count = 0;
-    let mut flac_16bit_count = 0;
-    let mut flac_24bit_count = 0;
-    let mut other_count = 0;
-
-    for track in tracks {
-        artists.insert(track.metadata.artist.clone());
-        albums.insert(format!("{} - {}", track.metadata.artist, track.metadata.album));
+    let problem_tracks = tracks.iter().filter(|t| is_problem_track(&t.metadata)).count();
+    let mut stats = LibraryStats {
+        total_tracks: tracks.len() - problem_tracks,
+        problem_tracks,
+        ..Default::default()
+    };
 
-        // Détermine le format par extension et bit_depth
-        let ext = Path::new(&track.path)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        match ext.as_str() {
-            "mp3" => mp3_count += 1,
-            "flac" => {
-                if let Some(bit_depth) = track.metadata.bit_depth {
-                    if bit_depth > 16 {
-                        flac_24bit_count += 1;
-                    } else {
-                        flac_16bit_count += 1;
-                    }
+    for track in tracks.iter().filter(|t| !is_problem_track(&t.metadata)) {
+        // Album artist d'abord : une compilation compte pour un seul artiste / album
+        let artist = aliases.resolve(track.metadata.album_artist.as_deref().unwrap_or(&track.metadata.artist));
+        artists.insert(artist.to_string());
+        albums.insert(format!("{} - {}", artist, track.metadata.album));
+
+        let codec = track_codec(track);
+        // Hi-res : au-delà de la qualité CD en profondeur ou en fréquence (le DSD l'est toujours)
+        let hires = track.metadata.bit_depth.is_some_and(|b| b > 16)
+            || track.metadata.sample_rate.is_some_and(|r| r > 48000);
+
+        match codec.as_str() {
+            "FLAC" | "ALAC" | "WAV" | "AIFF" | "WAVPACK" | "APE" | "DSD" => {
+                if hires {
+                    stats.hires_count += 1;
                 } else {
-                    flac_16bit_count += 1; // Par défaut 16-bit si inconnu
+                    stats.lossless_cd_count += 1;
                 }
             }
-            _ => other_count += 1,
+            "MP3" | "AAC" | "VORBIS" | "OPUS" | "MPC" => stats.lossy_count += 1,
+            _ => {}
         }
-    }
 
-    LibraryStats {
-        artists_count: artists.len(),
-        albums_count: albums.len(),
-        total_tracks: tracks.len(),
-        mp3_count,
-        flac_16bit_count,
-        flac_24bit_count,
-        other_count,
+        // Compteurs historiques de l'écran d'indexation
+        match codec.as_str() {
+            "MP3" => stats.mp3_count += 1,
+            // 16-bit par défaut si la profondeur est inconnue
+            "FLAC" if track.metadata.bit_depth.is_some_and(|b| b > 16) => stats.flac_24bit_count += 1,
+            "FLAC" => stats.flac_16bit_count += 1,
+            _ => stats.other_count += 1,
+        }
     }
+
+    stats.artists_count = artists.len();
+    stats.albums_count = albums.len();
+    stats
 }
 
 // === PLAYLISTS ===
@@ -609,12 +1135,83 @@ fn ensure_favorites_playlist(data: &mut PlaylistsData) {
     }
 }
 
+/// Écart avec DEFAULT_AUDIO_EXTENSIONS : un format ajouté aux défauts par une mise à jour
+/// est scanné sans que l'utilisateur ait à le réactiver
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct ExtensionOverrides {
+    #[serde(default)]
+    added: Vec<String>,
+    #[serde(default)]
+    removed: Vec<String>,
+}
+
 // === UTILITAIRES ===
+// wv : décodé par wavpack.rs ; ape : indexé avec ses métadonnées, lecture non supportée (audio_decoder::metadata_only_codec)
+const DEFAULT_AUDIO_EXTENSIONS: [&str; 13] = ["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac", "dsf", "dff", "dsd", "wv", "ape"];
+
+// Défauts en vigueur quand config.json stockait la liste complète (avant wv / ape)
+const LEGACY_DEFAULT_AUDIO_EXTENSIONS: [&str; 11] = ["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac", "dsf", "dff", "dsd"];
+
+// Extensions scannées (config.json), consultées pour chaque fichier rencontré
+static SCANNED_EXTENSIONS: Lazy<Mutex<Vec<String>>> = Lazy::new(|| {
+    Mutex::new(scanned_extensions_from_config(&load_config()))
+});
+
+// SECURITY: profondeur maximale quel que soit le réglage, filet contre les boucles de liens
+// symboliques (walkdir les détecte aussi quand il les suit : l'entrée en erreur est ignorée)
+const DEFAULT_SCAN_DEPTH: usize = 20;
+const MAX_SCAN_DEPTH: usize = 64;
+
+// Options de parcours (config.json), lues au début de chaque scan de dossier
+static SCAN_OPTIONS: Lazy<Mutex<ScanOptions>> = Lazy::new(|| Mutex::new(load_config().scan_options));
+
+// Parcours d'un dossier de la bibliothèque selon les options de scan
+fn library_walker(path: impl AsRef<Path>) -> WalkDir {
+    let options = SCAN_OPTIONS.lock().map(|o| *o).unwrap_or_default();
+    WalkDir::new(path)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth.clamp(1, MAX_SCAN_DEPTH))
+}
+
+fn default_audio_extensions() -> Vec<String> {
+    DEFAULT_AUDIO_EXTENSIONS.iter().map(|e| e.to_string()).collect()
+}
+
+// Écart entre une liste complète et `defaults`
+fn extension_overrides(extensions: &[String], defaults: &[&str]) -> ExtensionOverrides {
+    ExtensionOverrides {
+        added: extensions.iter().filter(|e| !defaults.contains(&e.as_str())).cloned().collect(),
+        removed: defaults.iter().filter(|d| !extensions.iter().any(|e| e == *d)).map(|d| d.to_string()).collect(),
+    }
+}
+
+// Défauts actuels + ajouts - retraits. Une ancienne liste complète est comparée aux
+// défauts de l'époque : seuls les formats retirés à la main restent exclus
+fn scanned_extensions_from_config(config: &Config) -> Vec<String> {
+    let overrides = match (&config.extension_overrides, &config.scanned_extensions) {
+        (Some(overrides), _) => overrides.clone(),
+        (None, Some(legacy)) => extension_overrides(legacy, &LEGACY_DEFAULT_AUDIO_EXTENSIONS),
+        (None, None) => ExtensionOverrides::default(),
+    };
+    let mut extensions: Vec<String> = DEFAULT_AUDIO_EXTENSIONS.iter()
+        .filter(|d| !overrides.removed.iter().any(|r| r == *d))
+        .map(|d| d.to_string())
+        .collect();
+    for ext in overrides.added {
+        if !extensions.contains(&ext) {
+            extensions.push(ext);
+        }
+    }
+    extensions
+}
+
 fn is_audio_file(path: &Path) -> bool {
-    let extensions = ["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac"];
-    path.extension()
-        .and_then(|e| e.to_str())
-        .map(|e| extensions.contains(&e.to_lowercase().as_str()))
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    let ext = ext.to_lowercase();
+    SCANNED_EXTENSIONS.lock()
+        .map(|extensions| extensions.contains(&ext))
         .unwrap_or(false)
 }
 
@@ -626,14 +1223,36 @@ fn md5_hash(input: &str) -> u64 {
     hasher.finish()
 }
 
+// Résultat d'une recherche d'image : Ok(None) = absente de la source (définitif),
+// Err = échec réseau, rate limit ou erreur serveur (transitoire, à ne pas mémoriser)
+type ImageLookup = Result<Option<Vec<u8>>, String>;
+
+// GET via le client global : Ok(None) pour un 404, Err pour tout échec transitoire
+async fn http_get(url: &str) -> Result<Option<reqwest::Response>, String> {
+    let response = HTTP_CLIENT.get(url).send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    Ok(Some(response))
+}
+
 // Recherche une pochette sur MusicBrainz + Cover Art Archive (async)
-async fn fetch_cover_from_musicbrainz(artist: &str, album: &str) -> Option<Vec<u8>> {
+async fn fetch_cover_from_musicbrainz(artist: &str, album: &str) -> ImageLookup {
+    // Recherche MusicBrainz puis image Cover Art Archive : les deux doivent être activés
+    if !provider_enabled(|p| p.musicbrainz && p.cover_art_archive) {
+        return Ok(None);
+    }
+
     // Nettoie et encode les paramètres
     let artist_clean = artist.replace("Various Artists", "").trim().to_string();
     let album_clean = album.trim();
 
     if album_clean.is_empty() || album_clean == "Unknown Album" {
-        return None;
+        return Ok(None);
     }
 
     // Construit la requête MusicBrainz
@@ -651,36 +1270,33 @@ async fn fetch_cover_from_musicbrainz(artist: &str, album: &str) -> Option<Vec<u
     );
 
     // Recherche sur MusicBrainz (async)
-    let response = HTTP_CLIENT.get(&search_url).send().await.ok()?;
-    let search_result: MusicBrainzSearchResponse = response.json().await.ok()?;
+    let Some(response) = http_get(&search_url).await? else { return Ok(None) };
+    let search_result: MusicBrainzSearchResponse = response.json().await.map_err(|e| e.to_string())?;
 
     // Prend le meilleur résultat
-    let releases = search_result.releases?;
-    let best_release = releases.into_iter()
-        .filter(|r| r.score.unwrap_or(0) > 50)
-        .next()?;
+    let Some(best_release) = search_result.releases.unwrap_or_default().into_iter()
+        .find(|r| r.score.unwrap_or(0) > 50) else { return Ok(None) };
 
-    // Récupère la pochette depuis Cover Art Archive
+    // Récupère la pochette depuis Cover Art Archive (404 = release sans pochette)
     let cover_url = format!(
         "https://coverartarchive.org/release/{}/front-500",
         best_release.id
     );
 
-    let cover_response = HTTP_CLIENT.get(&cover_url).send().await.ok()?;
-
-    if cover_response.status().is_success() {
-        cover_response.bytes().await.ok().map(|b| b.to_vec())
-    } else {
-        None
-    }
+    let Some(cover_response) = http_get(&cover_url).await? else { return Ok(None) };
+    cover_response.bytes().await.map(|b| Some(b.to_vec())).map_err(|e| e.to_string())
 }
 
 // Recherche une photo d'artiste via Deezer API (prioritaire car plus de photos) - async
-async fn fetch_artist_image_from_deezer(artist_name: &str) -> Option<Vec<u8>> {
+async fn fetch_artist_image_from_deezer(artist_name: &str) -> ImageLookup {
+    if !provider_enabled(|p| p.deezer) {
+        return Ok(None);
+    }
+
     let artist_clean = artist_name.trim();
 
     if artist_clean.is_empty() || artist_clean == "Unknown Artist" || artist_clean == "Various Artists" {
-        return None;
+        return Ok(None);
     }
 
     // Recherche sur Deezer (API gratuite, pas de clé requise)
@@ -689,47 +1305,48 @@ async fn fetch_artist_image_from_deezer(artist_name: &str) -> Option<Vec<u8>> {
         urlencoding_simple(artist_clean)
     );
 
-    let response = HTTP_CLIENT.get(&search_url).send().await.ok()?;
-    let json: serde_json::Value = response.json().await.ok()?;
-
-    // Récupère le premier artiste
-    let data = json.get("data")?.as_array()?;
+    let Some(response) = http_get(&search_url).await? else { return Ok(None) };
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
 
-    // Deezer peut retourner un tableau vide
-    if data.is_empty() {
-        return None;
+    // Deezer signale ses erreurs (quota dépassé…) dans le corps, avec un statut 200
+    if let Some(error) = json.get("error") {
+        return Err(error.to_string());
     }
 
-    let first_artist = data.first()?;
+    // Récupère le premier artiste (Deezer peut retourner un tableau vide)
+    let Some(first_artist) = json.get("data").and_then(|d| d.as_array()).and_then(|d| d.first()) else {
+        return Ok(None);
+    };
 
     // Deezer fournit plusieurs tailles : picture_small, picture_medium, picture_big, picture_xl
     // On prend picture_big (500x500) ou picture_xl (1000x1000)
-    let image_url = first_artist.get("picture_big")
+    let Some(image_url) = first_artist.get("picture_big")
         .or_else(|| first_artist.get("picture_xl"))
         .or_else(|| first_artist.get("picture_medium"))
         .and_then(|v| v.as_str())
         // Filtre les URLs vides et les placeholders Deezer
-        .filter(|s| !s.is_empty() && !s.contains("/artist//") && s.starts_with("http"))?;
+        .filter(|s| !s.is_empty() && !s.contains("/artist//") && s.starts_with("http"))
+    else {
+        return Ok(None);
+    };
 
     // Télécharge l'image
-    let image_response = HTTP_CLIENT.get(image_url).send().await.ok()?;
-    if image_response.status().is_success() {
-        let bytes = image_response.bytes().await.ok()?;
-        // Vérifie que l'image n'est pas vide (placeholder)
-        if bytes.len() > 1000 {
-            return Some(bytes.to_vec());
-        }
-    }
-
-    None
+    let Some(image_response) = http_get(image_url).await? else { return Ok(None) };
+    let bytes = image_response.bytes().await.map_err(|e| e.to_string())?;
+    // Vérifie que l'image n'est pas vide (placeholder)
+    Ok((bytes.len() > 1000).then(|| bytes.to_vec()))
 }
 
 // Recherche une photo d'artiste via MusicBrainz + Wikimedia Commons (fallback) - async
-async fn fetch_artist_image_from_musicbrainz(artist_name: &str) -> Option<Vec<u8>> {
+async fn fetch_artist_image_from_musicbrainz(artist_name: &str) -> ImageLookup {
+    if !provider_enabled(|p| p.musicbrainz) {
+        return Ok(None);
+    }
+
     let artist_clean = artist_name.trim();
 
     if artist_clean.is_empty() || artist_clean == "Unknown Artist" || artist_clean == "Various Artists" {
-        return None;
+        return Ok(None);
     }
 
     // 1. Recherche l'artiste sur MusicBrainz
@@ -738,14 +1355,12 @@ async fn fetch_artist_image_from_musicbrainz(artist_name: &str) -> Option<Vec<u8
         urlencoding_simple(artist_clean)
     );
 
-    let response = HTTP_CLIENT.get(&search_url).send().await.ok()?;
-    let search_result: MusicBrainzArtistSearchResponse = response.json().await.ok()?;
+    let Some(response) = http_get(&search_url).await? else { return Ok(None) };
+    let search_result: MusicBrainzArtistSearchResponse = response.json().await.map_err(|e| e.to_string())?;
 
     // Prend le meilleur résultat (score réduit à 50 pour plus de résultats)
-    let artists = search_result.artists?;
-    let best_artist = artists.into_iter()
-        .filter(|a| a.score.unwrap_or(0) > 50)
-        .next()?;
+    let Some(best_artist) = search_result.artists.unwrap_or_default().into_iter()
+        .find(|a| a.score.unwrap_or(0) > 50) else { return Ok(None) };
 
     // 2. Récupère les détails de l'artiste avec les relations (url-rels)
     let details_url = format!(
@@ -756,21 +1371,19 @@ async fn fetch_artist_image_from_musicbrainz(artist_name: &str) -> Option<Vec<u8
     // Petit délai pour respecter le rate limit de MusicBrainz (async sleep)
     tokio::time::sleep(std::time::Duration::from_millis(300)).await;
 
-    let details_response = HTTP_CLIENT.get(&details_url).send().await.ok()?;
-    let details: MusicBrainzArtistDetails = details_response.json().await.ok()?;
+    let Some(details_response) = http_get(&details_url).await? else { return Ok(None) };
+    let details: MusicBrainzArtistDetails = details_response.json().await.map_err(|e| e.to_string())?;
 
     // 3. Cherche une URL d'image dans les relations
-    if let Some(relations) = details.relations {
-        for relation in relations {
-            if let Some(rel_type) = &relation.relation_type {
-                // Cherche les relations de type "image" ou "picture"
-                if rel_type == "image" || rel_type == "picture" {
-                    if let Some(url) = relation.url.and_then(|u| u.resource) {
-                        // Wikimedia Commons - convertit l'URL en URL d'image directe
-                        if url.contains("commons.wikimedia.org") {
-                            if let Some(image_data) = fetch_wikimedia_image(&url).await {
-                                return Some(image_data);
-                            }
+    for relation in details.relations.unwrap_or_default() {
+        if let Some(rel_type) = &relation.relation_type {
+            // Cherche les relations de type "image" ou "picture"
+            if rel_type == "image" || rel_type == "picture" {
+                if let Some(url) = relation.url.and_then(|u| u.resource) {
+                    // Wikimedia Commons - convertit l'URL en URL d'image directe
+                    if url.contains("commons.wikimedia.org") {
+                        if let Some(image_data) = fetch_wikimedia_image(&url).await? {
+                            return Ok(Some(image_data));
                         }
                     }
                 }
@@ -778,18 +1391,24 @@ async fn fetch_artist_image_from_musicbrainz(artist_name: &str) -> Option<Vec<u8
         }
     }
 
-    None
+    Ok(None)
 }
 
 // Télécharge une image depuis Wikimedia Commons - async
-async fn fetch_wikimedia_image(wikimedia_url: &str) -> Option<Vec<u8>> {
-    // Extrait le nom du fichier de l'URL Wikimedia
+async fn fetch_wikimedia_image(wikimedia_url: &str) -> ImageLookup {
+    if !is_online_enabled() {
+        return Ok(None);
+    }
+
+    // Extrait le nom du fichier de l'URL Wikimedia
     // Format: https://commons.wikimedia.org/wiki/File:Nom_du_fichier.jpg
-    let file_name = wikimedia_url
+    let Some(file_name) = wikimedia_url
         .split("File:")
-        .nth(1)?
-        .split('?')
-        .next()?;
+        .nth(1)
+        .and_then(|rest| rest.split('?').next())
+    else {
+        return Ok(None);
+    };
 
     // Utilise l'API Wikimedia pour obtenir l'URL directe de l'image (taille 500px)
     let api_url = format!(
@@ -797,31 +1416,30 @@ async fn fetch_wikimedia_image(wikimedia_url: &str) -> Option<Vec<u8>> {
         file_name
     );
 
-    let response = HTTP_CLIENT.get(&api_url).send().await.ok()?;
-    let json: serde_json::Value = response.json().await.ok()?;
+    let Some(response) = http_get(&api_url).await? else { return Ok(None) };
+    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
 
     // Navigue dans la réponse JSON pour trouver l'URL de l'image
-    let pages = json.get("query")?.get("pages")?;
+    let Some(pages) = json.get("query").and_then(|q| q.get("pages")).and_then(|p| p.as_object()) else {
+        return Ok(None);
+    };
 
     // L'ID de page est dynamique, donc on itère
-    for (_page_id, page_data) in pages.as_object()? {
-        if let Some(imageinfo) = page_data.get("imageinfo") {
-            if let Some(first_info) = imageinfo.as_array()?.first() {
-                // Préfère thumburl (redimensionné) sinon url (original)
-                let image_url = first_info.get("thumburl")
-                    .or_else(|| first_info.get("url"))?
-                    .as_str()?;
-
-                // Télécharge l'image
-                let image_response = HTTP_CLIENT.get(image_url).send().await.ok()?;
-                if image_response.status().is_success() {
-                    return image_response.bytes().await.ok().map(|b| b.to_vec());
-                }
-            }
+    for page_data in pages.values() {
+        // Préfère thumburl (redimensionné) sinon url (original)
+        let image_url = page_data.get("imageinfo")
+            .and_then(|info| info.as_array())
+            .and_then(|info| info.first())
+            .and_then(|info| info.get("thumburl").or_else(|| info.get("url")))
+            .and_then(|url| url.as_str());
+        if let Some(image_url) = image_url {
+            // Télécharge l'image
+            let Some(image_response) = http_get(image_url).await? else { continue };
+            return image_response.bytes().await.map(|b| Some(b.to_vec())).map_err(|e| e.to_string());
         }
     }
 
-    None
+    Ok(None)
 }
 
 /// Nettoie un nom d'album pour la recherche API (Deezer/MusicBrainz).
@@ -860,19 +1478,8 @@ fn clean_album_name_for_search(album: &str) -> String {
         break;
     }
 
-    // 3. Normalise les tirets Unicode
-    cleaned = cleaned
-        .replace('\u{2013}', "-")  // en-dash
-        .replace('\u{2014}', "-")  // em-dash
-        .replace('\u{2015}', "-")  // horizontal bar
-        .replace('\u{2012}', "-"); // figure dash
-
-    // 4. Normalise les guillemets et apostrophes Unicode
-    cleaned = cleaned
-        .replace('\u{2018}', "'")
-        .replace('\u{2019}', "'")
-        .replace('\u{201C}', "\"")
-        .replace('\u{201D}', "\"");
+    // 3-4. Normalise les tirets, guillemets et apostrophes Unicode
+    cleaned = normalize_unicode_punctuation(&cleaned);
 
     // 5. Supprime les trailing bitrate/format suffixes (ex: "24/192", "24B44")
     cleaned = cleaned.trim().to_string();
@@ -893,6 +1500,21 @@ fn clean_album_name_for_search(album: &str) -> String {
     cleaned.trim().to_string()
 }
 
+/// Remplace les tirets, guillemets et apostrophes typographiques par leur équivalent ASCII.
+fn normalize_unicode_punctuation(text: &str) -> String {
+    text
+        // Tirets Unicode
+        .replace('\u{2013}', "-")  // en-dash
+        .replace('\u{2014}', "-")  // em-dash
+        .replace('\u{2015}', "-")  // horizontal bar
+        .replace('\u{2012}', "-")  // figure dash
+        // Guillemets et apostrophes Unicode
+        .replace('\u{2018}', "'")
+        .replace('\u{2019}', "'")
+        .replace('\u{201C}', "\"")
+        .replace('\u{201D}', "\"")
+}
+
 /// Nettoie un nom d'artiste pour la recherche API.
 fn clean_artist_name_for_search(artist: &str) -> String {
     artist
@@ -907,6 +1529,10 @@ fn clean_artist_name_for_search(artist: &str) -> String {
 
 /// Recherche le genre d'un album sur Deezer (API gratuite, pas de clé)
 async fn fetch_genre_from_deezer(artist: &str, album: &str) -> Option<String> {
+    if !provider_enabled(|p| p.deezer) {
+        return None;
+    }
+
     let artist_clean = clean_artist_name_for_search(artist);
     let album_clean = clean_album_name_for_search(&album.replace('"', ""));
 
@@ -939,6 +1565,10 @@ async fn fetch_genre_from_deezer(artist: &str, album: &str) -> Option<String> {
 
 /// Fallback : recherche le genre via MusicBrainz release-group tags
 async fn fetch_genre_from_musicbrainz(artist: &str, album: &str) -> Option<String> {
+    if !provider_enabled(|p| p.musicbrainz) {
+        return None;
+    }
+
     let artist_clean = clean_artist_name_for_search(artist);
     let album_clean = clean_album_name_for_search(&album.replace('"', ""));
 
@@ -975,17 +1605,7 @@ async fn fetch_genre_from_musicbrainz(artist: &str, album: &str) -> Option<Strin
         let normalized = normalize_genre(&tag.name);
         if !normalized.is_empty() {
             // Vérifie que le genre normalisé est dans GENRE_MAP (genre reconnu)
-            let key = normalized.to_lowercase()
-                .replace('-', " ")
-                .replace('_', " ")
-                .replace('&', "and")
-                .replace('/', " ")
-                .replace('.', "")
-                .replace('\'', "")
-                .split_whitespace()
-                .collect::<Vec<&str>>()
-                .join(" ");
-            if GENRE_MAP.contains_key(key.as_str()) {
+            if GENRE_MAP.contains_key(genre_lookup_key(&normalized).as_str()) {
                 return Some(normalized);
             }
         }
@@ -1004,84 +1624,198 @@ async fn fetch_genre_from_musicbrainz(artist: &str, album: &str) -> Option<Strin
     None
 }
 
-/// Enrichit les genres manquants via l'API Deezer (post-scan, async)
+/// Genre brut du premier tag du fichier qui en a un (tous tags confondus, pas seulement le principal)
+fn read_raw_genre(path: &str) -> Option<String> {
+    let tagged_file = Probe::open(path).and_then(|p| p.read()).ok()?;
+    tagged_file.tags()
+        .iter()
+        .filter_map(|tag| tag.genre())
+        .map(|genre| genre.trim().to_string())
+        .find(|genre| !genre.is_empty())
+}
+
+/// Relit les genres embarqués des tracks sans genre et les applique aux caches.
+/// Un track sans genre propre hérite du premier genre local de son album.
+/// Retourne les albums résolus localement (inutile d'interroger Deezer).
+fn apply_local_genres(album_tracks: &HashMap<(String, String), Vec<String>>) -> std::collections::HashSet<(String, String)> {
+    let mut track_genres: HashMap<String, String> = HashMap::new();
+    let mut resolved = std::collections::HashSet::new();
+
+    for (album, paths) in album_tracks {
+        // SMB : relire le tag imposerait un téléchargement, on laisse Deezer s'en charger
+        let local: Vec<(&String, String)> = paths.iter()
+            .filter(|path| !path.starts_with("smb://"))
+            .filter_map(|path| {
+                let raw = read_raw_genre(cue::split_virtual_path(path).map_or(path, |(file, _)| file))?;
+                let genre = split_and_normalize_genre(&raw).unwrap_or_else(|| title_case(&raw));
+                (!genre.is_empty()).then_some((path, genre))
+            })
+            .collect();
+        let Some(album_genre) = local.first().map(|(_, genre)| genre.clone()) else {
+            continue;
+        };
+
+        for path in paths {
+            track_genres.insert(path.clone(), album_genre.clone());
+        }
+        for (path, genre) in local {
+            track_genres.insert(path.clone(), genre);
+        }
+        resolved.insert(album.clone());
+    }
+
+    if track_genres.is_empty() {
+        return resolved;
+    }
+
+    #[cfg(debug_assertions)]
+    println!("[Genre Enrichment] {} albums resolved from embedded tags ({} tracks)", resolved.len(), track_genres.len());
+
+    if let Ok(mut cache) = TRACKS_CACHE.lock() {
+        for track in cache.tracks.iter_mut() {
+            if let Some(genre) = track_genres.get(&track.path) {
+                track.metadata.genre = Some(genre.clone());
+            }
+        }
+        save_tracks_cache(&cache);
+    }
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        for (path, genre) in &track_genres {
+            if let Some(meta) = cache.entries.get_mut(path) {
+                meta.genre = Some(genre.clone());
+            }
+        }
+        save_metadata_cache_to_file(&cache);
+    }
+
+    resolved
+}
+
+/// Enrichit les genres manquants : tags embarqués d'abord, puis l'API Deezer (post-scan, async)
 async fn enrich_genres_from_deezer(app_handle: tauri::AppHandle) {
     use tauri::Emitter;
 
-    // Collecte les albums à enrichir (genre absent + pas encore enrichi)
-    let albums_to_enrich: Vec<(String, String)> = {
+    // Collecte les albums à enrichir (genre absent + pas encore enrichi) avec leurs tracks
+    let album_tracks: HashMap<(String, String), Vec<String>> = {
         let cache = match TRACKS_CACHE.lock() {
             Ok(c) => c,
             Err(_) => return,
         };
 
-        let mut album_set: std::collections::HashSet<(String, String)> = std::collections::HashSet::new();
+        let mut albums: HashMap<(String, String), Vec<String>> = HashMap::new();
         for track in &cache.tracks {
-            if track.metadata.genre.is_none() && !track.metadata.genre_enriched {
-                album_set.insert((
+            if track.metadata.genre.is_none() && !track.metadata.genre_enriched && !track.metadata.genre_locked {
+                albums.entry((
                     track.metadata.artist.clone(),
                     track.metadata.album.clone(),
-                ));
+                )).or_default().push(track.path.clone());
             }
         }
-        album_set.into_iter().collect()
+        albums
     };
 
+    // Passe locale (hors ligne) : les tags embarqués priment sur Deezer
+    let albums_with_local_genre = tokio::task::spawn_blocking({
+        let album_tracks = album_tracks.clone();
+        move || apply_local_genres(&album_tracks)
+    })
+    .await
+    .unwrap_or_default();
+
+    let local_count = albums_with_local_genre.len();
+    let albums_to_enrich: Vec<(String, String)> = album_tracks
+        .into_keys()
+        .filter(|album| !albums_with_local_genre.contains(album))
+        .collect();
+
+    // Rien à chercher en ligne, hors ligne, ou aucun fournisseur de genres :
+    // les albums restants seront enrichis plus tard
     let total = albums_to_enrich.len();
-    if total == 0 {
+    if total == 0 || !provider_enabled(|p| p.deezer || p.musicbrainz) {
         #[cfg(debug_assertions)]
-        println!("[Genre Enrichment] No albums to enrich");
+        println!("[Genre Enrichment] No albums to query online ({} resolved locally)", local_count);
+        if local_count > 0 {
+            let _ = app_handle.emit("genre_enrichment_complete", serde_json::json!({
+                "enriched_albums": local_count,
+                "total_albums": local_count + total
+            }));
+        }
         return;
     }
 
     #[cfg(debug_assertions)]
     println!("[Genre Enrichment] Starting: {} albums to query on Deezer", total);
 
-    let mut enriched_count = 0usize;
-    let mut genre_results: Vec<(String, String, Option<String>)> = Vec::new();
-
-    for (idx, (artist, album)) in albums_to_enrich.iter().enumerate() {
-        // Rate limit : 50ms entre chaque appel Deezer
-        if idx > 0 {
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        }
-
-        let genre = fetch_genre_from_deezer(artist, album).await;
+    let queue = Arc::new(Mutex::new(std::collections::VecDeque::from(albums_to_enrich)));
+    let genre_results = Arc::new(Mutex::new(Vec::<(String, String, Option<String>)>::new()));
+    let processed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let enriched = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    // Pool borné : chaque worker prend l'album suivant, les limiteurs partagés espacent les requêtes
+    let workers: Vec<_> = (0..GENRE_ENRICHMENT_CONCURRENCY.min(total))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let genre_results = Arc::clone(&genre_results);
+            let processed = Arc::clone(&processed);
+            let enriched = Arc::clone(&enriched);
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                // Le verrou est relâché dans la closure, avant l'await
+                let next_album = || queue.lock().ok().and_then(|mut q| q.pop_front());
+                while let Some((artist, album)) = next_album() {
+                    DEEZER_RATE_LIMITER.acquire().await;
+                    let genre = fetch_genre_from_deezer(&artist, &album).await;
+
+                    // Fallback MusicBrainz si Deezer n'a pas trouvé (limiteur plus strict)
+                    let genre = if genre.is_none() {
+                        MUSICBRAINZ_RATE_LIMITER.acquire().await;
+                        let mb_genre = fetch_genre_from_musicbrainz(&artist, &album).await;
+                        if mb_genre.is_some() {
+                            #[cfg(debug_assertions)]
+                            println!("[Genre Enrichment] {} - {} → {:?} (MusicBrainz fallback)",
+                                artist, album, mb_genre);
+                        }
+                        mb_genre
+                    } else {
+                        genre
+                    };
 
-        // Fallback MusicBrainz si Deezer n'a pas trouvé
-        let genre = if genre.is_none() {
-            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
-            let mb_genre = fetch_genre_from_musicbrainz(artist, album).await;
-            if mb_genre.is_some() {
-                #[cfg(debug_assertions)]
-                println!("[Genre Enrichment] {}/{} {} - {} → {:?} (MusicBrainz fallback)",
-                    idx + 1, total, artist, album, mb_genre);
-            }
-            mb_genre
-        } else {
-            genre
-        };
+                    if genre.is_some() {
+                        enriched.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    let current = processed.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
 
-        if genre.is_some() {
-            enriched_count += 1;
-        }
+                    #[cfg(debug_assertions)]
+                    println!("[Genre Enrichment] {}/{} {} - {} → {:?}",
+                        current, total, artist, album, genre);
 
-        #[cfg(debug_assertions)]
-        println!("[Genre Enrichment] {}/{} {} - {} → {:?}",
-            idx + 1, total, artist, album, genre);
+                    if let Ok(mut results) = genre_results.lock() {
+                        results.push((artist, album, genre));
+                    }
 
-        genre_results.push((artist.clone(), album.clone(), genre));
+                    // Progress feedback toutes les 10 réponses
+                    if current.is_multiple_of(10) || current == total {
+                        let _ = app_handle.emit("genre_enrichment_progress", serde_json::json!({
+                            "current": current,
+                            "total": total,
+                            "enriched": enriched.load(std::sync::atomic::Ordering::Relaxed)
+                        }));
+                    }
+                }
+            })
+        })
+        .collect();
 
-        // Progress feedback toutes les 10 requêtes
-        if (idx + 1) % 10 == 0 || idx + 1 == total {
-            let _ = app_handle.emit("genre_enrichment_progress", serde_json::json!({
-                "current": idx + 1,
-                "total": total,
-                "enriched": enriched_count
-            }));
-        }
+    for worker in workers {
+        let _ = worker.await;
     }
 
+    let enriched_count = enriched.load(std::sync::atomic::Ordering::Relaxed);
+    let genre_results: Vec<(String, String, Option<String>)> = genre_results
+        .lock()
+        .map(|mut results| std::mem::take(&mut *results))
+        .unwrap_or_default();
+
     // Applique les résultats dans METADATA_CACHE + TRACKS_CACHE
     {
         let mut metadata_cache = match METADATA_CACHE.lock() {
@@ -1101,6 +1835,7 @@ async fn enrich_genres_from_deezer(app_handle: tauri::AppHandle) {
                 if track.metadata.artist == *artist
                     && track.metadata.album == *album
                     && track.metadata.genre.is_none()
+                    && !track.metadata.genre_locked
                 {
                     if let Some(ref genre) = normalized_genre {
                         track.metadata.genre = Some(genre.clone());
@@ -1115,6 +1850,7 @@ async fn enrich_genres_from_deezer(app_handle: tauri::AppHandle) {
                 if meta.artist == *artist
                     && meta.album == *album
                     && meta.genre.is_none()
+                    && !meta.genre_locked
                 {
                     if let Some(ref genre) = normalized_genre {
                         meta.genre = Some(genre.clone());
@@ -1133,8 +1869,8 @@ async fn enrich_genres_from_deezer(app_handle: tauri::AppHandle) {
     println!("[Genre Enrichment] Complete: {}/{} albums enriched with genre", enriched_count, total);
 
     let _ = app_handle.emit("genre_enrichment_complete", serde_json::json!({
-        "enriched_albums": enriched_count,
-        "total_albums": total
+        "enriched_albums": enriched_count + local_count,
+        "total_albums": total + local_count
     }));
 }
 
@@ -1212,6 +1948,9 @@ fn save_all_caches() {
     if let Ok(cache) = INTERNET_NOT_FOUND_CACHE.lock() {
         save_internet_not_found_cache(&cache);
     }
+    if let Ok(cache) = ARTIST_IMAGE_CACHE.lock() {
+        save_artist_image_cache(&cache);
+    }
     // Réinitialise le flag dirty
     if let Ok(mut dirty) = CACHE_DIRTY.lock() {
         *dirty = false;
@@ -1223,8 +1962,8 @@ fn save_all_caches() {
 fn scan_folder(path: &str) -> Vec<AudioTrack> {
     let mut files = Vec::new();
 
-    // SECURITY: Limit depth to prevent infinite symlink loops while still following links
-    for entry in WalkDir::new(path).follow_links(true).max_depth(20).into_iter().filter_map(|e| e.ok()) {
+    // SECURITY: profondeur bornée (MAX_SCAN_DEPTH) même si les liens sont suivis
+    for entry in library_walker(path).into_iter().filter_map(|e| e.ok()) {
         let file_path = entry.path();
         if file_path.is_file() && is_audio_file(file_path) {
             let name = file_path.file_stem()
@@ -1269,272 +2008,396 @@ static ID3V1_GENRES: &[&str] = &[
 
 // Table de correspondance des variantes de genres → genre canonique
 // Les clés sont en lowercase, sans tirets/underscores/slashs (remplacés par espaces), & → "and"
-static GENRE_MAP: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
-    let mut m = HashMap::new();
-
+// (forme produite par genre_lookup_key — une clé sous une autre forme ne matcherait jamais)
+static GENRE_MAP_ENTRIES: &[(&str, &str)] = &[
     // === Hip-Hop / Rap ===
-    m.insert("hip hop", "Hip-Hop");
-    m.insert("hiphop", "Hip-Hop");
-    m.insert("hip hop rap", "Hip-Hop");
-    m.insert("rap hip hop", "Hip-Hop");
-    m.insert("hip hop and rap", "Hip-Hop");
-    m.insert("rap and hip hop", "Hip-Hop");
-    m.insert("hip hop soul", "Hip-Hop");
-    m.insert("gangsta rap", "Hip-Hop");
-    m.insert("gangsta", "Hip-Hop");
-    m.insert("rap", "Rap");
-    m.insert("conscious rap", "Rap");
-    m.insert("trap", "Trap");
-    m.insert("trap music", "Trap");
+    ("hip hop", "Hip-Hop"),
+    ("hiphop", "Hip-Hop"),
+    ("hip hop rap", "Hip-Hop"),
+    ("rap hip hop", "Hip-Hop"),
+    ("hip hop and rap", "Hip-Hop"),
+    ("rap and hip hop", "Hip-Hop"),
+    ("hip hop soul", "Hip-Hop"),
+    ("gangsta rap", "Hip-Hop"),
+    ("gangsta", "Hip-Hop"),
+    ("rap", "Rap"),
+    ("conscious rap", "Rap"),
+    ("trap", "Trap"),
+    ("trap music", "Trap"),
 
     // === R&B / Soul ===
-    m.insert("randb", "R&B");
-    m.insert("r and b", "R&B");
-    m.insert("rnb", "R&B");
-    m.insert("rhythm and blues", "R&B");
-    m.insert("r and b soul", "R&B");
-    m.insert("randb soul", "R&B");
-    m.insert("contemporary randb", "R&B");
-    m.insert("soul", "Soul");
-    m.insert("neo soul", "Neo Soul");
-    m.insert("neosoul", "Neo Soul");
-    m.insert("neo soul", "Neo Soul");
-    m.insert("motown", "Soul");
+    ("randb", "R&B"),
+    ("r and b", "R&B"),
+    ("rnb", "R&B"),
+    ("rhythm and blues", "R&B"),
+    ("r and b soul", "R&B"),
+    ("randb soul", "R&B"),
+    ("contemporary randb", "R&B"),
+    ("soul", "Soul"),
+    ("neo soul", "Neo Soul"),
+    ("neosoul", "Neo Soul"),
+    ("motown", "Soul"),
 
     // === Rock ===
-    m.insert("rock", "Rock");
-    m.insert("rock and roll", "Rock & Roll");
-    m.insert("rock n roll", "Rock & Roll");
-    m.insert("rock 'n' roll", "Rock & Roll");
-    m.insert("rockandroll", "Rock & Roll");
-    m.insert("alternative rock", "Alternative Rock");
-    m.insert("alt rock", "Alternative Rock");
-    m.insert("alt. rock", "Alternative Rock");
-    m.insert("altrock", "Alternative Rock");
-    m.insert("alternative", "Alternative");
-    m.insert("indie rock", "Indie Rock");
-    m.insert("indierock", "Indie Rock");
-    m.insert("indie", "Indie");
-    m.insert("indie pop", "Indie Pop");
-    m.insert("indiepop", "Indie Pop");
-    m.insert("classic rock", "Classic Rock");
-    m.insert("classicrock", "Classic Rock");
-    m.insert("hard rock", "Hard Rock");
-    m.insert("hardrock", "Hard Rock");
-    m.insert("soft rock", "Soft Rock");
-    m.insert("softrock", "Soft Rock");
-    m.insert("progressive rock", "Progressive Rock");
-    m.insert("prog rock", "Progressive Rock");
-    m.insert("progrock", "Progressive Rock");
-    m.insert("southern rock", "Southern Rock");
-    m.insert("psychedelic rock", "Psychedelic Rock");
-    m.insert("garage rock", "Garage Rock");
-    m.insert("stoner rock", "Stoner Rock");
+    ("rock", "Rock"),
+    ("rock and roll", "Rock & Roll"),
+    ("rock n roll", "Rock & Roll"),
+    ("rockandroll", "Rock & Roll"),
+    ("alternative rock", "Alternative Rock"),
+    ("alt rock", "Alternative Rock"),
+    ("altrock", "Alternative Rock"),
+    ("alternative", "Alternative"),
+    ("indie rock", "Indie Rock"),
+    ("indierock", "Indie Rock"),
+    ("indie", "Indie"),
+    ("indie pop", "Indie Pop"),
+    ("indiepop", "Indie Pop"),
+    ("classic rock", "Classic Rock"),
+    ("classicrock", "Classic Rock"),
+    ("hard rock", "Hard Rock"),
+    ("hardrock", "Hard Rock"),
+    ("soft rock", "Soft Rock"),
+    ("softrock", "Soft Rock"),
+    ("progressive rock", "Progressive Rock"),
+    ("prog rock", "Progressive Rock"),
+    ("progrock", "Progressive Rock"),
+    ("southern rock", "Southern Rock"),
+    ("psychedelic rock", "Psychedelic Rock"),
+    ("garage rock", "Garage Rock"),
+    ("stoner rock", "Stoner Rock"),
 
     // === Metal ===
-    m.insert("metal", "Metal");
-    m.insert("heavy metal", "Metal");
-    m.insert("heavymetal", "Metal");
-    m.insert("death metal", "Death Metal");
-    m.insert("deathmetal", "Death Metal");
-    m.insert("black metal", "Black Metal");
-    m.insert("blackmetal", "Black Metal");
-    m.insert("thrash metal", "Thrash Metal");
-    m.insert("doom metal", "Doom Metal");
-    m.insert("progressive metal", "Progressive Metal");
-    m.insert("prog metal", "Progressive Metal");
-    m.insert("nu metal", "Nu Metal");
-    m.insert("numetal", "Nu Metal");
+    ("metal", "Metal"),
+    ("heavy metal", "Metal"),
+    ("heavymetal", "Metal"),
+    ("death metal", "Death Metal"),
+    ("deathmetal", "Death Metal"),
+    ("black metal", "Black Metal"),
+    ("blackmetal", "Black Metal"),
+    ("thrash metal", "Thrash Metal"),
+    ("doom metal", "Doom Metal"),
+    ("progressive metal", "Progressive Metal"),
+    ("prog metal", "Progressive Metal"),
+    ("nu metal", "Nu Metal"),
+    ("numetal", "Nu Metal"),
 
     // === Pop ===
-    m.insert("pop", "Pop");
-    m.insert("pop rock", "Pop Rock");
-    m.insert("pop and rock", "Pop Rock");
-    m.insert("synth pop", "Synth Pop");
-    m.insert("synthpop", "Synth Pop");
-    m.insert("electropop", "Electropop");
-    m.insert("electro pop", "Electropop");
-    m.insert("dream pop", "Dream Pop");
-    m.insert("dreampop", "Dream Pop");
-    m.insert("chamber pop", "Chamber Pop");
-    m.insert("art pop", "Art Pop");
-    m.insert("power pop", "Power Pop");
-    m.insert("k pop", "K-Pop");
-    m.insert("kpop", "K-Pop");
-    m.insert("j pop", "J-Pop");
-    m.insert("jpop", "J-Pop");
+    ("pop", "Pop"),
+    ("pop rock", "Pop Rock"),
+    ("pop and rock", "Pop Rock"),
+    ("synth pop", "Synth Pop"),
+    ("synthpop", "Synth Pop"),
+    ("electropop", "Electropop"),
+    ("electro pop", "Electropop"),
+    ("dream pop", "Dream Pop"),
+    ("dreampop", "Dream Pop"),
+    ("chamber pop", "Chamber Pop"),
+    ("art pop", "Art Pop"),
+    ("power pop", "Power Pop"),
+    ("k pop", "K-Pop"),
+    ("kpop", "K-Pop"),
+    ("j pop", "J-Pop"),
+    ("jpop", "J-Pop"),
 
     // === Electronic / Dance ===
-    m.insert("electronic", "Electronic");
-    m.insert("electronica", "Electronic");
-    m.insert("electronic music", "Electronic");
-    m.insert("electro", "Electro");
-    m.insert("edm", "EDM");
-    m.insert("dance", "Dance");
-    m.insert("dance music", "Dance");
-    m.insert("house", "House");
-    m.insert("house music", "House");
-    m.insert("deep house", "Deep House");
-    m.insert("tech house", "Tech House");
-    m.insert("progressive house", "Progressive House");
-    m.insert("techno", "Techno");
-    m.insert("techno music", "Techno");
-    m.insert("minimal techno", "Minimal Techno");
-    m.insert("trance", "Trance");
-    m.insert("trance music", "Trance");
-    m.insert("drum and bass", "Drum & Bass");
-    m.insert("drum n bass", "Drum & Bass");
-    m.insert("drumnbass", "Drum & Bass");
-    m.insert("dnb", "Drum & Bass");
-    m.insert("dandb", "Drum & Bass");
-    m.insert("d'n'b", "Drum & Bass");
-    m.insert("jungle", "Jungle");
-    m.insert("dubstep", "Dubstep");
-    m.insert("garage", "UK Garage");
-    m.insert("uk garage", "UK Garage");
-    m.insert("ambient", "Ambient");
-    m.insert("ambient music", "Ambient");
-    m.insert("downtempo", "Downtempo");
-    m.insert("chillout", "Chillout");
-    m.insert("chill out", "Chillout");
-    m.insert("idm", "IDM");
-    m.insert("breakbeat", "Breakbeat");
-    m.insert("eurodance", "Eurodance");
-    m.insert("euro dance", "Eurodance");
+    ("electronic", "Electronic"),
+    ("electronica", "Electronic"),
+    ("electronic music", "Electronic"),
+    ("electro", "Electro"),
+    ("edm", "EDM"),
+    ("dance", "Dance"),
+    ("dance music", "Dance"),
+    ("house", "House"),
+    ("house music", "House"),
+    ("deep house", "Deep House"),
+    ("tech house", "Tech House"),
+    ("progressive house", "Progressive House"),
+    ("techno", "Techno"),
+    ("techno music", "Techno"),
+    ("minimal techno", "Minimal Techno"),
+    ("trance", "Trance"),
+    ("trance music", "Trance"),
+    ("drum and bass", "Drum & Bass"),
+    ("drum n bass", "Drum & Bass"),
+    ("drumnbass", "Drum & Bass"),
+    ("dnb", "Drum & Bass"),
+    ("dandb", "Drum & Bass"),
+    ("jungle", "Jungle"),
+    ("dubstep", "Dubstep"),
+    ("garage", "UK Garage"),
+    ("uk garage", "UK Garage"),
+    ("ambient", "Ambient"),
+    ("ambient music", "Ambient"),
+    ("downtempo", "Downtempo"),
+    ("chillout", "Chillout"),
+    ("chill out", "Chillout"),
+    ("idm", "IDM"),
+    ("breakbeat", "Breakbeat"),
+    ("eurodance", "Eurodance"),
+    ("euro dance", "Eurodance"),
 
     // === Jazz ===
-    m.insert("jazz", "Jazz");
-    m.insert("jazz music", "Jazz");
-    m.insert("jazz fusion", "Jazz Fusion");
-    m.insert("jazz and funk", "Jazz Fusion");
-    m.insert("acid jazz", "Acid Jazz");
-    m.insert("acidjazz", "Acid Jazz");
-    m.insert("smooth jazz", "Smooth Jazz");
-    m.insert("bebop", "Bebop");
-    m.insert("free jazz", "Free Jazz");
-    m.insert("latin jazz", "Latin Jazz");
-    m.insert("cool jazz", "Cool Jazz");
+    ("jazz", "Jazz"),
+    ("jazz music", "Jazz"),
+    ("jazz fusion", "Jazz Fusion"),
+    ("jazz and funk", "Jazz Fusion"),
+    ("acid jazz", "Acid Jazz"),
+    ("acidjazz", "Acid Jazz"),
+    ("smooth jazz", "Smooth Jazz"),
+    ("bebop", "Bebop"),
+    ("free jazz", "Free Jazz"),
+    ("latin jazz", "Latin Jazz"),
+    ("cool jazz", "Cool Jazz"),
 
     // === Classical ===
-    m.insert("classical", "Classical");
-    m.insert("classical music", "Classical");
-    m.insert("baroque", "Baroque");
-    m.insert("romantic", "Romantic");
-    m.insert("opera", "Opera");
-    m.insert("orchestral", "Orchestral");
-    m.insert("chamber music", "Chamber Music");
-    m.insert("contemporary classical", "Contemporary Classical");
+    ("classical", "Classical"),
+    ("classical music", "Classical"),
+    ("baroque", "Baroque"),
+    ("romantic", "Romantic"),
+    ("opera", "Opera"),
+    ("orchestral", "Orchestral"),
+    ("chamber music", "Chamber Music"),
+    ("contemporary classical", "Contemporary Classical"),
 
     // === Country / Folk ===
-    m.insert("country", "Country");
-    m.insert("country and western", "Country");
-    m.insert("candw", "Country");
-    m.insert("country rock", "Country Rock");
-    m.insert("folk", "Folk");
-    m.insert("folk music", "Folk");
-    m.insert("folk rock", "Folk Rock");
-    m.insert("folkrock", "Folk Rock");
-    m.insert("singer songwriter", "Singer-Songwriter");
-    m.insert("singersongwriter", "Singer-Songwriter");
-    m.insert("americana", "Americana");
+    ("country", "Country"),
+    ("country and western", "Country"),
+    ("candw", "Country"),
+    ("country rock", "Country Rock"),
+    ("folk", "Folk"),
+    ("folk music", "Folk"),
+    ("folk rock", "Folk Rock"),
+    ("folkrock", "Folk Rock"),
+    ("singer songwriter", "Singer-Songwriter"),
+    ("singersongwriter", "Singer-Songwriter"),
+    ("americana", "Americana"),
 
     // === Reggae / Dub / Ska ===
-    m.insert("reggae", "Reggae");
-    m.insert("reggae dancehall", "Reggae");
-    m.insert("dancehall", "Dancehall");
-    m.insert("dub", "Dub");
-    m.insert("dub music", "Dub");
-    m.insert("ska", "Ska");
-    m.insert("ska punk", "Ska Punk");
-    m.insert("reggaeton", "Reggaeton");
+    ("reggae", "Reggae"),
+    ("reggae dancehall", "Reggae"),
+    ("dancehall", "Dancehall"),
+    ("dub", "Dub"),
+    ("dub music", "Dub"),
+    ("ska", "Ska"),
+    ("ska punk", "Ska Punk"),
+    ("reggaeton", "Reggaeton"),
 
     // === Blues ===
-    m.insert("blues", "Blues");
-    m.insert("blues rock", "Blues Rock");
-    m.insert("bluesrock", "Blues Rock");
-    m.insert("delta blues", "Delta Blues");
-    m.insert("electric blues", "Electric Blues");
-    m.insert("chicago blues", "Chicago Blues");
+    ("blues", "Blues"),
+    ("blues rock", "Blues Rock"),
+    ("bluesrock", "Blues Rock"),
+    ("delta blues", "Delta Blues"),
+    ("electric blues", "Electric Blues"),
+    ("chicago blues", "Chicago Blues"),
 
     // === Funk ===
-    m.insert("funk", "Funk");
-    m.insert("funk soul", "Funk");
-    m.insert("funk and soul", "Funk");
-    m.insert("p funk", "P-Funk");
-    m.insert("disco", "Disco");
-    m.insert("disco music", "Disco");
+    ("funk", "Funk"),
+    ("funk soul", "Funk"),
+    ("funk and soul", "Funk"),
+    ("p funk", "P-Funk"),
+    ("disco", "Disco"),
+    ("disco music", "Disco"),
 
     // === Punk ===
-    m.insert("punk", "Punk");
-    m.insert("punk rock", "Punk Rock");
-    m.insert("punkrock", "Punk Rock");
-    m.insert("post punk", "Post-Punk");
-    m.insert("postpunk", "Post-Punk");
-    m.insert("pop punk", "Pop Punk");
-    m.insert("poppunk", "Pop Punk");
-    m.insert("hardcore", "Hardcore");
-    m.insert("hardcore punk", "Hardcore Punk");
+    ("punk", "Punk"),
+    ("punk rock", "Punk Rock"),
+    ("punkrock", "Punk Rock"),
+    ("post punk", "Post-Punk"),
+    ("postpunk", "Post-Punk"),
+    ("pop punk", "Pop Punk"),
+    ("poppunk", "Pop Punk"),
+    ("hardcore", "Hardcore"),
+    ("hardcore punk", "Hardcore Punk"),
 
     // === World / Afro / Latin ===
-    m.insert("world", "World");
-    m.insert("world music", "World");
-    m.insert("afrobeat", "Afrobeat");
-    m.insert("afro beat", "Afrobeat");
-    m.insert("afrobeats", "Afrobeats");
-    m.insert("afro beats", "Afrobeats");
-    m.insert("afro pop", "Afro Pop");
-    m.insert("latin", "Latin");
-    m.insert("latin music", "Latin");
-    m.insert("bossa nova", "Bossa Nova");
-    m.insert("salsa", "Salsa");
-    m.insert("flamenco", "Flamenco");
-    m.insert("cumbia", "Cumbia");
-    m.insert("samba", "Samba");
-    m.insert("celtic", "Celtic");
+    ("world", "World"),
+    ("world music", "World"),
+    ("afrobeat", "Afrobeat"),
+    ("afro beat", "Afrobeat"),
+    ("afrobeats", "Afrobeats"),
+    ("afro beats", "Afrobeats"),
+    ("afro pop", "Afro Pop"),
+    ("latin", "Latin"),
+    ("latin music", "Latin"),
+    ("bossa nova", "Bossa Nova"),
+    ("salsa", "Salsa"),
+    ("flamenco", "Flamenco"),
+    ("cumbia", "Cumbia"),
+    ("samba", "Samba"),
+    ("celtic", "Celtic"),
 
     // === Autres genres courants ===
-    m.insert("trip hop", "Trip-Hop");
-    m.insert("triphop", "Trip-Hop");
-    m.insert("lo fi", "Lo-Fi");
-    m.insert("lofi", "Lo-Fi");
-    m.insert("new wave", "New Wave");
-    m.insert("newwave", "New Wave");
-    m.insert("post rock", "Post-Rock");
-    m.insert("postrock", "Post-Rock");
-    m.insert("shoegaze", "Shoegaze");
-    m.insert("shoe gaze", "Shoegaze");
-    m.insert("grunge", "Grunge");
-    m.insert("emo", "Emo");
-    m.insert("goth", "Gothic");
-    m.insert("gothic", "Gothic");
-    m.insert("gothic rock", "Gothic Rock");
-    m.insert("darkwave", "Darkwave");
-    m.insert("dark wave", "Darkwave");
-    m.insert("industrial", "Industrial");
-    m.insert("industrial music", "Industrial");
-    m.insert("noise", "Noise");
-    m.insert("noise rock", "Noise Rock");
-    m.insert("experimental", "Experimental");
-    m.insert("avant garde", "Avant-Garde");
-    m.insert("avantgarde", "Avant-Garde");
-    m.insert("new age", "New Age");
-    m.insert("newage", "New Age");
-    m.insert("gospel", "Gospel");
-    m.insert("gospel music", "Gospel");
-    m.insert("christian", "Christian");
-    m.insert("christian rock", "Christian Rock");
-    m.insert("soundtrack", "Soundtrack");
-    m.insert("ost", "Soundtrack");
-    m.insert("film score", "Soundtrack");
-    m.insert("score", "Soundtrack");
-    m.insert("spoken word", "Spoken Word");
-    m.insert("spokenword", "Spoken Word");
-    m.insert("podcast", "Podcast");
-    m.insert("audiobook", "Audiobook");
-    m.insert("asmr", "ASMR");
+    ("trip hop", "Trip-Hop"),
+    ("triphop", "Trip-Hop"),
+    ("lo fi", "Lo-Fi"),
+    ("lofi", "Lo-Fi"),
+    ("new wave", "New Wave"),
+    ("newwave", "New Wave"),
+    ("post rock", "Post-Rock"),
+    ("postrock", "Post-Rock"),
+    ("shoegaze", "Shoegaze"),
+    ("shoe gaze", "Shoegaze"),
+    ("grunge", "Grunge"),
+    ("emo", "Emo"),
+    ("goth", "Gothic"),
+    ("gothic", "Gothic"),
+    ("gothic rock", "Gothic Rock"),
+    ("darkwave", "Darkwave"),
+    ("dark wave", "Darkwave"),
+    ("industrial", "Industrial"),
+    ("industrial music", "Industrial"),
+    ("noise", "Noise"),
+    ("noise rock", "Noise Rock"),
+    ("experimental", "Experimental"),
+    ("avant garde", "Avant-Garde"),
+    ("avantgarde", "Avant-Garde"),
+    ("new age", "New Age"),
+    ("newage", "New Age"),
+    ("gospel", "Gospel"),
+    ("gospel music", "Gospel"),
+    ("christian", "Christian"),
+    ("christian rock", "Christian Rock"),
+    ("soundtrack", "Soundtrack"),
+    ("ost", "Soundtrack"),
+    ("film score", "Soundtrack"),
+    ("score", "Soundtrack"),
+    ("spoken word", "Spoken Word"),
+    ("spokenword", "Spoken Word"),
+    ("podcast", "Podcast"),
+    ("audiobook", "Audiobook"),
+    ("asmr", "ASMR"),
+];
 
-    m
-});
+// Index construit une seule fois à partir de GENRE_MAP_ENTRIES
+static GENRE_MAP: Lazy<HashMap<&str, &str>> = Lazy::new(|| GENRE_MAP_ENTRIES.iter().copied().collect());
+
+// Sous-genre → genre parent, pour replier le navigateur de genres en grandes catégories.
+// Clés et valeurs sont des formes canoniques (valeurs de GENRE_MAP) ; un seul niveau de profondeur.
+static GENRE_PARENT_ENTRIES: &[(&str, &str)] = &[
+    // === Rock ===
+    ("Alternative Rock", "Rock"),
+    ("Blues Rock", "Rock"),
+    ("Christian Rock", "Rock"),
+    ("Classic Rock", "Rock"),
+    ("Country Rock", "Rock"),
+    ("Folk Rock", "Rock"),
+    ("Garage Rock", "Rock"),
+    ("Gothic Rock", "Rock"),
+    ("Grunge", "Rock"),
+    ("Hard Rock", "Rock"),
+    ("Indie Rock", "Rock"),
+    ("Noise Rock", "Rock"),
+    ("Pop Rock", "Rock"),
+    ("Post-Rock", "Rock"),
+    ("Progressive Rock", "Rock"),
+    ("Psychedelic Rock", "Rock"),
+    ("Rock & Roll", "Rock"),
+    ("Shoegaze", "Rock"),
+    ("Soft Rock", "Rock"),
+    ("Southern Rock", "Rock"),
+    ("Stoner Rock", "Rock"),
+
+    // === Metal ===
+    ("Black Metal", "Metal"),
+    ("Death Metal", "Metal"),
+    ("Doom Metal", "Metal"),
+    ("Nu Metal", "Metal"),
+    ("Progressive Metal", "Metal"),
+    ("Thrash Metal", "Metal"),
+
+    // === Punk ===
+    ("Hardcore Punk", "Punk"),
+    ("Pop Punk", "Punk"),
+    ("Post-Punk", "Punk"),
+    ("Punk Rock", "Punk"),
+    ("Ska Punk", "Punk"),
+    ("Emo", "Punk"),
+
+    // === Pop ===
+    ("Art Pop", "Pop"),
+    ("Chamber Pop", "Pop"),
+    ("Dream Pop", "Pop"),
+    ("Electropop", "Pop"),
+    ("Indie Pop", "Pop"),
+    ("J-Pop", "Pop"),
+    ("K-Pop", "Pop"),
+    ("Power Pop", "Pop"),
+    ("Synth Pop", "Pop"),
+
+    // === Hip-Hop / R&B ===
+    ("Rap", "Hip-Hop"),
+    ("Trap", "Hip-Hop"),
+    ("Neo Soul", "Soul"),
+
+    // === Jazz ===
+    ("Acid Jazz", "Jazz"),
+    ("Bebop", "Jazz"),
+    ("Cool Jazz", "Jazz"),
+    ("Free Jazz", "Jazz"),
+    ("Jazz Fusion", "Jazz"),
+    ("Latin Jazz", "Jazz"),
+    ("Smooth Jazz", "Jazz"),
+
+    // === Blues ===
+    ("Chicago Blues", "Blues"),
+    ("Delta Blues", "Blues"),
+    ("Electric Blues", "Blues"),
+
+    // === Electronic ===
+    ("Ambient", "Electronic"),
+    ("Breakbeat", "Electronic"),
+    ("Chillout", "Electronic"),
+    ("Deep House", "Electronic"),
+    ("Downtempo", "Electronic"),
+    ("Drum & Bass", "Electronic"),
+    ("Dubstep", "Electronic"),
+    ("EDM", "Electronic"),
+    ("Electro", "Electronic"),
+    ("House", "Electronic"),
+    ("IDM", "Electronic"),
+    ("Jungle", "Electronic"),
+    ("Minimal Techno", "Electronic"),
+    ("Progressive House", "Electronic"),
+    ("Tech House", "Electronic"),
+    ("Techno", "Electronic"),
+    ("Trance", "Electronic"),
+    ("Trip-Hop", "Electronic"),
+    ("UK Garage", "Electronic"),
+
+    // === Classical ===
+    ("Baroque", "Classical"),
+    ("Chamber Music", "Classical"),
+    ("Contemporary Classical", "Classical"),
+    ("Opera", "Classical"),
+    ("Orchestral", "Classical"),
+    ("Romantic", "Classical"),
+
+    // === Folk / Country ===
+    ("Americana", "Country"),
+    ("Celtic", "Folk"),
+    ("Singer-Songwriter", "Folk"),
+
+    // === Reggae / Latin / World ===
+    ("Dancehall", "Reggae"),
+    ("Dub", "Reggae"),
+    ("Bossa Nova", "Latin"),
+    ("Cumbia", "Latin"),
+    ("Reggaeton", "Latin"),
+    ("Salsa", "Latin"),
+    ("Samba", "Latin"),
+    ("Afro Pop", "World"),
+    ("Afrobeat", "World"),
+    ("Afrobeats", "World"),
+    ("Flamenco", "World"),
+
+    // === Divers ===
+    ("Eurodance", "Dance"),
+    ("Disco", "Dance"),
+    ("P-Funk", "Funk"),
+    ("Gospel", "Christian"),
+];
+
+static GENRE_PARENT_MAP: Lazy<HashMap<&str, &str>> = Lazy::new(|| GENRE_PARENT_ENTRIES.iter().copied().collect());
 
 /// Mapping Deezer genre_id → nom canonique (26 genres Deezer, on ignore id=0 "Tous")
 static DEEZER_GENRE_MAP: Lazy<HashMap<u64, &'static str>> = Lazy::new(|| {
@@ -1583,9 +2446,19 @@ fn normalize_genre(raw: &str) -> String {
         }
     }
 
-    // Nettoyage pour matching : lowercase, supprime ponctuation, collapse espaces
-    let cleaned = trimmed.to_lowercase();
-    let key = cleaned
+    // Lookup dans la table de correspondance
+    let key = genre_lookup_key(trimmed);
+    if let Some(canonical) = GENRE_MAP.get(key.as_str()) {
+        return canonical.to_string();
+    }
+
+    // Pas trouvé dans la table : title-case le genre original
+    title_case(trimmed)
+}
+
+/// Clé de matching GENRE_MAP : lowercase, supprime ponctuation, collapse espaces
+fn genre_lookup_key(raw: &str) -> String {
+    raw.to_lowercase()
         .replace('-', " ")
         .replace('_', " ")
         .replace('&', "and")
@@ -1594,15 +2467,7 @@ fn normalize_genre(raw: &str) -> String {
         .replace('\'', "")
         .split_whitespace()
         .collect::<Vec<&str>>()
-        .join(" ");
-
-    // Lookup dans la table de correspondance
-    if let Some(canonical) = GENRE_MAP.get(key.as_str()) {
-        return canonical.to_string();
-    }
-
-    // Pas trouvé dans la table : title-case le genre original
-    title_case(trimmed)
+        .join(" ")
 }
 
 /// Met en majuscule la première lettre de chaque mot
@@ -1653,6 +2518,84 @@ fn split_and_normalize_genre(raw: &str) -> Option<String> {
     None
 }
 
+/// Drapeau compilation : COMPILATION / TCMP / cpil (mappés par lofty) ou ITUNESCOMPILATION
+pub(crate) fn is_compilation(tag: &lofty::Tag) -> bool {
+    [lofty::ItemKey::FlagCompilation, lofty::ItemKey::Unknown("ITUNESCOMPILATION".to_string())]
+        .iter()
+        .filter_map(|key| tag.get_string(key))
+        .any(is_truthy_flag)
+}
+
+/// BPM : décimal (BPM, iTunes) ou entier (TBPM, tmpo) ; "128,5" accepté
+pub(crate) fn read_bpm(tag: &lofty::Tag) -> Option<f32> {
+    [lofty::ItemKey::Bpm, lofty::ItemKey::IntegerBpm]
+        .iter()
+        .filter_map(|key| tag.get_string(key))
+        .find_map(|value| value.trim().replace(',', ".").parse::<f32>().ok())
+        .filter(|bpm| *bpm > 0.0)
+}
+
+/// "1" / "true" / "yes" (valeurs des tags booléens selon les taggers)
+pub(crate) fn is_truthy_flag(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+// Groupes dont le nom contient " & " : jamais coupés en plusieurs artistes
+const ARTIST_NAME_EXCEPTIONS: [&str; 8] = [
+    "simon & garfunkel",
+    "earth, wind & fire",
+    "crosby, stills, nash & young",
+    "hall & oates",
+    "daryl hall & john oates",
+    "sam & dave",
+    "ike & tina turner",
+    "kool & the gang",
+];
+
+/// Sépare un champ artiste multi-valeurs ("A feat. B", "A & B", "A; B", "A / B").
+/// Le slash n'est un séparateur qu'entouré d'espaces, pour ne pas couper "AC/DC".
+/// " & " est appliqué en dernier, une fois les "feat." retirés, pour reconnaître
+/// les noms de groupes de ARTIST_NAME_EXCEPTIONS.
+pub(crate) fn split_artists(raw: &str) -> Vec<String> {
+    const SEPARATORS: [&str; 7] = [";", " / ", " feat. ", " feat ", " ft. ", " featuring ", " & "];
+
+    // "Artiste (feat. X)" : la parenthèse fermante fait partie du séparateur
+    let unwrapped = raw.replace(" (feat. ", " feat. ").replace(" (ft. ", " ft. ");
+    let mut parts = vec![if unwrapped != raw {
+        unwrapped.trim_end().trim_end_matches(')').to_string()
+    } else {
+        unwrapped
+    }];
+    for separator in SEPARATORS {
+        parts = parts
+            .iter()
+            .flat_map(|part| {
+                let lower = part.to_ascii_lowercase();
+                if ARTIST_NAME_EXCEPTIONS.contains(&lower.trim()) {
+                    return vec![part.clone()];
+                }
+                let mut pieces = Vec::new();
+                let mut last = 0;
+                for (idx, _) in lower.match_indices(separator) {
+                    pieces.push(part[last..idx].to_string());
+                    last = idx + separator.len();
+                }
+                pieces.push(part[last..].to_string());
+                pieces
+            })
+            .collect();
+    }
+
+    let mut artists: Vec<String> = Vec::new();
+    for part in parts {
+        let part = part.trim();
+        if !part.is_empty() && !artists.iter().any(|a| a.eq_ignore_ascii_case(part)) {
+            artists.push(part.to_string());
+        }
+    }
+    artists
+}
+
 // Fonction interne pour obtenir les métadonnées (utilisée par le scan parallèle)
 fn get_metadata_internal(path: &str) -> Metadata {
     // Vérifie le cache mémoire d'abord
@@ -1663,6 +2606,35 @@ fn get_metadata_internal(path: &str) -> Metadata {
     }
 
     // Pas en cache, lecture depuis le fichier audio
+    read_metadata_from_file(path)
+}
+
+// Lecture des métadonnées depuis le fichier audio (sans passer par le cache)
+fn read_metadata_from_file(path: &str) -> Metadata {
+    read_metadata_checked(path).0
+}
+
+// Nom du codec depuis le type de fichier (ALAC et AAC partagent le conteneur MP4 :
+// seul l'ALAC déclare une profondeur de bits)
+pub(crate) fn codec_name(file_type: lofty::FileType, bit_depth: Option<u8>) -> String {
+    match file_type {
+        lofty::FileType::Flac => "FLAC",
+        lofty::FileType::Mpeg => "MP3",
+        lofty::FileType::Mp4 => if bit_depth.is_some() { "ALAC" } else { "AAC" },
+        lofty::FileType::Aac => "AAC",
+        lofty::FileType::Wav => "WAV",
+        lofty::FileType::Aiff => "AIFF",
+        lofty::FileType::WavPack => "WAVPACK",
+        lofty::FileType::Ape => "APE",
+        lofty::FileType::Vorbis => "VORBIS",
+        lofty::FileType::Opus => "OPUS",
+        lofty::FileType::Mpc => "MPC",
+        _ => "Other",
+    }.to_string()
+}
+
+// Comme read_metadata_from_file, avec le problème rencontré (métadonnées par défaut sinon)
+fn read_metadata_checked(path: &str) -> (Metadata, Option<ScanIssue>) {
     let file_path = Path::new(path);
     let file_name = file_path.file_stem()
         .and_then(|n| n.to_str())
@@ -1681,90 +2653,111 @@ fn get_metadata_internal(path: &str) -> Metadata {
         year: None,
         genre: None,
         genre_enriched: false,
+        genre_locked: false,
         duration: 0.0,
         bit_depth: None,
         sample_rate: None,
         bitrate: None,
         codec: None,
         file_size: actual_file_size,
+        album_artist: None,
+        artists: Vec::new(),
+        composer: None,
+        compilation: false,
+        bpm: None,
+        initial_key: None,
     };
 
-    if let Ok(tagged_file) = Probe::open(path).and_then(|p| p.read()) {
-        let properties = tagged_file.properties();
-        metadata.duration = properties.duration().as_secs_f64();
-        metadata.sample_rate = properties.sample_rate();
-        metadata.bit_depth = properties.bit_depth();
-        metadata.bitrate = properties.audio_bitrate();
-
-        // Détermine le codec depuis le type de fichier
-        metadata.codec = Some(match tagged_file.file_type() {
-            lofty::FileType::Flac => "FLAC".to_string(),
-            lofty::FileType::Mpeg => "MP3".to_string(),
-            lofty::FileType::Mp4 => {
-                if metadata.bit_depth.is_some() { "ALAC".to_string() }
-                else { "AAC".to_string() }
-            }
-            lofty::FileType::Wav => "WAV".to_string(),
-            lofty::FileType::Aiff => "AIFF".to_string(),
-            _ => "Other".to_string(),
-        });
-
-        if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
-            if let Some(title) = tag.title() {
-                metadata.title = title.to_string();
-            }
-            if let Some(artist) = tag.artist() {
-                metadata.artist = artist.to_string();
-            }
-            if let Some(album) = tag.album() {
-                metadata.album = album.to_string();
-            }
-            if let Some(track) = tag.track() {
-                metadata.track = track;
-            }
-            if let Some(disc) = tag.disk() {
-                metadata.disc = Some(disc);
-            }
-            if let Some(year) = tag.year() {
-                metadata.year = Some(year);
+    let probed = Probe::open(path)
+        .map_err(|e| ScanIssue::new(path, "unreadable", e))
+        .and_then(|p| p.read().map_err(|e| ScanIssue::new(path, "tag_parse_failed", e)));
+    let mut issue = None;
+    match probed {
+        Ok(tagged_file) => {
+            let properties = tagged_file.properties();
+            metadata.duration = properties.duration().as_secs_f64();
+            metadata.sample_rate = properties.sample_rate();
+            metadata.bit_depth = properties.bit_depth();
+            metadata.bitrate = properties.audio_bitrate();
+
+            // Détermine le codec depuis le type de fichier
+            metadata.codec = Some(codec_name(tagged_file.file_type(), metadata.bit_depth));
+
+            if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
+                if let Some(title) = tag.title() {
+                    metadata.title = title.to_string();
+                }
+                if let Some(artist) = tag.artist() {
+                    metadata.artist = artist.to_string();
+                    metadata.artists = split_artists(&artist);
+                }
+                metadata.album_artist = tag.get_string(&lofty::ItemKey::AlbumArtist).map(|v| v.to_string());
+                metadata.composer = tag.get_string(&lofty::ItemKey::Composer).map(|v| v.to_string());
+                metadata.compilation = is_compilation(tag);
+                metadata.bpm = read_bpm(tag);
+                metadata.initial_key = tag.get_string(&lofty::ItemKey::InitialKey).map(|v| v.trim().to_string());
+                if let Some(album) = tag.album() {
+                    metadata.album = album.to_string();
+                }
+                if let Some(track) = tag.track() {
+                    metadata.track = track;
+                }
+                if let Some(disc) = tag.disk() {
+                    metadata.disc = Some(disc);
+                }
+                if let Some(year) = tag.year() {
+                    metadata.year = Some(year);
+                }
+                if let Some(genre) = tag.genre() {
+                    metadata.genre = split_and_normalize_genre(&genre);
+                }
             }
-            if let Some(genre) = tag.genre() {
-                metadata.genre = split_and_normalize_genre(&genre);
+        }
+        Err(_) if dsd::is_dsd_path(file_path) => {
+            // lofty ne lit pas le DSD : propriétés depuis l'en-tête DSF/DFF (pas de tags)
+            match dsd::read_dsd_header(path) {
+                Ok(header) => {
+                    metadata.duration = header.duration_seconds();
+                    metadata.sample_rate = Some(header.dsd_rate);
+                    metadata.bit_depth = Some(1);
+                    metadata.codec = Some("DSD".to_string());
+                }
+                Err(e) => issue = Some(ScanIssue::new(path, "unreadable", e)),
             }
         }
+        Err(e) => issue = Some(e),
     }
 
-    metadata
+    if issue.is_none() {
+        issue = ScanIssue::from_audio_properties(path, &metadata);
+    }
+    (metadata, issue)
 }
 
 // Scanner un dossier AVEC métadonnées - Version optimisée parallèle
 // Retourne les tracks avec leurs métadonnées en UN SEUL appel IPC
 #[tauri::command]
-fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
-    let start = std::time::Instant::now();
-    #[cfg(debug_assertions)]
-    println!("=== Scan starting for: {} ===", path);
-
-    // Vérifie si le chemin existe
-    let path_obj = Path::new(path);
-    if !path_obj.exists() {
-        #[cfg(debug_assertions)]
-        println!("ERROR: Path does not exist: {}", path);
-        return Vec::new();
-    }
-    if !path_obj.is_dir() {
-        #[cfg(debug_assertions)]
-        println!("ERROR: Path is not a directory: {}", path);
-        return Vec::new();
-    }
-    #[cfg(debug_assertions)]
-    println!("Path exists and is directory: {}", path);
-
-    // 1. Collecte tous les chemins de fichiers audio (rapide, séquentiel)
-    // SECURITY: Limit depth to prevent infinite symlink loops while still following links
-    let paths: Vec<PathBuf> = WalkDir::new(path)
-        .follow_links(true)
-        .max_depth(20)
+fn scan_folder_with_metadata(app_handle: tauri::AppHandle, path: &str) -> Vec<TrackWithMetadata> {
+    scan_folder_with_metadata_internal(path, false, &mut Vec::new(), Some(&app_handle))
+}
+
+// Rescan incrémental : seuls les fichiers nouveaux ou dont le mtime a avancé sont relus,
+// les autres réutilisent METADATA_CACHE. force_full = relit tout (tags modifiés hors de l'app).
+// Les fichiers à problème sont ajoutés à `issues`. Avec `app_handle`, la lecture des
+// métadonnées émet scan_progress (phase "loading_metadata") toutes les 200ms.
+fn scan_folder_with_metadata_internal(
+    path: &str,
+    force_full: bool,
+    issues: &mut Vec<ScanIssue>,
+    app_handle: Option<&tauri::AppHandle>,
+) -> Vec<TrackWithMetadata> {
+    scan_folders_with_metadata_internal(&[path.to_string()], force_full, issues, app_handle)
+}
+
+// Parcours d'un dossier : fichiers audio reconnus, dans l'ordre de walkdir
+fn collect_audio_paths(path: &str) -> Vec<PathBuf> {
+    // SECURITY: profondeur bornée (MAX_SCAN_DEPTH) même si les liens sont suivis
+    library_walker(path)
         .into_iter()
         .filter_map(|e| {
             match e {
@@ -1778,6 +2771,79 @@ fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
         })
         .filter(|e| e.path().is_file() && is_audio_file(e.path()))
         .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+// Comme scan_folder_with_metadata_internal, sur plusieurs dossiers en une seule passe :
+// les dossiers sont parcourus en parallèle (souvent sur des disques différents), puis une
+// seule lecture parallèle des métadonnées couvre tous les fichiers, avec une progression
+// globale. Un fichier présent sous deux dossiers imbriqués n'est lu qu'une fois.
+fn scan_folders_with_metadata_internal(
+    folders: &[String],
+    force_full: bool,
+    issues: &mut Vec<ScanIssue>,
+    app_handle: Option<&tauri::AppHandle>,
+) -> Vec<TrackWithMetadata> {
+    let start = std::time::Instant::now();
+    #[cfg(debug_assertions)]
+    println!("=== Scan starting for: {:?} ===", folders);
+
+    // Vérifie que les chemins existent et sont des dossiers
+    let folders: Vec<&str> = folders.iter()
+        .map(String::as_str)
+        .filter(|path| {
+            let path_obj = Path::new(path);
+            if !path_obj.exists() {
+                #[cfg(debug_assertions)]
+                println!("ERROR: Path does not exist: {}", path);
+                return false;
+            }
+            if !path_obj.is_dir() {
+                #[cfg(debug_assertions)]
+                println!("ERROR: Path is not a directory: {}", path);
+                return false;
+            }
+            true
+        })
+        .collect();
+    if folders.is_empty() {
+        return Vec::new();
+    }
+
+    // 1. Collecte tous les chemins de fichiers audio (un parcours par dossier, en parallèle).
+    // Progression "scanning" : dossiers terminés, compteur partagé entre les workers rayon
+    let walked = std::sync::atomic::AtomicUsize::new(0);
+    let walks: Vec<(Vec<PathBuf>, std::time::Duration)> = folders.par_iter()
+        .map(|&folder| {
+            let walk_start = std::time::Instant::now();
+            let folder_paths = collect_audio_paths(folder);
+            let done = walked.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+            if let Some(app) = app_handle {
+                use tauri::Emitter;
+                let _ = app.emit("scan_progress", ScanProgress {
+                    phase: "scanning".to_string(),
+                    current: done,
+                    total: folders.len(),
+                    folder: Path::new(folder).file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or(folder)
+                        .to_string(),
+                });
+            }
+            (folder_paths, walk_start.elapsed())
+        })
+        .collect();
+
+    // Mesure : la somme des parcours est le coût de l'ancien parcours séquentiel dossier par
+    // dossier, le temps écoulé celui du parcours parallèle (gain attendu avec plusieurs disques)
+    #[cfg(debug_assertions)]
+    println!("[RUST-PERF] Walked {} folders in {:?} (sequential cost {:?})",
+        folders.len(), start.elapsed(), walks.iter().map(|(_, elapsed)| *elapsed).sum::<std::time::Duration>());
+
+    let mut seen_paths: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+    let paths: Vec<PathBuf> = walks.into_iter()
+        .flat_map(|(folder_paths, _)| folder_paths)
+        .filter(|p| seen_paths.insert(p.clone()))
         .collect();
 
     let file_count = paths.len();
@@ -1786,12 +2852,59 @@ fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
 
     // 2. Charge les métadonnées EN PARALLÈLE avec Rayon
     let parallel_start = std::time::Instant::now();
-    let results: Vec<TrackWithMetadata> = paths.par_iter()
+    let known_mtimes: HashMap<String, u64> = MTIME_CACHE.lock()
+        .map(|c| c.entries.clone())
+        .unwrap_or_default();
+
+    // Progression : compteur incrémenté par les workers rayon (work-stealing), lu par un
+    // thread moniteur qui s'arrête dès que `loading_done` est fermé
+    let loaded = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let (loading_done, loading_done_rx) = crossbeam_channel::bounded::<()>(0);
+    if let Some(app) = app_handle.cloned() {
+        use tauri::Emitter;
+        let loaded = Arc::clone(&loaded);
+        let folder = match folders.as_slice() {
+            &[path] => Path::new(path).file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(path)
+                .to_string(),
+            _ => format!("{} folders", folders.len()),
+        };
+        std::thread::spawn(move || {
+            while loading_done_rx.recv_timeout(std::time::Duration::from_millis(200))
+                == Err(crossbeam_channel::RecvTimeoutError::Timeout)
+            {
+                let _ = app.emit("scan_progress", ScanProgress {
+                    phase: "loading_metadata".to_string(),
+                    current: loaded.load(std::sync::atomic::Ordering::Relaxed),
+                    total: file_count,
+                    folder: folder.clone(),
+                });
+            }
+        });
+    }
+
+    // Annulation : les fichiers restants sont ignorés sans être lus
+    let results: Vec<(TrackWithMetadata, Option<u64>, bool, Option<ScanIssue>)> = paths.par_iter()
+        .filter(|_| !SCAN_CANCELLED.load(std::sync::atomic::Ordering::Relaxed))
         .map(|file_path| {
             let path_str = file_path.to_string_lossy().to_string();
-            let metadata = get_metadata_internal(&path_str);
+            let mtime = file_mtime(file_path);
+
+            // Fichier sans mtime connu (cache antérieur) : considéré inchangé
+            let changed = force_full || match (known_mtimes.get(&path_str), mtime) {
+                (Some(known), Some(current)) => current > *known,
+                _ => false,
+            };
+            let (metadata, issue) = if changed {
+                read_metadata_checked(&path_str)
+            } else {
+                let metadata = get_metadata_internal(&path_str);
+                let issue = ScanIssue::from_metadata(&path_str, &metadata);
+                (metadata, issue)
+            };
 
-            TrackWithMetadata {
+            let track = TrackWithMetadata {
                 path: path_str,
                 name: file_path.file_stem()
                     .and_then(|n| n.to_str())
@@ -1803,17 +2916,22 @@ fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
                     .unwrap_or("")
                     .to_string(),
                 metadata,
-            }
+            };
+            loaded.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            (track, mtime, changed, issue)
         })
         .collect();
+    drop(loading_done);
 
     #[cfg(debug_assertions)]
-    println!("Metadata loaded in {:?} ({} files)", parallel_start.elapsed(), file_count);
+    println!("Metadata loaded in {:?} ({} files, {} re-read)", parallel_start.elapsed(), file_count,
+        results.iter().filter(|(_, _, changed, _)| *changed).count());
 
     // 3. Met à jour le cache avec les nouvelles métadonnées
+    // (les fichiers modifiés remplacent leur ancienne entrée)
     if let Ok(mut cache) = METADATA_CACHE.lock() {
-        for track in &results {
-            if !cache.entries.contains_key(&track.path) {
+        for (track, _, changed, _) in &results {
+            if *changed || !cache.entries.contains_key(&track.path) {
                 cache.entries.insert(track.path.clone(), track.metadata.clone());
             }
         }
@@ -1821,6 +2939,18 @@ fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
     if let Ok(mut dirty) = CACHE_DIRTY.lock() {
         *dirty = true;
     }
+    if let Ok(mut cache) = MTIME_CACHE.lock() {
+        for (track, mtime, _, _) in &results {
+            if let Some(mtime) = mtime {
+                cache.entries.insert(track.path.clone(), *mtime);
+            }
+        }
+        save_mtime_cache(&cache);
+    }
+    let results = expand_cue_tracks(results.into_iter().map(|(track, _, _, issue)| {
+        issues.extend(issue);
+        track
+    }).collect());
 
     // 4. Enregistre les dates d'ajout pour les nouvelles tracks
     let now = std::time::SystemTime::now()
@@ -1846,6 +2976,55 @@ fn scan_folder_with_metadata(path: &str) -> Vec<TrackWithMetadata> {
     results
 }
 
+// Albums en un seul fichier + .cue voisin : une piste virtuelle (album.flac#track=N) par TRACK.
+// Chaque piste hérite des specs audio du fichier ; ses métadonnées sont mises en cache
+// sous le chemin virtuel pour que get_metadata les retrouve.
+fn expand_cue_tracks(tracks: Vec<TrackWithMetadata>) -> Vec<TrackWithMetadata> {
+    let mut expanded = Vec::with_capacity(tracks.len());
+    let mut virtual_metadata: Vec<(String, Metadata)> = Vec::new();
+
+    for track in tracks {
+        let cue_tracks = cue::find_cue_sheet(Path::new(&track.path))
+            .and_then(|cue_path| cue::read_cue_sheet(&cue_path))
+            .map(|sheet| (cue::tracks_for_file(&sheet, &track.path), sheet))
+            .filter(|(cue_tracks, _)| !cue_tracks.is_empty());
+        let Some((cue_tracks, sheet)) = cue_tracks else {
+            expanded.push(track);
+            continue;
+        };
+
+        for cue_track in cue_tracks {
+            let mut metadata = track.metadata.clone();
+            metadata.title = cue_track.title.clone();
+            if !cue_track.performer.is_empty() {
+                metadata.artist = cue_track.performer.clone();
+            }
+            if !sheet.title.is_empty() {
+                metadata.album = sheet.title.clone();
+            }
+            metadata.track = cue_track.number;
+            let end = cue_track.end.unwrap_or(track.metadata.duration);
+            metadata.duration = (end - cue_track.start).max(0.0);
+
+            let path = cue::virtual_path(&track.path, cue_track.number);
+            virtual_metadata.push((path.clone(), metadata.clone()));
+            expanded.push(TrackWithMetadata {
+                path,
+                name: cue_track.title,
+                folder: track.folder.clone(),
+                metadata,
+            });
+        }
+    }
+
+    if !virtual_metadata.is_empty() {
+        if let Ok(mut cache) = METADATA_CACHE.lock() {
+            cache.entries.extend(virtual_metadata);
+        }
+    }
+    expanded
+}
+
 // === COMMANDES POUR DÉMARRAGE INSTANTANÉ ===
 
 // Charge les tracks depuis le cache (instantané)
@@ -1859,11 +3038,24 @@ fn load_tracks_from_cache() -> (Vec<TrackWithMetadata>, LibraryStats) {
     }
 }
 
+// Recherche floue dans la bibliothèque (titre, artiste, album), résultats classés
+#[tauri::command]
+fn search_library(query: String, limit: usize) -> Vec<TrackWithMetadata> {
+    if let Ok(cache) = TRACKS_CACHE.lock() {
+        search::search(&cache.tracks, &query, limit)
+    } else {
+        Vec::new()
+    }
+}
+
 // Lance le scan en arrière-plan et émet des événements de progression
 #[tauri::command]
 fn start_background_scan(app_handle: tauri::AppHandle) {
     use tauri::Emitter;
 
+    SCAN_CANCELLED.store(false, std::sync::atomic::Ordering::SeqCst);
+    let force_full = FORCE_FULL_RESCAN.swap(false, std::sync::atomic::Ordering::SeqCst);
+
     std::thread::spawn(move || {
         let start = std::time::Instant::now();
 
@@ -1929,33 +3121,38 @@ fn start_background_scan(app_handle: tauri::AppHandle) {
             #[cfg(debug_assertions)]
             println!("[Scan] {} excluded tracks will be filtered out", excluded_paths.len());
         }
+        let exclude_problem_tracks = config.exclude_problem_tracks;
 
         let mut all_tracks: Vec<TrackWithMetadata> = Vec::new();
+        let mut scan_issues: Vec<ScanIssue> = Vec::new();
         let mut seen_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
         let total_folders = library_paths.len();
 
-        for (folder_idx, folder_path) in library_paths.iter().enumerate() {
-            let folder_name = Path::new(folder_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or(folder_path)
-                .to_string();
-
-            // Émet la progression du scan
-            let _ = app_handle.emit("scan_progress", ScanProgress {
-                phase: "scanning".to_string(),
-                current: folder_idx + 1,
-                total: total_folders,
-                folder: folder_name.clone(),
-            });
+        // Début du scan ; la progression par dossier est émise pendant le parcours parallèle
+        let _ = app_handle.emit("scan_progress", ScanProgress {
+            phase: "scanning".to_string(),
+            current: 0,
+            total: total_folders,
+            folder: match library_paths.as_slice() {
+                [folder_path] => Path::new(folder_path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(folder_path)
+                    .to_string(),
+                _ => format!("{} folders", total_folders),
+            },
+        });
 
-            // Scanne le dossier avec métadonnées
-            let tracks = scan_folder_with_metadata(folder_path);
-            // Déduplique par chemin de fichier + filtre les exclus
-            for track in tracks {
-                if seen_paths.insert(track.path.clone()) && !excluded_paths.contains(&track.path) {
-                    all_tracks.push(track);
-                }
+        // Tous les dossiers en une passe : parcours en parallèle puis une seule lecture
+        // parallèle des métadonnées (un dossier lent ne bloque plus les suivants)
+        let tracks = scan_folders_with_metadata_internal(&library_paths, force_full, &mut scan_issues, Some(&app_handle));
+        // Déduplique par chemin de fichier + filtre les exclus
+        for track in tracks {
+            if exclude_problem_tracks && is_problem_track(&track.metadata) {
+                continue;
+            }
+            if seen_paths.insert(track.path.clone()) && !excluded_paths.contains(&track.path) {
+                all_tracks.push(track);
             }
         }
 
@@ -1963,6 +3160,18 @@ fn start_background_scan(app_handle: tauri::AppHandle) {
         // (déclenché par le bouton "Indexer" dans les settings ou après add_network_source)
         // afin de ne pas bloquer le mutex SMB au démarrage de l'application.
 
+        // Scan annulé : résultat partiel, TRACKS_CACHE n'est PAS écrasé
+        // (sinon les tracks non encore scannés disparaîtraient de la bibliothèque)
+        if SCAN_CANCELLED.load(std::sync::atomic::Ordering::SeqCst) {
+            #[cfg(debug_assertions)]
+            println!("[Scan] Cancelled after {:?}: {} tracks read", start.elapsed(), all_tracks.len());
+
+            let _ = app_handle.emit("scan_cancelled", ScanCancelled {
+                stats: calculate_library_stats(&all_tracks),
+            });
+            return;
+        }
+
         // Calcule les différences
         let new_tracks: std::collections::HashSet<String> =
             all_tracks.iter().map(|t| t.path.clone()).collect();
@@ -2032,6 +3241,20 @@ fn start_background_scan(app_handle: tauri::AppHandle) {
             removed_tracks: removed_count,
         });
 
+        // Rapport des fichiers à problème (liste vide = rien à signaler depuis le dernier scan)
+        scan_issues.retain(|issue| !excluded_paths.contains(&issue.path));
+        scan_issues.sort_by(|a, b| a.path.cmp(&b.path));
+        scan_issues.dedup_by(|a, b| a.path == b.path);
+        let _ = app_handle.emit("scan_issues", &scan_issues);
+
+        // Garde le cache d'images sous contrôle (covers générées pendant le scan)
+        prune_image_cache_internal(IMAGE_CACHE_AUTO_PRUNE_BYTES);
+        // Entrées des fichiers sortis de la bibliothèque. Pas si un dossier était inaccessible :
+        // ses tracks reviendront avec le disque, et leurs dates d'ajout doivent survivre.
+        if inaccessible_paths.is_empty() {
+            prune_metadata_cache_internal();
+        }
+
         // Lance l'enrichissement des genres en arrière-plan (async, post-scan)
         let app_clone = app_handle.clone();
         tauri::async_runtime::spawn(async move {
@@ -2040,6 +3263,19 @@ fn start_background_scan(app_handle: tauri::AppHandle) {
     });
 }
 
+// Rescan complet : relit les métadonnées de tous les fichiers (ignore les mtimes)
+#[tauri::command]
+fn force_full_rescan(app_handle: tauri::AppHandle) {
+    FORCE_FULL_RESCAN.store(true, std::sync::atomic::Ordering::SeqCst);
+    start_background_scan(app_handle);
+}
+
+// Annuler le scan en arrière-plan en cours
+#[tauri::command]
+fn cancel_scan() {
+    SCAN_CANCELLED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
 // Obtenir les statistiques de la bibliothèque actuelle
 #[tauri::command]
 fn get_library_stats() -> LibraryStats {
@@ -2050,6 +3286,184 @@ fn get_library_stats() -> LibraryStats {
     }
 }
 
+/// Chemins des tracks sans audio exploitable (durée ou sample rate nuls), triés
+#[tauri::command]
+fn get_problem_tracks() -> Vec<String> {
+    let mut paths: Vec<String> = if let Ok(cache) = TRACKS_CACHE.lock() {
+        cache.tracks.iter()
+            .filter(|t| is_problem_track(&t.metadata))
+            .map(|t| t.path.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    paths.sort();
+    paths
+}
+
+/// Retire automatiquement les tracks sans audio de la bibliothèque au prochain scan
+#[tauri::command]
+fn set_exclude_problem_tracks(enabled: bool) {
+    let mut config = load_config();
+    config.exclude_problem_tracks = enabled;
+    save_config(&config);
+}
+
+#[tauri::command]
+fn get_exclude_problem_tracks() -> bool {
+    load_config().exclude_problem_tracks
+}
+
+/// Tracks dont le BPM est dans [min, max], du plus lent au plus rapide
+#[tauri::command]
+fn get_tracks_by_bpm_range(min: f32, max: f32) -> Vec<TrackWithMetadata> {
+    let mut tracks: Vec<TrackWithMetadata> = if let Ok(cache) = TRACKS_CACHE.lock() {
+        cache.tracks.iter()
+            .filter(|t| t.metadata.bpm.is_some_and(|bpm| bpm >= min && bpm <= max))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    tracks.sort_by(|a, b| a.metadata.bpm.unwrap_or(0.0).total_cmp(&b.metadata.bpm.unwrap_or(0.0)));
+    tracks
+}
+
+/// Compositeurs de la bibliothèque avec leur nombre de tracks (ordre alphabétique)
+#[tauri::command]
+fn get_composers() -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if let Ok(cache) = TRACKS_CACHE.lock() {
+        for composer in cache.tracks.iter().filter_map(|t| t.metadata.composer.as_deref()) {
+            let composer = composer.trim();
+            if !composer.is_empty() {
+                *counts.entry(composer.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut composers: Vec<(String, usize)> = counts.into_iter().collect();
+    composers.sort_by_cached_key(|(name, _)| search::sort_key(name));
+    composers
+}
+
+// Album agrégé depuis ses tracks (vue albums sans envoyer toute la bibliothèque au frontend)
+#[derive(Serialize, Clone)]
+struct AlbumSummary {
+    artist: String,
+    album: String,
+    year: Option<u32>,
+    track_count: usize,
+    total_duration: f64,
+    genre: Option<String>,   // Genre le plus fréquent parmi les tracks
+    sample_path: String,     // Un track de l'album, pour get_cover
+}
+
+/// Albums de la bibliothèque, regroupés par (album artist ou artiste, album), triés par artiste puis année
+/// (clés de tri sans accents ni article initial : "The Cure" est rangé à C)
+#[tauri::command]
+fn get_albums() -> Vec<AlbumSummary> {
+    let aliases = artist_aliases_snapshot();
+    let mut albums: HashMap<(String, String), (AlbumSummary, HashMap<String, usize>)> = HashMap::new();
+    if let Ok(cache) = TRACKS_CACHE.lock() {
+        for track in &cache.tracks {
+            let meta = &track.metadata;
+            // Même regroupement que calculate_library_stats : une compilation reste un seul album
+            let artist = aliases.resolve(meta.album_artist.as_deref().unwrap_or(&meta.artist));
+            let (summary, genres) = albums
+                .entry((artist.to_string(), meta.album.clone()))
+                .or_insert_with(|| (AlbumSummary {
+                    artist: artist.to_string(),
+                    album: meta.album.clone(),
+                    year: None,
+                    track_count: 0,
+                    total_duration: 0.0,
+                    genre: None,
+                    sample_path: track.path.clone(),
+                }, HashMap::new()));
+            summary.track_count += 1;
+            summary.total_duration += meta.duration;
+            // Année la plus ancienne : une réédition partielle ne rajeunit pas l'album
+            summary.year = match (summary.year, meta.year) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            if let Some(genre) = meta.genre.as_deref() {
+                *genres.entry(genre.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut result: Vec<AlbumSummary> = albums.into_values()
+        .map(|(mut summary, genres)| {
+            summary.genre = genres.into_iter()
+                .max_by(|(a_name, a_count), (b_name, b_count)| a_count.cmp(b_count).then_with(|| b_name.cmp(a_name)))
+                .map(|(genre, _)| genre);
+            summary
+        })
+        .collect();
+    result.sort_by_cached_key(|a| (search::sort_key(&a.artist), a.year, search::sort_key(&a.album)));
+    result
+}
+
+/// Genres de la bibliothèque avec leur nombre de tracks (plus fréquents d'abord)
+#[tauri::command]
+fn get_genres() -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if let Ok(cache) = TRACKS_CACHE.lock() {
+        for genre in cache.tracks.iter().filter_map(|t| t.metadata.genre.as_deref()) {
+            // Normalisé à l'indexation, sauf dans les caches antérieurs à la normalisation
+            let genre = normalize_genre(genre);
+            if !genre.is_empty() {
+                *counts.entry(genre).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut genres: Vec<(String, usize)> = counts.into_iter().collect();
+    genres.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.to_lowercase().cmp(&b_name.to_lowercase()))
+    });
+    genres
+}
+
+/// Genre parent d'un sous-genre ("Hard Rock" → "Rock"), None pour un genre de premier niveau
+#[tauri::command]
+fn get_parent_genre(genre: String) -> Option<String> {
+    GENRE_PARENT_MAP.get(normalize_genre(&genre).as_str()).map(|p| p.to_string())
+}
+
+// Genre de premier niveau avec ses sous-genres présents dans la bibliothèque
+#[derive(Serialize, Clone)]
+struct GenreNode {
+    name: String,
+    track_count: usize,              // Tracks du parent lui-même + de tous ses sous-genres
+    children: Vec<(String, usize)>,  // Sous-genres et leur nombre de tracks
+}
+
+/// Genres de la bibliothèque regroupés sous leur genre parent (plus fréquents d'abord)
+#[tauri::command]
+fn get_genre_tree() -> Vec<GenreNode> {
+    let mut nodes: HashMap<String, GenreNode> = HashMap::new();
+    // get_genres est déjà trié : les enfants de chaque nœud héritent de cet ordre
+    for (genre, count) in get_genres() {
+        let parent = GENRE_PARENT_MAP.get(genre.as_str()).map(|p| p.to_string());
+        let name = parent.clone().unwrap_or_else(|| genre.clone());
+        let node = nodes.entry(name.clone()).or_insert_with(|| GenreNode {
+            name,
+            track_count: 0,
+            children: Vec::new(),
+        });
+        node.track_count += count;
+        if parent.is_some() {
+            node.children.push((genre, count));
+        }
+    }
+    let mut tree: Vec<GenreNode> = nodes.into_values().collect();
+    tree.sort_by(|a, b| {
+        b.track_count.cmp(&a.track_count).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    tree
+}
+
 /// Force l'enrichissement des genres (peut être appelé manuellement depuis le frontend)
 #[tauri::command]
 fn trigger_genre_enrichment(app_handle: tauri::AppHandle) {
@@ -2069,7 +3483,7 @@ fn reset_genre_enrichment(app_handle: tauri::AppHandle) {
         for track in cache.tracks.iter_mut() {
             // Reset uniquement les tracks qui ont été enrichies mais qui n'ont PAS de genre
             // (c'est-à-dire les échecs précédents)
-            if track.metadata.genre_enriched && track.metadata.genre.is_none() {
+            if track.metadata.genre_enriched && track.metadata.genre.is_none() && !track.metadata.genre_locked {
                 track.metadata.genre_enriched = false;
                 reset_count += 1;
             }
@@ -2080,7 +3494,7 @@ fn reset_genre_enrichment(app_handle: tauri::AppHandle) {
     // Reset dans METADATA_CACHE
     if let Ok(mut cache) = METADATA_CACHE.lock() {
         for (_, meta) in cache.entries.iter_mut() {
-            if meta.genre_enriched && meta.genre.is_none() {
+            if meta.genre_enriched && meta.genre.is_none() && !meta.genre_locked {
                 meta.genre_enriched = false;
             }
         }
@@ -2096,6 +3510,45 @@ fn reset_genre_enrichment(app_handle: tauri::AppHandle) {
     });
 }
 
+// Corrige le genre d'un album à la main (l'enrichissement Deezer/MusicBrainz se trompe parfois).
+// Le genre est verrouillé : ni reset_genre_enrichment ni l'enrichissement n'y toucheront.
+// Retourne le nombre de tracks modifiés.
+#[tauri::command]
+fn set_album_genre(artist: String, album: String, genre: String) -> Result<usize, String> {
+    let genre = normalize_genre(&genre);
+    if genre.is_empty() {
+        return Err("Genre cannot be empty".to_string());
+    }
+    let matches_album = |meta: &Metadata| {
+        meta.album == album && (meta.artist == artist || meta.album_artist.as_deref() == Some(artist.as_str()))
+    };
+    let lock_genre = |meta: &mut Metadata| {
+        meta.genre = Some(genre.clone());
+        meta.genre_enriched = true;
+        meta.genre_locked = true;
+    };
+
+    let mut updated = 0usize;
+    if let Ok(mut cache) = TRACKS_CACHE.lock() {
+        for track in cache.tracks.iter_mut().filter(|t| matches_album(&t.metadata)) {
+            lock_genre(&mut track.metadata);
+            updated += 1;
+        }
+        save_tracks_cache(&cache);
+    }
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        for meta in cache.entries.values_mut().filter(|m| matches_album(m)) {
+            lock_genre(meta);
+        }
+        save_metadata_cache_to_file(&cache);
+    }
+
+    if updated == 0 {
+        return Err(format!("Album not found: {} - {}", artist, album));
+    }
+    Ok(updated)
+}
+
 // Obtenir les métadonnées (depuis le cache mémoire ou lecture fichier)
 #[tauri::command]
 fn get_metadata(path: &str) -> Metadata {
@@ -2125,12 +3578,19 @@ fn get_metadata(path: &str) -> Metadata {
         year: None,
         genre: None,
         genre_enriched: false,
+        genre_locked: false,
         duration: 0.0,
         bit_depth: None,
         sample_rate: None,
         bitrate: None,
         codec: None,
         file_size: actual_file_size,
+        album_artist: None,
+        artists: Vec::new(),
+        composer: None,
+        compilation: false,
+        bpm: None,
+        initial_key: None,
     };
 
     if let Ok(tagged_file) = Probe::open(path).and_then(|p| p.read()) {
@@ -2141,17 +3601,7 @@ fn get_metadata(path: &str) -> Metadata {
         metadata.bitrate = properties.audio_bitrate();
 
         // Détermine le codec depuis le type de fichier
-        metadata.codec = Some(match tagged_file.file_type() {
-            lofty::FileType::Flac => "FLAC".to_string(),
-            lofty::FileType::Mpeg => "MP3".to_string(),
-            lofty::FileType::Mp4 => {
-                if metadata.bit_depth.is_some() { "ALAC".to_string() }
-                else { "AAC".to_string() }
-            }
-            lofty::FileType::Wav => "WAV".to_string(),
-            lofty::FileType::Aiff => "AIFF".to_string(),
-            _ => "Other".to_string(),
-        });
+        metadata.codec = Some(codec_name(tagged_file.file_type(), metadata.bit_depth));
 
         if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
             if let Some(title) = tag.title() {
@@ -2159,7 +3609,13 @@ fn get_metadata(path: &str) -> Metadata {
             }
             if let Some(artist) = tag.artist() {
                 metadata.artist = artist.to_string();
+                metadata.artists = split_artists(&artist);
             }
+            metadata.album_artist = tag.get_string(&lofty::ItemKey::AlbumArtist).map(|v| v.to_string());
+            metadata.composer = tag.get_string(&lofty::ItemKey::Composer).map(|v| v.to_string());
+            metadata.compilation = is_compilation(tag);
+            metadata.bpm = read_bpm(tag);
+            metadata.initial_key = tag.get_string(&lofty::ItemKey::InitialKey).map(|v| v.trim().to_string());
             if let Some(album) = tag.album() {
                 metadata.album = album.to_string();
             }
@@ -2210,6 +3666,67 @@ fn refresh_metadata(path: &str) -> Metadata {
     get_metadata(path)
 }
 
+// Modifications de tags : seuls les champs fournis (Some) sont écrits,
+// les autres restent intacts dans chaque fichier
+// (BPM / tonalité : write_metadata_batch uniquement, y compris pour un seul fichier)
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct PartialMetadata {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    year: Option<u32>,
+    track_number: Option<u32>,
+    genre: Option<String>,
+    bpm: Option<f32>,
+    initial_key: Option<String>,
+}
+
+impl PartialMetadata {
+    fn apply_to(&self, meta: &mut Metadata) {
+        if let Some(ref v) = self.title        { meta.title  = v.clone(); }
+        if let Some(ref v) = self.artist       { meta.artist = v.clone(); meta.artists = split_artists(v); }
+        if let Some(ref v) = self.album        { meta.album  = v.clone(); }
+        if let Some(ref v) = self.album_artist { meta.album_artist = Some(v.clone()); }
+        if let Some(v) = self.year             { meta.year   = Some(v); }
+        if let Some(v) = self.track_number     { meta.track  = v; }
+        if let Some(ref v) = self.genre        { meta.genre  = Some(v.clone()); }
+        if let Some(v) = self.bpm              { meta.bpm    = Some(v); }
+        if let Some(ref v) = self.initial_key  { meta.initial_key = Some(v.clone()); }
+    }
+}
+
+// Applique les modifications à TRACKS_CACHE + METADATA_CACHE (une seule sauvegarde par cache)
+fn update_cached_metadata(paths: &[String], changes: &PartialMetadata) {
+    if paths.is_empty() {
+        return;
+    }
+    let targets: std::collections::HashSet<&str> = paths.iter().map(|p| p.as_str()).collect();
+
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        // CRITIQUE : UPDATE l'entrée au lieu de la supprimer.
+        // La suppression créait une fenêtre où le background scan pouvait
+        // re-insérer les anciennes métadonnées depuis le fichier audio
+        // (avant que lofty n'ait écrit les nouveaux tags) — corruption permanente.
+        // L'update garantit que get_metadata_internal() retourne toujours
+        // les valeurs les plus récentes, et que scan_folder_with_metadata()
+        // ne réinsère pas de données stale (check !contains_key à l.1785).
+        for path in paths {
+            if let Some(entry) = cache.entries.get_mut(path) {
+                changes.apply_to(entry);
+            }
+        }
+        save_metadata_cache_to_file(&cache);
+    }
+    if let Ok(mut cache) = TRACKS_CACHE.lock() {
+        for track in cache.tracks.iter_mut().filter(|t| targets.contains(t.path.as_str())) {
+            changes.apply_to(&mut track.metadata);
+        }
+        save_tracks_cache(&cache);
+    }
+}
+
 // Écrire les métadonnées d'un fichier audio et invalider son cache
 // Supporte les fichiers locaux ET les fichiers réseau (SMB/NAS) :
 // pour SMB, le fichier est téléchargé → modifié → ré-uploadé
@@ -2228,50 +3745,123 @@ fn write_metadata(
     track_number: Option<u32>,
     genre: Option<String>,
 ) -> Result<(), String> {
-    let is_smb = path.starts_with("smb://");
+    let changes = PartialMetadata {
+        title,
+        artist,
+        album,
+        album_artist: None,
+        year,
+        track_number,
+        genre,
+        bpm: None,
+        initial_key: None,
+    };
 
     // ═══════════════════════════════════════════════════════════════════════
     // ÉTAPE 1 : Mettre à jour TRACKS_CACHE + METADATA_CACHE IMMÉDIATEMENT
     // Avant toute I/O fichier ou NAS — garantit la persistance même si
     // l'app quitte avant la fin de l'écriture NAS
     // ═══════════════════════════════════════════════════════════════════════
-    if let Ok(mut cache) = METADATA_CACHE.lock() {
-        // CRITIQUE : UPDATE l'entrée au lieu de la supprimer.
-        // La suppression créait une fenêtre où le background scan pouvait
-        // re-insérer les anciennes métadonnées depuis le fichier audio
-        // (avant que lofty n'ait écrit les nouveaux tags) — corruption permanente.
-        // L'update garantit que get_metadata_internal() retourne toujours
-        // les valeurs les plus récentes, et que scan_folder_with_metadata()
-        // ne réinsère pas de données stale (check !contains_key à l.1785).
-        if let Some(entry) = cache.entries.get_mut(&path) {
-            if let Some(ref v) = title        { entry.title  = v.clone(); }
-            if let Some(ref v) = artist       { entry.artist = v.clone(); }
-            if let Some(ref v) = album        { entry.album  = v.clone(); }
-            if let Some(v) = year             { entry.year   = Some(v); }
-            if let Some(v) = track_number     { entry.track  = v; }
-            if let Some(ref v) = genre        { entry.genre  = Some(v.clone()); }
+    update_cached_metadata(std::slice::from_ref(&path), &changes);
+
+    // ÉTAPE 2 : Écriture effective des tags dans le fichier
+    write_tags_to_file(&path, &changes)
+}
+
+// Nombre de fichiers entre deux événements de progression d'une édition groupée
+const METADATA_BATCH_PROGRESS_INTERVAL: usize = 10;
+
+#[derive(Serialize, Clone)]
+struct MetadataBatchProgress {
+    current: usize,
+    total: usize,
+}
+
+// Écrire les mêmes modifications de tags sur plusieurs fichiers (ex: un album entier)
+// Écritures en parallèle avec Rayon, puis mise à jour des caches pour les fichiers réussis.
+// Retourne le nombre de fichiers effectivement modifiés.
+#[tauri::command]
+async fn write_metadata_batch(app_handle: tauri::AppHandle, paths: Vec<String>, changes: PartialMetadata) -> usize {
+    tokio::task::spawn_blocking(move || {
+        use tauri::Emitter;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let total = paths.len();
+        let processed = AtomicUsize::new(0);
+
+        let updated: Vec<String> = paths.par_iter()
+            .filter_map(|path| {
+                let result = write_tags_to_file(path, &changes);
+
+                let current = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                if total > METADATA_BATCH_PROGRESS_INTERVAL
+                    && (current.is_multiple_of(METADATA_BATCH_PROGRESS_INTERVAL) || current == total)
+                {
+                    let _ = app_handle.emit("metadata_batch_progress", MetadataBatchProgress { current, total });
+                }
+
+                match result {
+                    Ok(()) => Some(path.clone()),
+                    Err(_e) => {
+                        #[cfg(debug_assertions)]
+                        println!("[write_metadata_batch] {} : {}", path, _e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        update_cached_metadata(&updated, &changes);
+        updated.len()
+    })
+    .await
+    .unwrap_or(0)
+}
+
+// Écriture effective des tags dans le fichier
+fn write_tags_to_file(path: &str, changes: &PartialMetadata) -> Result<(), String> {
+    edit_file_tags(path, |tag| {
+        if let Some(ref v) = changes.title        { tag.set_title(v.clone()); }
+        if let Some(ref v) = changes.artist       { tag.set_artist(v.clone()); }
+        if let Some(ref v) = changes.album        { tag.set_album(v.clone()); }
+        if let Some(ref v) = changes.album_artist { tag.insert_text(lofty::ItemKey::AlbumArtist, v.clone()); }
+        if let Some(v) = changes.year             { tag.set_year(v); }
+        if let Some(v) = changes.track_number     { tag.set_track(v); }
+        if let Some(ref v) = changes.genre        { tag.set_genre(v.clone()); }
+        if let Some(v) = changes.bpm {
+            // Chaque format ne connaît qu'une des deux clés, l'autre est ignorée par lofty
+            tag.insert_text(lofty::ItemKey::Bpm, v.to_string());
+            tag.insert_text(lofty::ItemKey::IntegerBpm, (v.round() as u32).to_string());
         }
-        save_metadata_cache_to_file(&cache);
+        if let Some(ref v) = changes.initial_key  { tag.insert_text(lofty::ItemKey::InitialKey, v.clone()); }
+        write_rating_tag(tag, RATINGS.lock().map(|r| r.get(path)).unwrap_or(0));
+    })
+}
+
+// Note du track (ratings.json) : POPM en ID3v2, champ RATING 0–100 (Vorbis, APE) ailleurs.
+// Sans note, le tag existant est laissé tel quel
+fn write_rating_tag(tag: &mut lofty::Tag, stars: u8) {
+    if stars == 0 {
+        return;
     }
-    if let Ok(mut cache) = TRACKS_CACHE.lock() {
-        if let Some(track) = cache.tracks.iter_mut().find(|t| t.path == path) {
-            if let Some(ref v) = title        { track.metadata.title  = v.clone(); }
-            if let Some(ref v) = artist       { track.metadata.artist = v.clone(); }
-            if let Some(ref v) = album        { track.metadata.album  = v.clone(); }
-            if let Some(v) = year             { track.metadata.year   = Some(v); }
-            if let Some(v) = track_number     { track.metadata.track  = v; }
-            if let Some(ref v) = genre        { track.metadata.genre  = Some(v.clone()); }
-        }
-        save_tracks_cache(&cache);
+    if tag.tag_type() == TagType::Id3v2 {
+        // Email, octet de note, compteur omis
+        let mut popm = b"Windows Media Player 9 Series\0".to_vec();
+        popm.push(ratings::popm_rating_byte(stars));
+        tag.insert(lofty::TagItem::new(lofty::ItemKey::Popularimeter, lofty::ItemValue::Binary(popm)));
+    } else {
+        tag.insert_text(lofty::ItemKey::Unknown("RATING".to_string()), (stars as u32 * 20).to_string());
     }
+}
+
+// Ouvre le tag principal du fichier (créé si absent), applique `edit` et sauvegarde
+// Pour SMB : download → modify → upload (peut être lent)
+// Pour local : modification directe sur disque
+fn edit_file_tags(path: &str, edit: impl FnOnce(&mut lofty::Tag)) -> Result<(), String> {
+    let is_smb = path.starts_with("smb://");
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // ÉTAPE 2 : Écriture effective des tags dans le fichier
-    // Pour SMB : download → modify → upload (peut être lent)
-    // Pour local : modification directe sur disque
-    // ═══════════════════════════════════════════════════════════════════════
     let local_path = if is_smb {
-        let (source_id, share, remote_path) = parse_smb_uri(&path)
+        let (source_id, share, remote_path) = parse_smb_uri(path)
             .ok_or_else(|| format!("Invalid SMB URI: {}", path))?;
 
         // Récupérer les credentials et les stocker pour ensure_connection
@@ -2315,7 +3905,8 @@ fn write_metadata(
         };
 
         // Écrire dans un fichier temporaire
-        let temp_path = std::env::temp_dir().join(format!("noir_meta_{}.{}", std::process::id(), ext));
+        // Nom unique par fichier : les écritures groupées tournent en parallèle
+        let temp_path = std::env::temp_dir().join(format!("noir_meta_{}_{:x}.{}", std::process::id(), h.finish(), ext));
         std::fs::write(&temp_path, &data)
             .map_err(|e| format!("Cannot write temp file: {}", e))?;
 
@@ -2335,7 +3926,7 @@ fn write_metadata(
         if !is_in_library {
             return Err("Security: file is not within any configured library path".to_string());
         }
-        path.clone()
+        path.to_string()
     };
 
     // Ouvrir et modifier les tags sur le fichier local (ou temp)
@@ -2362,19 +3953,14 @@ fn write_metadata(
             .ok_or_else(|| "No tag found in this file".to_string())?
     };
 
-    if let Some(ref v) = title        { tag.set_title(v.clone()); }
-    if let Some(ref v) = artist       { tag.set_artist(v.clone()); }
-    if let Some(ref v) = album        { tag.set_album(v.clone()); }
-    if let Some(v) = year             { tag.set_year(v); }
-    if let Some(v) = track_number     { tag.set_track(v); }
-    if let Some(ref v) = genre        { tag.set_genre(v.clone()); }
+    edit(tag);
 
     tag.save_to_path(&local_path)
         .map_err(|e| format!("Error saving tags: {}", e))?;
 
     // Pour SMB : ré-uploader le fichier modifié vers le NAS
     if is_smb {
-        let (source_id, share, remote_path) = parse_smb_uri(&path).unwrap();
+        let (source_id, share, remote_path) = parse_smb_uri(path).unwrap();
         let source = {
             let sources = NETWORK_SOURCES.lock().map_err(|e| e.to_string())?;
             sources.iter().find(|s| s.id == source_id).cloned()
@@ -2428,6 +4014,36 @@ fn get_added_dates() -> HashMap<String, u64> {
     }
 }
 
+/// Derniers ajouts : les `limit` tracks les plus récemment ajoutés, regroupés par album
+/// (un album prend la place de son track le plus récent, ses tracks dans l'ordre disque/piste)
+#[tauri::command]
+fn get_recently_added(limit: usize) -> Vec<TrackWithMetadata> {
+    let added_dates = get_added_dates();
+    let mut tracks: Vec<(u64, TrackWithMetadata)> = if let Ok(cache) = TRACKS_CACHE.lock() {
+        cache.tracks.iter()
+            .filter_map(|t| added_dates.get(&t.path).map(|&added| (added, t.clone())))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    tracks.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+
+    // Rang de chaque album = rang de son track le plus récent
+    let album_key = |t: &TrackWithMetadata| (
+        t.metadata.album_artist.clone().unwrap_or_else(|| t.metadata.artist.clone()),
+        t.metadata.album.clone(),
+    );
+    let mut album_rank: HashMap<(String, String), usize> = HashMap::new();
+    for (_, track) in &tracks {
+        let next_rank = album_rank.len();
+        album_rank.entry(album_key(track)).or_insert(next_rank);
+    }
+    let mut tracks: Vec<TrackWithMetadata> = tracks.into_iter().map(|(_, track)| track).collect();
+    tracks.sort_by_cached_key(|t| (album_rank[&album_key(t)], t.metadata.disc.unwrap_or(1), t.metadata.track));
+    tracks.truncate(limit);
+    tracks
+}
+
 /// Parse un URI SMB en (source_id, share, remote_path)
 /// Format : smb://{source_id}/{share}/{remote_path}
 fn parse_smb_uri(uri: &str) -> Option<(String, String, String)> {
@@ -2491,9 +4107,17 @@ fn get_cover_smb(smb_path: &str) -> Option<String> {
     Some(format!("noir://localhost/covers/{}", filename))
 }
 
-// Obtenir la pochette (depuis le cache ou lecture fichier)
+// Obtenir la pochette (depuis le cache ou lecture fichier). Hors du thread principal :
+// les demandes simultanées de la vue albums s'exécutent en parallèle (et se coalescent)
 #[tauri::command]
-fn get_cover(path: &str) -> Option<String> {
+async fn get_cover(path: String) -> Option<String> {
+    tokio::task::spawn_blocking(move || get_cover_internal(&path)).await.ok().flatten()
+}
+
+fn get_cover_internal(path: &str) -> Option<String> {
+    // Piste CUE : pochette du fichier réel
+    let path = cue::split_virtual_path(path).map_or(path, |(file, _)| file);
+
     // Délègue aux fonctions SMB pour les paths réseau
     if path.starts_with("smb://") {
         return get_cover_smb(path);
@@ -2524,7 +4148,13 @@ fn get_cover(path: &str) -> Option<String> {
         }
     }
 
-    // Pas en cache, lit depuis le fichier audio
+    // Pas en cache : une seule extraction par fichier, les appels concurrents
+    // (grille d'albums qui défile) attendent son résultat
+    COVER_EXTRACTIONS.run(path, || extract_cover(path, start))
+}
+
+// Lit la pochette depuis le fichier audio (ou le dossier) et la copie dans le cache disque
+fn extract_cover(path: &str, start: std::time::Instant) -> Option<String> {
     let probe_start = std::time::Instant::now();
     if let Ok(tagged_file) = Probe::open(path).and_then(|p| p.read()) {
         if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
@@ -2571,6 +4201,12 @@ fn get_cover(path: &str) -> Option<String> {
         }
     }
 
+    // Pas de pochette embarquée : cover.jpg / folder.jpg dans le dossier de l'album
+    if let Some(cache_file) = cache_folder_cover(path) {
+        let filename = cache_file.file_name()?.to_str()?;
+        return Some(format!("noir://localhost/covers/{}", filename));
+    }
+
     let elapsed = start.elapsed().as_millis();
     if elapsed > 50 {
         #[cfg(debug_assertions)]
@@ -2579,8 +4215,56 @@ fn get_cover(path: &str) -> Option<String> {
     None
 }
 
+// Noms de pochettes cherchés à côté des fichiers audio (par ordre de priorité)
+const FOLDER_COVER_NAMES: [&str; 4] = ["cover", "folder", "front", "album"];
+
+// Cherche une pochette dans le dossier du track (insensible à la casse, .jpg/.jpeg/.png)
+fn find_folder_cover(path: &str) -> Option<PathBuf> {
+    let dir = Path::new(path).parent()?;
+    let candidates: Vec<PathBuf> = fs::read_dir(dir).ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+
+    FOLDER_COVER_NAMES.iter().find_map(|name| {
+        candidates.iter().find(|candidate| {
+            let stem = candidate.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            let ext = candidate.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+            stem.eq_ignore_ascii_case(name) && matches!(ext.as_str(), "jpg" | "jpeg" | "png")
+        }).cloned()
+    })
+}
+
+// Copie la pochette du dossier dans le cache covers (comme une pochette embarquée)
+fn cache_folder_cover(path: &str) -> Option<PathBuf> {
+    let folder_cover = find_folder_cover(path)?;
+    let data = fs::read(&folder_cover).ok()?;
+
+    let cover_dir = get_cover_cache_dir();
+    fs::create_dir_all(&cover_dir).ok();
+
+    let hash = format!("{:x}", md5_hash(path));
+    let ext = if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) { "png" } else { "jpg" };
+    let cache_file = cover_dir.join(format!("{}.{}", hash, ext));
+
+    fs::write(&cache_file, &data).ok()?;
+    if let Ok(mut cache) = COVER_CACHE.lock() {
+        cache.entries.insert(path.to_string(), cache_file.to_string_lossy().to_string());
+    }
+    if let Ok(mut dirty) = CACHE_DIRTY.lock() {
+        *dirty = true;
+    }
+
+    #[cfg(debug_assertions)]
+    println!("[Cover] Folder cover {} for {}", folder_cover.display(), path.rsplit('/').next().unwrap_or(path));
+
+    Some(cache_file)
+}
+
 // Obtenir les bytes bruts de la pochette (pour génération thumbnail)
 fn get_cover_bytes_internal(path: &str) -> Option<Vec<u8>> {
+    let path = cue::split_virtual_path(path).map_or(path, |(file, _)| file);
     // Vérifie le cache mémoire des pochettes
     let cached_file = {
         if let Ok(cache) = COVER_CACHE.lock() {
@@ -2611,7 +4295,10 @@ fn get_cover_bytes_internal(path: &str) -> Option<Vec<u8>> {
             }
         }
     }
-    None
+
+    // Fallback : pochette dans le dossier de l'album
+    let cache_file = cache_folder_cover(path)?;
+    fs::read(cache_file).ok()
 }
 
 // Retourne la pochette en base64 data URI (pour extraction de couleurs côté JS)
@@ -2628,23 +4315,195 @@ fn get_cover_base64(path: &str) -> Option<String> {
     Some(format!("data:{};base64,{}", mime, b64))
 }
 
-// Génère un thumbnail 150x150 en JPEG (plus rapide que WebP)
-fn generate_thumbnail(source_data: &[u8], thumb_path: &Path) -> Result<(), String> {
-    // 1. Décoder l'image source
+// === POCHETTES PERSONNALISÉES ===
+
+// Préfixe des images choisies par l'utilisateur dans covers/ (jamais purgées automatiquement)
+const CUSTOM_IMAGE_PREFIX: &str = "custom_";
+
+// Côté max d'une pochette personnalisée (au-delà, l'image est réduite)
+const CUSTOM_COVER_MAX_DIMENSION: u32 = 2000;
+
+fn is_custom_image(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(CUSTOM_IMAGE_PREFIX))
+}
+
+// Décode une image (JPEG, PNG, WebP…) et la réencode en JPEG, réduite à max_dimension si besoin
+fn encode_cover_jpeg(source_data: &[u8], max_dimension: u32) -> Result<Vec<u8>, String> {
     let img = image::load_from_memory(source_data)
         .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let img = if img.width() > max_dimension || img.height() > max_dimension {
+        img.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+    } else {
+        img
+    };
 
-    // 2. Redimensionner à 150x150 - Triangle est 10x plus rapide que Lanczos3
-    let thumbnail = img.resize_to_fill(150, 150, FilterType::Triangle);
-
-    // 3. Encoder en JPEG qualité 80% (beaucoup plus rapide que WebP)
+    // JPEG sans canal alpha
     let mut buffer = Vec::new();
-    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, 80);
-    thumbnail.write_with_encoder(encoder)
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, 90);
+    image::DynamicImage::ImageRgb8(img.to_rgb8())
+        .write_with_encoder(encoder)
         .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    Ok(buffer)
+}
 
-    // 4. Sauvegarder
-    if let Some(parent) = thumb_path.parent() {
+// Remplace la pochette d'un track par une image choisie par l'utilisateur.
+// COVER_CACHE est consulté avant les tags et Internet : l'override gagne toujours.
+#[tauri::command]
+fn set_custom_cover(path: String, image_path: String) -> Result<String, String> {
+    let path = cue::split_virtual_path(&path).map_or(path.as_str(), |(file, _)| file).to_string();
+    let data = fs::read(&image_path).map_err(|e| format!("Cannot read image {}: {}", image_path, e))?;
+    let jpeg = encode_cover_jpeg(&data, CUSTOM_COVER_MAX_DIMENSION)?;
+
+    let cover_dir = get_cover_cache_dir();
+    fs::create_dir_all(&cover_dir).map_err(|e| format!("Cannot create covers directory: {}", e))?;
+    let hash = format!("{:x}", md5_hash(&path));
+    let filename = format!("{}{}.jpg", CUSTOM_IMAGE_PREFIX, hash);
+    let cache_file = cover_dir.join(&filename);
+    fs::write(&cache_file, jpeg).map_err(|e| format!("Cannot write cover: {}", e))?;
+
+    // Persisté tout de suite : l'override doit survivre à un crash
+    if let Ok(mut cache) = COVER_CACHE.lock() {
+        cache.entries.insert(path, cache_file.to_string_lossy().to_string());
+        save_cover_cache_to_file(&cache);
+    }
+
+    // L'ancien thumbnail sera régénéré depuis la nouvelle pochette
+    let thumb_dir = get_thumbnail_cache_dir();
+    fs::remove_file(thumb_dir.join(format!("{}_thumb.jpg", hash))).ok();
+    fs::remove_file(thumb_dir.join(format!("{}_thumb.webp", hash))).ok();
+
+    Ok(format!("noir://localhost/covers/{}", filename))
+}
+
+// Remplace la photo d'un artiste (prioritaire sur Deezer / MusicBrainz via ARTIST_IMAGE_CACHE)
+#[tauri::command]
+fn set_custom_artist_image(artist: String, image_path: String) -> Result<String, String> {
+    let data = fs::read(&image_path).map_err(|e| format!("Cannot read image {}: {}", image_path, e))?;
+    let jpeg = encode_cover_jpeg(&data, CUSTOM_COVER_MAX_DIMENSION)?;
+
+    let artist_key = format!("artist|||{}", artist.to_lowercase());
+    let cover_dir = get_cover_cache_dir();
+    fs::create_dir_all(&cover_dir).map_err(|e| format!("Cannot create covers directory: {}", e))?;
+    let filename = format!("{}artist_{:x}.jpg", CUSTOM_IMAGE_PREFIX, md5_hash(&artist_key));
+    fs::write(cover_dir.join(&filename), jpeg).map_err(|e| format!("Cannot write artist image: {}", e))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if let Ok(mut cache) = ARTIST_IMAGE_CACHE.lock() {
+        cache.entries.insert(artist_key, ArtistImageEntry {
+            filename: Some(filename.clone()),
+            checked_at: now,
+        });
+        save_artist_image_cache(&cache);
+    }
+
+    Ok(format!("noir://localhost/covers/{}", filename))
+}
+
+// Côté max d'une pochette embarquée dans les fichiers audio (évite de gonfler chaque track)
+const EMBEDDED_COVER_MAX_DIMENSION: u32 = 1000;
+
+// Image source d'embed_cover : URL noir://localhost/covers/… (ex. résultat de
+// fetch_internet_cover) ou chemin de fichier
+fn resolve_cover_source(cover_path: &str) -> Option<PathBuf> {
+    match cover_path.strip_prefix("noir://localhost/covers/") {
+        Some(filename) if filename.contains('/') || filename.contains("..") => None,
+        Some(filename) => Some(get_cover_cache_dir().join(filename)),
+        None => Some(PathBuf::from(cover_path)),
+    }
+}
+
+// Écrit une pochette (front cover, JPEG ≤ 1000 px) dans les tags de chaque fichier.
+// Les pochettes en cache pointent ensuite vers l'image embarquée.
+// Retourne le nombre de fichiers effectivement modifiés.
+#[tauri::command]
+async fn embed_cover(paths: Vec<String>, cover_path: String) -> usize {
+    tokio::task::spawn_blocking(move || {
+        let Some(data) = resolve_cover_source(&cover_path).and_then(|source| fs::read(source).ok()) else {
+            return 0;
+        };
+        let jpeg = match encode_cover_jpeg(&data, EMBEDDED_COVER_MAX_DIMENSION) {
+            Ok(jpeg) => jpeg,
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                println!("[embed_cover] {} : {}", cover_path, _e);
+                return 0;
+            }
+        };
+
+        // Pistes CUE : la pochette va dans le fichier réel, une seule fois
+        let mut files: Vec<String> = paths.iter()
+            .map(|p| cue::split_virtual_path(p).map_or(p.as_str(), |(file, _)| file).to_string())
+            .collect();
+        files.sort();
+        files.dedup();
+
+        let updated: Vec<String> = files.par_iter()
+            .filter_map(|path| {
+                let result = edit_file_tags(path, |tag| {
+                    tag.remove_picture_type(lofty::PictureType::CoverFront);
+                    tag.push_picture(lofty::Picture::new_unchecked(
+                        lofty::PictureType::CoverFront,
+                        Some(MimeType::Jpeg),
+                        None,
+                        jpeg.clone(),
+                    ));
+                });
+                match result {
+                    Ok(()) => Some(path.clone()),
+                    Err(_e) => {
+                        #[cfg(debug_assertions)]
+                        println!("[embed_cover] {} : {}", path, _e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        // Même fichier que celui qu'extrairait get_cover ; les thumbnails seront régénérés
+        let cover_dir = get_cover_cache_dir();
+        let thumb_dir = get_thumbnail_cache_dir();
+        fs::create_dir_all(&cover_dir).ok();
+        if let Ok(mut cache) = COVER_CACHE.lock() {
+            for path in &updated {
+                let hash = format!("{:x}", md5_hash(path));
+                let cache_file = cover_dir.join(format!("{}.jpg", hash));
+                if fs::write(&cache_file, &jpeg).is_ok() {
+                    cache.entries.insert(path.clone(), cache_file.to_string_lossy().to_string());
+                }
+                fs::remove_file(thumb_dir.join(format!("{}_thumb.jpg", hash))).ok();
+                fs::remove_file(thumb_dir.join(format!("{}_thumb.webp", hash))).ok();
+            }
+            save_cover_cache_to_file(&cache);
+        }
+
+        updated.len()
+    })
+    .await
+    .unwrap_or(0)
+}
+
+// Génère un thumbnail 150x150 en JPEG (plus rapide que WebP)
+fn generate_thumbnail(source_data: &[u8], thumb_path: &Path) -> Result<(), String> {
+    // 1. Décoder l'image source
+    let img = image::load_from_memory(source_data)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    // 2. Redimensionner à 150x150 - Triangle est 10x plus rapide que Lanczos3
+    let thumbnail = img.resize_to_fill(150, 150, FilterType::Triangle);
+
+    // 3. Encoder en JPEG qualité 80% (beaucoup plus rapide que WebP)
+    let mut buffer = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, 80);
+    thumbnail.write_with_encoder(encoder)
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+
+    // 4. Sauvegarder
+    if let Some(parent) = thumb_path.parent() {
         fs::create_dir_all(parent).ok();
     }
     fs::write(thumb_path, buffer)
@@ -2778,13 +4637,28 @@ async fn fetch_internet_cover(artist: String, album: String) -> Option<String> {
         return Some(format!("noir://localhost/covers/internet_{}.jpg", hash));
     }
 
+    // Hors ligne ou fournisseur désactivé : pas de recherche (la pochette déjà téléchargée
+    // reste servie ci-dessus), et surtout pas de "not found" mémorisé 30 jours
+    if !provider_enabled(|p| p.musicbrainz && p.cover_art_archive) {
+        return None;
+    }
+
     // Recherche sur Internet (async)
-    if let Some(image_data) = fetch_cover_from_musicbrainz(&artist, &album).await {
-        // Sauvegarde dans le cache local
-        fs::create_dir_all(&cover_dir).ok();
-        if fs::write(&cache_file, &image_data).is_ok() {
-            // Retourne une URL noir:// au lieu de base64
-            return Some(format!("noir://localhost/covers/internet_{}.jpg", hash));
+    match fetch_cover_from_musicbrainz(&artist, &album).await {
+        Ok(Some(image_data)) => {
+            // Sauvegarde dans le cache local
+            fs::create_dir_all(&cover_dir).ok();
+            if fs::write(&cache_file, &image_data).is_ok() {
+                // Retourne une URL noir:// au lieu de base64
+                return Some(format!("noir://localhost/covers/internet_{}.jpg", hash));
+            }
+        }
+        Ok(None) => {}
+        // Échec réseau / serveur : réessayé au prochain affichage, pas de "not found"
+        Err(e) => {
+            #[cfg(debug_assertions)]
+            println!("[Cover] Lookup failed for {} - {}: {}", artist, album, e);
+            return None;
         }
     }
 
@@ -2800,72 +4674,157 @@ async fn fetch_internet_cover(artist: String, album: String) -> Option<String> {
     None
 }
 
-// Recherche une image d'artiste sur Internet (Deezer + MusicBrainz) - async
-// Fallback: utilise une pochette d'album Internet, puis pochette locale
-#[tauri::command]
-async fn fetch_artist_image(artist: String, fallback_album: Option<String>, fallback_cover_path: Option<String>) -> Option<String> {
+// Photo d'artiste depuis l'index, sinon Internet (Deezer, MusicBrainz, pochette d'album).
+// Retourne l'URL noir:// du fichier en cache. Une absence confirmée par toutes les sources
+// est mémorisée 7 jours ; un échec réseau est réessayé à la demande suivante.
+async fn resolve_artist_image(artist: &str, fallback_album: Option<&str>) -> Option<String> {
     // Clé unique pour cet artiste
     let artist_key = format!("artist|||{}", artist.to_lowercase());
+    let cover_dir = get_cover_cache_dir();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 
-    // PAS DE CACHE "NOT FOUND" - on réessaie toujours car Deezer est rapide
+    let indexed = ARTIST_IMAGE_CACHE.lock().ok().and_then(|cache| cache.entries.get(&artist_key).cloned());
+    match indexed {
+        // Fichier encore présent (le prune du cache d'images peut l'avoir supprimé)
+        Some(ArtistImageEntry { filename: Some(filename), .. }) if cover_dir.join(&filename).exists() => {
+            return Some(format!("noir://localhost/covers/{}", filename));
+        }
+        Some(ArtistImageEntry { filename: None, checked_at })
+            if now.saturating_sub(checked_at) < ARTIST_IMAGE_NOT_FOUND_TTL_SECS =>
+        {
+            return None; // Encore dans le TTL → on ne refait pas la recherche
+        }
+        _ => {}
+    }
 
-    // Vérifie si déjà en cache local (photo d'artiste téléchargée)
-    let cover_dir = get_cover_cache_dir();
     let hash = format!("{:x}", md5_hash(&artist_key));
-    let cache_file = cover_dir.join(format!("artist_{}.jpg", hash));
-
-    if cache_file.exists() {
-        // Vérifie que le fichier n'est pas vide/corrompu (check taille via metadata)
-        if let Ok(meta) = fs::metadata(&cache_file) {
-            if meta.len() > 1000 {
-                // Retourne une URL noir:// au lieu de base64
-                return Some(format!("noir://localhost/covers/artist_{}.jpg", hash));
+    let filename = format!("artist_{}.jpg", hash);
+    let cache_file = cover_dir.join(&filename);
+
+    // Photo téléchargée avant l'index : on la réindexe simplement
+    let already_on_disk = fs::metadata(&cache_file).map(|meta| meta.len() > 1000).unwrap_or(false);
+    // Un échec avec un fournisseur désactivé n'est pas mémorisé (réessayé une fois réactivé)
+    let all_providers = provider_enabled(|p| p.deezer && p.musicbrainz);
+    let mut lookup_failed = false;
+    let found = already_on_disk || {
+        let mut keep = |lookup: ImageLookup| lookup.unwrap_or_else(|_| {
+            lookup_failed = true;
+            None
+        });
+        // 1. Priorité: Deezer (a beaucoup de photos d'artistes)
+        // 2. Fallback: MusicBrainz + Wikimedia (moins de photos mais plus précis)
+        // 3. Fallback: pochette d'album depuis Internet (MusicBrainz), sauvegardée comme image artiste
+        let mut image_data = keep(fetch_artist_image_from_deezer(artist).await);
+        if image_data.is_none() {
+            image_data = keep(fetch_artist_image_from_musicbrainz(artist).await);
+        }
+        if image_data.is_none() {
+            if let Some(album) = fallback_album {
+                image_data = keep(fetch_cover_from_musicbrainz(artist, album).await);
             }
         }
-    }
+        image_data.is_some_and(|data| {
+            fs::create_dir_all(&cover_dir).ok();
+            fs::write(&cache_file, &data).is_ok()
+        })
+    };
 
-    // 1. Priorité: Deezer (a beaucoup de photos d'artistes) - async
-    if let Some(image_data) = fetch_artist_image_from_deezer(&artist).await {
-        // Sauvegarde dans le cache local
-        fs::create_dir_all(&cover_dir).ok();
-        if fs::write(&cache_file, &image_data).is_ok() {
-            // Retourne une URL noir:// au lieu de base64
-            return Some(format!("noir://localhost/covers/artist_{}.jpg", hash));
+    if found || (all_providers && !lookup_failed) {
+        if let Ok(mut cache) = ARTIST_IMAGE_CACHE.lock() {
+            cache.entries.insert(artist_key, ArtistImageEntry {
+                filename: found.then(|| filename.clone()),
+                checked_at: now,
+            });
+        }
+        if let Ok(mut dirty) = CACHE_DIRTY.lock() {
+            *dirty = true;
         }
     }
 
-    // 2. Fallback: MusicBrainz + Wikimedia (moins de photos mais plus précis) - async
-    if let Some(image_data) = fetch_artist_image_from_musicbrainz(&artist).await {
-        // Sauvegarde dans le cache local
-        fs::create_dir_all(&cover_dir).ok();
-        if fs::write(&cache_file, &image_data).is_ok() {
-            // Retourne une URL noir:// au lieu de base64
-            return Some(format!("noir://localhost/covers/artist_{}.jpg", hash));
-        }
+    found.then(|| format!("noir://localhost/covers/{}", filename))
+}
+
+// Recherche une image d'artiste sur Internet (Deezer + MusicBrainz) - async
+// Fallback: utilise une pochette d'album Internet, puis pochette locale
+#[tauri::command]
+async fn fetch_artist_image(artist: String, fallback_album: Option<String>, fallback_cover_path: Option<String>) -> Option<String> {
+    // Hors ligne : photo personnalisée / déjà indexée, puis pochette locale ; seuls les
+    // appels Deezer / MusicBrainz sont coupés (provider_enabled)
+    if let Some(url) = resolve_artist_image(&artist, fallback_album.as_deref()).await {
+        return Some(url);
     }
 
-    // 3. Fallback: pochette d'album depuis Internet (MusicBrainz) - async
-    if let Some(album) = &fallback_album {
-        if let Some(image_data) = fetch_cover_from_musicbrainz(&artist, album).await {
-            // Sauvegarde comme image artiste (fallback)
-            fs::create_dir_all(&cover_dir).ok();
-            if fs::write(&cache_file, &image_data).is_ok() {
-                // Retourne une URL noir:// au lieu de base64
-                return Some(format!("noir://localhost/covers/artist_{}.jpg", hash));
-            }
-        }
+    // Dernier fallback: pochette locale (déjà en cache depuis le fichier audio), jamais indexée
+    fallback_cover_path.and_then(|cover_path| get_cover_internal(&cover_path))
+}
+
+#[derive(Clone, Serialize)]
+struct ArtistImagesProgress {
+    current: usize,
+    total: usize,
+    found: usize,
+}
+
+// Précharge les photos d'une liste d'artistes (vue artistes) avec une concurrence bornée.
+// Émet artist_images_progress après chaque artiste, puis artist_images_complete.
+#[tauri::command]
+async fn prefetch_artist_images(app_handle: tauri::AppHandle, artists: Vec<String>) {
+    use tauri::Emitter;
+
+    if !is_online_enabled() {
+        return;
     }
 
-    // 4. Dernier fallback: pochette locale (déjà en cache depuis le fichier audio)
-    if let Some(cover_path) = fallback_cover_path {
-        // Essaie de récupérer la pochette depuis le cache local ou le fichier audio
-        if let Some(cover) = get_cover(&cover_path) {
-            return Some(cover);
-        }
+    let mut seen = std::collections::HashSet::new();
+    let queue: std::collections::VecDeque<String> = artists
+        .into_iter()
+        .filter(|artist| seen.insert(artist.to_lowercase()))
+        .collect();
+    let total = queue.len();
+    let queue = Arc::new(Mutex::new(queue));
+    let current = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let found = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let workers: Vec<_> = (0..ARTIST_PREFETCH_CONCURRENCY.min(total))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let current = Arc::clone(&current);
+            let found = Arc::clone(&found);
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                // Le verrou est relâché dans la closure, avant l'await
+                let next_artist = || queue.lock().ok().and_then(|mut q| q.pop_front());
+                while let Some(artist) = next_artist() {
+                    if resolve_artist_image(&artist, None).await.is_some() {
+                        found.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    let _ = app_handle.emit("artist_images_progress", ArtistImagesProgress {
+                        current: current.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1,
+                        total,
+                        found: found.load(std::sync::atomic::Ordering::Relaxed),
+                    });
+                    // Rate limit : 50ms entre deux artistes par worker (comme l'enrichissement des genres)
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        let _ = worker.await;
     }
 
-    // PAS DE MARQUAGE "NOT FOUND" - permet de réessayer à chaque ouverture
-    None
+    if let Ok(cache) = ARTIST_IMAGE_CACHE.lock() {
+        save_artist_image_cache(&cache);
+    }
+    let _ = app_handle.emit("artist_images_complete", ArtistImagesProgress {
+        current: total,
+        total,
+        found: found.load(std::sync::atomic::Ordering::Relaxed),
+    });
 }
 
 // Vider le cache
@@ -2881,6 +4840,9 @@ fn clear_cache() {
     if let Ok(mut cache) = INTERNET_NOT_FOUND_CACHE.lock() {
         cache.entries.clear();
     }
+    if let Ok(mut cache) = ARTIST_IMAGE_CACHE.lock() {
+        cache.entries.clear();
+    }
 
     // Supprime les fichiers sur disque
     let metadata_path = get_metadata_cache_path();
@@ -2891,17 +4853,137 @@ fn clear_cache() {
     fs::remove_file(metadata_path).ok();
     fs::remove_file(cover_cache_path).ok();
     fs::remove_file(internet_not_found_path).ok();
+    fs::remove_file(get_data_dir().join("artist_image_cache.json")).ok();
     fs::remove_dir_all(cover_dir).ok();
 }
 
-// Ajouter un chemin à la bibliothèque
+// === CACHE D'IMAGES SUR DISQUE (covers + thumbnails) ===
+
+// Taille max appliquée automatiquement après chaque scan (2 Go)
+const IMAGE_CACHE_AUTO_PRUNE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+// Fichiers des dossiers covers/ et thumbnails/ avec taille et date de dernier accès (ou mtime)
+fn list_image_cache_files() -> Vec<(PathBuf, u64, SystemTime)> {
+    [get_cover_cache_dir(), get_thumbnail_cache_dir()]
+        .iter()
+        .flat_map(|dir| WalkDir::new(dir).into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            let last_used = meta.accessed().or_else(|_| meta.modified()).unwrap_or(UNIX_EPOCH);
+            Some((e.into_path(), meta.len(), last_used))
+        })
+        .collect()
+}
+
+// Supprime les images les moins récemment utilisées jusqu'à passer sous max_bytes
+fn prune_image_cache_internal(max_bytes: u64) -> u64 {
+    let mut files = list_image_cache_files();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return 0;
+    }
+
+    files.sort_by_key(|(_, _, last_used)| *last_used);
+
+    let mut freed = 0u64;
+    let mut removed: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for (path, size, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        // Les images choisies par l'utilisateur ne peuvent pas être ré-extraites
+        if is_custom_image(&path) {
+            continue;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= size;
+            freed += size;
+            removed.insert(path.to_string_lossy().to_string());
+        }
+    }
+
+    // Les entrées COVER_CACHE pointant vers une pochette supprimée seront ré-extraites
+    if let Ok(mut cache) = COVER_CACHE.lock() {
+        cache.entries.retain(|_, cover_file| !removed.contains(cover_file));
+        save_cover_cache_to_file(&cache);
+    }
+
+    #[cfg(debug_assertions)]
+    println!("[ImageCache] Pruned {} files, {} bytes freed", removed.len(), freed);
+
+    freed
+}
+
+// Réduire le cache d'images à max_bytes (retourne les octets libérés)
+#[tauri::command]
+fn prune_image_cache(max_bytes: u64) -> u64 {
+    prune_image_cache_internal(max_bytes)
+}
+
+// Retire de METADATA_CACHE et ADDED_DATES_CACHE les chemins absents de TRACKS_CACHE
+// (fichiers sortis de la bibliothèque). Retourne le nombre d'entrées supprimées.
+fn prune_metadata_cache_internal() -> usize {
+    let library: std::collections::HashSet<String> = match TRACKS_CACHE.lock() {
+        Ok(cache) if !cache.tracks.is_empty() => cache.tracks.iter()
+            .flat_map(|t| {
+                // Piste CUE : le fichier audio garde son entrée (sinon relu à chaque scan)
+                let file = cue::split_virtual_path(&t.path).map(|(file, _)| file.to_string());
+                std::iter::once(t.path.clone()).chain(file)
+            })
+            .collect(),
+        _ => return 0,
+    };
+
+    let mut removed = 0;
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        let before = cache.entries.len();
+        cache.entries.retain(|path, _| library.contains(path));
+        if cache.entries.len() < before {
+            removed += before - cache.entries.len();
+            save_metadata_cache_to_file(&cache);
+        }
+    }
+    if let Ok(mut cache) = ADDED_DATES_CACHE.lock() {
+        let before = cache.entries.len();
+        cache.entries.retain(|path, _| library.contains(path));
+        if cache.entries.len() < before {
+            removed += before - cache.entries.len();
+            save_added_dates_cache(&cache);
+        }
+    }
+    #[cfg(debug_assertions)]
+    println!("[Cache] Pruned {} stale metadata/added-date entries", removed);
+    removed
+}
+
+#[tauri::command]
+fn prune_metadata_cache() -> usize {
+    prune_metadata_cache_internal()
+}
+
+// Taille actuelle du cache d'images sur disque (pour les settings)
 #[tauri::command]
-fn add_library_path(path: &str) {
+fn get_image_cache_size() -> u64 {
+    list_image_cache_files().iter().map(|(_, size, _)| size).sum()
+}
+
+// Ajouter un chemin à la bibliothèque (canonicalisé ; doublons et sous-dossiers refusés,
+// un dossier parent remplace les dossiers qu'il englobe)
+#[tauri::command]
+fn add_library_path(path: &str) -> Result<library_paths::LibraryPathAddition, String> {
+    let candidate = fs::canonicalize(path)
+        .map_err(|e| format!("Cannot access folder {}: {}", path, e))?;
     let mut config = load_config();
-    if !config.library_paths.contains(&path.to_string()) {
-        config.library_paths.push(path.to_string());
+    let addition = library_paths::plan_addition(&config.library_paths, &candidate, |p| {
+        fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p))
+    });
+    if let library_paths::LibraryPathAddition::Added { ref path, ref replaced } = addition {
+        config.library_paths.retain(|p| !replaced.contains(p));
+        config.library_paths.push(path.clone());
         save_config(&config);
     }
+    Ok(addition)
 }
 
 // Retirer un chemin de la bibliothèque et supprimer ses tracks du cache
@@ -2971,59 +5053,409 @@ fn get_library_paths() -> Vec<String> {
     load_config().library_paths
 }
 
-// Dialog de sélection de dossier
-#[tauri::command]
-async fn select_folder(app: tauri::AppHandle) -> Option<String> {
-    use std::sync::mpsc::channel;
+// Entrées réécrites par remap_library_paths, par stockage
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RemapReport {
+    tracks: usize,
+    metadata: usize,
+    added_dates: usize,
+    playlists: usize,       // Références de tracks dans les playlists (favoris compris)
+    history: usize,
+    excluded_paths: usize,
+    library_paths: usize,
+    missing: usize,         // Chemins sous l'ancien préfixe absents du nouvel emplacement (non réécrits)
+}
+
+// Réécriture d'un préfixe, uniquement vers des fichiers présents sur disque.
+// Un même chemin apparaît dans plusieurs stockages : l'existence n'est testée qu'une fois.
+struct PathRemapper<'a> {
+    old_prefix: &'a str,
+    new_prefix: &'a str,
+    resolved: HashMap<String, Option<String>>,
+}
+
+impl PathRemapper<'_> {
+    fn remap(&mut self, path: &str) -> Option<String> {
+        let candidate = library_paths::remap_prefix(path, self.old_prefix, self.new_prefix)?;
+        self.resolved.entry(path.to_string())
+            .or_insert_with(|| {
+                // Piste CUE virtuelle : c'est le fichier audio qui doit exister
+                let file = cue::split_virtual_path(&candidate).map_or(candidate.as_str(), |(file, _)| file);
+                Path::new(file).exists().then_some(candidate)
+            })
+            .clone()
+    }
 
-    let (tx, rx) = channel();
+    fn rewrite(&mut self, path: &mut String) -> bool {
+        match self.remap(path) {
+            Some(new_path) => {
+                *path = new_path;
+                true
+            }
+            None => false,
+        }
+    }
 
-    app.dialog()
-        .file()
-        .set_title("Choose a music folder")
-        .pick_folder(move |folder_path| {
-            let _ = tx.send(folder_path.map(|p| p.to_string()));
-        });
+    fn rewrite_all<'p>(&mut self, paths: impl Iterator<Item = &'p mut String>) -> usize {
+        paths.map(|path| self.rewrite(path)).filter(|&moved| moved).count()
+    }
 
-    rx.recv().ok().flatten()
-}
+    fn rewrite_keys<V>(&mut self, map: &mut HashMap<String, V>) -> usize {
+        let moves: Vec<(String, String)> = map.keys()
+            .filter_map(|path| self.remap(path).map(|new_path| (path.clone(), new_path)))
+            .collect();
+        for (old_path, new_path) in &moves {
+            if let Some(value) = map.remove(old_path) {
+                map.insert(new_path.clone(), value);
+            }
+        }
+        moves.len()
+    }
 
-// === EXPORT / IMPORT M3U ===
+    fn missing(&self) -> usize {
+        self.resolved.values().filter(|new_path| new_path.is_none()).count()
+    }
+}
 
+// La musique a changé de disque : réécrit `old_prefix` → `new_prefix` dans la bibliothèque,
+// les caches, les playlists, l'historique et la config, puis sauvegarde tout
 #[tauri::command]
-async fn export_playlist_m3u(playlist_id: String, app: tauri::AppHandle) -> Result<String, String> {
-    use std::sync::mpsc::channel;
-
-    // 1. Charger la playlist
-    let data = load_playlists();
-    let playlist = data.playlists.iter()
-        .find(|p| p.id == playlist_id)
-        .ok_or("Playlist not found")?;
+fn remap_library_paths(old_prefix: String, new_prefix: String) -> Result<RemapReport, String> {
+    if old_prefix.trim_end_matches('/').is_empty() || new_prefix.trim_end_matches('/').is_empty() {
+        return Err("Both prefixes must be non-empty folders".to_string());
+    }
+    if !Path::new(&new_prefix).is_dir() {
+        return Err(format!("Folder not found: {}", new_prefix));
+    }
+    let mut remapper = PathRemapper { old_prefix: &old_prefix, new_prefix: &new_prefix, resolved: HashMap::new() };
+    let mut report = RemapReport::default();
 
-    // 2. Générer le contenu M3U
-    let mut m3u = String::from("#EXTM3U\n");
-    if let Ok(cache) = TRACKS_CACHE.lock() {
-        for track_path in &playlist.track_paths {
-            if let Some(track) = cache.tracks.iter().find(|t| t.path == *track_path) {
-                let duration_secs = track.metadata.duration as i64;
-                let artist = &track.metadata.artist;
-                let title = &track.metadata.title;
-                m3u.push_str(&format!("#EXTINF:{},{} - {}\n", duration_secs, artist, title));
+    if let Ok(mut cache) = TRACKS_CACHE.lock() {
+        for track in cache.tracks.iter_mut() {
+            if remapper.rewrite(&mut track.path) {
+                track.folder = Path::new(&track.path).parent()
+                    .and_then(|p| p.file_name())
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("")
+                    .to_string();
+                report.tracks += 1;
             }
-            m3u.push_str(track_path);
-            m3u.push('\n');
+        }
+        if report.tracks > 0 {
+            save_tracks_cache(&cache);
         }
     }
-
-    // 3. Dialogue de sauvegarde
-    let safe_name = playlist.name.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
-    let default_filename = format!("{}.m3u", safe_name);
-
-    let (tx, rx) = channel();
-    app.dialog()
-        .file()
-        .set_title("Export playlist as M3U")
-        .set_file_name(&default_filename)
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        report.metadata = remapper.rewrite_keys(&mut cache.entries);
+        if report.metadata > 0 {
+            save_metadata_cache_to_file(&cache);
+        }
+    }
+    if let Ok(mut cache) = ADDED_DATES_CACHE.lock() {
+        report.added_dates = remapper.rewrite_keys(&mut cache.entries);
+        if report.added_dates > 0 {
+            save_added_dates_cache(&cache);
+        }
+    }
+    // Caches annexes (rescan incrémental, reprise de lecture, file d'attente, notes) : non comptés
+    if let Ok(mut cache) = MTIME_CACHE.lock() {
+        if remapper.rewrite_keys(&mut cache.entries) > 0 {
+            save_mtime_cache(&cache);
+        }
+    }
+    if let Ok(mut cache) = PLAYBACK_POSITIONS.lock() {
+        if remapper.rewrite_keys(&mut cache.entries) > 0 {
+            save_playback_positions(&cache);
+        }
+    }
+    if let Ok(mut queue) = PLAY_QUEUE.lock() {
+        if remapper.rewrite_all(queue.tracks.iter_mut()) > 0 {
+            save_queue(&queue);
+        }
+    }
+    if let Ok(mut cache) = RATINGS.lock() {
+        if remapper.rewrite_keys(&mut cache.ratings) > 0 {
+            ratings::save_ratings(&cache);
+        }
+    }
+
+    let mut playlists = load_playlists();
+    report.playlists = remapper.rewrite_all(
+        playlists.playlists.iter_mut().flat_map(|playlist| playlist.track_paths.iter_mut())
+    );
+    if report.playlists > 0 {
+        save_playlists(&playlists);
+    }
+
+    if let Ok(mut history) = LISTENING_HISTORY.lock() {
+        let history = &mut *history;
+        let mut moved = remapper.rewrite_all(
+            history.entries.iter_mut().chain(history.last_played.iter_mut()).map(|entry| &mut entry.path)
+        );
+        moved += remapper.rewrite_keys(&mut history.imported_plays);
+        history.played_paths = history.played_paths.drain()
+            .map(|path| match remapper.remap(&path) {
+                Some(new_path) => {
+                    moved += 1;
+                    new_path
+                }
+                None => path,
+            })
+            .collect();
+        report.history = moved;
+        if moved > 0 {
+            history.play_counts = PlayCounts::from_history(&history.entries, &history.imported_plays);
+            save_listening_history(history);
+        }
+    }
+
+    let mut config = load_config();
+    report.excluded_paths = remapper.rewrite_all(config.excluded_paths.iter_mut());
+    // Dossiers de la bibliothèque : sans eux, le prochain scan retirerait les tracks déplacés
+    for path in config.library_paths.iter_mut() {
+        if let Some(new_path) = library_paths::remap_prefix(path, &old_prefix, &new_prefix) {
+            if Path::new(&new_path).is_dir() {
+                *path = new_path;
+                report.library_paths += 1;
+            }
+        }
+    }
+    if report.excluded_paths > 0 || report.library_paths > 0 {
+        save_config(&config);
+    }
+
+    report.missing = remapper.missing();
+    Ok(report)
+}
+
+// Dialog de sélection de dossier
+#[tauri::command]
+async fn select_folder(app: tauri::AppHandle) -> Option<String> {
+    use std::sync::mpsc::channel;
+
+    let (tx, rx) = channel();
+
+    app.dialog()
+        .file()
+        .set_title("Choose a music folder")
+        .pick_folder(move |folder_path| {
+            let _ = tx.send(folder_path.map(|p| p.to_string()));
+        });
+
+    rx.recv().ok().flatten()
+}
+
+// === EXPORT DE LA BIBLIOTHÈQUE ===
+
+// Exporte toute la bibliothèque en "json" ou "csv" (métadonnées + date d'ajout + écoutes).
+// Retourne le nombre de tracks écrits.
+#[tauri::command]
+async fn export_library(format: String, dest_path: String) -> Result<usize, String> {
+    let format = format.to_lowercase();
+    if format != "json" && format != "csv" {
+        return Err(format!("Unsupported export format: {}", format));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let tracks = TRACKS_CACHE.lock().map_err(|e| e.to_string())?.tracks.clone();
+        let added_dates = ADDED_DATES_CACHE.lock().map_err(|e| e.to_string())?.entries.clone();
+        let play_counts = LISTENING_HISTORY.lock().map_err(|e| e.to_string())?.play_counts.tracks.clone();
+
+        let rows: Vec<library_export::ExportRow> = tracks.iter()
+            .map(|track| library_export::ExportRow {
+                track,
+                added_at: added_dates.get(&track.path).copied(),
+                play_count: play_counts.get(&track.path).copied().unwrap_or(0),
+            })
+            .collect();
+
+        let content = if format == "json" {
+            library_export::to_json(&rows)?
+        } else {
+            library_export::to_csv(&rows)
+        };
+        fs::write(&dest_path, content).map_err(|e| format!("Failed to write export: {}", e))?;
+        Ok(rows.len())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Exporte l'historique d'écoute en "json" ou "csv" (format d'import Last.fm :
+// artist,track,album,timestamp ISO-8601), de l'écoute la plus ancienne à la plus récente.
+// Seules les écoutes conservées (HISTORY_LIMIT) sont datées : les compteurs importés ne sont
+// pas exportés. Retourne le nombre d'écoutes écrites.
+#[tauri::command]
+fn export_listening_history(format: String, dest: String) -> Result<usize, String> {
+    let format = format.to_lowercase();
+    if format != "json" && format != "csv" {
+        return Err(format!("Unsupported export format: {}", format));
+    }
+
+    let history = LISTENING_HISTORY.lock().map_err(|e| e.to_string())?.clone();
+    let entries: Vec<&ListeningEntry> = history.entries.iter().rev().collect();
+    let content = if format == "json" {
+        library_export::history_to_json(&entries)?
+    } else {
+        library_export::history_to_csv(&entries)
+    };
+    fs::write(&dest, content).map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(entries.len())
+}
+
+// === SAUVEGARDE DES DONNÉES UTILISATEUR ===
+
+// Fichiers non régénérables (config, playlists/favoris et intelligentes, notes, historique,
+// dates d'ajout, EQ, réglages de lecture).
+// Les caches (métadonnées, pochettes, miniatures, tracks) se reconstruisent au scan.
+const BACKUP_FILES: [&str; 10] = [
+    "config.json",
+    "playlists.json",
+    "listening_history.json",
+    "added_dates_cache.json",
+    "eq_settings.json",
+    "eq_presets.json",
+    "artist_aliases.json",
+    "ratings.json",
+    "smart_playlists.json",
+    "playback_settings.json",
+];
+
+// Vérifie qu'un fichier de la sauvegarde se relit avec le format attendu
+fn validate_backup_file(name: &str, content: &str) -> Result<(), String> {
+    let result = match name {
+        "config.json" => serde_json::from_str::<Config>(content).map(|_| ()),
+        "playlists.json" => serde_json::from_str::<PlaylistsData>(content).map(|_| ()),
+        "listening_history.json" => serde_json::from_str::<ListeningHistory>(content).map(|_| ()),
+        "added_dates_cache.json" => serde_json::from_str::<AddedDatesCache>(content).map(|_| ()),
+        "eq_presets.json" => serde_json::from_str::<EqPresetsData>(content).map(|_| ()),
+        "artist_aliases.json" => serde_json::from_str::<artist_aliases::ArtistAliases>(content).map(|_| ()),
+        "ratings.json" => serde_json::from_str::<ratings::RatingsCache>(content).map(|_| ()),
+        "smart_playlists.json" => serde_json::from_str::<smart_playlists::SmartPlaylistsData>(content).map(|_| ()),
+        "playback_settings.json" => serde_json::from_str::<PlaybackSettings>(content).map(|_| ()),
+        _ => serde_json::from_str::<serde_json::Value>(content).map(|_| ()),
+    };
+    result.map_err(|e| format!("{} is invalid: {}", name, e))
+}
+
+// Archive zip des données utilisateur. Retourne les fichiers sauvegardés.
+#[tauri::command]
+fn export_app_data(dest_path: String) -> Result<Vec<String>, String> {
+    let data_dir = get_data_dir();
+    let entries: Vec<backup::ArchiveEntry> = BACKUP_FILES.iter()
+        .filter_map(|name| fs::read(data_dir.join(name)).ok().map(|data| backup::ArchiveEntry {
+            name: name.to_string(),
+            data,
+        }))
+        .collect();
+    if entries.is_empty() {
+        return Err("No app data to back up".to_string());
+    }
+    fs::write(&dest_path, backup::write_archive(&entries)?)
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+    Ok(entries.into_iter().map(|e| e.name).collect())
+}
+
+// Restaure une archive d'export_app_data. Tout est validé avant d'écrire quoi que ce soit ;
+// les caches mémoire sont rechargés depuis les fichiers restaurés. Retourne les fichiers restaurés.
+#[tauri::command]
+fn import_app_data(src_path: String) -> Result<Vec<String>, String> {
+    let archive = fs::read(&src_path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let mut files: Vec<(String, String)> = Vec::new();
+    for entry in backup::read_archive(&archive)? {
+        // Archive recompressée depuis un dossier : fichiers sous "Dossier/", métadonnées macOS
+        if entry.name.starts_with("__MACOSX/") {
+            continue;
+        }
+        let name = entry.name.rsplit('/').next().unwrap_or(&entry.name).to_string();
+        if !BACKUP_FILES.contains(&name.as_str()) {
+            continue;
+        }
+        let content = String::from_utf8(entry.data)
+            .map_err(|_| format!("{} is not valid UTF-8", name))?;
+        validate_backup_file(&name, &content)?;
+        files.push((name, content));
+    }
+    if files.is_empty() {
+        return Err("Backup contains no Noir data".to_string());
+    }
+
+    let data_dir = get_data_dir();
+    for (name, content) in &files {
+        save_file_secure(&data_dir.join(name), content);
+    }
+
+    if let Ok(mut history) = LISTENING_HISTORY.lock() {
+        *history = load_listening_history();
+    }
+    if let Ok(mut dates) = ADDED_DATES_CACHE.lock() {
+        *dates = load_added_dates_cache();
+    }
+    if let Ok(mut aliases) = ARTIST_ALIASES.lock() {
+        *aliases = artist_aliases::load_artist_aliases();
+    }
+    if let Ok(mut ratings) = RATINGS.lock() {
+        *ratings = ratings::load_ratings();
+    }
+    let config = load_config();
+    ONLINE_ENABLED.store(config.online_enabled.unwrap_or(true), std::sync::atomic::Ordering::Relaxed);
+    HISTORY_LIMIT.store(
+        config.history_limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(MIN_HISTORY_LIMIT, MAX_HISTORY_LIMIT),
+        std::sync::atomic::Ordering::Relaxed,
+    );
+    if let Ok(mut providers) = METADATA_PROVIDERS.lock() {
+        *providers = config.metadata_providers;
+    }
+    if let Ok(mut extensions) = SCANNED_EXTENSIONS.lock() {
+        *extensions = scanned_extensions_from_config(&config);
+    }
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            load_eq_settings(&engine.eq_state);
+            apply_playback_settings(engine);
+        }
+    }
+
+    Ok(files.into_iter().map(|(name, _)| name).collect())
+}
+
+// === EXPORT / IMPORT M3U ===
+
+#[tauri::command]
+async fn export_playlist_m3u(playlist_id: String, app: tauri::AppHandle) -> Result<String, String> {
+    use std::sync::mpsc::channel;
+
+    // 1. Charger la playlist
+    let data = load_playlists();
+    let playlist = data.playlists.iter()
+        .find(|p| p.id == playlist_id)
+        .ok_or("Playlist not found")?;
+
+    // 2. Générer le contenu M3U
+    let mut m3u = String::from("#EXTM3U\n");
+    if let Ok(cache) = TRACKS_CACHE.lock() {
+        for track_path in &playlist.track_paths {
+            if let Some(track) = cache.tracks.iter().find(|t| t.path == *track_path) {
+                let duration_secs = track.metadata.duration as i64;
+                let artist = &track.metadata.artist;
+                let title = &track.metadata.title;
+                m3u.push_str(&format!("#EXTINF:{},{} - {}\n", duration_secs, artist, title));
+            }
+            m3u.push_str(track_path);
+            m3u.push('\n');
+        }
+    }
+
+    // 3. Dialogue de sauvegarde
+    let safe_name = playlist.name.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
+    let default_filename = format!("{}.m3u", safe_name);
+
+    let (tx, rx) = channel();
+    app.dialog()
+        .file()
+        .set_title("Export playlist as M3U")
+        .set_file_name(&default_filename)
         .add_filter("M3U Playlist", &["m3u"])
         .save_file(move |file_path| {
             let _ = tx.send(file_path.map(|p| p.to_string()));
@@ -3040,12 +5472,61 @@ async fn export_playlist_m3u(playlist_id: String, app: tauri::AppHandle) -> Resu
     Ok(file_path)
 }
 
+/// Résultat d'un import M3U : la playlist créée et les entrées introuvables ignorées
+#[derive(Serialize, Clone)]
+struct PlaylistImportReport {
+    playlist: Playlist,
+    #[serde(rename = "skippedMissing")]
+    skipped_missing: usize,
+}
+
+/// Parse un M3U / M3U8 (étendu ou simple) : retourne les chemins audio existants
+/// et le nombre d'entrées ignorées car introuvables sur disque.
+/// Les chemins relatifs sont résolus par rapport au dossier de la playlist.
+fn parse_m3u(content: &str, base_dir: &Path) -> (Vec<String>, usize) {
+    let mut track_paths = Vec::new();
+    let mut skipped_missing = 0;
+
+    // lines() gère \n et \r\n ; le BOM UTF-8 éventuel précède la première ligne
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        // #EXTM3U, #EXTINF:... et commentaires
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Flux (http://, https://…) : pas des fichiers locaux, ni importés ni comptés manquants
+        let entry = match line.strip_prefix("file://") {
+            Some(url_path) => percent_decode_str(url_path).decode_utf8_lossy().to_string(),
+            None if line.contains("://") => continue,
+            None => line.to_string(),
+        };
+        let entry_path = Path::new(&entry);
+        let resolved = if entry_path.is_absolute() {
+            entry_path.to_path_buf()
+        } else {
+            // Playlists exportées depuis Windows : séparateurs '\\'
+            base_dir.join(entry.replace('\\', "/"))
+        };
+
+        if !is_audio_file(&resolved) {
+            continue;
+        }
+        if !resolved.exists() {
+            skipped_missing += 1;
+            continue;
+        }
+        track_paths.push(resolved.to_string_lossy().to_string());
+    }
+
+    (track_paths, skipped_missing)
+}
+
+/// Dialogue de sélection d'un fichier M3U à importer (retourne son chemin)
 #[tauri::command]
-async fn import_playlist_m3u(app: tauri::AppHandle) -> Result<Playlist, String> {
+async fn pick_playlist_m3u(app: tauri::AppHandle) -> Result<String, String> {
     use std::sync::mpsc::channel;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
-    // 1. Dialogue d'ouverture
     let (tx, rx) = channel();
     app.dialog()
         .file()
@@ -3055,39 +5536,40 @@ async fn import_playlist_m3u(app: tauri::AppHandle) -> Result<Playlist, String>
             let _ = tx.send(file_path.map(|p| p.to_string()));
         });
 
-    let file_path = rx.recv()
+    rx.recv()
         .map_err(|_| "Dialog error".to_string())?
-        .ok_or("Import cancelled")?;
+        .ok_or_else(|| "Import cancelled".to_string())
+}
+
+/// Importe un M3U / M3U8 en nouvelle playlist (nom = nom du fichier sans extension).
+/// Le nombre d'entrées ignorées (fichiers introuvables) est retourné avec la playlist.
+#[tauri::command]
+fn import_playlist_m3u(path: String) -> Result<PlaylistImportReport, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
 
-    // 2. Lire et parser le fichier M3U
-    let content = std::fs::read_to_string(&file_path)
+    // Lecture en octets : un M3U non-UTF-8 ne doit pas faire échouer tout l'import
+    let bytes = std::fs::read(&path)
         .map_err(|e| format!("Failed to read M3U: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
 
-    let track_paths: Vec<String> = content.lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty() && !line.starts_with('#'))
-        .filter(|line| std::path::Path::new(line).exists())
-        .map(|line| line.to_string())
-        .collect();
+    let playlist_path = Path::new(&path);
+    let base_dir = playlist_path.parent().unwrap_or_else(|| Path::new("/"));
+    let (track_paths, skipped_missing) = parse_m3u(&content, base_dir);
 
-    // 3. Nom de la playlist = nom du fichier sans extension
-    let playlist_name = std::path::Path::new(&file_path)
+    let playlist_name = playlist_path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Imported Playlist")
         .to_string();
 
-    // 4. Créer la playlist
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64;
-
     let playlist = Playlist {
-        id: format!("playlist_{}", now),
+        id: generate_playlist_id(),
         name: playlist_name,
         track_paths,
-        created_at: now,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
         is_system: false,
     };
 
@@ -3095,7 +5577,147 @@ async fn import_playlist_m3u(app: tauri::AppHandle) -> Result<Playlist, String>
     data.playlists.push(playlist.clone());
     save_playlists(&data);
 
-    Ok(playlist)
+    #[cfg(debug_assertions)]
+    println!("[M3U] Imported '{}': {} tracks, {} missing files skipped",
+        playlist.name, playlist.track_paths.len(), skipped_missing);
+
+    Ok(PlaylistImportReport { playlist, skipped_missing })
+}
+
+// === IMPORT BIBLIOTHÈQUE ITUNES ===
+
+/// Résultat d'un import iTunes
+#[derive(Serialize, Clone)]
+struct ItunesImportReport {
+    matched: usize,
+    unmatched: usize,
+    /// Tracks iTunes sans fichier dans la bibliothèque (chemin, ou "artiste - titre" sans fichier local)
+    #[serde(rename = "unmatchedTracks")]
+    unmatched_tracks: Vec<String>,
+    #[serde(rename = "playlistsImported")]
+    playlists_imported: usize,
+    #[serde(rename = "playCountsMerged")]
+    play_counts_merged: usize,
+    #[serde(rename = "ratingsImported")]
+    ratings_imported: usize,
+}
+
+/// Importe un `iTunes Library.xml` : les tracks sont associés par chemin de fichier à la
+/// bibliothèque scannée, les playlists utilisateur sont recréées (tracks associés uniquement)
+/// et les compteurs d'écoute s'ajoutent à l'historique. Les notes iTunes sont reprises pour
+/// les tracks que l'utilisateur n'a pas encore notés dans Noir
+#[tauri::command]
+async fn import_itunes_library(xml_path: String) -> Result<ItunesImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let library = itunes::read_library(&xml_path)?;
+
+        // path -> (artist, album) tels que connus de la bibliothèque (clé des compteurs par album)
+        let known: HashMap<String, (String, String)> = TRACKS_CACHE.lock()
+            .map_err(|e| e.to_string())?
+            .tracks.iter()
+            .map(|t| (t.path.clone(), (t.metadata.artist.clone(), t.metadata.album.clone())))
+            .collect();
+
+        let mut matched_paths: HashMap<i64, String> = HashMap::new();
+        let mut unmatched_tracks = Vec::new();
+        let mut tracks: Vec<&itunes::ItunesTrack> = library.tracks.values().collect();
+        tracks.sort_by_key(|t| t.id);
+        for track in &tracks {
+            match track.path.as_ref().filter(|path| known.contains_key(*path)) {
+                Some(path) => {
+                    matched_paths.insert(track.id, path.clone());
+                }
+                None => unmatched_tracks.push(
+                    track.path.clone().unwrap_or_else(|| format!("{} - {}", track.artist, track.name)),
+                ),
+            }
+        }
+
+        // Compteurs d'écoute
+        let mut play_counts_merged = 0;
+        {
+            let mut history = LISTENING_HISTORY.lock().map_err(|e| e.to_string())?;
+            for track in &tracks {
+                let Some(path) = matched_paths.get(&track.id) else { continue };
+                if track.play_count == 0 {
+                    continue;
+                }
+                let (artist, album) = known[path].clone();
+                history.played_paths.insert(path.clone());
+                history.imported_plays.insert(path.clone(), ImportedPlays {
+                    artist,
+                    album,
+                    count: track.play_count,
+                    last_played: track.last_played,
+                });
+                play_counts_merged += 1;
+            }
+            history.play_counts = PlayCounts::from_history(&history.entries, &history.imported_plays);
+            save_listening_history(&history);
+        }
+
+        // Notes : celles déjà posées dans Noir sont prioritaires
+        let mut ratings_imported = 0;
+        {
+            let mut ratings = RATINGS.lock().map_err(|e| e.to_string())?;
+            for track in &tracks {
+                let Some(path) = matched_paths.get(&track.id) else { continue };
+                if track.stars == 0 || ratings.get(path) > 0 {
+                    continue;
+                }
+                ratings.set(path, track.stars);
+                ratings_imported += 1;
+            }
+            if ratings_imported > 0 {
+                ratings::save_ratings(&ratings);
+            }
+        }
+
+        // Playlists : un ré-import ne recrée pas une playlist identique (même nom, mêmes tracks)
+        let mut data = load_playlists();
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let base_id = generate_playlist_id();
+        let mut playlists_imported = 0;
+        for (index, itunes_playlist) in library.playlists.iter().enumerate() {
+            let track_paths: Vec<String> = itunes_playlist.track_ids.iter()
+                .filter_map(|id| matched_paths.get(id).cloned())
+                .collect();
+            let already_imported = data.playlists.iter()
+                .any(|p| !p.is_system && p.name == itunes_playlist.name && p.track_paths == track_paths);
+            if track_paths.is_empty() || already_imported {
+                continue;
+            }
+            data.playlists.push(Playlist {
+                // generate_playlist_id() est à la milliseconde : suffixe pour garder des IDs uniques
+                id: format!("{}_{}", base_id, index),
+                name: itunes_playlist.name.clone(),
+                track_paths,
+                created_at,
+                is_system: false,
+            });
+            playlists_imported += 1;
+        }
+        if playlists_imported > 0 {
+            save_playlists(&data);
+        }
+
+        #[cfg(debug_assertions)]
+        println!("[iTunes] {} matched, {} unmatched, {} playlists, {} play counts, {} ratings",
+            matched_paths.len(), unmatched_tracks.len(), playlists_imported, play_counts_merged, ratings_imported);
+
+        Ok(ItunesImportReport {
+            matched: matched_paths.len(),
+            unmatched: unmatched_tracks.len(),
+            unmatched_tracks,
+            playlists_imported,
+            play_counts_merged,
+            ratings_imported,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 // === COMMANDES PLAYLISTS ===
@@ -3147,6 +5769,75 @@ fn rename_playlist(id: String, new_name: String) -> bool {
     false
 }
 
+// Dupliquer une playlist : copie utilisateur (jamais système, même depuis les favoris),
+// insérée juste après l'originale
+#[tauri::command]
+fn duplicate_playlist(id: String, new_name: String) -> Result<Playlist, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut data = load_playlists();
+    let index = data.playlists.iter()
+        .position(|p| p.id == id)
+        .ok_or("Playlist not found")?;
+
+    let copy = Playlist {
+        id: generate_playlist_id(),
+        name: new_name,
+        track_paths: data.playlists[index].track_paths.clone(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        is_system: false,
+    };
+
+    data.playlists.insert(index + 1, copy.clone());
+    save_playlists(&data);
+
+    Ok(copy)
+}
+
+// Fusionner des playlists : nouvelle playlist avec l'union de leurs tracks, dans l'ordre des
+// sources (premier exemplaire conservé). `delete_sources` supprime ensuite les sources,
+// sauf les playlists système (favoris)
+#[tauri::command]
+fn merge_playlists(source_ids: Vec<String>, dest_name: String, delete_sources: Option<bool>) -> Result<Playlist, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut data = load_playlists();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut track_paths = Vec::new();
+    for id in &source_ids {
+        let source = data.playlists.iter()
+            .find(|p| &p.id == id)
+            .ok_or_else(|| format!("Playlist not found: {}", id))?;
+        for path in &source.track_paths {
+            if seen.insert(path.clone()) {
+                track_paths.push(path.clone());
+            }
+        }
+    }
+
+    let merged = Playlist {
+        id: generate_playlist_id(),
+        name: dest_name,
+        track_paths,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        is_system: false,
+    };
+
+    if delete_sources.unwrap_or(false) {
+        data.playlists.retain(|p| p.is_system || !source_ids.contains(&p.id));
+    }
+    data.playlists.push(merged.clone());
+    save_playlists(&data);
+
+    Ok(merged)
+}
+
 // Supprimer une playlist (impossible pour les playlists système)
 #[tauri::command]
 fn delete_playlist(id: String) -> bool {
@@ -3219,6 +5910,199 @@ fn reorder_playlist_tracks(playlist_id: String, track_paths: Vec<String>) -> boo
     false
 }
 
+// Fichier d'une entrée de playlist introuvable sur le disque (piste CUE : son fichier audio).
+// Les chemins SMB ne sont pas vérifiés : un partage non monté n'est pas une entrée cassée
+fn is_playlist_entry_missing(path: &str) -> bool {
+    if path.starts_with("smb://") {
+        return false;
+    }
+    let file = cue::split_virtual_path(path).map_or(path, |(file, _)| file);
+    !Path::new(file).exists()
+}
+
+// Résumé affiché dans la barre latérale ("42 tracks · 3h 12m")
+#[derive(Serialize, Clone)]
+struct PlaylistSummary {
+    track_count: usize,
+    total_duration: f64,   // Secondes, d'après le cache de métadonnées
+    missing_count: usize,  // Entrées dont le fichier n'existe plus
+}
+
+// Nombre de tracks, durée totale et entrées cassées d'une playlist
+#[tauri::command]
+fn get_playlist_summary(playlist_id: String) -> Result<PlaylistSummary, String> {
+    let data = load_playlists();
+    let playlist = data.playlists.iter()
+        .find(|p| p.id == playlist_id)
+        .ok_or("Playlist not found")?;
+
+    let total_duration = METADATA_CACHE.lock()
+        .map(|cache| playlist.track_paths.iter()
+            .filter_map(|path| cache.entries.get(path))
+            .map(|metadata| metadata.duration)
+            .sum())
+        .unwrap_or(0.0);
+    Ok(PlaylistSummary {
+        track_count: playlist.track_paths.len(),
+        total_duration,
+        missing_count: playlist.track_paths.iter().filter(|path| is_playlist_entry_missing(path)).count(),
+    })
+}
+
+// Retire d'une playlist les entrées dont le fichier n'existe plus ; retourne le nombre retiré.
+// Vaut aussi pour les favoris : seules leurs entrées sont nettoyées, la playlist reste
+#[tauri::command]
+fn clean_playlist(playlist_id: String) -> Result<usize, String> {
+    let mut data = load_playlists();
+    let playlist = data.playlists.iter_mut()
+        .find(|p| p.id == playlist_id)
+        .ok_or("Playlist not found")?;
+
+    let initial_len = playlist.track_paths.len();
+    playlist.track_paths.retain(|path| !is_playlist_entry_missing(path));
+    let removed = initial_len - playlist.track_paths.len();
+
+    if removed > 0 {
+        save_playlists(&data);
+    }
+    Ok(removed)
+}
+
+// Entrées cassées de toutes les playlists (id → chemins), pour les montrer avant nettoyage
+#[tauri::command]
+fn find_broken_playlist_tracks() -> HashMap<String, Vec<String>> {
+    load_playlists().playlists.into_iter()
+        .filter_map(|playlist| {
+            let broken: Vec<String> = playlist.track_paths.into_iter()
+                .filter(|path| is_playlist_entry_missing(path))
+                .collect();
+            (!broken.is_empty()).then_some((playlist.id, broken))
+        })
+        .collect()
+}
+
+// === COMMANDES PLAYLISTS INTELLIGENTES ===
+
+// Évaluer un arbre de règles sur la bibliothèque (chemins des tracks correspondants)
+#[tauri::command]
+fn evaluate_smart_playlist(rules: smart_playlists::SmartRule) -> Vec<String> {
+    // Cache empoisonné : playlist vide plutôt qu'un panic de la commande
+    let (Ok(tracks), Ok(added_dates)) = (TRACKS_CACHE.lock(), ADDED_DATES_CACHE.lock()) else {
+        return Vec::new();
+    };
+    smart_playlists::evaluate(&rules, &tracks.tracks, &added_dates.entries)
+}
+
+// Obtenir les définitions des playlists intelligentes
+#[tauri::command]
+fn get_smart_playlists() -> Vec<smart_playlists::SmartPlaylist> {
+    smart_playlists::load_smart_playlists().playlists
+}
+
+// Créer (id absent) ou mettre à jour une playlist intelligente
+#[tauri::command]
+fn save_smart_playlist(id: Option<String>, name: String, rules: smart_playlists::SmartRule) -> smart_playlists::SmartPlaylist {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut data = smart_playlists::load_smart_playlists();
+
+    if let Some(existing) = id.as_ref().and_then(|id| data.playlists.iter_mut().find(|p| &p.id == id)) {
+        existing.name = name;
+        existing.rules = rules;
+        let playlist = existing.clone();
+        smart_playlists::save_smart_playlists(&data);
+        return playlist;
+    }
+
+    let playlist = smart_playlists::SmartPlaylist {
+        id: generate_playlist_id().replacen("pl_", "spl_", 1),
+        name,
+        rules,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    data.playlists.push(playlist.clone());
+    smart_playlists::save_smart_playlists(&data);
+
+    playlist
+}
+
+// Supprimer une playlist intelligente
+#[tauri::command]
+fn delete_smart_playlist(id: String) -> bool {
+    let mut data = smart_playlists::load_smart_playlists();
+
+    let initial_len = data.playlists.len();
+    data.playlists.retain(|p| p.id != id);
+
+    if data.playlists.len() < initial_len {
+        smart_playlists::save_smart_playlists(&data);
+        return true;
+    }
+
+    false
+}
+
+// === COMMANDES PAROLES ===
+
+// Paroles d'un track (tag embarqué, sinon sidecar .lrc / .txt)
+#[tauri::command]
+fn get_lyrics(path: String) -> Option<String> {
+    lyrics::get_lyrics(&path)
+}
+
+// Paroles horodatées (LRC) pour surligner la ligne courante
+#[tauri::command]
+fn get_synced_lyrics(path: String) -> Vec<lyrics::LyricLine> {
+    lyrics::get_synced_lyrics(&path)
+}
+
+// === COMMANDES CUE SHEETS ===
+
+// Pistes d'un fichier .cue (titre, interprète, bornes en secondes)
+#[tauri::command]
+fn parse_cue_sheet(path: String) -> Vec<cue::CueTrack> {
+    cue::read_cue_sheet(Path::new(&path)).map(|sheet| sheet.tracks).unwrap_or_default()
+}
+
+// === COMMANDES FORME D'ONDE ===
+
+#[derive(Serialize, Clone)]
+struct WaveformReady {
+    path: String,
+    peaks: Vec<f32>,
+}
+
+// Forme d'onde normalisée (0.0–1.0) en `buckets` fenêtres, décodée hors du main thread
+// et mise en cache disque ; émet aussi waveform_ready pour les appels fire-and-forget
+#[tauri::command]
+async fn generate_waveform(app_handle: tauri::AppHandle, path: String, buckets: usize) -> Result<Vec<f32>, String> {
+    use tauri::Emitter;
+
+    if path.starts_with("smb://") {
+        return Err("Waveform not available for network files".to_string());
+    }
+    // Piste CUE : forme d'onde du fichier complet
+    let file = cue::split_virtual_path(&path).map_or(path.as_str(), |(file, _)| file).to_string();
+
+    let peaks = tokio::task::spawn_blocking(move || waveform::get_or_generate(&file, buckets))
+        .await
+        .map_err(|e| e.to_string())??;
+    let _ = app_handle.emit("waveform_ready", WaveformReady { path, peaks: peaks.clone() });
+    Ok(peaks)
+}
+
+// === COMMANDES CHAPITRES ===
+
+// Chapitres embarqués (ID3 CHAP, MP4 chpl) ; navigation via audio_seek
+#[tauri::command]
+fn get_chapters(path: String) -> Vec<chapters::Chapter> {
+    chapters::get_chapters(&path)
+}
+
 // === COMMANDES FAVORIS ===
 
 // Toggle favori : ajoute ou retire une track des favoris
@@ -3265,6 +6149,97 @@ fn get_favorites() -> Vec<String> {
     vec![]
 }
 
+// Ajoute / retire un album des favoris (true = désormais favori)
+#[tauri::command]
+fn toggle_favorite_album(artist: String, album: String) -> bool {
+    let mut data = load_playlists();
+    let key = FavoriteAlbum { artist, album };
+    let is_favorite = match data.favorite_albums.iter().position(|a| a == &key) {
+        Some(pos) => {
+            data.favorite_albums.remove(pos);
+            false
+        }
+        None => {
+            data.favorite_albums.push(key);
+            true
+        }
+    };
+    save_playlists(&data);
+    is_favorite
+}
+
+#[tauri::command]
+fn is_favorite_album(artist: String, album: String) -> bool {
+    let key = FavoriteAlbum { artist, album };
+    load_playlists().favorite_albums.contains(&key)
+}
+
+// Albums favoris, dans l'ordre où ils ont été ajoutés
+#[tauri::command]
+fn get_favorite_albums() -> Vec<FavoriteAlbum> {
+    load_playlists().favorite_albums
+}
+
+// Ajoute / retire un artiste des favoris (true = désormais favori)
+#[tauri::command]
+fn toggle_favorite_artist(artist: String) -> bool {
+    let mut data = load_playlists();
+    let is_favorite = match data.favorite_artists.iter().position(|a| a == &artist) {
+        Some(pos) => {
+            data.favorite_artists.remove(pos);
+            false
+        }
+        None => {
+            data.favorite_artists.push(artist);
+            true
+        }
+    };
+    save_playlists(&data);
+    is_favorite
+}
+
+#[tauri::command]
+fn is_favorite_artist(artist: String) -> bool {
+    load_playlists().favorite_artists.contains(&artist)
+}
+
+#[tauri::command]
+fn get_favorite_artists() -> Vec<String> {
+    load_playlists().favorite_artists
+}
+
+// === NOTES (ÉTOILES) ===
+
+// Note un track de 1 à 5 étoiles ; 0 retire la note
+#[tauri::command]
+fn set_rating(path: String, stars: u8) -> Result<(), String> {
+    if stars > ratings::MAX_RATING {
+        return Err(format!("Rating must be between 0 and {} stars", ratings::MAX_RATING));
+    }
+    let mut cache = RATINGS.lock().map_err(|_| "Ratings unavailable".to_string())?;
+    cache.set(&path, stars);
+    ratings::save_ratings(&cache);
+    Ok(())
+}
+
+// Note d'un track (0 = pas de note)
+#[tauri::command]
+fn get_rating(path: String) -> u8 {
+    RATINGS.lock().map(|cache| cache.get(&path)).unwrap_or(0)
+}
+
+// Toutes les notes (chemin → étoiles), pour l'affichage des listes
+#[tauri::command]
+fn get_ratings() -> HashMap<String, u8> {
+    RATINGS.lock().map(|cache| cache.ratings.clone()).unwrap_or_default()
+}
+
+// Tracks notés au moins `min` étoiles, les mieux notés d'abord (listes "4 étoiles et plus")
+#[tauri::command]
+fn get_tracks_by_min_rating(min: u8) -> Vec<String> {
+    RATINGS.lock().map(|cache| cache.paths_with_min_rating(min)).unwrap_or_default()
+}
+
 // === COMMANDES AUDIO ENGINE (Player Audiophile) ===
 
 /// Structure pour l'état de lecture retourné au frontend
@@ -3284,11 +6259,87 @@ fn emit_frontend_error(code: &str, message: &str, details: &str) {
     }
 }
 
-/// Joue un fichier audio (non-bloquant)
+/// Indique si un fichier peut être lu, sans le jouer (griser les fichiers illisibles).
+/// Piste CUE : vérifie le fichier réel. SMB sans montage local : le fichier n'est pas
+/// téléchargé, le verdict repose sur le codec connu du scan
+#[tauri::command]
+async fn can_play(path: String) -> audio_decoder::PlayabilitySummary {
+    let file = cue::split_virtual_path(&path).map(|(file, _)| file.to_string()).unwrap_or(path);
+
+    // APE : illisible quel que soit l'emplacement, verdict sans ouvrir le fichier
+    if audio_decoder::metadata_only_codec(Path::new(&file)).is_some() {
+        return audio_decoder::check_playability(&file);
+    }
+
+    // Montage local (AFP, NFS, SMB Finder) : même repli qu'audio_play
+    let local_path = if file.starts_with("smb://") {
+        let mounted = network::smb_utils::resolve_smb_path(&file, &network::smb_utils::build_smb_mount_map());
+        if Path::new(&mounted).exists() { mounted } else { file.clone() }
+    } else {
+        file.clone()
+    };
+    if local_path.starts_with("smb://") {
+        // Codec du scan, sinon l'extension : un Opus distant reste illisible
+        let (cached_codec, bit_depth) = METADATA_CACHE.lock().ok()
+            .and_then(|cache| cache.entries.get(&file).map(|m| (m.codec.clone(), m.bit_depth)))
+            .unwrap_or_default();
+        let codec = cached_codec
+            .filter(|c| !c.eq_ignore_ascii_case("other"))
+            .map(|c| c.to_uppercase())
+            .unwrap_or_else(|| codec_from_extension(&file, bit_depth).to_string());
+        if matches!(codec.as_str(), "OPUS" | "MPC" | "WAVPACK" | "APE" | "OTHER") {
+            let reason = format!("{}: {}", audio_decoder::UNSUPPORTED_CODEC_ERROR, codec);
+            return audio_decoder::PlayabilitySummary { supported: false, codec, reason: Some(reason) };
+        }
+        return audio_decoder::PlayabilitySummary { supported: true, codec, reason: None };
+    }
+    if !Path::new(&local_path).exists() {
+        return audio_decoder::PlayabilitySummary {
+            supported: false,
+            codec: String::new(),
+            reason: Some(format!("File not found: {}", local_path)),
+        };
+    }
+
+    tokio::task::spawn_blocking(move || audio_decoder::check_playability(&local_path))
+        .await
+        .unwrap_or_else(|e| audio_decoder::PlayabilitySummary {
+            supported: false,
+            codec: String::new(),
+            reason: Some(e.to_string()),
+        })
+}
+
+/// Joue un fichier audio (non-bloquant). Retourne true si la file d'attente backend pilote
+/// ce track : c'est alors elle qui choisit le suivant (playback_queue_advanced), pas le frontend.
+/// `start_position` : démarre à cette position du track (reprise de session) au lieu du début
+#[tauri::command]
+async fn audio_play(path: String, start_position: Option<f64>) -> Result<bool, AudioError> {
+    let queue_driven = mark_queue_playing(&path);
+    play_path(path, start_position.filter(|p| p.is_finite() && *p > 0.0)).await?;
+    Ok(queue_driven)
+}
+
+/// Lance la lecture d'un chemin (local, SMB ou piste CUE), au début ou à `start` secondes
 /// Pour les paths SMB : téléchargement progressif en arrière-plan (retourne après 4MB dispo)
 /// La durée sera envoyée via l'événement playback_progress
-#[tauri::command]
-async fn audio_play(path: String) -> Result<(), String> {
+async fn play_path(path: String, start: Option<f64>) -> Result<(), AudioError> {
+    // APE : aucun décodeur, inutile de télécharger le fichier ou de solliciter l'engine
+    if let Some(codec) = audio_decoder::metadata_only_codec(Path::new(&path)) {
+        let message = format!("{}: {}", audio_decoder::UNSUPPORTED_CODEC_ERROR, codec);
+        emit_frontend_error("unsupported_codec", "Unsupported audio codec", &message);
+        return Err(AudioError::UnsupportedCodec(message));
+    }
+
+    // Piste virtuelle d'un CUE sheet (album.flac#track=3) : segment du fichier réel
+    if cue::split_virtual_path(&path).is_some() {
+        let track = cue::resolve_virtual_track(&path).ok_or_else(|| {
+            emit_frontend_error("file_not_found", "File not found", &path);
+            AudioError::FileNotFound(path.clone())
+        })?;
+        return play_cue_segment(track.file, track.start, track.end, start.unwrap_or(0.0));
+    }
+
     // Gestion des fichiers réseau SMB : téléchargement progressif puis play local
     if path.starts_with("smb://") {
         use std::sync::atomic::Ordering as AOrdering;
@@ -3305,11 +6356,10 @@ async fn audio_play(path: String) -> Result<(), String> {
             println!("[SMB FALLBACK] Playing via local mount: {}", &local_path[..local_path.len().min(100)]);
             if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
                 if let Some(ref engine) = *engine_guard {
-                    let result = engine.play(&local_path);
-                    return result.map_err(|e| format!("Playback error: {}", e));
+                    return engine.play_at(&local_path, start);
                 }
             }
-            return Err("Audio engine not initialized".into());
+            return Err(AudioError::NotInitialized);
         }
 
         // ── [TIMING T0] Entrée audio_play SMB ──────────────────────────────
@@ -3320,14 +6370,14 @@ async fn audio_play(path: String) -> Result<(), String> {
 
         // 1. Parse URI (rapide, synchrone)
         let (source_id, share, remote_path) = parse_smb_uri(&path)
-            .ok_or_else(|| format!("Invalid SMB URI: {}", path))?;
+            .ok_or_else(|| AudioError::NetworkUnavailable(format!("Invalid SMB URI: {}", path)))?;
 
         // 2. Récupérer source et credentials (synchrone, verrous courts)
         let source = {
             let sources = NETWORK_SOURCES.lock().map_err(|e| e.to_string())?;
             sources.iter().find(|s| s.id == source_id)
                 .cloned()
-                .ok_or_else(|| format!("Network source not found: {}", source_id))?
+                .ok_or_else(|| AudioError::NetworkUnavailable(format!("Network source not found: {}", source_id)))?
         };
 
         let password = if source.credentials.is_guest {
@@ -3338,7 +6388,7 @@ async fn audio_play(path: String) -> Result<(), String> {
                 Err(e) => {
                     #[cfg(debug_assertions)]
                     println!("[SMB TIMING] Keychain retrieve failed: {}", e);
-                    return Err(format!("SMB credentials not available for source {}: {}. Try reconnecting the NAS source.", source.name, e));
+                    return Err(AudioError::NetworkUnavailable(format!("SMB credentials not available for source {}: {}. Try reconnecting the NAS source.", source.name, e)));
                 }
             }
         };
@@ -3354,7 +6404,8 @@ async fn audio_play(path: String) -> Result<(), String> {
         // 3. Démarrer le téléchargement progressif en arrière-plan (retourne immédiatement)
         // cancel_previous = true : annule le download précédent → libère CONNECTION mutex en ~2ms
         let (temp_path, bytes_written, download_done) =
-            network::scanner::start_progressive_download(&source, &share, &remote_path, true)?;
+            network::scanner::start_progressive_download(&source, &share, &remote_path, true)
+                .map_err(AudioError::NetworkUnavailable)?;
         #[cfg(debug_assertions)]
         println!("[SMB TIMING] T+{}ms — progressive download started, waiting for 4MB…",
             t0.elapsed().as_millis());
@@ -3370,7 +6421,7 @@ async fn audio_play(path: String) -> Result<(), String> {
                 break;
             }
             if std::time::Instant::now() > deadline {
-                return Err(format!("Timeout: SMB download too slow for {}", path));
+                return Err(AudioError::NetworkUnavailable(format!("Timeout: SMB download too slow for {}", path)));
             }
             tokio::time::sleep(std::time::Duration::from_millis(20)).await;
         }
@@ -3384,95 +6435,216 @@ async fn audio_play(path: String) -> Result<(), String> {
         if available == 0 {
             let detail = network::scanner::take_last_download_error()
                 .unwrap_or_else(|| "unknown error (no details from download thread)".to_string());
-            return Err(format!("SMB download failed for {}: {}", path, detail));
+            return Err(AudioError::NetworkUnavailable(format!("SMB download failed for {}: {}", path, detail)));
         }
 
         // 5. Démarrer la lecture depuis le fichier local (peut encore être en cours de DL)
         let temp_str = temp_path.to_string_lossy().to_string();
         if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
             if let Some(ref engine) = *engine_guard {
-                let result = engine.play(&temp_str);
+                let result = engine.play_at(&temp_str, start);
                 #[cfg(debug_assertions)]
                 println!("[SMB TIMING] T+{}ms — engine.play() command sent ← TOTAL: {}ms",
                     t0.elapsed().as_millis(), t0.elapsed().as_millis());
                 return result;
             }
         }
-        return Err("Audio engine not initialized".to_string());
+        return Err(AudioError::NotInitialized);
+    }
+
+    // Comportement existant pour fichiers locaux
+    if !Path::new(&path).exists() {
+        emit_frontend_error("file_not_found", "File not found", &path);
+        return Err(AudioError::FileNotFound(path));
+    }
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            // Envoie la commande au thread audio (non-bloquant)
+            return engine.play_at(&path, start);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Joue un segment d'un fichier local, de `start` à `end` secondes (fin du fichier si None).
+/// Utilisé pour les pistes virtuelles des CUE sheets.
+#[tauri::command]
+fn audio_play_at(path: String, start: f64, end: Option<f64>) -> Result<(), AudioError> {
+    play_cue_segment(path, start, end, 0.0)
+}
+
+/// Segment `[start, end)` d'un fichier local, lu à partir de `offset` secondes dans le segment
+fn play_cue_segment(path: String, start: f64, end: Option<f64>, offset: f64) -> Result<(), AudioError> {
+    if !Path::new(&path).exists() {
+        emit_frontend_error("file_not_found", "File not found", &path);
+        return Err(AudioError::FileNotFound(path));
+    }
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.play_segment(&path, start, end, offset);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Active/désactive la capture pour le visualiseur de spectre (coûte du CPU dans le callback)
+#[tauri::command]
+fn set_spectrum_enabled(enabled: bool) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_spectrum_enabled(enabled);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Magnitudes (0.0–1.0) de `bins` bandes logarithmiques, lissées entre deux appels
+#[tauri::command]
+fn get_spectrum(bins: usize) -> Result<Vec<f32>, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_spectrum(bins));
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Remplissage du RingBuffer et underruns depuis le dernier Play (diagnostic des coupures
+/// en lecture réseau ; l'événement `buffer_underrun` est émis à chaque nouvelle coupure)
+#[tauri::command]
+fn get_buffer_health() -> Result<audio_engine::BufferHealth, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_buffer_health());
+        }
     }
+    Err(AudioError::NotInitialized)
+}
 
-    // Comportement existant pour fichiers locaux
-    if !Path::new(&path).exists() {
-        emit_frontend_error("file_not_found", "File not found", &path);
-        return Err(format!("File not found: {}", path));
-    }
+/// Charge du décodage et du resampling (temps moyen par buffer, µs) et underruns :
+/// permet de voir si verrouiller le rate de sortie éviterait un resampling coûteux
+#[tauri::command]
+fn get_engine_stats() -> Result<engine_stats::EngineStats, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
-            // Envoie la commande au thread audio (non-bloquant)
-            return engine.play(&path);
+            return Ok(engine.get_engine_stats());
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Met en pause la lecture
 #[tauri::command]
-fn audio_pause() -> Result<(), String> {
+fn audio_pause() -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.pause();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Reprend la lecture
 #[tauri::command]
-fn audio_resume() -> Result<(), String> {
+fn audio_resume() -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.resume();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Arrête la lecture
 #[tauri::command]
-fn audio_stop() -> Result<(), String> {
+fn audio_stop() -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.stop();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+/// Minuterie de mise en veille : fondu puis arrêt dans `minutes` minutes (réarmable).
+/// `finish_current_track` laisse le track en cours se terminer avant l'arrêt
+#[tauri::command]
+fn set_sleep_timer(minutes: u32, finish_current_track: Option<bool>) -> Result<(), AudioError> {
+    if minutes == 0 {
+        return Err(AudioError::InvalidArgument("Sleep timer must be at least 1 minute".to_string()));
+    }
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_sleep_timer(
+                std::time::Duration::from_secs(minutes as u64 * 60),
+                finish_current_track.unwrap_or(false),
+            );
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Annule la minuterie de mise en veille
+#[tauri::command]
+fn cancel_sleep_timer() -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.cancel_sleep_timer();
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
 }
 
 /// Seek à une position (en secondes)
 #[tauri::command]
-fn audio_seek(time: f64) -> Result<(), String> {
+fn audio_seek(time: f64) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.seek(time);
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+/// Seek à un pourcentage de la durée (0 - 100)
+#[tauri::command]
+fn audio_seek_percent(percent: f64) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.seek_percent(percent);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Seek relatif à la position courante (boutons -10s / +30s)
+#[tauri::command]
+fn audio_seek_relative(delta_seconds: f64) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.seek_relative(delta_seconds);
+        }
+    }
+    Err(AudioError::NotInitialized)
 }
 
-/// Définit le volume (0.0 - 1.0)
+/// Définit le volume (position du curseur 0.0 - 1.0, convertie en gain par la courbe de volume)
 #[tauri::command]
-fn audio_set_volume(volume: f32) -> Result<(), String> {
+fn audio_set_volume(volume: f32) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.set_volume(volume);
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Récupère l'état de lecture actuel
 #[tauri::command]
-fn audio_get_state() -> Result<AudioPlaybackState, String> {
+fn audio_get_state() -> Result<AudioPlaybackState, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return Ok(AudioPlaybackState {
@@ -3482,14 +6654,18 @@ fn audio_get_state() -> Result<AudioPlaybackState, String> {
             });
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Précharge le prochain track pour gapless playback.
 /// Pour les tracks SMB : télécharge progressivement vers un fichier temp, attend 4MB,
 /// puis passe le chemin local à l'engine — identique à audio_play sans annuler le download courant.
 #[tauri::command]
-async fn audio_preload_next(path: String) -> Result<(), String> {
+async fn audio_preload_next(path: String) -> Result<(), AudioError> {
+    // Pistes CUE : pas de gapless, l'enchaînement passe par playback_ended + audio_play
+    if cue::split_virtual_path(&path).is_some() {
+        return Ok(());
+    }
     if path.starts_with("smb://") {
         use std::sync::atomic::Ordering as AOrdering;
 
@@ -3502,25 +6678,24 @@ async fn audio_preload_next(path: String) -> Result<(), String> {
             println!("[SMB FALLBACK] Preloading via local mount: {}", &local_path[..local_path.len().min(100)]);
             if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
                 if let Some(ref engine) = *engine_guard {
-                    let result = engine.preload_next(&local_path);
-                    return result.map_err(|e| format!("Preload error: {}", e));
+                    return engine.preload_next(&local_path);
                 }
             }
-            return Err("Audio engine not initialized".into());
+            return Err(AudioError::NotInitialized);
         }
 
         println!("[SMB Preload] preload gapless démarré: {}", &path[..path.len().min(80)]);
 
         // 1. Parse URI
         let (source_id, share, remote_path) = parse_smb_uri(&path)
-            .ok_or_else(|| format!("Invalid SMB URI (preload): {}", path))?;
+            .ok_or_else(|| AudioError::NetworkUnavailable(format!("Invalid SMB URI (preload): {}", path)))?;
 
         // 2. Récupérer source et credentials
         let source = {
             let sources = NETWORK_SOURCES.lock().map_err(|e| e.to_string())?;
             sources.iter().find(|s| s.id == source_id)
                 .cloned()
-                .ok_or_else(|| format!("Network source not found (preload): {}", source_id))?
+                .ok_or_else(|| AudioError::NetworkUnavailable(format!("Network source not found (preload): {}", source_id)))?
         };
 
         let password = network::credentials::retrieve_password(&source.id).unwrap_or_default();
@@ -3536,7 +6711,8 @@ async fn audio_preload_next(path: String) -> Result<(), String> {
         // CURRENT_DOWNLOAD_CANCEL est tout de même mis à jour → un futur audio_play pourra
         // annuler ce preload si l'utilisateur change de track.
         let (temp_path, bytes_written, download_done) =
-            network::scanner::start_progressive_download(&source, &share, &remote_path, false)?;
+            network::scanner::start_progressive_download(&source, &share, &remote_path, false)
+                .map_err(AudioError::NetworkUnavailable)?;
 
         // 4. Attendre que 4MB soient disponibles (ou que le download soit complet).
         // Timeout de 30s pour les connexions lentes. Le download peut attendre que le
@@ -3561,83 +6737,602 @@ async fn audio_preload_next(path: String) -> Result<(), String> {
             tokio::time::sleep(std::time::Duration::from_millis(50)).await;
         }
 
-        // 5. Envoyer le chemin du fichier temp à l'engine pour le preload gapless
-        let temp_str = temp_path.to_string_lossy().to_string();
-        if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
-            if let Some(ref engine) = *engine_guard {
-                return engine.preload_next(&temp_str);
-            }
-        }
-        return Err("Audio engine not initialized".to_string());
-    }
+        // 5. Envoyer le chemin du fichier temp à l'engine pour le preload gapless
+        let temp_str = temp_path.to_string_lossy().to_string();
+        if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+            if let Some(ref engine) = *engine_guard {
+                return engine.preload_next(&temp_str);
+            }
+        }
+        return Err(AudioError::NotInitialized);
+    }
+
+    // Comportement existant pour fichiers locaux
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.preload_next(&path);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Active/désactive le gapless playback
+#[tauri::command]
+fn set_gapless_enabled(enabled: bool) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.set_gapless(enabled);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Définit la durée du crossfade entre deux tracks (ms, 0 = désactivé).
+/// Bornée à 12s ; persistée dans playback_settings.json.
+#[tauri::command]
+fn audio_set_crossfade(duration_ms: u32) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            let duration_ms = duration_ms.min(audio_engine::MAX_CROSSFADE_MS);
+            engine.set_crossfade(duration_ms)?;
+
+            let mut settings = load_playback_settings();
+            settings.crossfade_ms = duration_ms;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Sur Stop, remet le DAC au sample rate qu'il avait avant que Noir ne le change.
+/// Désactivable pour laisser le DAC au rate du dernier track. Persisté dans playback_settings.json.
+/// S'applique aussi après une pause de plus de 2 minutes (le stream est alors arrêté, Resume le relance)
+#[tauri::command]
+fn set_restore_sample_rate_on_stop(enabled: bool) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_restore_sample_rate_on_stop(enabled);
+
+            let mut settings = load_playback_settings();
+            settings.keep_sample_rate_on_stop = !enabled;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Qualité du resampler : "fast", "balanced" (défaut) ou "high".
+/// S'applique aux streams créés ensuite (prochain Play / seek) ; persistée dans playback_settings.json.
+#[tauri::command]
+fn set_resampler_quality(quality: String) -> Result<(), AudioError> {
+    let quality = resampler::ResamplerQuality::from_name(&quality).ok_or_else(|| {
+        AudioError::InvalidArgument(format!("Unknown resampler quality '{}' (expected fast, balanced or high)", quality))
+    })?;
+    resampler::set_quality(quality);
+
+    let mut settings = load_playback_settings();
+    settings.resampler_quality = quality;
+    save_playback_settings(&settings);
+    Ok(())
+}
+
+/// Taille du RingBuffer (1–30 s) et pré-remplissage avant lecture (5–50 %).
+/// Un buffer plus grand évite les underruns sur NAS au prix d'un démarrage plus lent ;
+/// appliqué à partir du prochain track
+#[tauri::command]
+fn set_buffer_config(seconds: f64, preroll_percent: f64) -> Result<(), AudioError> {
+    let config = audio_decoder::BufferConfig::new(seconds, preroll_percent)
+        .map_err(AudioError::InvalidArgument)?;
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_buffer_config(config);
+        }
+    }
+
+    let mut settings = load_playback_settings();
+    settings.buffer_seconds = Some(seconds);
+    settings.preroll_percent = Some(preroll_percent);
+    save_playback_settings(&settings);
+    Ok(())
+}
+
+/// Configuration du buffer en vigueur : { seconds, prerollPercent }
+#[tauri::command]
+fn get_buffer_config() -> Result<serde_json::Value, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            let config = engine.get_buffer_config();
+            return Ok(serde_json::json!({
+                "seconds": config.seconds,
+                "prerollPercent": config.pre_roll * 100.0,
+            }));
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Mode de répétition de la file : "off", "one" (rejoue le track) ou "all" (reboucle)
+#[tauri::command]
+fn set_repeat_mode(mode: String) -> Result<(), AudioError> {
+    let repeat_mode = queue::RepeatMode::from_name(&mode).ok_or_else(|| {
+        AudioError::InvalidArgument(format!("Unknown repeat mode '{}' (expected off, one or all)", mode))
+    })?;
+    let mut settings = load_playback_settings();
+    settings.repeat_mode = repeat_mode;
+    save_playback_settings(&settings);
+    sync_engine_queue_modes(&settings);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_repeat_mode() -> queue::RepeatMode {
+    load_playback_settings().repeat_mode
+}
+
+/// Shuffle de la file : le track suivant est tiré en tenant compte de l'historique d'écoute
+#[tauri::command]
+fn set_shuffle(enabled: bool) {
+    let mut settings = load_playback_settings();
+    settings.shuffle = enabled;
+    save_playback_settings(&settings);
+    sync_engine_queue_modes(&settings);
+}
+
+/// L'engine garde une copie des modes pour la session (pas de lecture disque côté audio)
+fn sync_engine_queue_modes(settings: &PlaybackSettings) {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_queue_modes(settings.shuffle, settings.repeat_mode);
+        }
+    }
+}
+
+#[tauri::command]
+fn get_shuffle() -> bool {
+    load_playback_settings().shuffle
+}
+
+/// Retourne la durée du crossfade actuelle (ms, 0 = désactivé)
+#[tauri::command]
+fn audio_get_crossfade() -> Result<u32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_crossfade_ms());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Courbe de volume : "linear" (le curseur est le gain) ou "logarithmic" (curseur en dB,
+/// progression perçue régulière sur toute la course). Persistée.
+#[tauri::command]
+fn set_volume_curve(mode: String) -> Result<(), AudioError> {
+    let curve = output_stage::VolumeCurve::from_name(&mode).ok_or_else(|| {
+        AudioError::InvalidArgument(format!("Unknown volume curve '{}' (expected linear or logarithmic)", mode))
+    })?;
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_volume_curve(curve);
+
+            let mut settings = load_playback_settings();
+            settings.volume_curve = curve;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+#[tauri::command]
+fn get_volume_curve() -> Result<output_stage::VolumeCurve, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_volume_curve());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Répartition des canaux en sortie : "stereo" (inchangée), "mono" (moyenne L+R),
+/// "left" / "right" (un canal dupliqué sur les deux). Persistée.
+#[tauri::command]
+fn set_channel_mode(mode: String) -> Result<(), AudioError> {
+    let channel_mode = output_stage::ChannelMode::from_name(&mode).ok_or_else(|| {
+        AudioError::InvalidArgument(format!("Unknown channel mode '{}' (expected stereo, mono, left or right)", mode))
+    })?;
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_channel_mode(channel_mode);
+
+            let mut settings = load_playback_settings();
+            settings.channel_mode = channel_mode;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+#[tauri::command]
+fn get_channel_mode() -> Result<output_stage::ChannelMode, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_channel_mode());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Balance de sortie : -1.0 (tout à gauche) … +1.0 (tout à droite), 0.0 = centrée.
+/// Retourne la valeur appliquée après bornage. Ignorée en Hog Mode. Persistée.
+#[tauri::command]
+fn set_balance(balance: f32) -> Result<f32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            let balance = engine.set_balance(balance)?;
+
+            let mut settings = load_playback_settings();
+            settings.balance = balance;
+            save_playback_settings(&settings);
+            return Ok(balance);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+#[tauri::command]
+fn get_balance() -> Result<f32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_balance());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Largeur stéréo (mid/side) : 0.0 = mono, 1.0 = inchangée, jusqu'à 2.0 = élargie.
+/// Retourne la valeur appliquée après bornage. Ignorée en Hog Mode. Persistée.
+#[tauri::command]
+fn set_stereo_width(width: f32) -> Result<f32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            let width = engine.set_stereo_width(width)?;
+
+            let mut settings = load_playback_settings();
+            settings.stereo_width = Some(width);
+            save_playback_settings(&settings);
+            return Ok(width);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+#[tauri::command]
+fn get_stereo_width() -> Result<f32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_stereo_width());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Active/désactive le limiteur doux de sortie (évite l'écrêtage dur quand EQ et gain
+/// dépassent la pleine échelle). Désactivé, les samples ne sont pas touchés. Persisté.
+#[tauri::command]
+fn set_limiter_enabled(enabled: bool) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.set_limiter_enabled(enabled);
+
+            let mut settings = load_playback_settings();
+            settings.limiter_enabled = enabled;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Seuil du limiteur en dBFS (-12 à -0.1). Retourne le seuil appliqué après bornage. Persisté.
+#[tauri::command]
+fn set_limiter_threshold(threshold_db: f32) -> Result<f32, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            let threshold_db = engine.set_limiter_threshold_db(threshold_db)?;
+
+            let mut settings = load_playback_settings();
+            settings.limiter_threshold_db = Some(threshold_db);
+            save_playback_settings(&settings);
+            return Ok(threshold_db);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// État du limiteur : (activé, seuil en dBFS)
+#[tauri::command]
+fn get_limiter_settings() -> Result<(bool, f32), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok((engine.is_limiter_enabled(), engine.get_limiter_threshold_db()));
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Change la vitesse de lecture (0.5x–2.0x, par pas de 0.05).
+/// Première version : la vitesse est obtenue par resampling, le pitch varie donc
+/// avec elle (effet "bande magnétique"). La position reste en temps du fichier.
+#[tauri::command]
+fn audio_set_playback_speed(speed: f32) -> Result<(), AudioError> {
+    if !(audio_engine::MIN_PLAYBACK_SPEED..=audio_engine::MAX_PLAYBACK_SPEED).contains(&speed) {
+        return Err(AudioError::InvalidArgument(format!(
+            "Playback speed must be between {} and {}",
+            audio_engine::MIN_PLAYBACK_SPEED, audio_engine::MAX_PLAYBACK_SPEED
+        )));
+    }
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.set_playback_speed(speed);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Boucle A-B : à la position `end`, la lecture revient à `start` (secondes).
+/// Reste active à travers pause/resume ; effacée au lancement d'un autre track.
+#[tauri::command]
+fn audio_set_ab_loop(start: f64, end: f64) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.set_ab_loop(start, end);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Désactive la boucle A-B ; la lecture continue normalement depuis la position courante
+#[tauri::command]
+fn audio_clear_ab_loop() -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.clear_ab_loop();
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Réglages de lecture persistés à côté des réglages EQ
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct PlaybackSettings {
+    /// Durée du crossfade en ms (0 = désactivé)
+    #[serde(default)]
+    crossfade_ms: u32,
+    /// Laisse le DAC au rate du dernier track sur Stop (par défaut il est restauré)
+    #[serde(default)]
+    keep_sample_rate_on_stop: bool,
+    /// Qualité du resampler (fast / balanced / high)
+    #[serde(default)]
+    resampler_quality: resampler::ResamplerQuality,
+    /// Répétition appliquée à l'enchaînement automatique de la file (off / one / all)
+    #[serde(default)]
+    repeat_mode: queue::RepeatMode,
+    #[serde(default)]
+    shuffle: bool,
+    /// Taille du RingBuffer (secondes) et pré-remplissage (%) ; None = valeurs par défaut
+    #[serde(default)]
+    buffer_seconds: Option<f64>,
+    #[serde(default)]
+    preroll_percent: Option<f64>,
+    /// Répartition des canaux en sortie (stereo / mono / left / right)
+    #[serde(default)]
+    channel_mode: output_stage::ChannelMode,
+    /// Balance (-1.0 … 1.0) et largeur stéréo (None = 1.0, inchangée)
+    #[serde(default)]
+    balance: f32,
+    #[serde(default)]
+    stereo_width: Option<f32>,
+    /// Limiteur doux de sortie ; seuil en dBFS (None = output_stage::DEFAULT_LIMITER_THRESHOLD_DB)
+    #[serde(default)]
+    limiter_enabled: bool,
+    #[serde(default)]
+    limiter_threshold_db: Option<f32>,
+    /// Sample rate de sortie imposé (None = bit-perfect automatique)
+    #[serde(default)]
+    locked_sample_rate: Option<u32>,
+    /// Correspondance curseur → gain (logarithmique par défaut)
+    #[serde(default)]
+    volume_curve: output_stage::VolumeCurve,
+}
+
+fn get_playback_settings_path() -> PathBuf {
+    get_data_dir().join("playback_settings.json")
+}
 
-    // Comportement existant pour fichiers locaux
-    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
-        if let Some(ref engine) = *engine_guard {
-            return engine.preload_next(&path);
-        }
+fn load_playback_settings() -> PlaybackSettings {
+    let path = get_playback_settings_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        PlaybackSettings::default()
     }
-    Err("Audio engine not initialized".to_string())
 }
 
-/// Active/désactive le gapless playback
-#[tauri::command]
-fn set_gapless_enabled(enabled: bool) -> Result<(), String> {
-    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
-        if let Some(ref engine) = *engine_guard {
-            return engine.set_gapless(enabled);
+fn save_playback_settings(settings: &PlaybackSettings) {
+    let content = serde_json::to_string_pretty(settings).unwrap_or_default();
+    save_file_secure(&get_playback_settings_path(), &content);
+}
+
+/// Applique les réglages de lecture sauvegardés à l'engine (au démarrage)
+fn apply_playback_settings(engine: &AudioEngine) {
+    let settings = load_playback_settings();
+    if settings.crossfade_ms > 0 {
+        let _ = engine.set_crossfade(settings.crossfade_ms);
+    }
+    engine.set_restore_sample_rate_on_stop(!settings.keep_sample_rate_on_stop);
+    resampler::set_quality(settings.resampler_quality);
+    engine.set_channel_mode(settings.channel_mode);
+    engine.set_volume_curve(settings.volume_curve);
+    engine.set_queue_modes(settings.shuffle, settings.repeat_mode);
+    let _ = engine.set_balance(settings.balance);
+    if let Some(width) = settings.stereo_width {
+        let _ = engine.set_stereo_width(width);
+    }
+    engine.set_limiter_enabled(settings.limiter_enabled);
+    if let Some(threshold_db) = settings.limiter_threshold_db {
+        let _ = engine.set_limiter_threshold_db(threshold_db);
+    }
+    if let Some(rate) = settings.locked_sample_rate {
+        if let Err(e) = engine.lock_sample_rate(Some(rate)) {
+            eprintln!("[Playback] Ignoring saved sample rate lock: {}", e);
+        }
+    }
+    if let (Some(seconds), Some(preroll_percent)) = (settings.buffer_seconds, settings.preroll_percent) {
+        match audio_decoder::BufferConfig::new(seconds, preroll_percent) {
+            Ok(config) => engine.set_buffer_config(config),
+            Err(e) => eprintln!("[Playback] Ignoring saved buffer config: {}", e),
         }
     }
-    Err("Audio engine not initialized".to_string())
 }
 
 // === COMMANDES AUDIO BACKEND (Bit-Perfect, Device Control) ===
 
 /// Liste tous les devices audio de sortie disponibles (depuis le cache)
 #[tauri::command]
-fn get_audio_devices() -> Result<Vec<audio::DeviceInfo>, String> {
+fn get_audio_devices() -> Result<Vec<audio::DeviceInfo>, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.list_devices();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Rafraîchit le cache devices depuis l'OS et retourne la liste mise à jour
 #[tauri::command]
-fn refresh_audio_devices() -> Result<Vec<audio::DeviceInfo>, String> {
+fn refresh_audio_devices() -> Result<Vec<audio::DeviceInfo>, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.refresh_devices();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Récupère le device audio de sortie actuel
 #[tauri::command]
-fn get_current_audio_device() -> Result<audio::DeviceInfo, String> {
+fn get_current_audio_device() -> Result<audio::DeviceInfo, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.current_device();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
-/// Change le device audio de sortie
+/// Change le device audio de sortie.
+/// `remember` : choix explicite dans les réglages, persisté (Noir ne suit plus la sortie
+/// système). Sans lui (menu du player, bascule hot-plug), le changement vaut pour la session
 #[tauri::command]
-fn set_audio_device(device_id: String) -> Result<(), String> {
+fn set_audio_device(device_id: String, remember: bool) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
-            return engine.set_output_device(&device_id).map_err(|e| {
-                emit_frontend_error("device_switch_failed", "Audio device unavailable", &e);
+            engine.set_output_device(&device_id).map_err(|e| {
+                emit_frontend_error("device_switch_failed", "Audio device unavailable", &e.to_string());
                 e
-            });
+            })?;
+
+            if !remember {
+                return Ok(());
+            }
+
+            // Choix manuel : réappliqué au prochain démarrage
+            SAVED_DEVICE_UNAVAILABLE.store(false, std::sync::atomic::Ordering::Relaxed);
+            let name = engine.current_device().map(|device| device.name).unwrap_or_default();
+            let mut config = load_config();
+            config.follow_system_default = Some(false);
+            config.audio_device = Some(SavedAudioDevice { id: device_id, name });
+            save_config(&config);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Revient à la sortie par défaut du système (comportement par défaut) ou réapplique
+/// la dernière sortie choisie manuellement
+#[tauri::command]
+fn set_follow_system_default(enabled: bool) -> Result<(), AudioError> {
+    let mut config = load_config();
+    config.follow_system_default = Some(enabled);
+    save_config(&config);
+
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            if enabled {
+                engine.follow_system_default();
+            } else if let Some(ref saved) = config.audio_device {
+                engine.set_output_device(&saved.id)?;
+            }
+            return Ok(());
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+// Sortie mémorisée introuvable au démarrage (l'événement peut précéder les listeners du frontend)
+static SAVED_DEVICE_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Préférence de sortie audio persistée
+#[tauri::command]
+fn get_audio_device_preference() -> serde_json::Value {
+    let config = load_config();
+    serde_json::json!({
+        "followSystemDefault": config.follow_system_default.unwrap_or(true),
+        "deviceId": config.audio_device.as_ref().map(|d| d.id.clone()),
+        "deviceName": config.audio_device.as_ref().map(|d| d.name.clone()),
+        "savedDeviceUnavailable": SAVED_DEVICE_UNAVAILABLE.load(std::sync::atomic::Ordering::Relaxed),
+    })
+}
+
+/// Au démarrage : réapplique la sortie choisie manuellement si elle est toujours branchée
+/// (même ID, sinon même nom). Absente : Noir suit la sortie par défaut pour cette session,
+/// la préférence est gardée et `saved_device_unavailable` prévient le frontend
+fn restore_audio_device(engine: &AudioEngine, app: &tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let config = load_config();
+    if config.follow_system_default.unwrap_or(true) {
+        return;
+    }
+    let Some(saved) = config.audio_device else {
+        return;
+    };
+
+    let devices = engine.refresh_devices().unwrap_or_default();
+    let device = devices.iter()
+        .find(|d| d.id == saved.id)
+        .or_else(|| devices.iter().find(|d| !saved.name.is_empty() && d.name == saved.name));
+    let restored = match device {
+        Some(device) => engine.set_output_device(&device.id)
+            .map_err(|e| eprintln!("[Audio] Failed to restore output device {}: {}", device.name, e))
+            .is_ok(),
+        None => false,
+    };
+
+    if restored {
+        #[cfg(debug_assertions)]
+        println!("[Audio] Restored output device: {}", saved.name);
+    } else {
+        println!("[Audio] Saved output device '{}' unavailable, following system default", saved.name);
+        SAVED_DEVICE_UNAVAILABLE.store(true, std::sync::atomic::Ordering::Relaxed);
+        let _ = app.emit("saved_device_unavailable", &saved);
+    }
 }
 
 /// Récupère l'ID du device de sortie par défaut du système macOS
@@ -3658,61 +7353,112 @@ fn get_system_default_device_id() -> Option<String> {
 
 /// Récupère le sample rate actuel du device
 #[tauri::command]
-fn get_audio_sample_rate() -> Result<u32, String> {
+fn get_audio_sample_rate() -> Result<u32, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.current_sample_rate();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Active/désactive le mode exclusif (Hog Mode sur macOS)
 /// En mode exclusif, Noir prend le contrôle total du DAC pour un playback bit-perfect
 #[tauri::command]
-fn set_exclusive_mode(enabled: bool) -> Result<(), String> {
+fn set_exclusive_mode(enabled: bool) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.set_exclusive_mode(enabled).map_err(|e| {
                 emit_frontend_error(
                     "exclusive_mode_failed",
                     "Exclusive mode failed — check that no other app is using the DAC",
-                    &e,
+                    &e.to_string(),
                 );
                 e
             });
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Vérifie si le mode exclusif est actif
 #[tauri::command]
-fn is_exclusive_mode() -> Result<bool, String> {
+fn is_exclusive_mode() -> Result<bool, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return Ok(engine.is_exclusive_mode());
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+/// Active/désactive la sortie en entiers au format natif du DAC (16/24/32 bits).
+/// Erreur si le DAC n'offre aucun format entier ; désactivé = sortie float 32 bits.
+#[tauri::command]
+fn set_integer_mode(enabled: bool) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.set_integer_mode(enabled);
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Verrouille le sample rate de sortie (tout est resamplé vers lui) ou, avec None,
+/// revient au bit-perfect automatique. Persisté dans playback_settings.json.
+#[tauri::command]
+fn lock_sample_rate(rate: Option<u32>) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.lock_sample_rate(rate)?;
+
+            let mut settings = load_playback_settings();
+            settings.locked_sample_rate = rate;
+            save_playback_settings(&settings);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Sample rate verrouillé (None = automatique)
+#[tauri::command]
+fn get_locked_sample_rate() -> Result<Option<u32>, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.get_locked_sample_rate());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Indique si le stream courant est bit-perfect (resampling, Hog Mode, formats)
+#[tauri::command]
+fn get_playback_quality() -> Result<audio_engine::PlaybackQuality, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return engine.get_playback_quality().ok_or(AudioError::NoTrackLoaded);
+        }
+    }
+    Err(AudioError::NotInitialized)
 }
 
 /// Retourne le statut détaillé du Hog Mode (device, PID, conflit)
 #[tauri::command]
-fn hog_mode_status() -> Result<crate::audio::HogModeStatus, String> {
+fn hog_mode_status() -> Result<crate::audio::HogModeStatus, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return engine.hog_mode_status();
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 // === COMMANDES ÉGALISEUR (EQ 8 BANDES) ===
 
 /// Active ou désactive l'égaliseur
 #[tauri::command]
-fn set_eq_enabled(enabled: bool) -> Result<(), String> {
+fn set_eq_enabled(enabled: bool) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             engine.eq_state.set_enabled(enabled);
@@ -3721,12 +7467,12 @@ fn set_eq_enabled(enabled: bool) -> Result<(), String> {
             return Ok(());
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
 }
 
 /// Met à jour les gains de toutes les bandes EQ (en dB, -12 à +12)
 #[tauri::command]
-fn set_eq_bands(gains: Vec<f32>) -> Result<(), String> {
+fn set_eq_bands(gains: Vec<f32>) -> Result<(), AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             engine.eq_state.set_all_gains(&gains);
@@ -3735,27 +7481,66 @@ fn set_eq_bands(gains: Vec<f32>) -> Result<(), String> {
             return Ok(());
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+/// Règle le préampli global de l'EQ (en dB, -12 à +12), appliqué avant les bandes
+#[tauri::command]
+fn set_eq_preamp(gain_db: f32) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.eq_state.set_preamp(gain_db);
+            save_eq_settings(&engine.eq_state);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
 }
 
 /// Retourne l'état actuel de l'EQ
 #[tauri::command]
-fn get_eq_state() -> Result<EqStateResponse, String> {
+fn get_eq_state() -> Result<EqStateResponse, AudioError> {
     if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
         if let Some(ref engine) = *engine_guard {
             return Ok(EqStateResponse {
                 enabled: engine.eq_state.is_enabled(),
                 gains: engine.eq_state.get_all_gains().to_vec(),
+                preamp: engine.eq_state.get_preamp(),
             });
         }
     }
-    Err("Audio engine not initialized".to_string())
+    Err(AudioError::NotInitialized)
+}
+
+/// Retourne la définition des bandes EQ (fréquence, gain, Q) pour l'affichage
+#[tauri::command]
+fn get_eq_bands() -> Result<Vec<eq::EqBand>, AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            return Ok(engine.eq_state.get_bands());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Change la fréquence centrale d'une bande (EQ paramétrique, 20 Hz – 20 kHz)
+#[tauri::command]
+fn set_eq_band_frequency(index: usize, frequency: f32) -> Result<(), AudioError> {
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.eq_state.set_frequency(index, frequency).map_err(AudioError::InvalidArgument)?;
+            save_eq_settings(&engine.eq_state);
+            return Ok(());
+        }
+    }
+    Err(AudioError::NotInitialized)
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct EqStateResponse {
     enabled: bool,
     gains: Vec<f32>,
+    preamp: f32,
 }
 
 /// Sauvegarde les paramètres EQ dans le fichier settings
@@ -3765,6 +7550,8 @@ fn save_eq_settings(eq_state: &eq::EqSharedState) {
     let settings = serde_json::json!({
         "enabled": eq_state.is_enabled(),
         "gains": eq_state.get_all_gains(),
+        "preamp": eq_state.get_preamp(),
+        "bands": eq_state.get_bands(),
     });
     if let Ok(json) = serde_json::to_string_pretty(&settings) {
         save_file_secure(&eq_file, &json);
@@ -3786,13 +7573,338 @@ fn load_eq_settings(eq_state: &eq::EqSharedState) {
                     .collect();
                 eq_state.set_all_gains(&gain_values);
             }
+            if let Some(preamp) = settings.get("preamp").and_then(|v| v.as_f64()) {
+                eq_state.set_preamp(preamp as f32);
+            }
+            // Fréquences personnalisées (absentes des anciens fichiers → valeurs par défaut)
+            if let Some(bands) = settings.get("bands").and_then(|v| v.as_array()) {
+                for (i, band) in bands.iter().enumerate() {
+                    if let Some(freq) = band.get("frequency").and_then(|v| v.as_f64()) {
+                        let _ = eq_state.set_frequency(i, freq as f32);
+                    }
+                }
+            }
             #[cfg(debug_assertions)]
-            println!("[EQ] Settings loaded: enabled={}, gains={:?}",
-                eq_state.is_enabled(), eq_state.get_all_gains());
+            println!("[EQ] Settings loaded: enabled={}, preamp={} dB, gains={:?}",
+                eq_state.is_enabled(), eq_state.get_preamp(), eq_state.get_all_gains());
+        }
+    }
+}
+
+// === PRESETS EQ ===
+
+#[derive(Serialize, Deserialize, Clone)]
+struct EqPreset {
+    name: String,
+    gains: Vec<f32>,
+    #[serde(rename = "isSystem", default)]
+    is_system: bool,  // True pour les presets intégrés - non supprimables
+}
+
+// Fichier eq_presets.json : uniquement les presets utilisateur
+#[derive(Serialize, Deserialize, Default)]
+struct EqPresetsData {
+    presets: Vec<EqPreset>,
+}
+
+fn load_eq_presets() -> EqPresetsData {
+    let path = get_data_dir().join("eq_presets.json");
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        EqPresetsData::default()
+    }
+}
+
+fn save_eq_presets(data: &EqPresetsData) {
+    let path = get_data_dir().join("eq_presets.json");
+    let content = serde_json::to_string_pretty(data).unwrap_or_default();
+    save_file_secure(&path, &content);
+}
+
+// Presets intégrés puis presets utilisateur
+fn all_eq_presets() -> Vec<EqPreset> {
+    let mut presets: Vec<EqPreset> = eq::EQ_BUILTIN_PRESETS.iter()
+        .map(|(name, gains)| EqPreset {
+            name: name.to_string(),
+            gains: gains.to_vec(),
+            is_system: true,
+        })
+        .collect();
+    presets.extend(load_eq_presets().presets);
+    presets
+}
+
+/// Noms de tous les presets EQ (intégrés puis utilisateur)
+#[tauri::command]
+fn list_eq_presets() -> Vec<String> {
+    all_eq_presets().into_iter().map(|p| p.name).collect()
+}
+
+/// Tous les presets EQ avec leurs gains (pour détecter le preset actif côté UI)
+#[tauri::command]
+fn get_eq_presets() -> Vec<EqPreset> {
+    all_eq_presets()
+}
+
+/// Enregistre les gains actuels sous un nom (remplace un preset utilisateur du même nom)
+#[tauri::command]
+fn save_eq_preset(name: String) -> Result<(), AudioError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AudioError::InvalidArgument("Preset name cannot be empty".to_string()));
+    }
+    if eq::EQ_BUILTIN_PRESETS.iter().any(|(builtin, _)| builtin.eq_ignore_ascii_case(&name)) {
+        return Err(AudioError::InvalidArgument(format!("'{}' is a built-in preset", name)));
+    }
+
+    let gains = {
+        let engine_guard = AUDIO_ENGINE.lock().map_err(|_| AudioError::NotInitialized)?;
+        let engine = engine_guard.as_ref().ok_or(AudioError::NotInitialized)?;
+        engine.eq_state.get_all_gains().to_vec()
+    };
+
+    let mut data = load_eq_presets();
+    data.presets.retain(|p| p.name != name);
+    data.presets.push(EqPreset { name, gains, is_system: false });
+    save_eq_presets(&data);
+    Ok(())
+}
+
+/// Applique un preset et le persiste comme réglage EQ actif. Retourne ses gains.
+#[tauri::command]
+fn load_eq_preset(name: String) -> Result<Vec<f32>, AudioError> {
+    let preset = all_eq_presets().into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| AudioError::InvalidArgument(format!("EQ preset not found: {}", name)))?;
+
+    if let Ok(engine_guard) = AUDIO_ENGINE.lock() {
+        if let Some(ref engine) = *engine_guard {
+            engine.eq_state.set_all_gains(&preset.gains);
+            save_eq_settings(&engine.eq_state);
+            return Ok(engine.eq_state.get_all_gains().to_vec());
+        }
+    }
+    Err(AudioError::NotInitialized)
+}
+
+/// Supprime un preset utilisateur (impossible pour les presets intégrés)
+#[tauri::command]
+fn delete_eq_preset(name: String) -> bool {
+    let mut data = load_eq_presets();
+    let initial_len = data.presets.len();
+    data.presets.retain(|p| p.name != name);
+
+    if data.presets.len() < initial_len {
+        save_eq_presets(&data);
+        return true;
+    }
+
+    false
+}
+
+// === COMMANDES FILE D'ATTENTE ===
+
+/// Sauvegarde la file et notifie le frontend (queue_changed)
+fn queue_changed(queue: &queue::PlayQueue) {
+    use tauri::Emitter;
+
+    save_queue(queue);
+    if let Ok(handle_guard) = APP_HANDLE.lock() {
+        if let Some(ref app) = *handle_guard {
+            let _ = app.emit("queue_changed", queue);
+        }
+    }
+}
+
+/// Applique une modification à la file, puis sauvegarde et notifie
+fn update_queue<R>(edit: impl FnOnce(&mut queue::PlayQueue) -> R) -> Result<R, String> {
+    let mut queue = PLAY_QUEUE.lock().map_err(|e| e.to_string())?;
+    let result = edit(&mut queue);
+    queue_changed(&queue);
+    Ok(result)
+}
+
+/// Historique et notes pour le tirage shuffle. Copiés AVANT de verrouiller PLAY_QUEUE :
+/// LISTENING_HISTORY et RATINGS ne sont jamais pris pendant que la file est tenue
+#[derive(Default)]
+struct ShuffleWeights {
+    last_played: HashMap<String, u64>,
+    ratings: ratings::RatingsCache,
+}
+
+impl ShuffleWeights {
+    /// Vide hors shuffle (aucun tirage pondéré)
+    fn snapshot(shuffle: bool) -> Self {
+        if !shuffle {
+            return Self::default();
+        }
+        Self {
+            last_played: last_played_timestamps(),
+            ratings: RATINGS.lock().map(|cache| cache.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Track à enchaîner selon les réglages repeat / shuffle. En shuffle, le tirage parmi les
+/// candidats est pondéré par l'historique (les tracks écoutés récemment sortent moins)
+/// et par la note (les tracks bien notés sortent un peu plus)
+fn next_queue_index(
+    queue: &queue::PlayQueue,
+    repeat: queue::RepeatMode,
+    shuffle: bool,
+    weights: &ShuffleWeights,
+) -> Option<usize> {
+    queue.next_index(repeat, shuffle, |candidates| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let weighted = candidates.iter()
+            .map(|&i| {
+                let path = &queue.tracks[i];
+                let weight = mixes::recency_weight(weights.last_played.get(path).copied(), now)
+                    * ratings::shuffle_weight(weights.ratings.get(path));
+                (i, weight)
+            })
+            .collect();
+        mixes::weighted_sample(weighted, 1, &mut mixes::Rng::from_clock()).pop()
+    })
+}
+
+/// Appelé par l'engine en fin de track : précharge le track qui suivra (gapless) si la
+/// lecture suit la file. Retourne true si un preload a été demandé
+pub(crate) fn preload_upcoming_queue_track() -> bool {
+    let settings = load_playback_settings();
+    let weights = ShuffleWeights::snapshot(settings.shuffle);
+    let upcoming = PLAY_QUEUE.lock().ok().and_then(|mut queue| {
+        if !queue.playing {
+            return None;
+        }
+        let index = next_queue_index(&queue, settings.repeat_mode, settings.shuffle, &weights)?;
+        queue.preloaded = Some(index);
+        Some(queue.tracks[index].clone())
+    });
+    let Some(path) = upcoming else {
+        return false;
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = audio_preload_next(path).await {
+            eprintln!("[Queue] Preload failed: {}", e);
+        }
+    });
+    true
+}
+
+/// Lecture lancée via audio_play : la file pilote l'enchaînement si c'est son track courant.
+/// Retourne true dans ce cas (le frontend n'enchaîne alors pas lui-même)
+fn mark_queue_playing(path: &str) -> bool {
+    PLAY_QUEUE.lock().map(|mut queue| {
+        queue.playing = queue.current_track() == Some(path);
+        queue.playing
+    }).unwrap_or(false)
+}
+
+/// playback_ended : si la file pilote la lecture, avance au track suivant (repeat / shuffle)
+/// et l'émet (playback_queue_advanced) ; le frontend le joue via audio_play. Sinon le
+/// frontend enchaîne avec sa propre file : un seul des deux côtés avance
+fn advance_queue_on_end() {
+    use tauri::Emitter;
+
+    let settings = load_playback_settings();
+    let weights = ShuffleWeights::snapshot(settings.shuffle);
+    let next = PLAY_QUEUE.lock().ok().and_then(|mut queue| {
+        if !queue.playing {
+            return None;
+        }
+        // Preload pas consommé (gapless désactivé, piste CUE) : il a déjà fixé le suivant
+        let index = queue.preloaded
+            .or_else(|| next_queue_index(&queue, settings.repeat_mode, settings.shuffle, &weights));
+        match index {
+            Some(index) => {
+                queue.move_to(index);
+                queue_changed(&queue);
+                queue.current_track().map(str::to_string)
+            }
+            None => {
+                queue.playing = false;
+                None
+            }
+        }
+    });
+    let Some(path) = next else {
+        return;
+    };
+
+    if let Ok(handle_guard) = APP_HANDLE.lock() {
+        if let Some(ref app) = *handle_guard {
+            let _ = app.emit("playback_queue_advanced", &path);
+        }
+    }
+}
+
+/// Appelé par l'engine après une transition gapless : la file suit le track enchaîné
+pub(crate) fn queue_gapless_transition() {
+    if let Ok(mut queue) = PLAY_QUEUE.lock() {
+        if queue.on_gapless_transition() {
+            queue_changed(&queue);
         }
     }
 }
 
+/// Remplace la file. `start_index` marque le track en cours de lecture (None = pas encore commencé)
+#[tauri::command]
+fn set_queue(paths: Vec<String>, start_index: Option<usize>) -> Result<queue::PlayQueue, String> {
+    update_queue(|queue| {
+        queue.set(paths, start_index);
+        queue.clone()
+    })
+}
+
+#[tauri::command]
+fn get_queue() -> queue::PlayQueue {
+    PLAY_QUEUE.lock().map(|queue| queue.clone()).unwrap_or_default()
+}
+
+/// Avance d'un track et renvoie le chemin à jouer (None en fin de file). Suit le shuffle ;
+/// en repeat "one", passer au suivant reste possible (comme en repeat "all").
+/// La lecture reste à la charge du frontend via audio_play
+#[tauri::command]
+fn queue_next() -> Result<Option<String>, String> {
+    let settings = load_playback_settings();
+    let repeat = match settings.repeat_mode {
+        queue::RepeatMode::One => queue::RepeatMode::All,
+        mode => mode,
+    };
+    let weights = ShuffleWeights::snapshot(settings.shuffle);
+    update_queue(|queue| {
+        let index = next_queue_index(queue, repeat, settings.shuffle, &weights)?;
+        queue.move_to(index);
+        queue.current_track().map(str::to_string)
+    })
+}
+
+/// Recule d'un track et renvoie le chemin à jouer (le premier track se relance)
+#[tauri::command]
+fn queue_prev() -> Result<Option<String>, String> {
+    update_queue(|queue| queue.back().map(str::to_string))
+}
+
+/// Ajoute un track en fin de file
+#[tauri::command]
+fn enqueue(path: String) -> Result<queue::PlayQueue, String> {
+    update_queue(|queue| {
+        queue.enqueue(path);
+        queue.clone()
+    })
+}
+
+#[tauri::command]
+fn clear_queue() -> Result<(), String> {
+    update_queue(|queue| queue.clear())
+}
+
 // === COMMANDES HISTORIQUE D'ÉCOUTE ===
 
 // Enregistre une lecture
@@ -3817,20 +7929,67 @@ fn record_play(path: String, artist: String, album: String, title: String) {
 
         // Met à jour last_played
         history.last_played = Some(entry.clone());
+        history.play_counts.add(&entry);
 
         // Ajoute en début de liste (plus récent en premier)
         history.entries.insert(0, entry);
 
-        // Limite l'historique à 1000 entrées pour éviter un fichier trop gros
-        if history.entries.len() > 1000 {
-            history.entries.truncate(1000);
-        }
+        // Limite l'historique (HISTORY_LIMIT) pour éviter un fichier trop gros
+        truncate_history(&mut history, HISTORY_LIMIT.load(std::sync::atomic::Ordering::Relaxed));
 
         // Sauvegarde immédiatement
         save_listening_history(&history);
     }
 }
 
+// Ne garde que les `limit` écoutes les plus récentes (played_paths n'est jamais tronqué)
+fn truncate_history(history: &mut ListeningHistory, limit: usize) {
+    if history.entries.len() > limit {
+        let dropped = history.entries.split_off(limit);
+        for old_entry in &dropped {
+            history.play_counts.remove(old_entry);
+        }
+    }
+}
+
+/// Nombre d'écoutes conservées dans l'historique (entre MIN_HISTORY_LIMIT et
+/// MAX_HISTORY_LIMIT, plafonné). Un historique plus long est tronqué aussitôt.
+/// Retourne la limite appliquée
+#[tauri::command]
+fn set_history_limit(limit: usize) -> Result<usize, String> {
+    if limit < MIN_HISTORY_LIMIT {
+        return Err(format!("History limit must be at least {}", MIN_HISTORY_LIMIT));
+    }
+    let limit = limit.min(MAX_HISTORY_LIMIT);
+    HISTORY_LIMIT.store(limit, std::sync::atomic::Ordering::Relaxed);
+    let mut config = load_config();
+    config.history_limit = Some(limit);
+    save_config(&config);
+
+    if let Ok(mut history) = LISTENING_HISTORY.lock() {
+        if history.entries.len() > limit {
+            truncate_history(&mut history, limit);
+            save_listening_history(&history);
+        }
+    }
+    Ok(limit)
+}
+
+#[tauri::command]
+fn get_history_limit() -> usize {
+    HISTORY_LIMIT.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+// Efface tout l'historique d'écoute : écoutes, dernière écoute, tracks déjà écoutés et
+// compteurs importés. Les positions de reprise des tracks longs ne sont pas touchées
+#[tauri::command]
+fn clear_listening_history() {
+    if let Ok(mut history) = LISTENING_HISTORY.lock() {
+        *history = ListeningHistory::default();
+        save_listening_history(&history);
+    }
+}
+
 // Récupère l'historique complet
 #[tauri::command]
 fn get_listening_history() -> ListeningHistory {
@@ -3841,6 +8000,132 @@ fn get_listening_history() -> ListeningHistory {
     }
 }
 
+/// Durée minimale d'un track pour mémoriser sa position (10 min)
+const POSITION_CACHE_MIN_DURATION: f64 = 600.0;
+
+/// Marge en début/fin de track en dessous de laquelle aucune position n'est gardée
+const POSITION_CACHE_MARGIN: f64 = 10.0;
+
+/// Mémorise la position d'un track long (appelé par l'engine toutes les 10s, sur pause/stop).
+/// Une position dans les premières/dernières secondes efface l'entrée : rien à reprendre.
+pub(crate) fn remember_playback_position(path: &str, position: f64, duration: f64) {
+    if duration < POSITION_CACHE_MIN_DURATION {
+        return;
+    }
+    if let Ok(mut cache) = PLAYBACK_POSITIONS.lock() {
+        let changed = if position < POSITION_CACHE_MARGIN || position > duration - POSITION_CACHE_MARGIN {
+            cache.entries.remove(path).is_some()
+        } else {
+            cache.entries.insert(path.to_string(), position);
+            true
+        };
+        if changed {
+            save_playback_positions(&cache);
+        }
+    }
+}
+
+/// Oublie la position d'un track (lu jusqu'au bout)
+pub(crate) fn forget_playback_position(path: &str) {
+    if let Ok(mut cache) = PLAYBACK_POSITIONS.lock() {
+        if cache.entries.remove(path).is_some() {
+            save_playback_positions(&cache);
+        }
+    }
+}
+
+/// Sauvegarde la session en cours (track, position, file, shuffle / repeat) dans session.json.
+/// Appelé par l'engine sur pause/stop et périodiquement pendant la lecture, avec ses modes
+/// shuffle / repeat en mémoire (pas de lecture des réglages sur le thread audio).
+/// La file est celle du backend si elle pilote la lecture, sinon celle du frontend
+pub(crate) fn remember_session(path: &str, position: f64, shuffle: bool, repeat: queue::RepeatMode) {
+    let driven = PLAY_QUEUE.lock().ok()
+        .filter(|queue| queue.playing)
+        .map(|queue| (queue.tracks.clone(), queue.current));
+    let (queue, queue_index) = driven.unwrap_or_else(|| {
+        let upcoming = SESSION_QUEUE.lock().map(|queue| queue.clone()).unwrap_or_default();
+        (std::iter::once(path.to_string()).chain(upcoming).collect(), Some(0))
+    });
+    let saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    session::save_session(&session::SessionState {
+        path: path.to_string(),
+        position: position.max(0.0),
+        queue,
+        queue_index,
+        shuffle,
+        repeat,
+        saved_at,
+    });
+}
+
+// Dernière session de lecture, pour proposer la reprise au démarrage (None si aucune)
+#[tauri::command]
+fn get_last_session() -> Option<session::SessionState> {
+    session::load_session()
+}
+
+// File d'attente du frontend (tracks à venir), sauvegardée avec la session
+#[tauri::command]
+fn set_session_queue(paths: Vec<String>) {
+    if let Ok(mut queue) = SESSION_QUEUE.lock() {
+        *queue = paths;
+    }
+}
+
+// Sauvegarde la position de lecture d'un track (ignorée pour les tracks de moins de 10 min)
+#[tauri::command]
+fn save_playback_position(path: String, position: f64) {
+    let cached_duration = METADATA_CACHE.lock().ok()
+        .and_then(|cache| cache.entries.get(&path).map(|m| m.duration))
+        .filter(|d| *d > 0.0);
+    let duration = cached_duration
+        .or_else(|| audio_decoder::probe_audio_file(&path).ok().map(|info| info.duration_seconds))
+        .unwrap_or(0.0);
+    remember_playback_position(&path, position, duration);
+}
+
+// Récupère la position mémorisée d'un track (None si jamais interrompu)
+#[tauri::command]
+fn get_playback_position(path: String) -> Option<f64> {
+    PLAYBACK_POSITIONS.lock().ok()
+        .and_then(|cache| cache.entries.get(&path).copied())
+}
+
+// Nombre d'écoutes d'un track (sur l'historique conservé)
+#[tauri::command]
+fn get_play_count(path: String) -> u32 {
+    LISTENING_HISTORY.lock().ok()
+        .and_then(|history| history.play_counts.tracks.get(&path).copied())
+        .unwrap_or(0)
+}
+
+// Nombre d'écoutes cumulées des tracks d'un album
+#[tauri::command]
+fn get_album_play_count(artist: String, album: String) -> u32 {
+    LISTENING_HISTORY.lock().ok()
+        .and_then(|history| history.play_counts.albums.get(&(artist, album)).copied())
+        .unwrap_or(0)
+}
+
+// Tracks les plus écoutés (path, nombre d'écoutes), décroissant
+#[tauri::command]
+fn get_most_played_tracks(limit: usize) -> Vec<(String, u32)> {
+    if let Ok(history) = LISTENING_HISTORY.lock() {
+        let mut tracks: Vec<(String, u32)> = history.play_counts.tracks
+            .iter()
+            .map(|(path, count)| (path.clone(), *count))
+            .collect();
+        tracks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tracks.truncate(limit);
+        tracks
+    } else {
+        Vec::new()
+    }
+}
+
 // Récupère la dernière track jouée
 #[tauri::command]
 fn get_last_played() -> Option<ListeningEntry> {
@@ -3872,6 +8157,115 @@ fn get_recent_albums(days: u64) -> Vec<ListeningEntry> {
     }
 }
 
+// Statistiques d'écoute agrégées (dashboard)
+#[derive(Serialize, Clone, Default)]
+struct ListeningStats {
+    total_seconds: f64,
+    total_plays: usize,
+    unique_artists: usize,
+    unique_albums: usize,
+    most_active_day: Option<ActiveDay>,
+}
+
+#[derive(Serialize, Clone)]
+struct ActiveDay {
+    day_start: u64,  // Minuit UTC (timestamp Unix en secondes)
+    plays: usize,
+}
+
+// Agrège l'historique conservé (HISTORY_LIMIT dernières écoutes), filtré aux N derniers jours si fourni.
+// Les entrées ne stockent pas la durée écoutée : chaque écoute compte pour la durée
+// complète du track (METADATA_CACHE), ce qui surestime les écoutes interrompues.
+#[tauri::command]
+fn get_listening_stats(since_days: Option<u64>) -> ListeningStats {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = since_days.map(|days| now.saturating_sub(days * 24 * 60 * 60)).unwrap_or(0);
+
+    let entries: Vec<ListeningEntry> = match LISTENING_HISTORY.lock() {
+        Ok(history) => history.entries.iter().filter(|e| e.timestamp >= cutoff).cloned().collect(),
+        Err(_) => return ListeningStats::default(),
+    };
+
+    let mut stats = ListeningStats {
+        total_plays: entries.len(),
+        ..Default::default()
+    };
+
+    if let Ok(cache) = METADATA_CACHE.lock() {
+        stats.total_seconds = entries.iter()
+            .filter_map(|e| cache.entries.get(&e.path))
+            .map(|m| m.duration)
+            .sum();
+    }
+
+    let aliases = artist_aliases_snapshot();
+    let mut artists = std::collections::HashSet::new();
+    let mut albums = std::collections::HashSet::new();
+    let mut plays_per_day: HashMap<u64, usize> = HashMap::new();
+    for entry in &entries {
+        let artist = aliases.resolve(&entry.artist);
+        if !artist.is_empty() {
+            artists.insert(artist);
+        }
+        if !entry.album.is_empty() {
+            albums.insert((artist, entry.album.as_str()));
+        }
+        *plays_per_day.entry(entry.timestamp / 86_400).or_insert(0) += 1;
+    }
+    stats.unique_artists = artists.len();
+    stats.unique_albums = albums.len();
+
+    // À égalité, le jour le plus récent l'emporte
+    stats.most_active_day = plays_per_day.into_iter()
+        .max_by_key(|(day, plays)| (*plays, *day))
+        .map(|(day, plays)| ActiveDay { day_start: day * 86_400, plays });
+
+    stats
+}
+
+// Date UTC (année, mois, jour) d'un timestamp Unix
+// (algorithme "civil from days" de H. Hinnant)
+fn utc_date(timestamp: u64) -> (i64, u32, u32) {
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// "Ce jour-là" : albums écoutés un jour comme aujourd'hui (même jour et mois, UTC)
+// les années précédentes, un seul par album, les plus récents d'abord
+#[tauri::command]
+fn get_listening_memories() -> Vec<ListeningEntry> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (this_year, month, day) = utc_date(now);
+
+    if let Ok(history) = LISTENING_HISTORY.lock() {
+        let mut seen_albums = std::collections::HashSet::new();
+        history.entries.iter()
+            .filter(|entry| {
+                let (year, m, d) = utc_date(entry.timestamp);
+                year < this_year && m == month && d == day
+            })
+            .filter(|entry| seen_albums.insert((entry.artist.as_str(), entry.album.as_str())))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
 // Récupère tous les albums jamais écoutés (pour "À découvrir")
 #[tauri::command]
 fn get_all_played_albums() -> Vec<ListeningEntry> {
@@ -3902,6 +8296,112 @@ fn get_all_played_paths() -> Vec<String> {
     }
 }
 
+// Dernière écoute connue par chemin. Les chemins de played_paths sortis de l'historique
+// conservé (HISTORY_LIMIT entrées) comptent comme écoutés il y a longtemps (timestamp 0).
+fn last_played_timestamps() -> HashMap<String, u64> {
+    let mut last_played = HashMap::new();
+    if let Ok(history) = LISTENING_HISTORY.lock() {
+        // Entrées triées de la plus récente à la plus ancienne : la première rencontrée gagne
+        for entry in &history.entries {
+            last_played.entry(entry.path.clone()).or_insert(entry.timestamp);
+        }
+        for (path, plays) in &history.imported_plays {
+            let timestamp = last_played.entry(path.clone()).or_insert(0);
+            *timestamp = (*timestamp).max(plays.last_played.unwrap_or(0));
+        }
+        for path in &history.played_paths {
+            last_played.entry(path.clone()).or_insert(0);
+        }
+    }
+    last_played
+}
+
+// Shuffle pondéré : les tracks écoutés récemment ont moins de chances de sortir tôt,
+// les tracks bien notés un peu plus. Sans doublon ; `seed` rend le tirage reproductible (tests)
+#[tauri::command]
+fn generate_shuffle(track_paths: Vec<String>, count: usize, seed: Option<u64>) -> Vec<String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut rng = seed.map_or_else(mixes::Rng::from_clock, mixes::Rng::new);
+    let rated = RATINGS.lock().map(|cache| cache.ratings.clone()).unwrap_or_default();
+    mixes::history_weighted_shuffle(track_paths, count, &last_played_timestamps(), &rated, now, &mut rng)
+}
+
+// Mix "À découvrir" : tracks jamais écoutés, variés en artistes et genres.
+// Complété par les tracks écoutés il y a le plus longtemps si la bibliothèque est épuisée
+#[tauri::command]
+fn generate_discovery_mix(count: usize) -> Vec<TrackWithMetadata> {
+    let tracks: Vec<TrackWithMetadata> = match TRACKS_CACHE.lock() {
+        Ok(cache) => cache.tracks.clone(),
+        Err(_) => return Vec::new(),
+    };
+    let last_played = last_played_timestamps();
+    let mut rng = mixes::Rng::from_clock();
+
+    let (mut candidates, mut played): (Vec<_>, Vec<_>) = tracks
+        .into_iter()
+        .partition(|t| !last_played.contains_key(&t.path));
+    mixes::shuffle(&mut candidates, &mut rng);
+
+    if candidates.len() < count {
+        mixes::shuffle(&mut played, &mut rng);
+        played.sort_by_key(|t| last_played.get(&t.path).copied().unwrap_or(0));
+        candidates.extend(played.into_iter().take(count - candidates.len()));
+    }
+
+    mixes::diversify(
+        candidates,
+        count,
+        |t| t.metadata.artist.as_str(),
+        |t| t.metadata.genre.as_deref(),
+    )
+}
+
+// Radio par genre : tracks dont le genre normalisé correspond ("hip hop" = "Hip-Hop"),
+// mélangés puis alternés entre artistes. Beaucoup de tracks n'ont pas de genre avant
+// l'enrichissement : `include_album_siblings` ajoute les tracks sans genre d'un album
+// dont au moins un track correspond
+#[tauri::command]
+fn generate_genre_radio(genre: String, count: usize, include_album_siblings: Option<bool>) -> Vec<TrackWithMetadata> {
+    let target = normalize_genre(&genre).to_lowercase();
+    if target.is_empty() {
+        return Vec::new();
+    }
+    let tracks: Vec<TrackWithMetadata> = match TRACKS_CACHE.lock() {
+        Ok(cache) => cache.tracks.clone(),
+        Err(_) => return Vec::new(),
+    };
+
+    let matches_genre = |t: &TrackWithMetadata| {
+        t.metadata.genre.as_deref().is_some_and(|g| normalize_genre(g).to_lowercase() == target)
+    };
+    let album_key = |t: &TrackWithMetadata| {
+        let artist = t.metadata.album_artist.as_deref().unwrap_or(&t.metadata.artist);
+        format!("{}|||{}", artist.to_lowercase(), t.metadata.album.to_lowercase())
+    };
+
+    let matching_albums: std::collections::HashSet<String> = if include_album_siblings.unwrap_or(false) {
+        tracks.iter().filter(|t| matches_genre(t)).map(album_key).collect()
+    } else {
+        std::collections::HashSet::new()
+    };
+
+    let mut selected: Vec<TrackWithMetadata> = tracks
+        .into_iter()
+        .filter(|t| {
+            matches_genre(t)
+                || (t.metadata.genre.is_none() && !t.metadata.album.is_empty() && matching_albums.contains(&album_key(t)))
+        })
+        .collect();
+
+    mixes::shuffle(&mut selected, &mut mixes::Rng::from_clock());
+    let mut radio = mixes::interleave_by_artist(selected, |t| t.metadata.artist.as_str());
+    radio.truncate(count);
+    radio
+}
+
 // Structure pour un artiste avec son nombre d'écoutes
 #[derive(serde::Serialize, Clone)]
 struct TopArtist {
@@ -3915,12 +8415,14 @@ struct TopArtist {
 // Récupère les artistes les plus écoutés
 #[tauri::command]
 fn get_top_artists(limit: usize) -> Vec<TopArtist> {
+    let aliases = artist_aliases_snapshot();
     if let Ok(history) = LISTENING_HISTORY.lock() {
         let mut artist_counts: std::collections::HashMap<String, (u32, String, String)> = std::collections::HashMap::new();
 
         for entry in &history.entries {
             if !entry.artist.is_empty() && entry.artist != "Unknown Artist" {
-                let counter = artist_counts.entry(entry.artist.clone()).or_insert((0, entry.album.clone(), entry.path.clone()));
+                let artist = aliases.resolve(&entry.artist).to_string();
+                let counter = artist_counts.entry(artist).or_insert((0, entry.album.clone(), entry.path.clone()));
                 counter.0 += 1;
             }
         }
@@ -3947,6 +8449,37 @@ fn get_top_artists(limit: usize) -> Vec<TopArtist> {
     }
 }
 
+// Alias d'artistes (alias en minuscules → nom canonique)
+#[tauri::command]
+fn get_artist_aliases() -> HashMap<String, String> {
+    ARTIST_ALIASES.lock().map(|aliases| aliases.aliases.clone()).unwrap_or_default()
+}
+
+// Regroupe des variantes d'un artiste sous un nom canonique, à l'affichage seulement
+// (les tags des fichiers ne sont pas modifiés)
+#[tauri::command]
+fn merge_artists(canonical: String, aliases: Vec<String>) -> Result<(), String> {
+    if canonical.trim().is_empty() {
+        return Err("Canonical artist name is empty".to_string());
+    }
+    let mut table = ARTIST_ALIASES.lock().map_err(|_| "Artist aliases unavailable".to_string())?;
+    table.merge(&canonical, &aliases);
+    artist_aliases::save_artist_aliases(&table);
+    Ok(())
+}
+
+// Annule un alias : l'artiste réapparaît sous son nom tagué
+#[tauri::command]
+fn remove_artist_alias(alias: String) -> bool {
+    if let Ok(mut table) = ARTIST_ALIASES.lock() {
+        if table.remove(&alias) {
+            artist_aliases::save_artist_aliases(&table);
+            return true;
+        }
+    }
+    false
+}
+
 // === FEEDBACK ===
 
 /// URL du Cloudflare Worker proxy pour le feedback.
@@ -4076,11 +8609,53 @@ fn update_media_metadata(title: String, artist: String, album: String) {
     media_controls::update_metadata(&title, &artist, &album);
 }
 
+/// Renseigne le Now Playing complet (durée + pochette en plus des métadonnées).
+/// `cover_path` : URL renvoyée par get_cover (noir://localhost/covers/…), URL http(s)
+/// ou chemin absolu. La durée manquante est prise dans l'engine.
+#[tauri::command]
+fn set_now_playing(metadata: media_controls::NowPlayingMetadata, cover_path: Option<String>) {
+    let mut metadata = metadata;
+    if metadata.duration.is_none() {
+        metadata.duration = AUDIO_ENGINE.lock().ok()
+            .and_then(|guard| guard.as_ref().map(|engine| engine.get_duration()))
+            .filter(|d| *d > 0.0);
+    }
+    let cover_url = cover_path.as_deref().and_then(now_playing_cover_url);
+    media_controls::set_now_playing(&metadata, cover_url.as_deref());
+}
+
+/// Convertit une pochette côté JS en URL lisible par MPNowPlayingInfoCenter
+fn now_playing_cover_url(cover: &str) -> Option<String> {
+    if let Some(file) = cover.strip_prefix("noir://localhost/covers/") {
+        let file = percent_decode_str(file).decode_utf8_lossy().to_string();
+        if file.contains('/') || file.contains("..") {
+            return None;
+        }
+        let path = get_cover_cache_dir().join(file);
+        if !path.exists() {
+            return None;
+        }
+        return tauri::Url::from_file_path(&path).ok().map(String::from);
+    }
+    if cover.starts_with("http://") || cover.starts_with("https://") {
+        return Some(cover.to_string());
+    }
+    let path = Path::new(cover);
+    if !path.exists() {
+        return None;
+    }
+    // from_file_path refuse les chemins relatifs et percent-encode espaces/accents
+    tauri::Url::from_file_path(path).ok().map(String::from)
+}
+
 /// Met à jour l'état play/pause dans MPNowPlayingInfoCenter.
-/// Appelé depuis JS quand l'état de lecture change.
+/// Appelé depuis JS quand l'état de lecture change. La position vient du PlaybackState
+/// (pas du JS) pour rester exacte après un seek.
 #[tauri::command]
 fn update_media_playback_state(is_playing: bool) {
-    media_controls::update_playback_state(is_playing);
+    let position = AUDIO_ENGINE.lock().ok()
+        .and_then(|guard| guard.as_ref().map(|engine| engine.get_position()));
+    media_controls::update_playback_state(is_playing, position);
 }
 
 /// Quitte l'application proprement (utilisé par le bouton "Quitter Noir" dans Settings).
@@ -4220,6 +8795,96 @@ fn set_sentry_enabled(enabled: bool) -> Result<bool, String> {
     Ok(sentry_init::is_initialized() || !enabled)
 }
 
+/// Réglages réseau actuels : kill switch global + fournisseurs de métadonnées
+#[tauri::command]
+fn get_online_settings() -> serde_json::Value {
+    let providers = METADATA_PROVIDERS.lock().map(|p| *p).unwrap_or_default();
+    serde_json::json!({
+        "onlineEnabled": is_online_enabled(),
+        "providers": providers,
+    })
+}
+
+/// Kill switch : désactivé, pochettes Internet, photos d'artistes et enrichissement des genres
+/// ne font plus aucune requête. Effet immédiat, persisté dans config.json.
+#[tauri::command]
+fn set_online_enabled(enabled: bool) {
+    ONLINE_ENABLED.store(enabled, std::sync::atomic::Ordering::Relaxed);
+
+    let mut config = load_config();
+    config.online_enabled = Some(enabled);
+    save_config(&config);
+}
+
+/// Extensions de fichiers reconnues par le scanner (ex. ajouter "opus", "ape").
+/// Sans point, minuscules, dédoublonnées ; prises en compte au prochain scan.
+#[tauri::command]
+fn set_scanned_extensions(exts: Vec<String>) -> Result<Vec<String>, String> {
+    let mut extensions: Vec<String> = Vec::new();
+    for ext in exts {
+        let ext = ext.trim().to_lowercase();
+        if ext.is_empty() || !ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid extension \"{}\" (expected e.g. \"flac\", without dot)", ext));
+        }
+        if !extensions.contains(&ext) {
+            extensions.push(ext);
+        }
+    }
+    if extensions.is_empty() {
+        return Err("At least one extension is required".to_string());
+    }
+
+    if let Ok(mut scanned) = SCANNED_EXTENSIONS.lock() {
+        *scanned = extensions.clone();
+    }
+    // Stocké comme écart avec les défauts (remplace l'ancienne liste complète)
+    let mut config = load_config();
+    config.extension_overrides = Some(extension_overrides(&extensions, &DEFAULT_AUDIO_EXTENSIONS));
+    config.scanned_extensions = None;
+    save_config(&config);
+    Ok(extensions)
+}
+
+/// Profondeur de parcours (1 = fichiers à la racine du dossier seulement, plafonnée à
+/// MAX_SCAN_DEPTH) et suivi des liens symboliques. Retourne les options appliquées.
+#[tauri::command]
+fn set_scan_options(max_depth: usize, follow_symlinks: bool) -> Result<ScanOptions, String> {
+    if max_depth == 0 {
+        return Err("max_depth must be at least 1".to_string());
+    }
+    let options = ScanOptions { max_depth: max_depth.min(MAX_SCAN_DEPTH), follow_symlinks };
+
+    if let Ok(mut current) = SCAN_OPTIONS.lock() {
+        *current = options;
+    }
+    let mut config = load_config();
+    config.scan_options = options;
+    save_config(&config);
+    Ok(options)
+}
+
+#[tauri::command]
+fn get_scan_options() -> ScanOptions {
+    SCAN_OPTIONS.lock().map(|o| *o).unwrap_or_default()
+}
+
+#[tauri::command]
+fn get_scanned_extensions() -> Vec<String> {
+    SCANNED_EXTENSIONS.lock().map(|e| e.clone()).unwrap_or_else(|_| default_audio_extensions())
+}
+
+/// Active/désactive Deezer, MusicBrainz et Cover Art Archive individuellement
+#[tauri::command]
+fn set_metadata_providers(config: MetadataProviders) {
+    if let Ok(mut providers) = METADATA_PROVIDERS.lock() {
+        *providers = config;
+    }
+
+    let mut app_config = load_config();
+    app_config.metadata_providers = config;
+    save_config(&app_config);
+}
+
 // =====================================================================
 // === NETWORK / NAS — TAURI COMMANDS ===
 // =====================================================================
@@ -4679,17 +9344,27 @@ pub fn run() {
 
             // Charge les paramètres EQ sauvegardés
             load_eq_settings(&engine.eq_state);
+            // Charge les réglages de lecture (crossfade)
+            apply_playback_settings(&engine);
+            // Sortie audio choisie manuellement lors d'une session précédente
+            restore_audio_device(&engine, &app_handle);
 
             if let Ok(mut engine_guard) = AUDIO_ENGINE.lock() {
                 *engine_guard = Some(engine);
             }
 
+            // Fin de track : la file d'attente enchaîne selon repeat / shuffle
+            {
+                use tauri::Listener;
+                app_handle.listen("playback_ended", |_| advance_queue_on_end());
+            }
+
             #[cfg(debug_assertions)]
             println!("Audio Engine initialized!");
 
             // Enregistre Noir comme propriétaire de MPRemoteCommandCenter
             // → les media keys (F7/F8/F9 / touches multimédia) sont routées vers Noir
-            //   même quand Apple Music tourne en arrière-plan.
+            //   même quand Apple Music tourne en arrière-plan. Sous Linux : serveur MPRIS.
             media_controls::init_media_controls(app_handle);
 
             Ok(())
@@ -4704,74 +9379,203 @@ pub fn run() {
             refresh_metadata,
             load_all_metadata_cache,
             get_added_dates,
+            get_recently_added,
             get_cover,
             get_cover_base64,
             get_cover_thumbnail,
             generate_thumbnails_batch,
             fetch_internet_cover,
             fetch_artist_image,
+            prefetch_artist_images,
+            set_custom_cover,
+            set_custom_artist_image,
+            embed_cover,
             clear_cache,
+            prune_image_cache,
+            prune_metadata_cache,
+            get_image_cache_size,
             add_library_path,
             remove_library_path,
             exclude_tracks_from_library,
             get_library_paths,
+            remap_library_paths,
             select_folder,
             // M3U Export/Import
             export_playlist_m3u,
+            export_app_data,
+            import_app_data,
+            export_library,
+            export_listening_history,
+            pick_playlist_m3u,
             import_playlist_m3u,
+            import_itunes_library,
             // Playlists
             get_playlists,
             create_playlist,
             rename_playlist,
+            duplicate_playlist,
+            merge_playlists,
             delete_playlist,
             add_track_to_playlist,
             remove_track_from_playlist,
             reorder_playlist_tracks,
+            get_playlist_summary,
+            clean_playlist,
+            find_broken_playlist_tracks,
+            evaluate_smart_playlist,
+            get_smart_playlists,
+            save_smart_playlist,
+            delete_smart_playlist,
+            // Paroles
+            get_lyrics,
+            get_synced_lyrics,
+            // CUE sheets
+            parse_cue_sheet,
+            // Chapitres
+            get_chapters,
+            // Forme d'onde
+            generate_waveform,
             // Favoris
             toggle_favorite,
             is_favorite,
             get_favorites,
+            toggle_favorite_album,
+            is_favorite_album,
+            get_favorite_albums,
+            toggle_favorite_artist,
+            is_favorite_artist,
+            get_favorite_artists,
+            set_rating,
+            get_rating,
+            get_ratings,
+            get_tracks_by_min_rating,
             // Audio Engine (Player Audiophile)
+            can_play,
             audio_play,
+            audio_play_at,
+            set_spectrum_enabled,
+            get_spectrum,
+            get_buffer_health,
+            get_engine_stats,
             audio_pause,
             audio_resume,
             audio_stop,
             audio_seek,
+            audio_seek_percent,
+            audio_seek_relative,
             audio_set_volume,
+            set_sleep_timer,
+            cancel_sleep_timer,
+            set_queue,
+            get_queue,
+            queue_next,
+            queue_prev,
+            enqueue,
+            clear_queue,
+            set_buffer_config,
+            get_buffer_config,
+            set_repeat_mode,
+            get_repeat_mode,
+            set_shuffle,
+            get_shuffle,
             audio_get_state,
             audio_preload_next,
             set_gapless_enabled,
+            audio_set_crossfade,
+            audio_get_crossfade,
+            set_volume_curve,
+            get_volume_curve,
+            set_channel_mode,
+            get_channel_mode,
+            set_balance,
+            get_balance,
+            set_stereo_width,
+            get_stereo_width,
+            set_limiter_enabled,
+            set_limiter_threshold,
+            get_limiter_settings,
+            set_restore_sample_rate_on_stop,
+            set_resampler_quality,
+            audio_set_playback_speed,
+            audio_set_ab_loop,
+            audio_clear_ab_loop,
             // Audio Backend (Bit-Perfect, Device Control)
             get_audio_devices,
             refresh_audio_devices,
             get_current_audio_device,
             set_audio_device,
             get_system_default_device_id,
+            set_follow_system_default,
+            get_audio_device_preference,
             get_audio_sample_rate,
             set_exclusive_mode,
             is_exclusive_mode,
             hog_mode_status,
+            get_playback_quality,
+            set_integer_mode,
+            lock_sample_rate,
+            get_locked_sample_rate,
             // Equalizer (8-band parametric EQ)
             set_eq_enabled,
             set_eq_bands,
+            set_eq_preamp,
             get_eq_state,
+            get_eq_bands,
+            set_eq_band_frequency,
+            list_eq_presets,
+            get_eq_presets,
+            save_eq_preset,
+            load_eq_preset,
+            delete_eq_preset,
             // Listening History
             record_play,
             get_listening_history,
+            set_history_limit,
+            get_history_limit,
+            clear_listening_history,
+            save_playback_position,
+            get_playback_position,
+            get_last_session,
+            set_session_queue,
             get_last_played,
+            get_play_count,
+            get_album_play_count,
+            get_most_played_tracks,
+            get_listening_stats,
             get_recent_albums,
             get_all_played_albums,
+            get_listening_memories,
             get_all_played_paths,
+            generate_shuffle,
+            generate_discovery_mix,
+            generate_genre_radio,
             get_top_artists,
+            get_artist_aliases,
+            merge_artists,
+            remove_artist_alias,
             // Instant Startup & Background Scan
             load_tracks_from_cache,
+            search_library,
             start_background_scan,
+            cancel_scan,
+            force_full_rescan,
             get_library_stats,
+            get_problem_tracks,
+            set_exclude_problem_tracks,
+            get_exclude_problem_tracks,
+            get_composers,
+            get_genres,
+            get_parent_genre,
+            get_genre_tree,
+            get_albums,
+            get_tracks_by_bpm_range,
             // Genre Enrichment
             trigger_genre_enrichment,
             reset_genre_enrichment,
+            set_album_genre,
             // Metadata Writing
             write_metadata,
+            write_metadata_batch,
             // Feedback
             submit_feedback,
             // Error tracking (JS → Sentry forwarder)
@@ -4781,6 +9585,13 @@ pub fn run() {
             // Privacy toggle (Sentry on/off depuis Settings)
             get_sentry_enabled,
             set_sentry_enabled,
+            get_online_settings,
+            set_online_enabled,
+            set_scanned_extensions,
+            get_scanned_extensions,
+            set_scan_options,
+            get_scan_options,
+            set_metadata_providers,
             // Network / NAS (SMB Library Sync)
             discover_nas_devices,
             smb_connect,
@@ -4796,6 +9607,7 @@ pub fn run() {
             scan_network_source_cmd,
             // Media Controls (MPRemoteCommandCenter / media keys)
             update_media_metadata,
+            set_now_playing,
             update_media_playback_state,
             // Application
             quit_app
@@ -4816,3 +9628,200 @@ pub fn run() {
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(0), (1970, 1, 1));
+        assert_eq!(utc_date(951_782_400), (2000, 2, 29));
+        assert_eq!(utc_date(1_704_067_199), (2023, 12, 31));
+        assert_eq!(utc_date(1_704_067_200), (2024, 1, 1));
+    }
+
+    fn track(path: &str, codec: Option<&str>, bit_depth: Option<u8>, sample_rate: u32) -> TrackWithMetadata {
+        let mut track = test_track(path);
+        track.metadata.codec = codec.map(str::to_string);
+        track.metadata.bit_depth = bit_depth;
+        track.metadata.sample_rate = Some(sample_rate);
+        track
+    }
+
+    #[test]
+    fn test_track_codec_falls_back_to_extension() {
+        assert_eq!(track_codec(&track("/m/a.flac", Some("FLAC"), Some(16), 44100)), "FLAC");
+        assert_eq!(track_codec(&track("/m/a.flac", None, Some(16), 44100)), "FLAC");
+        // "Other" = type non reconnu par lofty : l'extension fait foi
+        assert_eq!(track_codec(&track("/m/a.wv", Some("Other"), Some(16), 44100)), "WAVPACK");
+        assert_eq!(track_codec(&track("/m/a.m4a", None, Some(16), 44100)), "ALAC");
+        assert_eq!(track_codec(&track("/m/a.m4a", None, None, 44100)), "AAC");
+        assert_eq!(track_codec(&track("/m/a.xyz", Some("Other"), None, 44100)), "OTHER");
+    }
+
+    #[test]
+    fn test_library_stats_codec_counts() {
+        let tracks = vec![
+            track("/m/1.flac", Some("FLAC"), Some(16), 44100),
+            track("/m/2.flac", Some("FLAC"), Some(24), 96000),
+            track("/m/3.mp3", Some("MP3"), None, 44100),
+            track("/m/4.ape", Some("Other"), Some(16), 44100),
+            track("/m/5.dsf", Some("DSD"), None, 2_822_400),
+            track("/m/6.ogg", None, None, 48000),
+            track("/m/7.flac", Some("FLAC"), Some(16), 0),
+        ];
+        let stats = calculate_library_stats(&tracks);
+        assert_eq!(stats.total_tracks, 6);
+        assert_eq!(stats.problem_tracks, 1);
+        assert_eq!(stats.hires_count, 2);
+        assert_eq!(stats.lossless_cd_count, 2);
+        assert_eq!(stats.lossy_count, 2);
+        assert_eq!(stats.mp3_count, 1);
+        assert_eq!(stats.flac_16bit_count, 1);
+        assert_eq!(stats.flac_24bit_count, 1);
+        assert_eq!(stats.other_count, 3);
+    }
+
+    #[test]
+    fn test_backup_archive_round_trip_validates_every_file() {
+        let content = |name: &str| match name {
+            "config.json" => serde_json::to_string(&Config::default()),
+            "playlists.json" => serde_json::to_string(&PlaylistsData::default()),
+            "listening_history.json" => serde_json::to_string(&ListeningHistory::default()),
+            "added_dates_cache.json" => serde_json::to_string(&AddedDatesCache::default()),
+            "eq_presets.json" => serde_json::to_string(&EqPresetsData::default()),
+            "artist_aliases.json" => serde_json::to_string(&artist_aliases::ArtistAliases::default()),
+            "ratings.json" => serde_json::to_string(&ratings::RatingsCache::default()),
+            "smart_playlists.json" => serde_json::to_string(&smart_playlists::SmartPlaylistsData::default()),
+            "playback_settings.json" => serde_json::to_string(&PlaybackSettings::default()),
+            _ => Ok("{}".to_string()),
+        }.unwrap();
+        let entries: Vec<backup::ArchiveEntry> = BACKUP_FILES.iter()
+            .map(|name| backup::ArchiveEntry { name: name.to_string(), data: content(name).into_bytes() })
+            .collect();
+
+        let read = backup::read_archive(&backup::write_archive(&entries).unwrap()).unwrap();
+        assert_eq!(read.len(), BACKUP_FILES.len());
+        for entry in &read {
+            assert!(BACKUP_FILES.contains(&entry.name.as_str()));
+            let text = String::from_utf8(entry.data.clone()).unwrap();
+            assert!(validate_backup_file(&entry.name, &text).is_ok(), "{}", entry.name);
+        }
+
+        // JSON valide mais schéma inattendu : rejeté avant toute écriture
+        assert!(validate_backup_file("smart_playlists.json", "{\"playlists\":{}}").is_err());
+        assert!(validate_backup_file("playback_settings.json", "{\"crossfade_ms\":\"fast\"}").is_err());
+    }
+
+    #[test]
+    fn test_split_artists_separators() {
+        assert_eq!(split_artists("Daft Punk"), vec!["Daft Punk"]);
+        assert_eq!(split_artists("Jay-Z feat. Alicia Keys"), vec!["Jay-Z", "Alicia Keys"]);
+        assert_eq!(split_artists("Jay-Z Feat. Alicia Keys"), vec!["Jay-Z", "Alicia Keys"]);
+        assert_eq!(split_artists("Drake (feat. Rihanna)"), vec!["Drake", "Rihanna"]);
+        assert_eq!(split_artists("A ft. B"), vec!["A", "B"]);
+        assert_eq!(split_artists("Nina Simone & Duke Ellington"), vec!["Nina Simone", "Duke Ellington"]);
+        assert_eq!(split_artists("A; B;C"), vec!["A", "B", "C"]);
+        assert_eq!(split_artists("A / B"), vec!["A", "B"]);
+        assert_eq!(split_artists("A feat. B & C"), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_split_artists_keeps_band_names() {
+        assert_eq!(split_artists("AC/DC"), vec!["AC/DC"]);
+        assert_eq!(split_artists("Simon & Garfunkel"), vec!["Simon & Garfunkel"]);
+        assert_eq!(split_artists("Earth, Wind & Fire"), vec!["Earth, Wind & Fire"]);
+        assert_eq!(split_artists("Simon & Garfunkel feat. Paul Desmond"), vec!["Simon & Garfunkel", "Paul Desmond"]);
+    }
+
+    #[test]
+    fn test_split_artists_trims_and_dedupes() {
+        assert_eq!(split_artists(" A ;  a ; B "), vec!["A", "B"]);
+        assert_eq!(split_artists(";"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_parse_m3u() {
+        let dir = std::env::temp_dir().join(format!("noir_test_m3u_{}", std::process::id()));
+        let sub = dir.join("Album");
+        fs::create_dir_all(&sub).unwrap();
+        for name in ["01 Intro.flac", "02 Song.mp3"] {
+            fs::write(sub.join(name), b"").unwrap();
+        }
+        let absolute = sub.join("02 Song.mp3").to_string_lossy().to_string();
+
+        let content = format!(
+            "\u{feff}#EXTM3U\r\n#EXTINF:123,Artist - Intro\r\nAlbum/01 Intro.flac\r\n\
+             #EXTINF:-1,Radio\r\nhttp://stream.example.com/live.mp3\r\n\
+             {}\r\nfile://{}\r\nAlbum\\03 Missing.flac\r\ncover.jpg\r\n\r\n",
+            absolute,
+            absolute.replace(' ', "%20"),
+        );
+        let (paths, missing) = parse_m3u(&content, &dir);
+
+        let intro = sub.join("01 Intro.flac").to_string_lossy().to_string();
+        assert_eq!(paths, vec![intro, absolute.clone(), absolute]);
+        assert_eq!(missing, 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_scanned_extensions_from_config() {
+        let extensions = |config: &Config| scanned_extensions_from_config(config);
+        assert_eq!(extensions(&Config::default()), default_audio_extensions());
+
+        // Ancienne liste complète : "aac" retiré, "opus" ajouté ; wv / ape arrivés depuis
+        let legacy: Vec<String> = ["mp3", "flac", "wav", "m4a", "ogg", "aiff", "alac", "dsf", "dff", "dsd", "opus"]
+            .iter().map(|e| e.to_string()).collect();
+        let config = Config { scanned_extensions: Some(legacy), ..Default::default() };
+        let result = extensions(&config);
+        assert!(result.contains(&"wv".to_string()) && result.contains(&"ape".to_string()));
+        assert!(result.contains(&"opus".to_string()));
+        assert!(!result.contains(&"aac".to_string()));
+
+        // Nouveau format : l'écart survit à l'ajout d'un défaut
+        let overrides = extension_overrides(&result, &DEFAULT_AUDIO_EXTENSIONS);
+        assert_eq!(overrides.added, vec!["opus".to_string()]);
+        assert_eq!(overrides.removed, vec!["aac".to_string()]);
+        let config = Config { extension_overrides: Some(overrides), ..Default::default() };
+        assert_eq!(extensions(&config), result);
+    }
+
+    #[test]
+    fn test_genre_map_has_no_duplicate_keys() {
+        let mut seen = HashSet::new();
+        for (key, _) in GENRE_MAP_ENTRIES {
+            assert!(seen.insert(*key), "duplicate GENRE_MAP key: {:?}", key);
+        }
+        assert_eq!(GENRE_MAP.len(), GENRE_MAP_ENTRIES.len());
+    }
+
+    #[test]
+    fn test_genre_map_keys_are_lookup_keys() {
+        // Une clé qui n'est pas sous forme normalisée est inatteignable
+        for (key, _) in GENRE_MAP_ENTRIES {
+            assert_eq!(genre_lookup_key(key), *key, "unreachable GENRE_MAP key: {:?}", key);
+        }
+    }
+
+    #[test]
+    fn test_genre_parent_map_uses_canonical_forms() {
+        for (child, parent) in GENRE_PARENT_ENTRIES {
+            assert_eq!(normalize_genre(child), *child, "non-canonical child genre: {:?}", child);
+            assert_eq!(normalize_genre(parent), *parent, "non-canonical parent genre: {:?}", parent);
+            // Un seul niveau : un parent n'a pas lui-même de parent
+            assert!(!GENRE_PARENT_MAP.contains_key(parent), "nested parent genre: {:?}", parent);
+        }
+        assert_eq!(GENRE_PARENT_MAP.len(), GENRE_PARENT_ENTRIES.len());
+    }
+
+    #[test]
+    fn test_genre_map_values_are_canonical() {
+        // Re-normaliser un genre canonique doit le laisser intact
+        for (_, value) in GENRE_MAP_ENTRIES {
+            assert_eq!(normalize_genre(value), *value, "non-canonical GENRE_MAP value: {:?}", value);
+        }
+    }
+}
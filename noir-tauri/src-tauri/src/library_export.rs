@@ -0,0 +1,143 @@
+//! Export de la bibliothèque (sauvegarde, analyse externe)
+//!
+//! Architecture :
+//! - Une ligne par track de TRACKS_CACHE : toutes les métadonnées + date d'ajout + nombre d'écoutes
+//! - JSON : tableau d'objets, mêmes noms de champs que le cache (`addedAt` / `playCount` en plus)
+//! - CSV : en-tête fixe, champs entre guillemets s'ils contiennent virgule, guillemet ou saut de ligne
+//! - Historique d'écoute (import dans un service de scrobbling) : JSON des entrées, ou CSV au
+//!   format Last.fm `artist,track,album,timestamp` (ISO-8601 UTC), de la plus ancienne à la plus récente
+
+use serde::Serialize;
+
+use crate::{ListeningEntry, TrackWithMetadata};
+
+/// Track exporté avec les données qui vivent hors de TrackWithMetadata
+#[derive(Serialize)]
+pub struct ExportRow<'a> {
+    #[serde(flatten)]
+    pub track: &'a TrackWithMetadata,
+    /// Timestamp Unix d'ajout à la bibliothèque (ADDED_DATES_CACHE)
+    #[serde(rename = "addedAt")]
+    pub added_at: Option<u64>,
+    /// Écoutes dans l'historique conservé
+    #[serde(rename = "playCount")]
+    pub play_count: u32,
+}
+
+const CSV_COLUMNS: [&str; 25] = [
+    "path", "name", "folder", "title", "artist", "artists", "album", "album_artist",
+    "track", "disc", "year", "genre", "composer", "compilation", "bpm", "initial_key",
+    "duration", "bit_depth", "sample_rate", "bitrate", "codec", "file_size",
+    "added_at", "play_count", "genre_enriched",
+];
+
+pub fn to_json(rows: &[ExportRow]) -> Result<String, String> {
+    serde_json::to_string_pretty(rows).map_err(|e| format!("Failed to serialize library: {}", e))
+}
+
+pub fn to_csv(rows: &[ExportRow]) -> String {
+    let mut csv = csv_line(CSV_COLUMNS.iter().map(|c| c.to_string()));
+    for row in rows {
+        let t = row.track;
+        let m = &t.metadata;
+        csv.push_str(&csv_line([
+            t.path.clone(),
+            t.name.clone(),
+            t.folder.clone(),
+            m.title.clone(),
+            m.artist.clone(),
+            m.artists.join("; "),
+            m.album.clone(),
+            opt(&m.album_artist),
+            m.track.to_string(),
+            opt(&m.disc),
+            opt(&m.year),
+            opt(&m.genre),
+            opt(&m.composer),
+            m.compilation.to_string(),
+            opt(&m.bpm),
+            opt(&m.initial_key),
+            format!("{:.3}", m.duration),
+            opt(&m.bit_depth),
+            opt(&m.sample_rate),
+            opt(&m.bitrate),
+            opt(&m.codec),
+            opt(&m.file_size),
+            opt(&row.added_at),
+            row.play_count.to_string(),
+            m.genre_enriched.to_string(),
+        ]));
+    }
+    csv
+}
+
+/// Entrées de l'historique, plus anciennes d'abord (ordre attendu par les imports de scrobbles)
+pub fn history_to_json(entries: &[&ListeningEntry]) -> Result<String, String> {
+    serde_json::to_string_pretty(entries).map_err(|e| format!("Failed to serialize history: {}", e))
+}
+
+pub fn history_to_csv(entries: &[&ListeningEntry]) -> String {
+    let mut csv = csv_line(["artist", "track", "album", "timestamp"].map(String::from));
+    for entry in entries {
+        csv.push_str(&csv_line([
+            entry.artist.clone(),
+            entry.title.clone(),
+            entry.album.clone(),
+            iso8601(entry.timestamp),
+        ]));
+    }
+    csv
+}
+
+/// Timestamp Unix → `2024-01-01T12:34:56Z`
+fn iso8601(timestamp: u64) -> String {
+    let (year, month, day) = crate::utc_date(timestamp);
+    let seconds = timestamp % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60
+    )
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Ligne CSV terminée par CRLF
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields.into_iter().map(|f| csv_field(&f)).collect::<Vec<_>>().join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Guillemets si le champ contient un séparateur ; les guillemets internes sont doublés
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quoting() {
+        assert_eq!(csv_field("So What"), "So What");
+        assert_eq!(csv_field("Crosby, Stills & Nash"), "\"Crosby, Stills & Nash\"");
+        assert_eq!(csv_field("12\" Mix"), "\"12\"\" Mix\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+
+        let line = csv_line(["a".to_string(), "b,c".to_string(), String::new()]);
+        assert_eq!(line, "a,\"b,c\",\r\n");
+        assert_eq!(csv_line(CSV_COLUMNS.iter().map(|c| c.to_string())).matches(',').count(), CSV_COLUMNS.len() - 1);
+    }
+
+    #[test]
+    fn test_iso8601() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso8601(1_704_112_496), "2024-01-01T12:34:56Z");
+    }
+}
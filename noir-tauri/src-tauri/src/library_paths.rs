@@ -0,0 +1,94 @@
+//! Dossiers de la bibliothèque : normalisation, dédoublonnage à l'ajout, déplacement
+//!
+//! Architecture :
+//! - Le chemin ajouté est canonicalisé (slash final, `..`, symlinks) avant comparaison et stockage
+//! - Les chemins déjà enregistrés sont canonicalisés à la volée (anciennes configs non normalisées)
+//! - Un sous-dossier d'un dossier présent est refusé : ses tracks sont déjà scannés
+//! - Un dossier parent remplace les dossiers qu'il englobe
+//! - Bibliothèque déplacée : réécriture d'un préfixe de chemin, limitée aux composants entiers
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Résultat de add_library_path
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum LibraryPathAddition {
+    /// Ajouté ; `replaced` = dossiers enregistrés qu'il englobe (retirés de la config)
+    Added { path: String, replaced: Vec<String> },
+    /// Déjà présent (même dossier sous une autre écriture)
+    Duplicate { existing: String },
+    /// Sous-dossier d'un dossier déjà présent
+    Nested { parent: String },
+}
+
+/// Classe `candidate` (déjà canonicalisé) par rapport aux dossiers enregistrés
+pub fn plan_addition(
+    existing: &[String],
+    candidate: &Path,
+    canonical: impl Fn(&str) -> PathBuf,
+) -> LibraryPathAddition {
+    let mut replaced = Vec::new();
+    for path in existing {
+        let known = canonical(path);
+        if known == candidate {
+            return LibraryPathAddition::Duplicate { existing: path.clone() };
+        }
+        if candidate.starts_with(&known) {
+            return LibraryPathAddition::Nested { parent: path.clone() };
+        }
+        if known.starts_with(candidate) {
+            replaced.push(path.clone());
+        }
+    }
+    LibraryPathAddition::Added { path: candidate.to_string_lossy().into_owned(), replaced }
+}
+
+/// `/Volumes/Old/a.flac` avec `/Volumes/Old` → `/Volumes/New` donne `/Volumes/New/a.flac`.
+/// None si `path` n'est pas sous `old_prefix` (`/Volumes/Older` ne correspond pas)
+pub fn remap_prefix(path: &str, old_prefix: &str, new_prefix: &str) -> Option<String> {
+    let old_prefix = old_prefix.trim_end_matches('/');
+    let rest = path.strip_prefix(old_prefix)?;
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    Some(format!("{}{}", new_prefix.trim_end_matches('/'), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(existing: &[&str], candidate: &str) -> LibraryPathAddition {
+        let existing: Vec<String> = existing.iter().map(|p| p.to_string()).collect();
+        // Canonicalisation simulée : seul le slash final diffère
+        plan_addition(&existing, Path::new(candidate), |p| PathBuf::from(p.trim_end_matches('/')))
+    }
+
+    #[test]
+    fn test_plan_addition() {
+        assert_eq!(plan(&["/Music/"], "/Music"), LibraryPathAddition::Duplicate { existing: "/Music/".to_string() });
+        assert_eq!(plan(&["/Music"], "/Music/Jazz"), LibraryPathAddition::Nested { parent: "/Music".to_string() });
+        // Comparaison par composants : /Music2 n'est pas dans /Music
+        assert_eq!(
+            plan(&["/Music"], "/Music2"),
+            LibraryPathAddition::Added { path: "/Music2".to_string(), replaced: vec![] }
+        );
+        assert_eq!(
+            plan(&["/Music/Jazz", "/Music/Rock/", "/Podcasts"], "/Music"),
+            LibraryPathAddition::Added {
+                path: "/Music".to_string(),
+                replaced: vec!["/Music/Jazz".to_string(), "/Music/Rock/".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_remap_prefix() {
+        assert_eq!(remap_prefix("/Volumes/Old/Jazz/a.flac", "/Volumes/Old/", "/Volumes/New").as_deref(), Some("/Volumes/New/Jazz/a.flac"));
+        assert_eq!(remap_prefix("/Volumes/Old", "/Volumes/Old", "/Volumes/New/").as_deref(), Some("/Volumes/New"));
+        assert_eq!(remap_prefix("/Volumes/Older/a.flac", "/Volumes/Old", "/Volumes/New"), None);
+        assert_eq!(remap_prefix("/Music/a.flac", "/Volumes/Old", "/Volumes/New"), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Paroles : tags embarqués (USLT / LYRICS) puis fichiers sidecar .lrc / .txt
+//!
+//! Architecture :
+//! - Recherche : tag lofty ItemKey::Lyrics → <stem>.lrc → <stem>.txt (même dossier)
+//! - Les paroles LRC horodatées sont parsées en Vec<LyricLine> triées par temps
+//! - Résultat (y compris "pas de paroles") mis en cache mémoire par chemin
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use lofty::{ItemKey, Probe, TaggedFileExt};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Une ligne de paroles synchronisées
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LyricLine {
+    /// Début de la ligne en secondes
+    pub time: f64,
+    pub text: String,
+}
+
+#[derive(Clone)]
+struct Lyrics {
+    /// Texte brut (horodatages LRC retirés)
+    text: String,
+    /// Vide si les paroles ne sont pas horodatées
+    synced: Vec<LyricLine>,
+}
+
+static LYRICS_CACHE: Lazy<Mutex<HashMap<String, Option<Lyrics>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Paroles en texte brut d'un track
+pub fn get_lyrics(path: &str) -> Option<String> {
+    load_cached(path).map(|l| l.text)
+}
+
+/// Paroles horodatées d'un track (vide si non synchronisées)
+pub fn get_synced_lyrics(path: &str) -> Vec<LyricLine> {
+    load_cached(path).map(|l| l.synced).unwrap_or_default()
+}
+
+fn load_cached(path: &str) -> Option<Lyrics> {
+    if let Ok(cache) = LYRICS_CACHE.lock() {
+        if let Some(entry) = cache.get(path) {
+            return entry.clone();
+        }
+    }
+
+    let lyrics = read_lyrics(path).map(|raw| {
+        let synced = parse_lrc(&raw);
+        let text = if synced.is_empty() {
+            raw.trim().to_string()
+        } else {
+            synced.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n")
+        };
+        Lyrics { text, synced }
+    });
+
+    if let Ok(mut cache) = LYRICS_CACHE.lock() {
+        cache.insert(path.to_string(), lyrics.clone());
+    }
+    lyrics
+}
+
+fn read_lyrics(path: &str) -> Option<String> {
+    // Fichiers réseau : pas d'accès direct aux tags ni au dossier
+    if path.starts_with("smb://") {
+        return None;
+    }
+
+    if let Some(embedded) = read_embedded_lyrics(path) {
+        return Some(embedded);
+    }
+
+    let file_path = Path::new(path);
+    ["lrc", "txt"].iter().find_map(|ext| {
+        let sidecar = file_path.with_extension(ext);
+        std::fs::read(&sidecar)
+            .ok()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .filter(|content| !content.trim().is_empty())
+    })
+}
+
+fn read_embedded_lyrics(path: &str) -> Option<String> {
+    let tagged_file = Probe::open(path).ok()?.read().ok()?;
+    tagged_file
+        .tags()
+        .iter()
+        .find_map(|tag| tag.get_string(&ItemKey::Lyrics))
+        .filter(|lyrics| !lyrics.trim().is_empty())
+        .map(|lyrics| lyrics.to_string())
+}
+
+/// Parse un contenu LRC (`[mm:ss.xx]texte`, plusieurs horodatages par ligne,
+/// tag `[offset:±ms]`). Retourne une liste vide si aucune ligne n'est horodatée.
+pub fn parse_lrc(content: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    let mut offset_seconds = 0.0;
+
+    for raw_line in content.trim_start_matches('\u{feff}').lines() {
+        let mut rest = raw_line.trim();
+        let mut times = Vec::new();
+
+        while let Some(tag_end) = rest.strip_prefix('[').and_then(|r| r.find(']')) {
+            let tag = &rest[1..=tag_end];
+            if let Some(time) = parse_timestamp(tag) {
+                times.push(time);
+            } else if let Some(offset) = tag.strip_prefix("offset:") {
+                // Offset positif = paroles affichées plus tôt
+                offset_seconds = offset.trim().parse::<f64>().unwrap_or(0.0) / 1000.0;
+            }
+            rest = rest[tag_end + 2..].trim_start();
+        }
+
+        for time in times {
+            lines.push(LyricLine { time, text: rest.trim().to_string() });
+        }
+    }
+
+    for line in &mut lines {
+        line.time = (line.time - offset_seconds).max(0.0);
+    }
+    lines.sort_by(|a, b| a.time.total_cmp(&b.time));
+    lines
+}
+
+/// `mm:ss`, `mm:ss.xx` ou `mm:ss:xx` → secondes
+fn parse_timestamp(tag: &str) -> Option<f64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u32 = minutes.trim().parse().ok()?;
+    let seconds: f64 = seconds.trim().replacen(':', ".", 1).parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some(minutes as f64 * 60.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc() {
+        let content = "[ar:Artist]\n[ti:Title]\n[00:12.50]First line\n[00:05.00][01:00.00]Chorus\nplain line\n";
+        let lines = parse_lrc(content);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], LyricLine { time: 5.0, text: "Chorus".to_string() });
+        assert_eq!(lines[1], LyricLine { time: 12.5, text: "First line".to_string() });
+        assert_eq!(lines[2].time, 60.0);
+    }
+
+    #[test]
+    fn test_parse_lrc_offset_and_plain_text() {
+        let lines = parse_lrc("[offset:+500]\n[00:02.00]Hello");
+        assert_eq!(lines[0].time, 1.5);
+
+        assert!(parse_lrc("Just some lyrics\nwithout timestamps").is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-// media_controls.rs — Intégration MPRemoteCommandCenter (macOS)
+// media_controls.rs — Intégration MPRemoteCommandCenter (macOS) et MPRIS (Linux)
 //
 // Sur macOS, les media keys (F7/F8/F9 ou touches multimédia clavier) sont routées
 // vers l'application qui "possède" MPRemoteCommandCenter (la dernière à s'être
@@ -7,37 +7,57 @@
 //
 // Ce module utilise la crate `souvlaki` pour enregistrer Noir comme propriétaire
 // de MPRemoteCommandCenter. Quand Noir s'enregistre, il reçoit les évènements
-// Play/Pause/Next/Previous via GCD callback → émission d'un event Tauri `media-control`
-// → module shortcuts.js gère l'action côté JS.
+// Play/Pause/Stop via GCD callback → émission d'un event Tauri `media-control`, et
+// Next/Previous → `media_key_next` / `media_key_prev` ; playback.js gère l'action côté JS.
+//
+// Sous Linux, la même API souvlaki expose Noir sur D-Bus (org.mpris.MediaPlayer2.noir_desktop)
+// via son backend dbus : les environnements de bureau et playerctl pilotent Noir avec les
+// mêmes events, et reçoivent les mêmes métadonnées / état de lecture.
 //
 // SAFETY : MediaControls sur macOS est implémenté via MPRemoteCommandCenter qui
 // utilise GCD dispatch queue en interne. Bien que Rust marque la struct comme !Send
 // (car elle contient des raw pointers ObjC), les callbacks sont dispatchés par GCD
 // sur la main queue — thread-safe par design macOS. Le unsafe Send wrapper est
-// justifié dans ce contexte natif macOS.
+// justifié dans ce contexte natif macOS. Sous Linux, le backend dbus tourne dans son
+// propre thread et MediaControls n'y contient que des channels : le wrapper est sans effet.
 
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Emitter;
 
-#[cfg(target_os = "macos")]
-use souvlaki::{MediaControls, MediaControlEvent, MediaMetadata, MediaPlayback, PlatformConfig};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use souvlaki::{MediaControls, MediaControlEvent, MediaMetadata, MediaPlayback, MediaPosition, PlatformConfig};
 
 // Wrapper pour contourner !Send (justifié — voir commentaire en haut de fichier)
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 struct MediaControlsWrapper(MediaControls);
 
-#[cfg(target_os = "macos")]
-// SAFETY : MPRemoteCommandCenter utilise GCD (thread-safe par design macOS)
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+// SAFETY : MPRemoteCommandCenter utilise GCD (thread-safe par design macOS) ; MPRIS : déjà Send
 unsafe impl Send for MediaControlsWrapper {}
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 static MEDIA_CONTROLS: Lazy<Mutex<Option<MediaControlsWrapper>>> = Lazy::new(|| Mutex::new(None));
 
-/// Initialise MPRemoteCommandCenter et enregistre Noir comme lecteur multimédia actif.
+/// Métadonnées du Now Playing envoyées par le JS à chaque changement de track
+#[derive(Debug, Clone, Deserialize)]
+pub struct NowPlayingMetadata {
+    pub title: String,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    /// Durée en secondes (None = inconnue, complétée par l'engine)
+    #[serde(default)]
+    pub duration: Option<f64>,
+}
+
+/// Initialise MPRemoteCommandCenter (macOS) ou le serveur MPRIS (Linux) et enregistre
+/// Noir comme lecteur multimédia actif.
 /// À appeler depuis le setup Tauri (une seule fois au démarrage).
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub fn init_media_controls(app_handle: AppHandle) {
     let config = PlatformConfig {
         display_name: "Noir",
@@ -49,13 +69,14 @@ pub fn init_media_controls(app_handle: AppHandle) {
         Ok(mut controls) => {
             let handle = app_handle.clone();
             if let Err(e) = controls.attach(move |event| {
+                // Next/Previous ont leurs propres events, partagés avec les global shortcuts JS
                 let action = match event {
                     MediaControlEvent::Play     => "play",
                     MediaControlEvent::Pause    => "pause",
                     MediaControlEvent::Toggle   => "toggle",
-                    MediaControlEvent::Next     => "next",
-                    MediaControlEvent::Previous => "previous",
                     MediaControlEvent::Stop     => "stop",
+                    MediaControlEvent::Next     => { let _ = handle.emit("media_key_next", ()); return; }
+                    MediaControlEvent::Previous => { let _ = handle.emit("media_key_prev", ()); return; }
                     _                           => return,
                 };
                 let _ = handle.emit("media-control", action);
@@ -70,7 +91,10 @@ pub fn init_media_controls(app_handle: AppHandle) {
             match MEDIA_CONTROLS.lock() {
                 Ok(mut guard) => {
                     *guard = Some(MediaControlsWrapper(controls));
+                    #[cfg(target_os = "macos")]
                     println!("[MediaControls] MPRemoteCommandCenter registered — media keys will route to Noir");
+                    #[cfg(target_os = "linux")]
+                    println!("[MediaControls] MPRIS server registered on D-Bus");
                 }
                 Err(e) => eprintln!("[MediaControls] Mutex poisoned: {:?}", e),
             }
@@ -83,7 +107,7 @@ pub fn init_media_controls(app_handle: AppHandle) {
 
 /// Met à jour les métadonnées affichées dans le Centre de contrôle / lock screen.
 /// Appeler à chaque changement de track.
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub fn update_metadata(title: &str, artist: &str, album: &str) {
     if let Ok(mut guard) = MEDIA_CONTROLS.lock() {
         if let Some(ref mut wrapper) = *guard {
@@ -97,15 +121,39 @@ pub fn update_metadata(title: &str, artist: &str, album: &str) {
     }
 }
 
+/// Remplit MPNowPlayingInfoCenter : titre, artiste, album, durée et pochette
+/// (Centre de contrôle, lock screen, AirPods). `cover_url` : file:// ou http(s)://.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub fn set_now_playing(metadata: &NowPlayingMetadata, cover_url: Option<&str>) {
+    if let Ok(mut guard) = MEDIA_CONTROLS.lock() {
+        if let Some(ref mut wrapper) = *guard {
+            let duration = metadata.duration
+                .filter(|d| d.is_finite() && *d > 0.0)
+                .map(std::time::Duration::from_secs_f64);
+            let _ = wrapper.0.set_metadata(MediaMetadata {
+                title: Some(&metadata.title),
+                artist: Some(&metadata.artist),
+                album: Some(&metadata.album),
+                cover_url,
+                duration,
+            });
+        }
+    }
+}
+
 /// Met à jour l'état play/pause dans MPNowPlayingInfoCenter.
-#[cfg(target_os = "macos")]
-pub fn update_playback_state(is_playing: bool) {
+/// `position` (secondes) — position courante du PlaybackState, affichée dans le Centre de contrôle.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub fn update_playback_state(is_playing: bool, position: Option<f64>) {
     if let Ok(mut guard) = MEDIA_CONTROLS.lock() {
         if let Some(ref mut wrapper) = *guard {
+            let progress = position
+                .filter(|p| p.is_finite() && *p >= 0.0)
+                .map(|p| MediaPosition(std::time::Duration::from_secs_f64(p)));
             let playback = if is_playing {
-                MediaPlayback::Playing { progress: None }
+                MediaPlayback::Playing { progress }
             } else {
-                MediaPlayback::Paused { progress: None }
+                MediaPlayback::Paused { progress }
             };
             let _ = wrapper.0.set_playback(playback);
         }
@@ -113,7 +161,7 @@ pub fn update_playback_state(is_playing: bool) {
 }
 
 /// Réinitialise l'état (stopped) quand aucune track n'est active.
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub fn clear_playback_state() {
     if let Ok(mut guard) = MEDIA_CONTROLS.lock() {
         if let Some(ref mut wrapper) = *guard {
@@ -122,15 +170,18 @@ pub fn clear_playback_state() {
     }
 }
 
-// Stubs no-op pour les plateformes non-macOS (permet la compilation cross-platform)
-#[cfg(not(target_os = "macos"))]
+// Stubs no-op pour les autres plateformes (permet la compilation cross-platform).
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 pub fn init_media_controls(_app_handle: AppHandle) {}
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 pub fn update_metadata(_title: &str, _artist: &str, _album: &str) {}
 
-#[cfg(not(target_os = "macos"))]
-pub fn update_playback_state(_is_playing: bool) {}
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn set_now_playing(_metadata: &NowPlayingMetadata, _cover_url: Option<&str>) {}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn update_playback_state(_is_playing: bool, _position: Option<f64>) {}
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 pub fn clear_playback_state() {}
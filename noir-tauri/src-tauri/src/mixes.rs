@@ -0,0 +1,274 @@
+//! Mix automatiques générés depuis la bibliothèque et l'historique d'écoute
+//!
+//! Architecture :
+//! - Générateur pseudo-aléatoire SplitMix64 : reproductible avec une seed (tests), seed horloge sinon
+//! - Shuffle : tirage pondéré sans remise (Efraimidis–Spirakis), poids croissant avec l'ancienneté
+//!   de la dernière écoute ; un track jamais écouté garde un léger avantage
+//! - Diversification : sélection gloutonne dans un ordre mélangé, au plus MAX_ARTIST_RUN tracks
+//!   consécutifs du même artiste, et un genre différent du précédent quand c'est possible
+//! - Radio par genre : round-robin entre artistes (ordre des artistes et des tracks mélangé)
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Au-delà de cette ancienneté, un track écouté n'est plus pénalisé
+const RECENCY_WINDOW_DAYS: f64 = 90.0;
+
+/// Poids plancher : même écouté il y a une heure, un track peut ressortir
+const MIN_RECENCY_WEIGHT: f64 = 0.02;
+
+/// Poids maximal d'un track déjà écouté (1.0 = jamais écouté)
+const PLAYED_MAX_WEIGHT: f64 = 0.8;
+
+/// Nombre maximum de tracks consécutifs du même artiste dans un mix
+const MAX_ARTIST_RUN: usize = 2;
+
+/// SplitMix64 : rapide, sans dépendance, suffisant pour des tirages non cryptographiques
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn from_clock() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Flottant uniforme dans ]0, 1[
+    pub fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+}
+
+/// Mélange de Fisher–Yates
+pub fn shuffle<T>(items: &mut [T], rng: &mut Rng) {
+    for i in (1..items.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Poids d'un track selon sa dernière écoute (timestamp Unix, None = jamais écouté)
+pub fn recency_weight(last_played: Option<u64>, now: u64) -> f64 {
+    match last_played {
+        None => 1.0,
+        Some(timestamp) => {
+            let age_days = now.saturating_sub(timestamp) as f64 / 86_400.0;
+            (age_days / RECENCY_WINDOW_DAYS * PLAYED_MAX_WEIGHT).clamp(MIN_RECENCY_WEIGHT, PLAYED_MAX_WEIGHT)
+        }
+    }
+}
+
+/// Tirage pondéré sans remise : clé u^(1/poids), les `count` plus grandes clés gagnent
+pub fn weighted_sample<T>(items: Vec<(T, f64)>, count: usize, rng: &mut Rng) -> Vec<T> {
+    let mut keyed: Vec<(f64, T)> = items
+        .into_iter()
+        .filter(|(_, weight)| *weight > 0.0)
+        .map(|(item, weight)| (rng.next_f64().powf(1.0 / weight), item))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().take(count).map(|(_, item)| item).collect()
+}
+
+/// Shuffle de `paths` pondéré par l'historique (dernier timestamp connu par chemin)
+/// et par la note en étoiles
+pub fn history_weighted_shuffle(
+    paths: Vec<String>,
+    count: usize,
+    last_played: &HashMap<String, u64>,
+    ratings: &HashMap<String, u8>,
+    now: u64,
+    rng: &mut Rng,
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let weighted = paths
+        .into_iter()
+        .filter(|path| seen.insert(path.clone()))
+        .map(|path| {
+            let weight = recency_weight(last_played.get(&path).copied(), now)
+                * crate::ratings::shuffle_weight(ratings.get(&path).copied().unwrap_or(0));
+            (path, weight)
+        })
+        .collect();
+    weighted_sample(weighted, count, rng)
+}
+
+/// Prend jusqu'à `count` éléments dans l'ordre de `items` en évitant plus de MAX_ARTIST_RUN
+/// artistes identiques d'affilée et, à défaut de mieux, deux genres identiques d'affilée.
+/// Les contraintes sont relâchées (genre puis artiste) quand plus aucun candidat ne les respecte
+pub fn diversify<T>(
+    items: Vec<T>,
+    count: usize,
+    artist_of: impl Fn(&T) -> &str,
+    genre_of: impl Fn(&T) -> Option<&str>,
+) -> Vec<T> {
+    let mut pool: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    let mut result: Vec<T> = Vec::with_capacity(count.min(pool.len()));
+
+    while result.len() < count {
+        let pick = {
+            let last = result.last();
+            let blocked_artist = last
+                .map(&artist_of)
+                .filter(|artist| {
+                    result.len() >= MAX_ARTIST_RUN
+                        && result[result.len() - MAX_ARTIST_RUN..].iter().all(|t| artist_of(t) == *artist)
+                });
+            let last_genre = last.and_then(&genre_of);
+
+            let artist_ok = |t: &T| blocked_artist != Some(artist_of(t));
+            let genre_ok = |t: &T| last_genre.is_none() || genre_of(t) != last_genre;
+            let find = |accept: &dyn Fn(&T) -> bool| pool.iter().position(|t| t.as_ref().is_some_and(accept));
+
+            find(&|t| artist_ok(t) && genre_ok(t))
+                .or_else(|| find(&artist_ok))
+                .or_else(|| find(&|_| true))
+        };
+        match pick {
+            Some(index) => result.extend(pool[index].take()),
+            None => break,
+        }
+    }
+    result
+}
+
+/// Round-robin entre artistes : un track de chaque artiste à tour de rôle, dans l'ordre
+/// de première apparition (mélanger `items` avant pour varier l'ordre des artistes)
+pub fn interleave_by_artist<T>(items: Vec<T>, artist_of: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut groups: Vec<VecDeque<T>> = Vec::new();
+    let mut group_of_artist: HashMap<String, usize> = HashMap::new();
+    for item in items {
+        let index = *group_of_artist
+            .entry(artist_of(&item).to_string())
+            .or_insert_with(|| {
+                groups.push(Default::default());
+                groups.len() - 1
+            });
+        groups[index].push_back(item);
+    }
+
+    let mut result = Vec::with_capacity(groups.iter().map(|g| g.len()).sum());
+    while groups.iter().any(|g| !g.is_empty()) {
+        for group in &mut groups {
+            result.extend(group.pop_front());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_750_000_000;
+    const DAY: u64 = 86_400;
+
+    fn paths(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("/music/{}.flac", i)).collect()
+    }
+
+    #[test]
+    fn test_shuffle_is_reproducible_without_repeats() {
+        let mut input = paths(50);
+        input.push("/music/3.flac".to_string());
+
+        let a = history_weighted_shuffle(input.clone(), 20, &HashMap::new(), &HashMap::new(), NOW, &mut Rng::new(42));
+        let b = history_weighted_shuffle(input.clone(), 20, &HashMap::new(), &HashMap::new(), NOW, &mut Rng::new(42));
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 20);
+        assert_eq!(a.iter().collect::<HashSet<_>>().len(), 20);
+
+        // count > nombre de tracks distincts : tout est rendu une seule fois
+        assert_eq!(history_weighted_shuffle(input, 100, &HashMap::new(), &HashMap::new(), NOW, &mut Rng::new(1)).len(), 50);
+    }
+
+    #[test]
+    fn test_recently_played_tracks_come_later() {
+        let history: HashMap<String, u64> = [
+            ("/music/0.flac".to_string(), NOW - DAY),
+            ("/music/1.flac".to_string(), NOW - 200 * DAY),
+        ]
+        .into();
+
+        let mut first_picks: HashMap<String, usize> = HashMap::new();
+        for seed in 0..2000 {
+            let pick = history_weighted_shuffle(paths(4), 1, &history, &HashMap::new(), NOW, &mut Rng::new(seed));
+            *first_picks.entry(pick[0].clone()).or_insert(0) += 1;
+        }
+        let picks = |i: usize| first_picks.get(&format!("/music/{}.flac", i)).copied().unwrap_or(0);
+
+        // Écouté hier : presque jamais en tête ; écouté il y a des mois : proche d'un inédit
+        assert!(picks(0) < 50, "yesterday's track picked first {} times", picks(0));
+        assert!(picks(1) > 300, "old track picked first {} times", picks(1));
+        assert!(picks(2) > picks(1) / 2);
+    }
+
+    #[test]
+    fn test_highly_rated_tracks_come_earlier() {
+        let ratings: HashMap<String, u8> = [
+            ("/music/0.flac".to_string(), 5),
+            ("/music/1.flac".to_string(), 1),
+        ]
+        .into();
+
+        let mut first_picks: HashMap<String, usize> = HashMap::new();
+        for seed in 0..2000 {
+            let pick = history_weighted_shuffle(paths(2), 1, &HashMap::new(), &ratings, NOW, &mut Rng::new(seed));
+            *first_picks.entry(pick[0].clone()).or_insert(0) += 1;
+        }
+        // Poids 1.3 contre 0.7 : environ 65 % des tirages
+        let five_stars = first_picks.get("/music/0.flac").copied().unwrap_or(0);
+        assert!((1100..1500).contains(&five_stars), "5-star track picked first {} times", five_stars);
+    }
+
+    #[test]
+    fn test_diversify_limits_artist_runs() {
+        let tracks = vec![
+            ("a1", "A", Some("Jazz")),
+            ("a2", "A", Some("Jazz")),
+            ("a3", "A", Some("Jazz")),
+            ("a4", "A", Some("Jazz")),
+            ("b1", "B", Some("Rock")),
+            ("c1", "C", None),
+        ];
+        let mix = diversify(tracks, 10, |t| t.1, |t| t.2);
+        let names: Vec<&str> = mix.iter().map(|t| t.0).collect();
+
+        // Genre alterné tant que possible, jamais plus de 2 "A" d'affilée avant épuisement
+        assert_eq!(names, vec!["a1", "b1", "a2", "c1", "a3", "a4"]);
+
+        let long_run = vec![("a1", "A", None), ("a2", "A", None), ("a3", "A", None), ("b1", "B", None)];
+        let mix = diversify(long_run, 3, |t| t.1, |t| t.2);
+        assert_eq!(mix.iter().map(|t| t.0).collect::<Vec<_>>(), vec!["a1", "a2", "b1"]);
+    }
+
+    #[test]
+    fn test_interleave_by_artist() {
+        let tracks = vec![("a1", "A"), ("a2", "A"), ("b1", "B"), ("a3", "A"), ("c1", "C"), ("b2", "B")];
+        let radio = interleave_by_artist(tracks, |t| t.1);
+        assert_eq!(
+            radio.iter().map(|t| t.0).collect::<Vec<_>>(),
+            vec!["a1", "b1", "c1", "a2", "b2", "a3"]
+        );
+    }
+
+    #[test]
+    fn test_recency_weight() {
+        assert_eq!(recency_weight(None, NOW), 1.0);
+        assert_eq!(recency_weight(Some(NOW), NOW), MIN_RECENCY_WEIGHT);
+        assert_eq!(recency_weight(Some(NOW - 365 * DAY), NOW), PLAYED_MAX_WEIGHT);
+        assert!(recency_weight(Some(NOW - 30 * DAY), NOW) < recency_weight(Some(NOW - 60 * DAY), NOW));
+    }
+}
@@ -352,12 +352,23 @@ fn parse_flac_blocks(
                                             "TITLE" => meta.title = value.to_string(),
                                             "ARTIST" => {
                                                 meta.artist = value.to_string();
+                                                meta.artists = crate::split_artists(value);
                                                 found_artist = true;
                                             }
-                                            "ALBUMARTIST" if !found_artist => {
-                                                meta.artist = value.to_string();
+                                            "ALBUMARTIST" => {
+                                                if !found_artist {
+                                                    meta.artist = value.to_string();
+                                                    meta.artists = crate::split_artists(value);
+                                                }
+                                                meta.album_artist = Some(value.to_string());
                                             }
                                             "ALBUM" => meta.album = value.to_string(),
+                                            "COMPOSER" => meta.composer = Some(value.to_string()),
+                                            "BPM" => meta.bpm = value.trim().replace(',', ".").parse().ok(),
+                                            "INITIALKEY" | "KEY" => meta.initial_key = Some(value.trim().to_string()),
+                                            "COMPILATION" | "ITUNESCOMPILATION" => {
+                                                meta.compilation = crate::is_truthy_flag(value);
+                                            }
                                             "TRACKNUMBER" => {
                                                 // Format: "5" ou "5/12"
                                                 let n_str = value.split('/').next().unwrap_or(value);
@@ -480,12 +491,19 @@ pub fn extract_smb_metadata_and_cover(
         year: None,
         genre: None,
         genre_enriched: false,
+        genre_locked: false,
         duration: 0.0,
         bit_depth: None,
         sample_rate: None,
         bitrate: None,
         codec: None,
         file_size: None,
+        album_artist: None,
+        artists: Vec::new(),
+        composer: None,
+        compilation: false,
+        bpm: None,
+        initial_key: None,
     };
 
     // Lecture unique de 512KB
@@ -563,17 +581,7 @@ pub fn extract_smb_metadata_and_cover(
     metadata.bit_depth = properties.bit_depth();
     metadata.bitrate = properties.audio_bitrate();
 
-    metadata.codec = Some(match tagged_file.file_type() {
-        lofty::FileType::Flac => "FLAC".to_string(),
-        lofty::FileType::Mpeg => "MP3".to_string(),
-        lofty::FileType::Mp4 => {
-            if metadata.bit_depth.is_some() { "ALAC".to_string() }
-            else { "AAC".to_string() }
-        }
-        lofty::FileType::Wav => "WAV".to_string(),
-        lofty::FileType::Aiff => "AIFF".to_string(),
-        _ => "Other".to_string(),
-    });
+    metadata.codec = Some(crate::codec_name(tagged_file.file_type(), metadata.bit_depth));
 
     if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
         if let Some(title) = tag.title() {
@@ -581,7 +589,13 @@ pub fn extract_smb_metadata_and_cover(
         }
         if let Some(artist) = tag.artist() {
             metadata.artist = artist.to_string();
+            metadata.artists = crate::split_artists(&artist);
         }
+        metadata.album_artist = tag.get_string(&lofty::ItemKey::AlbumArtist).map(|v| v.to_string());
+        metadata.composer = tag.get_string(&lofty::ItemKey::Composer).map(|v| v.to_string());
+        metadata.compilation = crate::is_compilation(tag);
+        metadata.bpm = crate::read_bpm(tag);
+        metadata.initial_key = tag.get_string(&lofty::ItemKey::InitialKey).map(|v| v.trim().to_string());
         if let Some(album) = tag.album() {
             metadata.album = album.to_string();
         }
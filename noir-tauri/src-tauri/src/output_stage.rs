@@ -0,0 +1,357 @@
+//! Étage de sortie : traitement appliqué par le callback audio après l'EQ et le volume
+//!
+//! Architecture :
+//! - Réglages atomiques partagés entre les commandes et le callback (ni verrou ni allocation)
+//! - Mode de canaux (sortie stéréo) : mono (moyenne L+R) ou un seul canal dupliqué sur les deux
+//! - Largeur stéréo (mid/side) puis balance (gain par canal) ; contournées en Hog Mode,
+//!   où l'utilisateur attend une chaîne bit-perfect
+//! - Limiteur doux : identité sous le seuil, courbe tanh au-dessus qui tend vers ±1.0
+//!   sans la dépasser (plus d'écrêtage dur quand EQ + gain dépassent la pleine échelle)
+//! - Désactivé : le callback ne touche pas aux samples (chaîne bit-perfect préservée)
+//! - Courbe de volume : position du curseur → gain appliqué par le callback (linéaire,
+//!   ou logarithmique sur VOLUME_DB_RANGE dB pour une progression perçue régulière)
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Répartition des canaux en sortie, persistée dans les réglages de lecture
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelMode {
+    #[default]
+    Stereo,
+    Mono,
+    Left,
+    Right,
+}
+
+impl ChannelMode {
+    /// "stereo" / "mono" / "left" / "right"
+    pub fn from_name(mode: &str) -> Option<Self> {
+        match mode {
+            "stereo" => Some(Self::Stereo),
+            "mono" => Some(Self::Mono),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Mono,
+            2 => Self::Left,
+            3 => Self::Right,
+            _ => Self::Stereo,
+        }
+    }
+}
+
+/// Correspondance entre la position du curseur de volume et le gain, persistée
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeCurve {
+    Linear,
+    #[default]
+    Logarithmic,
+}
+
+/// Plage couverte par la courbe logarithmique : le bas du curseur est à -60 dB
+const VOLUME_DB_RANGE: f32 = 60.0;
+
+impl VolumeCurve {
+    /// "linear" / "logarithmic"
+    pub fn from_name(mode: &str) -> Option<Self> {
+        match mode {
+            "linear" => Some(Self::Linear),
+            "logarithmic" => Some(Self::Logarithmic),
+            _ => None,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Linear,
+            _ => Self::Logarithmic,
+        }
+    }
+
+    /// Gain pour une position de curseur 0.0–1.0 (0 = silence, 1.0 = gain unité pour les deux courbes)
+    pub fn gain(self, level: f32) -> f32 {
+        let level = level.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => level,
+            Self::Logarithmic if level <= 0.0 => 0.0,
+            Self::Logarithmic => db_to_linear((level - 1.0) * VOLUME_DB_RANGE),
+        }
+    }
+}
+
+/// Largeur stéréo maximale (1.0 = inchangée, 0.0 = mono)
+pub const MAX_STEREO_WIDTH: f32 = 2.0;
+
+/// Seuil du limiteur par défaut (dBFS)
+pub const DEFAULT_LIMITER_THRESHOLD_DB: f32 = -1.0;
+
+/// Bornes du seuil : sous -12 dB le limiteur colore le signal en permanence,
+/// au-dessus de -0.1 dB il n'a plus de marge pour arrondir
+pub const MIN_LIMITER_THRESHOLD_DB: f32 = -12.0;
+pub const MAX_LIMITER_THRESHOLD_DB: f32 = -0.1;
+
+pub struct OutputStage {
+    /// ChannelMode (discriminant)
+    channel_mode: AtomicU8,
+    /// Balance -1.0 (gauche) … +1.0 (droite) et largeur stéréo (f32 bits)
+    balance: AtomicU32,
+    stereo_width: AtomicU32,
+    /// Hog Mode actif : balance et largeur ne sont pas appliquées
+    exclusive: AtomicBool,
+    limiter_enabled: AtomicBool,
+    /// Seuil linéaire (f32 bits), dérivé du seuil en dB
+    limiter_threshold: AtomicU32,
+}
+
+impl OutputStage {
+    pub fn new() -> Self {
+        Self {
+            channel_mode: AtomicU8::new(ChannelMode::Stereo as u8),
+            balance: AtomicU32::new(0f32.to_bits()),
+            stereo_width: AtomicU32::new(1f32.to_bits()),
+            exclusive: AtomicBool::new(false),
+            limiter_enabled: AtomicBool::new(false),
+            limiter_threshold: AtomicU32::new(db_to_linear(DEFAULT_LIMITER_THRESHOLD_DB).to_bits()),
+        }
+    }
+
+    pub fn set_channel_mode(&self, mode: ChannelMode) {
+        self.channel_mode.store(mode as u8, Ordering::Relaxed);
+    }
+
+    pub fn channel_mode(&self) -> ChannelMode {
+        ChannelMode::from_u8(self.channel_mode.load(Ordering::Relaxed))
+    }
+
+    /// Balance bornée à [-1.0, 1.0] ; retourne la valeur appliquée
+    pub fn set_balance(&self, balance: f32) -> Result<f32, String> {
+        if !balance.is_finite() {
+            return Err("Invalid balance".to_string());
+        }
+        let balance = balance.clamp(-1.0, 1.0);
+        self.balance.store(balance.to_bits(), Ordering::Relaxed);
+        Ok(balance)
+    }
+
+    pub fn balance(&self) -> f32 {
+        f32::from_bits(self.balance.load(Ordering::Relaxed))
+    }
+
+    /// Largeur bornée à [0.0, MAX_STEREO_WIDTH] ; retourne la valeur appliquée
+    pub fn set_stereo_width(&self, width: f32) -> Result<f32, String> {
+        if !width.is_finite() {
+            return Err("Invalid stereo width".to_string());
+        }
+        let width = width.clamp(0.0, MAX_STEREO_WIDTH);
+        self.stereo_width.store(width.to_bits(), Ordering::Relaxed);
+        Ok(width)
+    }
+
+    pub fn stereo_width(&self) -> f32 {
+        f32::from_bits(self.stereo_width.load(Ordering::Relaxed))
+    }
+
+    /// Suit le Hog Mode demandé par l'utilisateur
+    pub fn set_exclusive(&self, exclusive: bool) {
+        self.exclusive.store(exclusive, Ordering::Relaxed);
+    }
+
+    /// Balance ou largeur à appliquer (réglage hors neutre et pas de Hog Mode)
+    fn spatial_active(&self) -> bool {
+        !self.exclusive.load(Ordering::Relaxed)
+            && (self.balance() != 0.0 || self.stereo_width() != 1.0)
+    }
+
+    pub fn set_limiter_enabled(&self, enabled: bool) {
+        self.limiter_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_limiter_enabled(&self) -> bool {
+        self.limiter_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Seuil en dBFS, borné à [MIN_LIMITER_THRESHOLD_DB, MAX_LIMITER_THRESHOLD_DB]
+    pub fn set_limiter_threshold_db(&self, threshold_db: f32) -> Result<f32, String> {
+        if !threshold_db.is_finite() {
+            return Err("Invalid limiter threshold".to_string());
+        }
+        let threshold_db = threshold_db.clamp(MIN_LIMITER_THRESHOLD_DB, MAX_LIMITER_THRESHOLD_DB);
+        self.limiter_threshold.store(db_to_linear(threshold_db).to_bits(), Ordering::Relaxed);
+        Ok(threshold_db)
+    }
+
+    pub fn limiter_threshold_db(&self) -> f32 {
+        20.0 * f32::from_bits(self.limiter_threshold.load(Ordering::Relaxed)).log10()
+    }
+
+    /// True si process() modifie les samples
+    pub fn is_active(&self) -> bool {
+        self.channel_mode() != ChannelMode::Stereo || self.spatial_active() || self.is_limiter_enabled()
+    }
+
+    /// Appelé depuis le callback audio, sur les samples interleaved après EQ et volume
+    pub fn process(&self, samples: &mut [f32], channels: usize) {
+        // Mode de canaux : sans objet pour une source mono
+        let mode = self.channel_mode();
+        if channels == 2 && mode != ChannelMode::Stereo {
+            for frame in samples.chunks_exact_mut(2) {
+                let (left, right) = (frame[0], frame[1]);
+                let (out_left, out_right) = match mode {
+                    ChannelMode::Mono => ((left + right) * 0.5, (left + right) * 0.5),
+                    ChannelMode::Left => (left, left),
+                    ChannelMode::Right => (right, right),
+                    ChannelMode::Stereo => (left, right),
+                };
+                frame[0] = out_left;
+                frame[1] = out_right;
+            }
+        }
+
+        if channels == 2 && self.spatial_active() {
+            let width = self.stereo_width();
+            // La balance atténue le canal opposé, sans jamais amplifier
+            let balance = self.balance();
+            let left_gain = (1.0 - balance).min(1.0);
+            let right_gain = (1.0 + balance).min(1.0);
+            for frame in samples.chunks_exact_mut(2) {
+                let mid = (frame[0] + frame[1]) * 0.5;
+                let side = (frame[0] - frame[1]) * 0.5 * width;
+                frame[0] = (mid + side) * left_gain;
+                frame[1] = (mid - side) * right_gain;
+            }
+        }
+
+        if self.is_limiter_enabled() {
+            let threshold = f32::from_bits(self.limiter_threshold.load(Ordering::Relaxed));
+            for sample in samples.iter_mut() {
+                *sample = soft_clip(*sample, threshold);
+            }
+        }
+    }
+}
+
+impl Default for OutputStage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Identité jusqu'au seuil, puis compression tanh de la marge restante jusqu'à 1.0.
+/// Pente continue au seuil : pas de cassure audible à l'entrée dans la zone limitée
+fn soft_clip(sample: f32, threshold: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= threshold {
+        return sample;
+    }
+    let headroom = 1.0 - threshold;
+    let limited = threshold + headroom * ((magnitude - threshold) / headroom).tanh();
+    limited.copysign(sample)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_curves() {
+        assert_eq!(VolumeCurve::Linear.gain(0.5), 0.5);
+        for curve in [VolumeCurve::Linear, VolumeCurve::Logarithmic] {
+            assert_eq!(curve.gain(0.0), 0.0);
+            assert_eq!(curve.gain(1.0), 1.0);
+            assert_eq!(curve.gain(1.5), 1.0);
+        }
+        // Mi-course : -30 dB, monotone
+        assert!((VolumeCurve::Logarithmic.gain(0.5) - db_to_linear(-30.0)).abs() < 1e-6);
+        assert!(VolumeCurve::Logarithmic.gain(0.25) < VolumeCurve::Logarithmic.gain(0.5));
+        assert_eq!(VolumeCurve::from_u8(VolumeCurve::Linear as u8), VolumeCurve::Linear);
+        assert_eq!(VolumeCurve::from_u8(VolumeCurve::Logarithmic as u8), VolumeCurve::Logarithmic);
+    }
+
+    #[test]
+    fn test_soft_clip() {
+        let threshold = db_to_linear(-1.0);
+        // Sous le seuil : inchangé, au bit près
+        assert_eq!(soft_clip(0.5, threshold), 0.5);
+        assert_eq!(soft_clip(-threshold, threshold), -threshold);
+        // Au-dessus : borné sous la pleine échelle, monotone, symétrique
+        let loud = soft_clip(1.5, threshold);
+        assert!(loud > threshold && loud < 1.0);
+        assert!(soft_clip(3.0, threshold) > loud);
+        assert_eq!(soft_clip(-1.5, threshold), -loud);
+    }
+
+    #[test]
+    fn test_process_bypassed_when_disabled() {
+        let stage = OutputStage::new();
+        let mut samples = [1.8f32, -2.0, 0.3];
+        stage.process(&mut samples, 1);
+        assert_eq!(samples, [1.8, -2.0, 0.3]);
+        assert!(!stage.is_active());
+
+        stage.set_limiter_enabled(true);
+        stage.process(&mut samples, 1);
+        assert!(samples[0] <= 1.0 && samples[1] >= -1.0);
+        assert_eq!(samples[2], 0.3);
+
+        assert_eq!(stage.set_limiter_threshold_db(-40.0), Ok(MIN_LIMITER_THRESHOLD_DB));
+        assert!((stage.limiter_threshold_db() - MIN_LIMITER_THRESHOLD_DB).abs() < 1e-4);
+        assert!(stage.set_limiter_threshold_db(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_channel_modes() {
+        let stage = OutputStage::new();
+        let process = |mode: ChannelMode| {
+            stage.set_channel_mode(mode);
+            let mut samples = [0.2f32, 0.6, -0.4, 0.0];
+            stage.process(&mut samples, 2);
+            samples
+        };
+        assert_eq!(process(ChannelMode::Stereo), [0.2, 0.6, -0.4, 0.0]);
+        assert_eq!(process(ChannelMode::Mono), [0.4, 0.4, -0.2, -0.2]);
+        assert_eq!(process(ChannelMode::Left), [0.2, 0.2, -0.4, -0.4]);
+        assert_eq!(process(ChannelMode::Right), [0.6, 0.6, 0.0, 0.0]);
+        assert!(stage.is_active());
+        assert_eq!(ChannelMode::from_name("mono"), Some(ChannelMode::Mono));
+        assert_eq!(ChannelMode::from_name("surround"), None);
+    }
+
+    #[test]
+    fn test_balance_and_width() {
+        let stage = OutputStage::new();
+        let process = |samples: [f32; 2]| {
+            let mut samples = samples;
+            stage.process(&mut samples, 2);
+            samples
+        };
+        assert_eq!(stage.set_balance(-0.5), Ok(-0.5));
+        assert_eq!(process([0.8, 0.8]), [0.8, 0.4]);
+        assert_eq!(stage.set_balance(3.0), Ok(1.0));
+        assert_eq!(process([0.8, 0.8]), [0.0, 0.8]);
+
+        stage.set_balance(0.0).unwrap();
+        assert_eq!(stage.set_stereo_width(0.0), Ok(0.0));
+        assert_eq!(process([0.75, 0.25]), [0.5, 0.5]);
+        stage.set_stereo_width(2.0).unwrap();
+        assert_eq!(process([0.75, 0.25]), [1.0, 0.0]);
+
+        // Hog Mode : samples intacts
+        stage.set_exclusive(true);
+        assert_eq!(process([0.75, 0.25]), [0.75, 0.25]);
+        assert!(!stage.is_active());
+        assert!(stage.set_stereo_width(f32::INFINITY).is_err());
+    }
+}
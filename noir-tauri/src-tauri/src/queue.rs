@@ -0,0 +1,256 @@
+//! File d'attente de lecture côté backend
+//!
+//! Architecture :
+//! - Liste de chemins + index du track courant, persistée dans queue.json (restaurée au démarrage)
+//! - Les commandes modifient la file et renvoient le track à jouer ; la lecture elle-même
+//!   passe toujours par audio_play (SMB, CUE, erreurs fichier)
+//! - Gapless : l'engine demande le préchargement du track suivant en fin de track et
+//!   avance la file quand le callback enchaîne sur le track préchargé
+//! - Enchaînement automatique (playback_ended) selon le mode repeat (off / one / all) et le
+//!   shuffle ; en shuffle, chaque track n'est tiré qu'une fois par passage sur la file
+
+use serde::{Deserialize, Serialize};
+
+/// Mode de répétition, persisté dans les réglages de lecture
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    One,
+    All,
+}
+
+impl RepeatMode {
+    /// "off" / "one" / "all"
+    pub fn from_name(mode: &str) -> Option<Self> {
+        match mode {
+            "off" => Some(Self::Off),
+            "one" => Some(Self::One),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::One,
+            2 => Self::All,
+            _ => Self::Off,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct PlayQueue {
+    pub tracks: Vec<String>,
+    /// Index du track courant (None = lecture pas encore commencée)
+    pub current: Option<usize>,
+    /// Index déjà joués pendant le passage shuffle en cours
+    #[serde(default)]
+    pub shuffle_played: Vec<usize>,
+    /// Index préchargé pour le gapless (non persisté : le preload ne survit pas au redémarrage)
+    #[serde(skip)]
+    pub preloaded: Option<usize>,
+    /// L'engine joue le track courant de la file. Faux au démarrage et quand le frontend
+    /// joue un track hors file : l'enchaînement automatique ne s'applique alors pas
+    #[serde(skip)]
+    pub playing: bool,
+}
+
+impl PlayQueue {
+    /// Remplace la file ; `start` hors limites est ignoré
+    pub fn set(&mut self, tracks: Vec<String>, start: Option<usize>) {
+        self.current = start.filter(|&i| i < tracks.len());
+        self.tracks = tracks;
+        self.shuffle_played.clear();
+        self.preloaded = None;
+    }
+
+    pub fn current_track(&self) -> Option<&str> {
+        self.current.and_then(|i| self.tracks.get(i)).map(String::as_str)
+    }
+
+    /// Track suivant le track courant (le premier si la lecture n'a pas commencé)
+    pub fn upcoming(&self) -> Option<(usize, &str)> {
+        let index = self.current.map_or(0, |i| i + 1);
+        self.tracks.get(index).map(|path| (index, path.as_str()))
+    }
+
+    /// Revient au track précédent ; sur le premier track, le renvoie (redémarrage)
+    pub fn back(&mut self) -> Option<&str> {
+        let index = self.current?.saturating_sub(1);
+        self.current = Some(index);
+        self.preloaded = None;
+        self.current_track()
+    }
+
+    pub fn enqueue(&mut self, path: String) {
+        self.tracks.push(path);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Index du track à enchaîner après le track courant. En shuffle, `pick` choisit parmi
+    /// les candidats (tracks pas encore joués pendant ce passage)
+    pub fn next_index(&self, repeat: RepeatMode, shuffle: bool, pick: impl FnOnce(&[usize]) -> Option<usize>) -> Option<usize> {
+        if repeat == RepeatMode::One {
+            return self.current.filter(|&i| i < self.tracks.len());
+        }
+        if !shuffle {
+            return match self.upcoming() {
+                Some((index, _)) => Some(index),
+                None if repeat == RepeatMode::All && !self.tracks.is_empty() => Some(0),
+                None => None,
+            };
+        }
+
+        let unplayed = |skip_history: bool| -> Vec<usize> {
+            (0..self.tracks.len())
+                .filter(|&i| Some(i) != self.current && (skip_history || !self.shuffle_played.contains(&i)))
+                .collect()
+        };
+        let mut candidates = unplayed(false);
+        if candidates.is_empty() && repeat == RepeatMode::All {
+            // Nouveau passage (file d'un seul track : il se rejoue)
+            candidates = unplayed(true);
+            if candidates.is_empty() {
+                candidates.extend(self.current);
+            }
+        }
+        pick(&candidates).filter(|i| candidates.contains(i))
+    }
+
+    /// Fait de `index` le track courant (enchaînement automatique ou preload consommé)
+    pub fn move_to(&mut self, index: usize) {
+        if index >= self.tracks.len() {
+            return;
+        }
+        // Fin d'un passage shuffle : l'historique repart de zéro
+        if self.shuffle_played.len() + 1 >= self.tracks.len() {
+            self.shuffle_played.clear();
+        }
+        if let Some(current) = self.current.filter(|&c| c != index) {
+            self.shuffle_played.push(current);
+        }
+        self.current = Some(index);
+        self.preloaded = None;
+    }
+
+    /// Transition gapless : la file suit le track qu'elle a préchargé. Un preload venu
+    /// d'ailleurs signifie que la lecture ne suit plus la file
+    pub fn on_gapless_transition(&mut self) -> bool {
+        match self.preloaded.take() {
+            Some(index) if index < self.tracks.len() => {
+                self.move_to(index);
+                true
+            }
+            _ => {
+                self.playing = false;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(n: usize) -> PlayQueue {
+        let mut queue = PlayQueue::default();
+        queue.set((0..n).map(|i| format!("/music/{}.flac", i)).collect(), None);
+        queue
+    }
+
+    /// Track suivant sans repeat ni shuffle
+    fn advance(q: &mut PlayQueue) -> Option<&str> {
+        let index = q.next_index(RepeatMode::Off, false, |_| None)?;
+        q.move_to(index);
+        q.current_track()
+    }
+
+    #[test]
+    fn test_queue_navigation() {
+        let mut q = queue(3);
+        assert_eq!(q.current_track(), None);
+        assert_eq!(q.back(), None);
+        assert_eq!(advance(&mut q), Some("/music/0.flac"));
+        assert_eq!(advance(&mut q), Some("/music/1.flac"));
+        assert_eq!(q.back(), Some("/music/0.flac"));
+        assert_eq!(q.back(), Some("/music/0.flac"));
+
+        q.set(q.tracks.clone(), Some(2));
+        assert_eq!(advance(&mut q), None);
+        assert_eq!(q.current_track(), Some("/music/2.flac"));
+        q.enqueue("/music/extra.flac".to_string());
+        assert_eq!(advance(&mut q), Some("/music/extra.flac"));
+
+        q.set(Vec::new(), Some(0));
+        assert_eq!(q.current, None);
+    }
+
+    #[test]
+    fn test_gapless_transition_follows_preload() {
+        let mut q = queue(3);
+        advance(&mut q);
+        q.playing = true;
+
+        q.preloaded = Some(1);
+        assert!(q.on_gapless_transition());
+        assert_eq!(q.current_track(), Some("/music/1.flac"));
+        assert_eq!(q.preloaded, None);
+        assert!(q.playing);
+
+        // Preload qui ne vient pas de la file : la file ne bouge pas et ne pilote plus la lecture
+        assert!(!q.on_gapless_transition());
+        assert_eq!(q.current, Some(1));
+        assert!(!q.playing);
+    }
+
+    #[test]
+    fn test_next_index_follows_repeat_mode() {
+        let first = |c: &[usize]| c.first().copied();
+        let mut q = queue(3);
+        advance(&mut q);
+        assert_eq!(q.next_index(RepeatMode::One, true, first), Some(0));
+        assert_eq!(q.next_index(RepeatMode::Off, false, first), Some(1));
+
+        q.move_to(2);
+        assert_eq!(q.next_index(RepeatMode::Off, false, first), None);
+        assert_eq!(q.next_index(RepeatMode::All, false, first), Some(0));
+    }
+
+    #[test]
+    fn test_shuffle_plays_each_track_once_per_pass() {
+        let last = |c: &[usize]| c.last().copied();
+        let mut q = queue(3);
+        advance(&mut q);
+
+        let mut order = vec![0];
+        while let Some(next) = q.next_index(RepeatMode::Off, true, last) {
+            q.move_to(next);
+            order.push(next);
+        }
+        assert_eq!(order, vec![0, 2, 1]);
+
+        // Repeat all : nouveau passage sans rejouer tout de suite le track courant
+        let next = q.next_index(RepeatMode::All, true, last).unwrap();
+        assert_ne!(next, 1);
+        q.move_to(next);
+        assert_eq!(q.shuffle_played, vec![1]);
+
+        // Un choix hors candidats est refusé
+        assert_eq!(q.next_index(RepeatMode::All, true, |_| Some(q.current.unwrap())), None);
+    }
+
+    #[test]
+    fn test_repeat_mode_u8_roundtrip() {
+        for mode in [RepeatMode::Off, RepeatMode::One, RepeatMode::All] {
+            assert_eq!(RepeatMode::from_u8(mode as u8), mode);
+        }
+        assert_eq!(RepeatMode::from_u8(9), RepeatMode::Off);
+    }
+}
@@ -0,0 +1,128 @@
+//! Notes des tracks (0 à 5 étoiles)
+//!
+//! Architecture :
+//! - Table chemin → étoiles, persistée dans ratings.json ; 0 = pas de note (entrée retirée)
+//! - Les notes sont écrites dans les tags avec les autres modifications (POPM en ID3v2,
+//!   RATING 0–100 ailleurs) mais la table reste la source de vérité
+//! - Le shuffle de la file et generate_shuffle pondèrent légèrement le tirage par la note
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Note maximale
+pub const MAX_RATING: u8 = 5;
+
+/// Écart de poids par étoile autour d'une note moyenne (3 étoiles = poids inchangé)
+const WEIGHT_PER_STAR: f64 = 0.15;
+
+/// Fichier ratings.json
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct RatingsCache {
+    pub ratings: HashMap<String, u8>,
+}
+
+impl RatingsCache {
+    pub fn get(&self, path: &str) -> u8 {
+        self.ratings.get(path).copied().unwrap_or(0)
+    }
+
+    /// Note un track (bornée à MAX_RATING) ; 0 retire la note
+    pub fn set(&mut self, path: &str, stars: u8) {
+        if stars == 0 {
+            self.ratings.remove(path);
+        } else {
+            self.ratings.insert(path.to_string(), stars.min(MAX_RATING));
+        }
+    }
+
+    /// Chemins notés au moins `min` étoiles, les mieux notés d'abord
+    pub fn paths_with_min_rating(&self, min: u8) -> Vec<String> {
+        let mut rated: Vec<(&String, u8)> = self.ratings.iter()
+            .filter(|(_, &stars)| stars >= min.max(1))
+            .map(|(path, &stars)| (path, stars))
+            .collect();
+        rated.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        rated.into_iter().map(|(path, _)| path.clone()).collect()
+    }
+}
+
+/// Multiplicateur de poids du shuffle : 0.7 (1 étoile) à 1.3 (5 étoiles), 1.0 sans note
+pub fn shuffle_weight(stars: u8) -> f64 {
+    if stars == 0 {
+        return 1.0;
+    }
+    1.0 + (stars.min(MAX_RATING) as f64 - 3.0) * WEIGHT_PER_STAR
+}
+
+/// Octet de note POPM (convention Windows Media Player : 1, 64, 128, 196, 255)
+pub fn popm_rating_byte(stars: u8) -> u8 {
+    match stars.min(MAX_RATING) {
+        0 => 0,
+        1 => 1,
+        2 => 64,
+        3 => 128,
+        4 => 196,
+        _ => 255,
+    }
+}
+
+fn get_ratings_path() -> PathBuf {
+    crate::get_data_dir().join("ratings.json")
+}
+
+pub fn load_ratings() -> RatingsCache {
+    let path = get_ratings_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        RatingsCache::default()
+    }
+}
+
+pub fn save_ratings(data: &RatingsCache) {
+    let path = get_ratings_path();
+    let content = serde_json::to_string_pretty(data).unwrap_or_default();
+    crate::save_file_secure(&path, &content);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_clamps_and_zero_removes() {
+        let mut ratings = RatingsCache::default();
+        ratings.set("/music/a.flac", 9);
+        assert_eq!(ratings.get("/music/a.flac"), MAX_RATING);
+        ratings.set("/music/a.flac", 0);
+        assert_eq!(ratings.get("/music/a.flac"), 0);
+        assert!(ratings.ratings.is_empty());
+    }
+
+    #[test]
+    fn test_paths_with_min_rating() {
+        let mut ratings = RatingsCache::default();
+        ratings.set("/music/b.flac", 4);
+        ratings.set("/music/a.flac", 5);
+        ratings.set("/music/c.flac", 2);
+        ratings.set("/music/d.flac", 4);
+        assert_eq!(
+            ratings.paths_with_min_rating(4),
+            vec!["/music/a.flac", "/music/b.flac", "/music/d.flac"]
+        );
+        assert_eq!(ratings.paths_with_min_rating(0).len(), 4);
+    }
+
+    #[test]
+    fn test_shuffle_weight_favors_higher_ratings() {
+        assert_eq!(shuffle_weight(0), 1.0);
+        assert_eq!(shuffle_weight(3), 1.0);
+        assert!(shuffle_weight(1) < shuffle_weight(2));
+        assert!(shuffle_weight(5) > shuffle_weight(4));
+        assert!(shuffle_weight(1) > 0.0);
+    }
+}
@@ -1,12 +1,80 @@
 // === RESAMPLER AUDIO ===
-// Wrapper autour de rubato pour resampling haute qualité FFT
+// Wrapper autour de rubato pour resampling sinc (interpolation fenêtrée)
 // Utilisé quand le DAC ne supporte pas le sample rate natif du fichier
 
-use rubato::{FftFixedInOut, Resampler};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Instant;
 
-/// Resampler audio utilisant rubato (FFT-based, haute qualité)
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::engine_stats::{BatchTimer, Stage};
+
+/// Compromis CPU / qualité du resampler
+///
+/// Coût approximatif (multiplications par sample de sortie et par canal) :
+/// - `Fast`     : ~128  (filtre 64 points, interpolation linéaire)
+/// - `Balanced` : ~256  (filtre 128 points, interpolation linéaire)
+/// - `High`     : ~1024 (filtre 256 points, interpolation cubique), soit ~4x `Balanced`.
+///   Sur du 192 kHz stéréo cela représente ~400 M multiplications/s : nettement
+///   visible sur la charge CPU, surtout sur les machines Intel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResamplerQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+impl ResamplerQuality {
+    /// "fast" / "balanced" / "high"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fast" => Some(Self::Fast),
+            "balanced" => Some(Self::Balanced),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    /// Paramètres du filtre sinc (longueur de fenêtre, suréchantillonnage)
+    fn sinc_parameters(self) -> SincInterpolationParameters {
+        let (sinc_len, oversampling_factor, interpolation, window) = match self {
+            Self::Fast => (64, 128, SincInterpolationType::Linear, WindowFunction::Hann2),
+            Self::Balanced => (128, 256, SincInterpolationType::Linear, WindowFunction::Blackman2),
+            Self::High => (256, 256, SincInterpolationType::Cubic, WindowFunction::BlackmanHarris2),
+        };
+        SincInterpolationParameters {
+            sinc_len,
+            f_cutoff: rubato::calculate_cutoff(sinc_len, window),
+            oversampling_factor,
+            interpolation,
+            window,
+        }
+    }
+}
+
+/// Qualité utilisée par les prochains resamplers créés (les streams en cours gardent la leur)
+static RESAMPLER_QUALITY: AtomicU8 = AtomicU8::new(ResamplerQuality::Balanced as u8);
+
+pub fn set_quality(quality: ResamplerQuality) {
+    RESAMPLER_QUALITY.store(quality as u8, Ordering::Relaxed);
+}
+
+pub fn quality() -> ResamplerQuality {
+    match RESAMPLER_QUALITY.load(Ordering::Relaxed) {
+        q if q == ResamplerQuality::Fast as u8 => ResamplerQuality::Fast,
+        q if q == ResamplerQuality::High as u8 => ResamplerQuality::High,
+        _ => ResamplerQuality::Balanced,
+    }
+}
+
+/// Resampler audio utilisant rubato (sinc fenêtré, qualité réglable)
 pub struct AudioResampler {
-    resampler: FftFixedInOut<f32>,
+    resampler: SincFixedIn<f32>,
     channels: usize,
     /// Buffer d'entrée désentrelacé (un Vec par canal)
     input_buffers: Vec<Vec<f32>>,
@@ -18,25 +86,38 @@ pub struct AudioResampler {
     pending_samples: Vec<f32>,
     /// Ratio de resampling (source_rate / target_rate)
     resample_ratio: f64,
+    /// Temps de traitement par buffer (get_engine_stats)
+    timer: BatchTimer,
 }
 
 impl AudioResampler {
-    /// Crée un nouveau resampler
+    /// Crée un nouveau resampler à la qualité courante (voir `set_quality`)
     ///
     /// # Arguments
     /// * `source_rate` - Sample rate du fichier source (ex: 192000)
     /// * `target_rate` - Sample rate cible du DAC (ex: 96000)
     /// * `channels` - Nombre de canaux (2 pour stéréo)
     pub fn new(source_rate: u32, target_rate: u32, channels: usize) -> Result<Self, String> {
+        Self::with_quality(source_rate, target_rate, channels, quality())
+    }
+
+    /// Crée un nouveau resampler avec une qualité explicite
+    pub fn with_quality(
+        source_rate: u32,
+        target_rate: u32,
+        channels: usize,
+        quality: ResamplerQuality,
+    ) -> Result<Self, String> {
         // Taille de chunk - rubato fonctionne par blocs
         // 1024 samples est un bon compromis latence/efficacité
         let chunk_size = 1024;
 
-        let resampler = FftFixedInOut::<f32>::new(
-            source_rate as usize,
-            target_rate as usize,
+        let resampler = SincFixedIn::<f32>::new(
+            target_rate as f64 / source_rate as f64,
+            1.0,  // ratio fixe pour toute la durée du stream
+            quality.sinc_parameters(),
             chunk_size,
-            2,  // sub_chunks pour meilleure qualité
+            channels,
         ).map_err(|e| format!("Failed to create resampler: {}", e))?;
 
         let chunk_size_in = resampler.input_frames_max();
@@ -50,8 +131,8 @@ impl AudioResampler {
 
         #[cfg(debug_assertions)]
         println!(
-            "=== Resampler created ===\n  {} Hz → {} Hz (ratio: {:.4})\n  channels: {}\n  chunk_size_in: {}\n  chunk_size_out: {}",
-            source_rate, target_rate, resample_ratio, channels, chunk_size_in, chunk_size_out
+            "=== Resampler created ===\n  {} Hz → {} Hz (ratio: {:.4}, quality: {:?})\n  channels: {}\n  chunk_size_in: {}\n  chunk_size_out: {}",
+            source_rate, target_rate, resample_ratio, quality, channels, chunk_size_in, chunk_size_out
         );
 
         Ok(Self {
@@ -62,6 +143,7 @@ impl AudioResampler {
             chunk_size_in,
             pending_samples: Vec::with_capacity(chunk_size_in * channels * 2),
             resample_ratio,
+            timer: BatchTimer::new(Stage::Resample),
         })
     }
 
@@ -70,6 +152,8 @@ impl AudioResampler {
     /// L'entrée est en format entrelacé : [L0, R0, L1, R1, L2, R2, ...]
     /// La sortie est aussi entrelacée au nouveau sample rate
     pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let started = Instant::now();
+
         // Ajoute les nouveaux samples au buffer en attente
         self.pending_samples.extend_from_slice(input);
 
@@ -105,6 +189,7 @@ impl AudioResampler {
             self.pending_samples.drain(..samples_per_chunk);
         }
 
+        self.timer.record(started.elapsed());
         output
     }
 
@@ -169,4 +254,17 @@ mod tests {
         let r = resampler.unwrap();
         assert!(!r.is_downsampling()); // Upsampling
     }
+
+    #[test]
+    fn test_resampler_qualities() {
+        assert_eq!(ResamplerQuality::from_name("high"), Some(ResamplerQuality::High));
+        assert_eq!(ResamplerQuality::from_name("ultra"), None);
+
+        for quality in [ResamplerQuality::Fast, ResamplerQuality::Balanced, ResamplerQuality::High] {
+            let mut r = AudioResampler::with_quality(96000, 48000, 2, quality).unwrap();
+            // 4 chunks d'entrée stéréo → environ moitié moins de samples en sortie
+            let output = r.process(&vec![0.0f32; 4096 * 2]);
+            assert!(!output.is_empty() && output.len() <= 4096);
+        }
+    }
 }
@@ -0,0 +1,199 @@
+//! Recherche plein texte dans la bibliothèque (titre, artiste, album)
+//!
+//! Architecture :
+//! - Normalisation : ponctuation Unicode (comme la recherche de genres), minuscules, accents retirés
+//! - Classement par qualité : exact > préfixe > sous-chaîne > tous les mots > sous-séquence
+//! - À qualité égale : titre > artiste > album, puis titre le plus court
+//! - Clés de tri des vues de navigation : même normalisation, article initial (anglais / français) ignoré
+
+use crate::TrackWithMetadata;
+
+/// Qualité d'une correspondance (ordre croissant)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchQuality {
+    /// Les caractères de la requête apparaissent dans l'ordre ("mdvs" → "miles davis")
+    Subsequence,
+    /// Chaque mot de la requête apparaît dans l'un des champs ("davis blue")
+    AllWords,
+    Substring,
+    Prefix,
+    Exact,
+}
+
+/// Minuscules + accents retirés + ponctuation typographique normalisée
+pub fn normalize_for_search(text: &str) -> String {
+    crate::normalize_unicode_punctuation(text)
+        .to_lowercase()
+        .chars()
+        .map(fold_accent)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Articles ignorés en tête des clés de tri (anglais, français). "l'" est collé au mot suivant
+const LEADING_ARTICLES: [&str; 6] = ["the ", "an ", "a ", "les ", "le ", "la "];
+
+/// Clé de tri d'un nom d'artiste ou d'album : "The Beatles" → "beatles", "Édith Piaf" → "edith piaf",
+/// "L'Impératrice" → "imperatrice". Le nom affiché reste l'original
+pub fn sort_key(text: &str) -> String {
+    let normalized = normalize_for_search(text);
+    let stripped = LEADING_ARTICLES
+        .iter()
+        .find_map(|article| normalized.strip_prefix(article))
+        .or_else(|| normalized.strip_prefix("l'"))
+        .filter(|rest| !rest.is_empty());
+    match stripped {
+        Some(rest) => rest.to_string(),
+        None => normalized,
+    }
+}
+
+/// Lettre latine accentuée → lettre de base (les ligatures restent inchangées)
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ł' => 'l',
+        _ => c,
+    }
+}
+
+fn is_subsequence(query: &str, text: &str) -> bool {
+    let mut text_chars = text.chars();
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text_chars.any(|t| t == q))
+}
+
+fn field_quality(query: &str, field: &str) -> Option<MatchQuality> {
+    if field == query {
+        Some(MatchQuality::Exact)
+    } else if field.starts_with(query) {
+        Some(MatchQuality::Prefix)
+    } else if field.contains(query) {
+        Some(MatchQuality::Substring)
+    } else if is_subsequence(query, field) {
+        Some(MatchQuality::Subsequence)
+    } else {
+        None
+    }
+}
+
+/// Meilleure correspondance d'un track : (qualité, index du champ ; 0 = titre)
+fn track_match(query: &str, words: &[&str], fields: &[String; 3]) -> Option<(MatchQuality, usize)> {
+    let best_field = fields
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, field)| field_quality(query, field).map(|q| (q, idx)))
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    // Requête multi-mots répartie sur plusieurs champs ("miles kind of blue")
+    let all_words = words.len() > 1
+        && words.iter().all(|w| fields.iter().any(|f| f.contains(w)));
+
+    match best_field {
+        Some((quality, idx)) if quality > MatchQuality::AllWords => Some((quality, idx)),
+        _ if all_words => Some((MatchQuality::AllWords, 0)),
+        other => other,
+    }
+}
+
+/// Tracks correspondant à la requête, du plus pertinent au moins pertinent
+pub fn search(tracks: &[TrackWithMetadata], query: &str, limit: usize) -> Vec<TrackWithMetadata> {
+    let query = normalize_for_search(query);
+    if query.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let words: Vec<&str> = query.split(' ').collect();
+
+    let mut matches: Vec<(MatchQuality, usize, usize, &TrackWithMetadata)> = tracks
+        .iter()
+        .filter_map(|track| {
+            let fields = [
+                normalize_for_search(&track.metadata.title),
+                normalize_for_search(&track.metadata.artist),
+                normalize_for_search(&track.metadata.album),
+            ];
+            track_match(&query, &words, &fields)
+                .map(|(quality, field)| (quality, field, fields[0].len(), track))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+    matches.truncate(limit);
+    matches.into_iter().map(|(_, _, _, track)| track.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(title: &str, artist: &str, album: &str) -> TrackWithMetadata {
+        let mut track = crate::test_track(&format!("/music/{}.flac", title));
+        track.name = title.to_string();
+        track.folder = album.to_string();
+        track.metadata.title = title.to_string();
+        track.metadata.artist = artist.to_string();
+        track.metadata.album = album.to_string();
+        track
+    }
+
+    #[test]
+    fn test_normalize_for_search() {
+        assert_eq!(normalize_for_search("  Beyoncé – Déjà  Vu "), "beyonce - deja vu");
+        assert_eq!(normalize_for_search("Don\u{2019}t Stop"), "don't stop");
+    }
+
+    #[test]
+    fn test_sort_key() {
+        assert_eq!(sort_key("The Beatles"), "beatles");
+        assert_eq!(sort_key("Édith Piaf"), "edith piaf");
+        assert_eq!(sort_key("L\u{2019}Impératrice"), "imperatrice");
+        assert_eq!(sort_key("Les Négresses Vertes"), "negresses vertes");
+        assert_eq!(sort_key("A Tribe Called Quest"), "tribe called quest");
+        // Un article seul ou en début de mot n'est pas retiré
+        assert_eq!(sort_key("The"), "the");
+        assert_eq!(sort_key("Theo Parrish"), "theo parrish");
+        assert_eq!(sort_key("Lana Del Rey"), "lana del rey");
+
+        let mut artists = vec!["ZZ Top", "Édith Piaf", "The Cure", "air"];
+        artists.sort_by_cached_key(|a| sort_key(a));
+        assert_eq!(artists, vec!["air", "The Cure", "Édith Piaf", "ZZ Top"]);
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let tracks = vec![
+            track("So What", "Miles Davis", "Kind of Blue"),
+            track("Blue in Green", "Miles Davis", "Kind of Blue"),
+            track("Blue", "Joni Mitchell", "Blue"),
+            track("Ballad of the Unknown Eagle", "Unknown", "Nocturnes"),
+        ];
+
+        let titles: Vec<String> = search(&tracks, "blue", 10)
+            .into_iter()
+            .map(|t| t.metadata.title)
+            .collect();
+        // exact (titre) > préfixe (titre) > sous-chaîne (album) > sous-séquence
+        assert_eq!(titles, vec!["Blue", "Blue in Green", "So What", "Ballad of the Unknown Eagle"]);
+
+        let results = search(&tracks, "davis what", 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].metadata.title, "So What");
+    }
+}
@@ -0,0 +1,54 @@
+//! Dernière session de lecture (reprise au démarrage)
+//!
+//! Architecture :
+//! - Instantané du track courant, de sa position, de la file et des modes shuffle / repeat,
+//!   persisté dans session.json
+//! - L'engine le réécrit sur pause / stop et toutes les POSITION_SAVE_INTERVAL_SECS pendant
+//!   la lecture, avec les modes shuffle / repeat qu'il garde en mémoire. La file est celle
+//!   du backend si elle pilote la lecture, sinon celle du frontend (set_session_queue)
+//! - Le frontend le relit au démarrage : file et modes restaurés, le track reprend à la
+//!   position sauvegardée au premier play (audio_play avec start_position)
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::queue::RepeatMode;
+
+/// Fichier session.json
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct SessionState {
+    /// Track en cours au moment de la sauvegarde
+    pub path: String,
+    /// Position dans le track (secondes)
+    pub position: f64,
+    /// File de lecture et index du track courant dans la file
+    #[serde(default)]
+    pub queue: Vec<String>,
+    #[serde(default)]
+    pub queue_index: Option<usize>,
+    #[serde(default)]
+    pub shuffle: bool,
+    #[serde(default)]
+    pub repeat: RepeatMode,
+    /// Timestamp Unix de la sauvegarde
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+fn get_session_path() -> PathBuf {
+    crate::get_data_dir().join("session.json")
+}
+
+/// None si aucune session n'a encore été sauvegardée (ou fichier illisible)
+pub fn load_session() -> Option<SessionState> {
+    let content = fs::read_to_string(get_session_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_session(data: &SessionState) {
+    let path = get_session_path();
+    let content = serde_json::to_string(data).unwrap_or_default();
+    crate::save_file_secure(&path, &content);
+}
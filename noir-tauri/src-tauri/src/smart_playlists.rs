@@ -0,0 +1,270 @@
+//! Playlists intelligentes : des règles sur les métadonnées plutôt qu'une liste figée
+//!
+//! Architecture :
+//! - Une playlist intelligente stocke un arbre de règles (All / Any / Condition)
+//! - Les définitions sont persistées dans smart_playlists.json (pas les résultats)
+//! - Le frontend ré-évalue les règles à chaque ouverture sur TRACKS_CACHE
+//!   (+ ADDED_DATES_CACHE pour le champ "date d'ajout")
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::TrackWithMetadata;
+
+/// Champ de métadonnée sur lequel porte une condition
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    Artist,
+    Album,
+    Genre,
+    Year,
+    BitDepth,
+    SampleRate,
+    Codec,
+    /// Date d'ajout à la bibliothèque (timestamp Unix en secondes)
+    AddedDate,
+}
+
+/// Opérateur de comparaison
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOperator {
+    Equals,
+    NotEquals,
+    Contains,
+    NotContains,
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+    /// Date d'ajout dans les N derniers jours (value = N)
+    WithinLastDays,
+}
+
+/// Arbre de règles sérialisable, ex. `{"type":"all","rules":[{"type":"condition",...}]}`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SmartRule {
+    /// Toutes les sous-règles doivent correspondre (vide = tout correspond)
+    All { rules: Vec<SmartRule> },
+    /// Au moins une sous-règle doit correspondre
+    Any { rules: Vec<SmartRule> },
+    Condition {
+        field: RuleField,
+        operator: RuleOperator,
+        /// Texte ou nombre selon le champ (les nombres en texte sont acceptés)
+        value: serde_json::Value,
+    },
+}
+
+/// Définition d'une playlist intelligente
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SmartPlaylist {
+    pub id: String,
+    pub name: String,
+    pub rules: SmartRule,
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+}
+
+/// Fichier smart_playlists.json
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SmartPlaylistsData {
+    pub playlists: Vec<SmartPlaylist>,
+}
+
+fn get_smart_playlists_path() -> PathBuf {
+    crate::get_data_dir().join("smart_playlists.json")
+}
+
+pub fn load_smart_playlists() -> SmartPlaylistsData {
+    let path = get_smart_playlists_path();
+    if path.exists() {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        SmartPlaylistsData::default()
+    }
+}
+
+pub fn save_smart_playlists(data: &SmartPlaylistsData) {
+    let path = get_smart_playlists_path();
+    let content = serde_json::to_string_pretty(data).unwrap_or_default();
+    crate::save_file_secure(&path, &content);
+}
+
+/// Valeur d'un champ pour un track donné
+enum FieldValue {
+    Text(String),
+    Number(f64),
+    Missing,
+}
+
+fn field_value(field: RuleField, track: &TrackWithMetadata, added_date: Option<u64>) -> FieldValue {
+    let meta = &track.metadata;
+    let text = |s: &str| FieldValue::Text(s.to_lowercase());
+    let number = |n: Option<f64>| n.map(FieldValue::Number).unwrap_or(FieldValue::Missing);
+
+    match field {
+        RuleField::Artist => text(&meta.artist),
+        RuleField::Album => text(&meta.album),
+        RuleField::Genre => meta.genre.as_deref().map(text).unwrap_or(FieldValue::Missing),
+        RuleField::Codec => meta.codec.as_deref().map(text).unwrap_or(FieldValue::Missing),
+        RuleField::Year => number(meta.year.map(f64::from)),
+        RuleField::BitDepth => number(meta.bit_depth.map(f64::from)),
+        RuleField::SampleRate => number(meta.sample_rate.map(f64::from)),
+        RuleField::AddedDate => number(added_date.map(|d| d as f64)),
+    }
+}
+
+fn value_as_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_as_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.trim().to_lowercase(),
+        other => other.to_string(),
+    }
+}
+
+fn condition_matches(
+    field: RuleField,
+    operator: RuleOperator,
+    value: &serde_json::Value,
+    track: &TrackWithMetadata,
+    added_date: Option<u64>,
+    now: u64,
+) -> bool {
+    let actual = field_value(field, track, added_date);
+
+    // Champ absent : seules les négations correspondent
+    if matches!(actual, FieldValue::Missing) {
+        return matches!(operator, RuleOperator::NotEquals | RuleOperator::NotContains);
+    }
+
+    match operator {
+        RuleOperator::WithinLastDays => {
+            let (FieldValue::Number(ts), Some(days)) = (&actual, value_as_number(value)) else {
+                return false;
+            };
+            let cutoff = now.saturating_sub((days.max(0.0) * 86_400.0) as u64);
+            *ts >= cutoff as f64
+        }
+        RuleOperator::Contains | RuleOperator::NotContains => {
+            let contains = match &actual {
+                FieldValue::Text(s) => s.contains(&value_as_text(value)),
+                FieldValue::Number(n) => n.to_string().contains(&value_as_text(value)),
+                FieldValue::Missing => false,
+            };
+            contains == (operator == RuleOperator::Contains)
+        }
+        RuleOperator::Equals | RuleOperator::NotEquals => {
+            let equals = match &actual {
+                FieldValue::Text(s) => *s == value_as_text(value),
+                FieldValue::Number(n) => value_as_number(value) == Some(*n),
+                FieldValue::Missing => false,
+            };
+            equals == (operator == RuleOperator::Equals)
+        }
+        RuleOperator::GreaterThan
+        | RuleOperator::GreaterOrEqual
+        | RuleOperator::LessThan
+        | RuleOperator::LessOrEqual => {
+            let (FieldValue::Number(n), Some(target)) = (&actual, value_as_number(value)) else {
+                return false;
+            };
+            match operator {
+                RuleOperator::GreaterThan => *n > target,
+                RuleOperator::GreaterOrEqual => *n >= target,
+                RuleOperator::LessThan => *n < target,
+                _ => *n <= target,
+            }
+        }
+    }
+}
+
+fn rule_matches(rule: &SmartRule, track: &TrackWithMetadata, added_date: Option<u64>, now: u64) -> bool {
+    match rule {
+        SmartRule::All { rules } => rules.iter().all(|r| rule_matches(r, track, added_date, now)),
+        SmartRule::Any { rules } => rules.iter().any(|r| rule_matches(r, track, added_date, now)),
+        SmartRule::Condition { field, operator, value } => {
+            condition_matches(*field, *operator, value, track, added_date, now)
+        }
+    }
+}
+
+/// Retourne les chemins des tracks qui satisfont les règles (ordre de la bibliothèque)
+pub fn evaluate(rules: &SmartRule, tracks: &[TrackWithMetadata], added_dates: &HashMap<String, u64>) -> Vec<String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    tracks
+        .iter()
+        .filter(|track| rule_matches(rules, track, added_dates.get(&track.path).copied(), now))
+        .map(|track| track.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, genre: &str, year: u32, bit_depth: u8) -> TrackWithMetadata {
+        let mut track = crate::test_track(path);
+        track.metadata.genre = Some(genre.to_string());
+        track.metadata.year = Some(year);
+        track.metadata.bit_depth = Some(bit_depth);
+        track.metadata.sample_rate = Some(96000);
+        track
+    }
+
+    #[test]
+    fn test_all_rules() {
+        let tracks = vec![
+            track("a.flac", "Jazz", 2015, 24),
+            track("b.flac", "Jazz", 2001, 24),
+            track("c.flac", "Rock", 2018, 16),
+        ];
+        let rules: SmartRule = serde_json::from_value(serde_json::json!({
+            "type": "all",
+            "rules": [
+                { "type": "condition", "field": "genre", "operator": "equals", "value": "jazz" },
+                { "type": "condition", "field": "year", "operator": "greater_or_equal", "value": 2010 }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(evaluate(&rules, &tracks, &HashMap::new()), vec!["a.flac"]);
+    }
+
+    #[test]
+    fn test_added_date_within_last_days() {
+        let tracks = vec![track("new.flac", "Jazz", 2020, 24), track("old.flac", "Jazz", 2020, 24)];
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let added = HashMap::from([
+            ("new.flac".to_string(), now - 86_400),
+            ("old.flac".to_string(), now - 90 * 86_400),
+        ]);
+        let rules = SmartRule::Condition {
+            field: RuleField::AddedDate,
+            operator: RuleOperator::WithinLastDays,
+            value: serde_json::json!(30),
+        };
+
+        assert_eq!(evaluate(&rules, &tracks, &added), vec!["new.flac"]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Spectre de fréquences pour le visualiseur
+//!
+//! Architecture :
+//! - Capture : le callback audio écrit un downmix mono (après EQ, avant volume) dans un
+//!   ring pré-alloué d'AtomicU32 (f32 bits) → ni allocation ni verrou côté temps réel
+//! - Analyse : get_spectrum() copie les FFT_SIZE derniers samples, fenêtre de Hann,
+//!   FFT rustfft sur le thread de la commande, bandes logarithmiques 20 Hz – Nyquist
+//! - Magnitudes en dB ramenées à 0.0–1.0, lissées (attaque immédiate, retombée progressive)
+//! - Désactivé par défaut : la capture coûte un store atomique par frame
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+/// Taille de la fenêtre d'analyse (≈ 46 ms à 44.1 kHz)
+const FFT_SIZE: usize = 2048;
+
+/// Capacité du ring de capture (puissance de 2, > FFT_SIZE pour absorber un callback en cours)
+const CAPTURE_SIZE: usize = 8192;
+
+/// Nombre maximum de bandes retournées
+pub const MAX_SPECTRUM_BINS: usize = 256;
+
+/// Plancher en dB : tout ce qui est en dessous vaut 0.0
+const SPECTRUM_FLOOR_DB: f32 = -80.0;
+
+/// Part de la valeur précédente conservée quand une bande retombe
+const SPECTRUM_DECAY: f32 = 0.75;
+
+/// Fréquence basse de la première bande (Hz)
+const SPECTRUM_MIN_FREQUENCY: f32 = 20.0;
+
+/// État de l'analyse, utilisé uniquement hors du callback
+struct Analyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    buffer: Vec<Complex<f32>>,
+    smoothed: Vec<f32>,
+}
+
+/// Capture partagée entre le callback audio et les commandes
+pub struct SpectrumTap {
+    enabled: AtomicBool,
+    ring: Box<[AtomicU32]>,
+    /// Nombre total de samples écrits (l'index dans le ring est pris modulo CAPTURE_SIZE)
+    write_pos: AtomicUsize,
+    sample_rate: AtomicU32,
+    analyzer: Mutex<Option<Analyzer>>,
+}
+
+impl SpectrumTap {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            ring: (0..CAPTURE_SIZE).map(|_| AtomicU32::new(0)).collect(),
+            write_pos: AtomicUsize::new(0),
+            sample_rate: AtomicU32::new(44100),
+            analyzer: Mutex::new(None),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Sample rate du stream courant (fixé à la création du stream)
+    pub fn set_sample_rate(&self, sample_rate: u32) {
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+    }
+
+    /// Appelé depuis le callback audio : downmix mono, sans allocation
+    pub fn capture(&self, interleaved: &[f32], channels: usize) {
+        if !self.is_enabled() || channels == 0 {
+            return;
+        }
+        let mut pos = self.write_pos.load(Ordering::Relaxed);
+        for frame in interleaved.chunks_exact(channels) {
+            let mono = frame.iter().sum::<f32>() / channels as f32;
+            self.ring[pos % CAPTURE_SIZE].store(mono.to_bits(), Ordering::Relaxed);
+            pos = pos.wrapping_add(1);
+        }
+        self.write_pos.store(pos, Ordering::Release);
+    }
+
+    /// Magnitudes lissées (0.0–1.0) de `bins` bandes logarithmiques
+    pub fn spectrum(&self, bins: usize) -> Vec<f32> {
+        let bins = bins.clamp(1, MAX_SPECTRUM_BINS);
+        if !self.is_enabled() {
+            return vec![0.0; bins];
+        }
+
+        let Ok(mut guard) = self.analyzer.lock() else {
+            return vec![0.0; bins];
+        };
+        let analyzer = guard.get_or_insert_with(|| Analyzer {
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+            window: hann_window(FFT_SIZE),
+            buffer: vec![Complex::new(0.0, 0.0); FFT_SIZE],
+            smoothed: Vec::new(),
+        });
+
+        // FFT_SIZE derniers samples, du plus ancien au plus récent
+        let end = self.write_pos.load(Ordering::Acquire);
+        let start = end.wrapping_sub(FFT_SIZE);
+        for (i, slot) in analyzer.buffer.iter_mut().enumerate() {
+            let sample = f32::from_bits(self.ring[start.wrapping_add(i) % CAPTURE_SIZE].load(Ordering::Relaxed));
+            *slot = Complex::new(sample * analyzer.window[i], 0.0);
+        }
+        analyzer.fft.process(&mut analyzer.buffer);
+
+        // Amplitude d'une sinusoïde pleine échelle = 1.0 (gain de la fenêtre de Hann = N/4)
+        let scale = 4.0 / FFT_SIZE as f32;
+        let magnitudes: Vec<f32> = analyzer.buffer[..FFT_SIZE / 2].iter().map(|c| c.norm() * scale).collect();
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed) as f32;
+        let bands = log_bands(&magnitudes, bins, sample_rate);
+
+        if analyzer.smoothed.len() != bins {
+            analyzer.smoothed = vec![0.0; bins];
+        }
+        for (smoothed, value) in analyzer.smoothed.iter_mut().zip(bands) {
+            *smoothed = if value >= *smoothed {
+                value
+            } else {
+                *smoothed * SPECTRUM_DECAY + value * (1.0 - SPECTRUM_DECAY)
+            };
+        }
+        analyzer.smoothed.clone()
+    }
+}
+
+fn hann_window(size: usize) -> Vec<f32> {
+    (0..size)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / size as f32).cos())
+        .collect()
+}
+
+/// Regroupe les magnitudes FFT en bandes logarithmiques (pic par bande), normalisées en dB
+fn log_bands(magnitudes: &[f32], bins: usize, sample_rate: f32) -> Vec<f32> {
+    let nyquist = sample_rate / 2.0;
+    let bin_hz = nyquist / magnitudes.len() as f32;
+    let ratio = (nyquist / SPECTRUM_MIN_FREQUENCY).max(1.0);
+
+    (0..bins)
+        .map(|band| {
+            let low = SPECTRUM_MIN_FREQUENCY * ratio.powf(band as f32 / bins as f32);
+            let high = SPECTRUM_MIN_FREQUENCY * ratio.powf((band + 1) as f32 / bins as f32);
+            let first = ((low / bin_hz) as usize).min(magnitudes.len() - 1);
+            let last = ((high / bin_hz) as usize).clamp(first + 1, magnitudes.len());
+            let peak = magnitudes[first..last].iter().copied().fold(0.0, f32::max);
+
+            let db = 20.0 * peak.max(1e-9).log10();
+            ((db - SPECTRUM_FLOOR_DB) / -SPECTRUM_FLOOR_DB).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectrum_peak_at_sine_frequency() {
+        let tap = SpectrumTap::new();
+        tap.set_enabled(true);
+        tap.set_sample_rate(48000);
+
+        // 1 kHz pleine échelle, stéréo
+        let samples: Vec<f32> = (0..FFT_SIZE)
+            .flat_map(|i| {
+                let s = (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 48000.0).sin();
+                [s, s]
+            })
+            .collect();
+        tap.capture(&samples, 2);
+
+        let bands = tap.spectrum(32);
+        let loudest = bands.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+
+        // Bande contenant 1 kHz sur une échelle log 20 Hz – 24 kHz
+        let expected = ((1000.0f32 / 20.0).ln() / (24000.0f32 / 20.0).ln() * 32.0) as usize;
+        assert_eq!(loudest, expected);
+        assert!(bands[loudest] > 0.9, "1 kHz full scale should be near 0 dB, got {}", bands[loudest]);
+    }
+
+    #[test]
+    fn test_spectrum_disabled_returns_silence() {
+        let tap = SpectrumTap::new();
+        tap.capture(&[1.0; 512], 1);
+        assert_eq!(tap.spectrum(8), vec![0.0; 8]);
+        assert_eq!(tap.write_pos.load(Ordering::Relaxed), 0, "capture must be skipped when disabled");
+    }
+}
@@ -0,0 +1,145 @@
+//! Forme d'onde (aperçu sous la barre de seek)
+//!
+//! Architecture :
+//! - Décodage complet via start_streaming_with_config (même chemin que la lecture, DSD compris),
+//!   au sample rate source : pas de resampling, on ne veut que l'amplitude
+//! - Pic absolu (tous canaux) par bloc de frames, puis regroupement en `buckets` fenêtres
+//!   et normalisation par le pic global → valeurs 0.0–1.0
+//! - Mémoire : le fichier n'est jamais chargé en entier. Coût = ring buffer du décodeur
+//!   (5 s, ~1.7 Mo en 44.1 kHz stéréo, ~15 Mo en 384 kHz) + ~4 f32 par bucket.
+//!   Le coût CPU reste celui d'un décodage complet (quelques secondes pour un long mix)
+//! - Cache disque : waveforms/{hash}.json, hash du chemin + mtime + nombre de buckets
+
+use std::path::{Path, PathBuf};
+
+use ringbuf::traits::Consumer;
+
+use crate::audio_decoder;
+
+/// Au-delà, la forme d'onde n'est plus lisible à l'écran et le cache grossit inutilement
+pub const MAX_WAVEFORM_BUCKETS: usize = 4096;
+
+/// Blocs calculés par bucket (permet de regrouper sans connaître la durée exacte)
+const BLOCKS_PER_BUCKET: u64 = 4;
+
+/// Taille de bloc si la durée du fichier est inconnue
+const FALLBACK_BLOCK_FRAMES: usize = 4096;
+
+fn waveform_cache_dir() -> PathBuf {
+    crate::get_data_dir().join("waveforms")
+}
+
+fn cache_path(path: &str, buckets: usize) -> PathBuf {
+    let mtime = crate::file_mtime(Path::new(path)).unwrap_or(0);
+    let key = format!("{}|{}|{}", path, mtime, buckets);
+    waveform_cache_dir().join(format!("{:x}.json", crate::md5_hash(&key)))
+}
+
+/// Forme d'onde depuis le cache disque, sinon calculée (bloquant : à appeler hors du main thread)
+pub fn get_or_generate(path: &str, buckets: usize) -> Result<Vec<f32>, String> {
+    let buckets = buckets.clamp(1, MAX_WAVEFORM_BUCKETS);
+    let cached = cache_path(path, buckets);
+
+    if let Some(peaks) = std::fs::read_to_string(&cached)
+        .ok()
+        .and_then(|json| serde_json::from_str::<Vec<f32>>(&json).ok())
+        .filter(|peaks| peaks.len() == buckets)
+    {
+        return Ok(peaks);
+    }
+
+    let peaks = generate(path, buckets)?;
+    if std::fs::create_dir_all(waveform_cache_dir()).is_ok() {
+        if let Ok(json) = serde_json::to_string(&peaks) {
+            let _ = std::fs::write(&cached, json);
+        }
+    }
+    Ok(peaks)
+}
+
+/// Décode le fichier et calcule `buckets` pics normalisés
+fn generate(path: &str, buckets: usize) -> Result<Vec<f32>, String> {
+    let info = audio_decoder::probe_audio_file(path)?;
+    let mut session = audio_decoder::start_streaming_with_config(path, 0.0, info.sample_rate, None)?;
+    let mut consumer = session.take_consumer().ok_or("Decoder consumer unavailable")?;
+    let channels = session.state.info.channels.max(1);
+
+    let block_frames = match session.state.info.total_frames {
+        0 => FALLBACK_BLOCK_FRAMES,
+        total => (total / (buckets as u64 * BLOCKS_PER_BUCKET)).max(1) as usize,
+    };
+    let block_samples = block_frames * channels;
+
+    let mut blocks: Vec<f32> = Vec::new();
+    let mut block_peak = 0.0f32;
+    let mut in_block = 0usize;
+    let mut buffer = vec![0.0f32; 16384];
+
+    loop {
+        // decoding_complete lu AVANT le pop : les derniers samples poussés sont vidés au tour suivant
+        let complete = session.state.decoding_complete.load(std::sync::atomic::Ordering::Acquire);
+        let read = consumer.pop_slice(&mut buffer);
+        if read == 0 {
+            if complete {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            continue;
+        }
+        for sample in &buffer[..read] {
+            block_peak = block_peak.max(sample.abs());
+            in_block += 1;
+            if in_block == block_samples {
+                blocks.push(block_peak);
+                block_peak = 0.0;
+                in_block = 0;
+            }
+        }
+    }
+    session.stop();
+    if in_block > 0 {
+        blocks.push(block_peak);
+    }
+
+    Ok(group_into_buckets(&blocks, buckets))
+}
+
+/// Regroupe les pics de blocs en `buckets` fenêtres régulières, normalisées par le pic global
+fn group_into_buckets(blocks: &[f32], buckets: usize) -> Vec<f32> {
+    if blocks.is_empty() {
+        return vec![0.0; buckets];
+    }
+    let mut peaks: Vec<f32> = (0..buckets)
+        .map(|i| {
+            let start = i * blocks.len() / buckets;
+            let end = ((i + 1) * blocks.len() / buckets).max(start + 1).min(blocks.len());
+            blocks[start..end].iter().copied().fold(0.0, f32::max)
+        })
+        .collect();
+
+    let max = peaks.iter().copied().fold(0.0, f32::max);
+    if max > 0.0 {
+        for peak in &mut peaks {
+            *peak /= max;
+        }
+    }
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_into_buckets() {
+        let blocks = [0.1, 0.2, 0.5, 0.25, 0.0, 0.05, 0.1, 0.4];
+        assert_eq!(group_into_buckets(&blocks, 4), vec![0.4, 1.0, 0.1, 0.8]);
+
+        // Moins de blocs que de buckets : chaque bloc est répété
+        assert_eq!(group_into_buckets(&[0.5, 1.0], 4), vec![0.5, 0.5, 1.0, 1.0]);
+
+        // Silence / fichier vide : pas de division par zéro
+        assert_eq!(group_into_buckets(&[0.0, 0.0], 2), vec![0.0, 0.0]);
+        assert_eq!(group_into_buckets(&[], 3), vec![0.0, 0.0, 0.0]);
+    }
+}
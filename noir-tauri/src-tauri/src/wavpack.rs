@@ -0,0 +1,1053 @@
+// === WavPack (.wv) ===
+// Décodage des fichiers WavPack lossless pour le pipeline existant
+// Architecture : [.wv] → WavPackFormatReader (blocs → PCM entier → f32) → paquets PCM f32 → décodeur PCM Symphonia
+//
+// Symphonia 0.5 n'a pas de codec WavPack : comme pour le DSD, on expose un FormatReader
+// qui produit directement des paquets PCM_F32LE (seek, resampler, RingBuffer inchangés).
+// Seul le mode lossless entier est décodé : les fichiers hybrides (lossy), flottants
+// ou DSD échouent avec UNSUPPORTED_CODEC_ERROR.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use symphonia::core::audio::Channels;
+use symphonia::core::codecs::{CodecParameters, CODEC_TYPE_PCM_F32LE};
+use symphonia::core::errors::{decode_error, unsupported_error, Error as SymphoniaError, Result as SymphoniaResult};
+use symphonia::core::formats::{Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{Metadata, MetadataLog};
+use symphonia::core::units::TimeBase;
+
+use crate::audio_decoder::{AudioInfo, UNSUPPORTED_CODEC_ERROR};
+
+/// Taille de l'en-tête d'un bloc ("wvpk" + 28 octets)
+const BLOCK_HEADER_SIZE: usize = 32;
+
+/// Versions de flux lues (WavPack 4.x et 5.x)
+const MIN_STREAM_VERSION: u16 = 0x402;
+const MAX_STREAM_VERSION: u16 = 0x410;
+
+/// Taille maximale d'un bloc (garde-fou contre un en-tête corrompu)
+const MAX_BLOCK_SIZE: u32 = 1 << 24;
+
+// Flags de l'en-tête de bloc
+const BYTES_STORED: u32 = 0x3;
+const MONO_FLAG: u32 = 0x4;
+const HYBRID_FLAG: u32 = 0x8;
+const JOINT_STEREO: u32 = 0x10;
+const FLOAT_DATA: u32 = 0x80;
+const INITIAL_BLOCK: u32 = 0x800;
+const FINAL_BLOCK: u32 = 0x1000;
+const SHIFT_LSB: u32 = 13;
+const SHIFT_MASK: u32 = 0x1f << SHIFT_LSB;
+const SRATE_LSB: u32 = 23;
+const SRATE_MASK: u32 = 0xf << SRATE_LSB;
+const FALSE_STEREO: u32 = 0x4000_0000;
+const DSD_FLAG: u32 = 0x8000_0000;
+/// Bloc codé en mono (vrai mono ou stéréo dont les deux canaux sont identiques)
+const MONO_DATA: u32 = MONO_FLAG | FALSE_STEREO;
+
+// Sous-blocs de métadonnées
+const ID_UNIQUE: u8 = 0x3f;
+const ID_ODD_SIZE: u8 = 0x40;
+const ID_LARGE: u8 = 0x80;
+const ID_DECORR_TERMS: u8 = 0x2;
+const ID_DECORR_WEIGHTS: u8 = 0x3;
+const ID_DECORR_SAMPLES: u8 = 0x4;
+const ID_ENTROPY_VARS: u8 = 0x5;
+const ID_INT32_INFO: u8 = 0x9;
+const ID_WV_BITSTREAM: u8 = 0xa;
+const ID_SAMPLE_RATE: u8 = 0x27;
+
+/// Nombre maximal de passes de décorrélation par bloc
+const MAX_TERMS: usize = 16;
+/// Plus grand terme "historique" (1..=8) ; 17 et 18 sont des prédicteurs d'ordre 2
+const MAX_TERM: i32 = 8;
+
+/// Au-delà de 16 "uns", le compte est suivi d'un code Elias gamma
+const LIMIT_ONES: u32 = 16;
+
+/// Sample rates indexés par les bits SRATE du header (15 = valeur dans ID_SAMPLE_RATE)
+const SAMPLE_RATES: [u32; 15] = [
+    6000, 8000, 9600, 11025, 12000, 16000, 22050, 24000, 32000, 44100, 48000, 64000, 88200, 96000, 192000,
+];
+
+/// Partie fractionnaire de 2^(i/256), sur 8 bits (wp_exp2s)
+const EXP2_TABLE: [u8; 256] = [
+    0x00, 0x01, 0x01, 0x02, 0x03, 0x03, 0x04, 0x05, 0x06, 0x06, 0x07, 0x08, 0x08, 0x09, 0x0a, 0x0b,
+    0x0b, 0x0c, 0x0d, 0x0e, 0x0e, 0x0f, 0x10, 0x10, 0x11, 0x12, 0x13, 0x13, 0x14, 0x15, 0x16, 0x16,
+    0x17, 0x18, 0x19, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1d, 0x1e, 0x1f, 0x20, 0x20, 0x21, 0x22, 0x23,
+    0x24, 0x24, 0x25, 0x26, 0x27, 0x28, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2c, 0x2d, 0x2e, 0x2f, 0x30,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3a, 0x3b, 0x3c, 0x3d,
+    0x3e, 0x3f, 0x40, 0x41, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x48, 0x49, 0x4a, 0x4b,
+    0x4c, 0x4d, 0x4e, 0x4f, 0x50, 0x51, 0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a,
+    0x5b, 0x5c, 0x5d, 0x5e, 0x5e, 0x5f, 0x60, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69,
+    0x6a, 0x6b, 0x6c, 0x6d, 0x6e, 0x6f, 0x70, 0x71, 0x72, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79,
+    0x7a, 0x7b, 0x7c, 0x7d, 0x7e, 0x7f, 0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x87, 0x88, 0x89, 0x8a,
+    0x8b, 0x8c, 0x8d, 0x8e, 0x8f, 0x90, 0x91, 0x92, 0x93, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0x9b,
+    0x9c, 0x9d, 0x9f, 0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa8, 0xa9, 0xaa, 0xab, 0xac, 0xad,
+    0xaf, 0xb0, 0xb1, 0xb2, 0xb3, 0xb4, 0xb6, 0xb7, 0xb8, 0xb9, 0xba, 0xbc, 0xbd, 0xbe, 0xbf, 0xc0,
+    0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc8, 0xc9, 0xca, 0xcb, 0xcd, 0xce, 0xcf, 0xd0, 0xd2, 0xd3, 0xd4,
+    0xd6, 0xd7, 0xd8, 0xd9, 0xdb, 0xdc, 0xdd, 0xde, 0xe0, 0xe1, 0xe2, 0xe4, 0xe5, 0xe6, 0xe8, 0xe9,
+    0xea, 0xec, 0xed, 0xee, 0xf0, 0xf1, 0xf2, 0xf4, 0xf5, 0xf6, 0xf8, 0xf9, 0xfa, 0xfc, 0xfd, 0xff,
+];
+
+/// Extensions traitées par ce module
+pub fn is_wavpack_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("wv"))
+        .unwrap_or(false)
+}
+
+/// En-tête d'un bloc WavPack
+#[derive(Clone, Copy, Debug)]
+struct BlockHeader {
+    /// Taille totale du bloc, en-tête compris
+    block_size: u32,
+    /// Nombre total de samples (par canal) du fichier, None si inconnu
+    total_samples: Option<u64>,
+    block_index: u64,
+    /// 0 pour un bloc sans audio (métadonnées seules)
+    block_samples: u32,
+    flags: u32,
+}
+
+impl BlockHeader {
+    fn parse(raw: &[u8; BLOCK_HEADER_SIZE]) -> Result<Self, String> {
+        if &raw[0..4] != b"wvpk" {
+            return Err("Not a WavPack block".to_string());
+        }
+        let u32_at = |offset: usize| u32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]]);
+
+        let version = u16::from_le_bytes([raw[8], raw[9]]);
+        if !(MIN_STREAM_VERSION..=MAX_STREAM_VERSION).contains(&version) {
+            return Err(format!("Unsupported WavPack stream version: {:#x}", version));
+        }
+        let block_size = u32_at(4).saturating_add(8);
+        if (block_size as usize) < BLOCK_HEADER_SIZE || block_size > MAX_BLOCK_SIZE {
+            return Err(format!("Invalid WavPack block size: {}", block_size));
+        }
+
+        let total_samples = match u32_at(12) {
+            u32::MAX => None,
+            low => Some(low as u64 | ((raw[11] as u64) << 32)),
+        };
+
+        Ok(Self {
+            block_size,
+            total_samples,
+            block_index: u32_at(16) as u64 | ((raw[10] as u64) << 32),
+            block_samples: u32_at(20),
+            flags: u32_at(24),
+        })
+    }
+
+    /// Canaux produits par ce bloc (FALSE_STEREO : codé en mono, restitué en stéréo)
+    fn channels(&self) -> usize {
+        if self.flags & MONO_FLAG != 0 { 1 } else { 2 }
+    }
+
+    fn bits_per_sample(&self) -> u8 {
+        ((self.flags & BYTES_STORED) as u8 + 1) * 8
+    }
+}
+
+/// Lit l'en-tête du prochain bloc. None en fin de flux (EOF ou tag APEv2/ID3v1 final)
+fn read_block_header<R: Read>(reader: &mut R) -> Result<Option<BlockHeader>, String> {
+    let mut raw = [0u8; BLOCK_HEADER_SIZE];
+    match reader.read_exact(&mut raw) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(format!("Cannot read WavPack block: {}", e)),
+    }
+    if &raw[0..4] != b"wvpk" {
+        return Ok(None);
+    }
+    BlockHeader::parse(&raw).map(Some)
+}
+
+/// Itère sur les sous-blocs de métadonnées d'un bloc : (id sans flags de taille, données)
+fn sub_blocks(body: &[u8]) -> impl Iterator<Item = Result<(u8, &[u8]), String>> + '_ {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        if pos + 2 > body.len() {
+            return None;
+        }
+        let id = body[pos];
+        let mut words = body[pos + 1] as usize;
+        pos += 2;
+        if id & ID_LARGE != 0 {
+            if pos + 2 > body.len() {
+                pos = body.len();
+                return Some(Err("Truncated WavPack metadata".to_string()));
+            }
+            words |= ((body[pos] as usize) << 8) | ((body[pos + 1] as usize) << 16);
+            pos += 2;
+        }
+        let padded = words * 2;
+        let len = if id & ID_ODD_SIZE != 0 { padded.saturating_sub(1) } else { padded };
+        if pos + padded > body.len() {
+            pos = body.len();
+            return Some(Err("Truncated WavPack metadata".to_string()));
+        }
+        let data = &body[pos..pos + len];
+        pos += padded;
+        Some(Ok((id & ID_UNIQUE, data)))
+    })
+}
+
+/// Propriétés d'un flux WavPack, lues sur la première frame audio
+#[derive(Clone, Debug)]
+pub struct WavPackInfo {
+    pub sample_rate: u32,
+    pub channels: usize,
+    pub bits_per_sample: u8,
+    /// Samples par canal, 0 si le fichier ne l'indique pas
+    pub total_samples: u64,
+    /// Flags du premier bloc audio (variantes non décodées)
+    flags: u32,
+    /// Position du premier bloc audio et son block_index (fichiers découpés)
+    data_offset: u64,
+    first_index: u64,
+}
+
+impl WavPackInfo {
+    pub fn duration_seconds(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.total_samples as f64 / self.sample_rate as f64
+    }
+
+    /// Variante que ce module ne sait pas décoder, None pour du lossless entier
+    pub fn unsupported_variant(&self) -> Option<&'static str> {
+        if self.flags & DSD_FLAG != 0 {
+            Some("DSD")
+        } else if self.flags & HYBRID_FLAG != 0 {
+            Some("hybrid (lossy)")
+        } else if self.flags & FLOAT_DATA != 0 {
+            Some("floating point")
+        } else {
+            None
+        }
+    }
+}
+
+/// Lit les propriétés du flux : saute les blocs sans audio, puis parcourt la première frame
+/// (un bloc par paire de canaux, de INITIAL_BLOCK à FINAL_BLOCK)
+fn read_info<R: Read + Seek>(reader: &mut R) -> Result<WavPackInfo, String> {
+    let io_err = |e: io::Error| format!("Cannot read WavPack file: {}", e);
+    let mut offset = reader.stream_position().map_err(io_err)?;
+    let mut info: Option<WavPackInfo> = None;
+
+    loop {
+        let Some(header) = read_block_header(reader)? else {
+            break;
+        };
+        let next = offset + header.block_size as u64;
+
+        if header.block_samples > 0 {
+            match info.as_mut() {
+                None => {
+                    let mut body = vec![0u8; header.block_size as usize - BLOCK_HEADER_SIZE];
+                    reader.read_exact(&mut body).map_err(io_err)?;
+                    info = Some(WavPackInfo {
+                        sample_rate: block_sample_rate(&header, &body)?,
+                        channels: header.channels(),
+                        bits_per_sample: header.bits_per_sample(),
+                        total_samples: header.total_samples.unwrap_or(0),
+                        flags: header.flags,
+                        data_offset: offset,
+                        first_index: header.block_index,
+                    });
+                }
+                Some(info) => info.channels += header.channels(),
+            }
+            if header.flags & FINAL_BLOCK != 0 {
+                break;
+            }
+        }
+
+        reader.seek(SeekFrom::Start(next)).map_err(io_err)?;
+        offset = next;
+    }
+
+    let info = info.ok_or("No audio block found in WavPack file")?;
+    if info.sample_rate == 0 {
+        return Err("Unknown WavPack sample rate".to_string());
+    }
+    Ok(info)
+}
+
+/// Sample rate d'un bloc : table indexée par les flags, ou sous-bloc ID_SAMPLE_RATE
+fn block_sample_rate(header: &BlockHeader, body: &[u8]) -> Result<u32, String> {
+    let index = ((header.flags & SRATE_MASK) >> SRATE_LSB) as usize;
+    if let Some(rate) = SAMPLE_RATES.get(index) {
+        return Ok(*rate);
+    }
+    for sub_block in sub_blocks(body) {
+        let (id, data) = sub_block?;
+        if id == ID_SAMPLE_RATE && data.len() >= 3 {
+            return Ok(data.iter().take(4).enumerate().fold(0u32, |rate, (i, b)| rate | ((*b as u32) << (8 * i))));
+        }
+    }
+    Ok(0)
+}
+
+/// Lit les propriétés d'un fichier WavPack sur disque
+pub fn read_wavpack_info(path: &str) -> Result<WavPackInfo, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Cannot open file: {}", e))?;
+    read_info(&mut file)
+}
+
+/// Infos audio d'un fichier WavPack (équivalent de probe_audio_file pour .wv)
+pub fn probe_wavpack(path: &str) -> Result<AudioInfo, String> {
+    let info = read_wavpack_info(path)?;
+
+    #[cfg(debug_assertions)]
+    println!("DEBUG PROBE (WavPack): {}Hz, {}bit, {}ch, {:.1}s",
+        info.sample_rate, info.bits_per_sample, info.channels, info.duration_seconds());
+
+    Ok(AudioInfo {
+        sample_rate: info.sample_rate,
+        output_sample_rate: info.sample_rate,
+        channels: info.channels,
+        duration_seconds: info.duration_seconds(),
+        total_frames: info.total_samples,
+        bit_depth: info.bits_per_sample,
+        is_resampled: false,
+    })
+}
+
+// =====================================================================
+// Décodage d'un bloc
+// =====================================================================
+
+/// 2^(log/256) : reconstruit les valeurs stockées en log (poids, historiques, médianes)
+fn wp_exp2s(log: i32) -> i32 {
+    if log < 0 {
+        return -wp_exp2s(-log);
+    }
+    let value = EXP2_TABLE[(log & 0xff) as usize] as u32 | 0x100;
+    let exponent = log >> 8;
+    if exponent <= 9 {
+        (value >> (9 - exponent)) as i32
+    } else {
+        (value << ((exponent - 9) & 0x1f)) as i32
+    }
+}
+
+/// Log signé 16 bits little-endian, tel que stocké dans les sous-blocs
+fn stored_exp2s(bytes: &[u8]) -> i32 {
+    wp_exp2s(i16::from_le_bytes([bytes[0], bytes[1]]) as i32)
+}
+
+/// Poids stocké sur 8 bits → poids sur 10 bits
+fn restore_weight(weight: i8) -> i32 {
+    let result = (weight as i32) << 3;
+    if result > 0 {
+        result + ((result + 64) >> 7)
+    } else {
+        result
+    }
+}
+
+/// Prédiction pondérée (poids en 1/1024)
+fn apply_weight(weight: i32, sample: i32) -> i32 {
+    ((weight as i64 * sample as i64 + 512) >> 10) as i32
+}
+
+/// Adaptation du poids (termes > 0) : +delta si la prédiction a le signe du résidu
+fn update_weight(weight: &mut i32, delta: i32, source: i32, result: i32) {
+    if source != 0 && result != 0 {
+        if (source ^ result) < 0 { *weight -= delta } else { *weight += delta }
+    }
+}
+
+/// Adaptation du poids des termes croisés (< 0), bornée à ±1024
+fn update_weight_clip(weight: &mut i32, delta: i32, source: i32, result: i32) {
+    if source != 0 && result != 0 {
+        *weight = if (source ^ result) < 0 {
+            (*weight - delta).max(-1024)
+        } else {
+            (*weight + delta).min(1024)
+        };
+    }
+}
+
+/// Une passe de décorrélation (prédicteur adaptatif)
+#[derive(Clone, Copy, Default)]
+struct DecorrPass {
+    term: i32,
+    delta: i32,
+    weight_a: i32,
+    weight_b: i32,
+    samples_a: [i32; 8],
+    samples_b: [i32; 8],
+}
+
+/// Lecteur de bits, LSB en premier
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bit(&mut self) -> Result<bool, String> {
+        let byte = self.data.get(self.pos >> 3).ok_or("Truncated WavPack bitstream")?;
+        let bit = (byte >> (self.pos & 7)) & 1 != 0;
+        self.pos += 1;
+        Ok(bit)
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        let mut value = 0u32;
+        for i in 0..count {
+            if self.bit()? {
+                value |= 1 << i;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Suite de 1 terminée par un 0, au plus `limit` uns (le 0 n'est alors pas lu)
+    fn unary(&mut self, limit: u32) -> Result<u32, String> {
+        let mut count = 0;
+        while count < limit && self.bit()? {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Code Elias gamma (compteur de zéros, extension du compteur de uns)
+    fn gamma(&mut self) -> Result<u32, String> {
+        let cbits = self.unary(33)?;
+        if cbits == 33 {
+            return Err("Invalid WavPack bitstream".to_string());
+        }
+        if cbits < 2 {
+            return Ok(cbits);
+        }
+        Ok(self.bits(cbits - 1)? | (1 << (cbits - 1)))
+    }
+
+    /// Valeur dans [0, max_code] codée sur le nombre de bits minimal (code tronqué)
+    fn code(&mut self, max_code: u32) -> Result<u32, String> {
+        if max_code < 2 {
+            return if max_code == 1 { Ok(self.bit()? as u32) } else { Ok(0) };
+        }
+        let bit_count = 32 - max_code.leading_zeros();
+        let extras = ((1u64 << bit_count) - max_code as u64 - 1) as u32;
+        let code = self.bits(bit_count - 1)?;
+        if code >= extras {
+            Ok((code << 1) - extras + self.bit()? as u32)
+        } else {
+            Ok(code)
+        }
+    }
+}
+
+/// Médianes adaptatives d'un canal
+#[derive(Clone, Copy, Default)]
+struct Entropy {
+    median: [u32; 3],
+}
+
+impl Entropy {
+    fn get(&self, n: usize) -> u32 {
+        (self.median[n] >> 4) + 1
+    }
+
+    fn increase(&mut self, n: usize) {
+        let div = 128 >> n;
+        self.median[n] = self.median[n].wrapping_add((self.median[n].wrapping_add(div) / div).wrapping_mul(5));
+    }
+
+    fn decrease(&mut self, n: usize) {
+        let div = 128 >> n;
+        self.median[n] = self.median[n].wrapping_sub((self.median[n].wrapping_add(div - 2) / div).wrapping_mul(2));
+    }
+}
+
+/// Décodeur des résidus (codage entropique adaptatif de WavPack, mode lossless)
+struct WordReader<'a> {
+    bits: BitReader<'a>,
+    entropy: [Entropy; 2],
+    zeros_acc: u32,
+    holding_zero: bool,
+    holding_one: bool,
+}
+
+impl<'a> WordReader<'a> {
+    fn new(data: &'a [u8], entropy: [Entropy; 2]) -> Self {
+        Self { bits: BitReader::new(data), entropy, zeros_acc: 0, holding_zero: false, holding_one: false }
+    }
+
+    fn next(&mut self, channel: usize) -> Result<i32, String> {
+        // Signal quasi nul sur tous les canaux : séquences de zéros codées par leur longueur
+        if self.entropy[0].median[0] < 2 && self.entropy[1].median[0] < 2
+            && !self.holding_zero && !self.holding_one
+        {
+            if self.zeros_acc > 0 {
+                self.zeros_acc -= 1;
+                if self.zeros_acc > 0 {
+                    return Ok(0);
+                }
+            } else {
+                self.zeros_acc = self.bits.gamma()?;
+                if self.zeros_acc > 0 {
+                    self.entropy = [Entropy::default(); 2];
+                    return Ok(0);
+                }
+            }
+        }
+
+        let ones_count = if self.holding_zero {
+            self.holding_zero = false;
+            0
+        } else {
+            let mut count = self.bits.unary(LIMIT_ONES + 1)?;
+            if count > LIMIT_ONES {
+                return Err("Invalid WavPack bitstream".to_string());
+            }
+            if count == LIMIT_ONES {
+                count += self.bits.gamma()?;
+            }
+            // Le bit de poids faible est reporté sur le sample suivant
+            let ones = if self.holding_one { (count >> 1) + 1 } else { count >> 1 };
+            self.holding_one = count & 1 != 0;
+            self.holding_zero = !self.holding_one;
+            ones
+        };
+
+        let c = &mut self.entropy[channel];
+        let (low, high) = match ones_count {
+            0 => {
+                let high = c.get(0) - 1;
+                c.decrease(0);
+                (0, high)
+            }
+            1 => {
+                let low = c.get(0);
+                c.increase(0);
+                let high = low.wrapping_add(c.get(1) - 1);
+                c.decrease(1);
+                (low, high)
+            }
+            2 => {
+                let low = c.get(0);
+                c.increase(0);
+                let low = low.wrapping_add(c.get(1));
+                c.increase(1);
+                let high = low.wrapping_add(c.get(2) - 1);
+                c.decrease(2);
+                (low, high)
+            }
+            n => {
+                let low = c.get(0);
+                c.increase(0);
+                let low = low.wrapping_add(c.get(1));
+                c.increase(1);
+                let low = low.wrapping_add((n - 2).wrapping_mul(c.get(2)));
+                let high = low.wrapping_add(c.get(2) - 1);
+                c.increase(2);
+                (low, high)
+            }
+        };
+        let (low, high) = (low & 0x7fff_ffff, high & 0x7fff_ffff);
+
+        let value = self.bits.code(high.wrapping_sub(low))?.wrapping_add(low);
+        let negative = self.bits.bit()?;
+        Ok(if negative { !(value as i32) } else { value as i32 })
+    }
+}
+
+/// Reconstitution des bits de poids faible retirés par l'encodeur (ID_INT32_INFO + SHIFT)
+#[derive(Clone, Copy, Default)]
+struct SampleFixup {
+    /// Bits stockés à part (flux .wvx) : perdus ici, la sortie f32 ne les représente pas
+    extra_bits: u32,
+    shift: u32,
+    and: i32,
+    or: i32,
+}
+
+impl SampleFixup {
+    fn apply(&self, sample: i32) -> i32 {
+        let sample = sample.wrapping_shl(self.extra_bits);
+        let bit = (sample & self.and) | self.or;
+        sample.wrapping_add(bit).wrapping_shl(self.shift).wrapping_sub(bit)
+    }
+}
+
+/// Décode un bloc audio : un vecteur de samples entiers par canal produit (1 ou 2)
+fn decode_block(header: &BlockHeader, body: &[u8]) -> Result<Vec<Vec<i32>>, String> {
+    let flags = header.flags;
+    let mono_data = flags & MONO_DATA != 0;
+
+    let mut passes: Vec<DecorrPass> = Vec::new();
+    let mut entropy = None;
+    let mut bitstream = None;
+    let mut fixup = SampleFixup::default();
+
+    for sub_block in sub_blocks(body) {
+        let (id, data) = sub_block?;
+        match id {
+            ID_DECORR_TERMS => {
+                if data.len() > MAX_TERMS {
+                    return Err("Too many WavPack decorrelation terms".to_string());
+                }
+                // Stockés dans l'ordre de l'encodeur : le dernier octet est la première passe décodée
+                passes = data.iter().rev().map(|b| DecorrPass {
+                    term: (b & 0x1f) as i32 - 5,
+                    delta: ((b >> 5) & 0x7) as i32,
+                    ..Default::default()
+                }).collect();
+                let valid = passes.iter().all(|p| {
+                    p.term != 0 && p.term >= -3 && (p.term <= MAX_TERM || p.term >= 17) && p.term <= 18
+                        && !(mono_data && p.term < 0)
+                });
+                if !valid {
+                    return Err("Invalid WavPack decorrelation term".to_string());
+                }
+            }
+            ID_DECORR_WEIGHTS => {
+                let per_term = if mono_data { 1 } else { 2 };
+                if data.len() / per_term > passes.len() {
+                    return Err("Invalid WavPack decorrelation weights".to_string());
+                }
+                for (pass, weights) in passes.iter_mut().rev().zip(data.chunks_exact(per_term)) {
+                    pass.weight_a = restore_weight(weights[0] as i8);
+                    if !mono_data {
+                        pass.weight_b = restore_weight(weights[1] as i8);
+                    }
+                }
+            }
+            ID_DECORR_SAMPLES => read_decorr_samples(&mut passes, data, mono_data)?,
+            ID_ENTROPY_VARS => {
+                if data.len() != if mono_data { 6 } else { 12 } {
+                    return Err("Invalid WavPack entropy variables".to_string());
+                }
+                let mut vars = [Entropy::default(); 2];
+                for (i, log) in data.chunks_exact(2).enumerate() {
+                    vars[i / 3].median[i % 3] = stored_exp2s(log) as u32;
+                }
+                entropy = Some(vars);
+            }
+            ID_INT32_INFO if data.len() >= 4 => {
+                // sent_bits, zeros, ones, dups : un seul est utilisé
+                if data[0] > 30 {
+                    return Err("Invalid WavPack integer info".to_string());
+                } else if data[0] != 0 {
+                    fixup.extra_bits = data[0] as u32;
+                } else if data[1] != 0 {
+                    fixup.shift = data[1] as u32;
+                } else if data[2] != 0 {
+                    fixup = SampleFixup { shift: data[2] as u32, and: 1, or: 1, ..fixup };
+                } else if data[3] != 0 {
+                    fixup = SampleFixup { shift: data[3] as u32, and: 1, ..fixup };
+                }
+            }
+            ID_WV_BITSTREAM => bitstream = Some(data),
+            _ => {}
+        }
+    }
+
+    fixup.shift += (flags & SHIFT_MASK) >> SHIFT_LSB;
+    if fixup.shift > 31 {
+        return Err("Invalid WavPack sample shift".to_string());
+    }
+    let entropy = entropy.ok_or("Missing WavPack entropy variables")?;
+    let bitstream = bitstream.ok_or("Missing WavPack bitstream")?;
+    let mut words = WordReader::new(bitstream, entropy);
+    let count = header.block_samples as usize;
+
+    if mono_data {
+        let mut samples = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut sample = words.next(0)?;
+            for pass in passes.iter_mut() {
+                sample = decorr_mono(pass, sample, i);
+            }
+            samples.push(fixup.apply(sample));
+        }
+        return Ok(if flags & FALSE_STEREO != 0 { vec![samples.clone(), samples] } else { vec![samples] });
+    }
+
+    let mut left = Vec::with_capacity(count);
+    let mut right = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut l = words.next(0)?;
+        let mut r = words.next(1)?;
+        for pass in passes.iter_mut() {
+            (l, r) = decorr_stereo(pass, l, r, i);
+        }
+        if flags & JOINT_STEREO != 0 {
+            r = r.wrapping_sub(l >> 1);
+            l = l.wrapping_add(r);
+        }
+        left.push(fixup.apply(l));
+        right.push(fixup.apply(r));
+    }
+    Ok(vec![left, right])
+}
+
+/// Historiques initiaux des passes (stockés en log, dernière passe en premier)
+fn read_decorr_samples(passes: &mut [DecorrPass], data: &[u8], mono_data: bool) -> Result<(), String> {
+    fn take<'a>(rest: &mut &'a [u8], count: usize) -> Result<&'a [u8], String> {
+        if rest.len() < count {
+            return Err("Invalid WavPack decorrelation samples".to_string());
+        }
+        let (bytes, tail) = rest.split_at(count);
+        *rest = tail;
+        Ok(bytes)
+    }
+
+    let mut rest = data;
+    for pass in passes.iter_mut().rev() {
+        pass.samples_a = [0; 8];
+        pass.samples_b = [0; 8];
+        if rest.is_empty() {
+            continue;
+        }
+        if pass.term > MAX_TERM {
+            let a = take(&mut rest, 4)?;
+            pass.samples_a[0] = stored_exp2s(&a[0..2]);
+            pass.samples_a[1] = stored_exp2s(&a[2..4]);
+            if !mono_data {
+                let b = take(&mut rest, 4)?;
+                pass.samples_b[0] = stored_exp2s(&b[0..2]);
+                pass.samples_b[1] = stored_exp2s(&b[2..4]);
+            }
+        } else if pass.term < 0 {
+            let ab = take(&mut rest, 4)?;
+            pass.samples_a[0] = stored_exp2s(&ab[0..2]);
+            pass.samples_b[0] = stored_exp2s(&ab[2..4]);
+        } else {
+            for m in 0..pass.term as usize {
+                pass.samples_a[m] = stored_exp2s(take(&mut rest, 2)?);
+                if !mono_data {
+                    pass.samples_b[m] = stored_exp2s(take(&mut rest, 2)?);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prédiction d'ordre 2 des termes 17 (extrapolation linéaire) et 18 (amortie)
+fn second_order(term: i32, samples: &mut [i32; 8]) -> i32 {
+    let prediction = if term & 1 != 0 {
+        samples[0].wrapping_mul(2).wrapping_sub(samples[1])
+    } else {
+        samples[0].wrapping_mul(3).wrapping_sub(samples[1]) >> 1
+    };
+    samples[1] = samples[0];
+    prediction
+}
+
+/// Inverse une passe sur un sample mono (i = index du sample dans le bloc)
+fn decorr_mono(pass: &mut DecorrPass, residual: i32, i: usize) -> i32 {
+    let (source, slot) = if pass.term > MAX_TERM {
+        (second_order(pass.term, &mut pass.samples_a), 0)
+    } else {
+        (pass.samples_a[i & 7], (i + pass.term as usize) & 7)
+    };
+    let sample = residual.wrapping_add(apply_weight(pass.weight_a, source));
+    update_weight(&mut pass.weight_a, pass.delta, source, residual);
+    pass.samples_a[slot] = sample;
+    sample
+}
+
+/// Inverse une passe sur une paire de samples stéréo (termes < 0 : prédiction croisée)
+fn decorr_stereo(pass: &mut DecorrPass, l: i32, r: i32, i: usize) -> (i32, i32) {
+    match pass.term {
+        term if term > 0 => {
+            let (source_a, source_b, slot) = if term > MAX_TERM {
+                (second_order(term, &mut pass.samples_a), second_order(term, &mut pass.samples_b), 0)
+            } else {
+                (pass.samples_a[i & 7], pass.samples_b[i & 7], (i + term as usize) & 7)
+            };
+            let new_l = l.wrapping_add(apply_weight(pass.weight_a, source_a));
+            let new_r = r.wrapping_add(apply_weight(pass.weight_b, source_b));
+            update_weight(&mut pass.weight_a, pass.delta, source_a, l);
+            update_weight(&mut pass.weight_b, pass.delta, source_b, r);
+            pass.samples_a[slot] = new_l;
+            pass.samples_b[slot] = new_r;
+            (new_l, new_r)
+        }
+        -1 => {
+            let new_l = l.wrapping_add(apply_weight(pass.weight_a, pass.samples_a[0]));
+            update_weight_clip(&mut pass.weight_a, pass.delta, pass.samples_a[0], l);
+            let new_r = r.wrapping_add(apply_weight(pass.weight_b, new_l));
+            update_weight_clip(&mut pass.weight_b, pass.delta, new_l, r);
+            pass.samples_a[0] = new_r;
+            (new_l, new_r)
+        }
+        term => {
+            let new_r = r.wrapping_add(apply_weight(pass.weight_b, pass.samples_b[0]));
+            update_weight_clip(&mut pass.weight_b, pass.delta, pass.samples_b[0], r);
+            let source_l = if term == -3 {
+                std::mem::replace(&mut pass.samples_a[0], new_r)
+            } else {
+                new_r
+            };
+            let new_l = l.wrapping_add(apply_weight(pass.weight_a, source_l));
+            update_weight_clip(&mut pass.weight_a, pass.delta, source_l, l);
+            pass.samples_b[0] = new_l;
+            (new_l, new_r)
+        }
+    }
+}
+
+// =====================================================================
+// FormatReader
+// =====================================================================
+
+/// FormatReader WavPack : décode une frame (tous les blocs d'un même block_index)
+/// par paquet et émet des paquets PCM f32 entrelacés
+pub struct WavPackFormatReader {
+    source: MediaSourceStream,
+    info: WavPackInfo,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    body: Vec<u8>,
+}
+
+impl WavPackFormatReader {
+    pub fn open(mut source: MediaSourceStream) -> Result<Self, String> {
+        let info = read_info(&mut source)?;
+        if let Some(variant) = info.unsupported_variant() {
+            return Err(format!("{}: WavPack {}", UNSUPPORTED_CODEC_ERROR, variant));
+        }
+        source.seek(SeekFrom::Start(info.data_offset)).map_err(|e| e.to_string())?;
+
+        let mut codec_params = CodecParameters::new();
+        codec_params
+            .for_codec(CODEC_TYPE_PCM_F32LE)
+            .with_sample_rate(info.sample_rate)
+            .with_channels(Channels::from_bits_truncate((1u32 << info.channels) - 1))
+            .with_time_base(TimeBase::new(1, info.sample_rate));
+        if info.total_samples > 0 {
+            codec_params.with_n_frames(info.total_samples);
+        }
+
+        Ok(Self {
+            source,
+            tracks: vec![Track::new(0, codec_params)],
+            cues: Vec::new(),
+            metadata: MetadataLog::default(),
+            body: Vec::new(),
+            info,
+        })
+    }
+
+    /// Profondeur native du flux (16, 24, 32 bits)
+    pub fn bits_per_sample(&self) -> u8 {
+        self.info.bits_per_sample
+    }
+
+    /// Lit et décode les blocs de la frame suivante : (block_index, samples, PCM entrelacé)
+    fn read_frame(&mut self) -> SymphoniaResult<Option<(u64, usize, Vec<f32>)>> {
+        let mut frame: Option<(u64, usize)> = None;
+        let mut channels: Vec<Vec<f32>> = Vec::new();
+
+        loop {
+            let header = match read_block_header(&mut self.source) {
+                Ok(Some(header)) => header,
+                Ok(None) if frame.is_none() => return Ok(None),
+                Ok(None) => break,
+                Err(_) => return decode_error("wavpack: invalid block header"),
+            };
+            self.body.resize(header.block_size as usize - BLOCK_HEADER_SIZE, 0);
+            self.source.read_exact(&mut self.body).map_err(SymphoniaError::IoError)?;
+            if header.block_samples == 0 {
+                continue;
+            }
+
+            let (_, samples) = *frame.get_or_insert((header.block_index, header.block_samples as usize));
+            if header.block_samples as usize != samples {
+                return decode_error("wavpack: inconsistent block sizes in frame");
+            }
+            let scale = 1.0 / (1u64 << (header.bits_per_sample() - 1)) as f32;
+            match decode_block(&header, &self.body) {
+                Ok(decoded) => channels.extend(
+                    decoded.into_iter().map(|ch| ch.into_iter().map(|s| s as f32 * scale).collect()),
+                ),
+                Err(e) => {
+                    eprintln!("WavPack block {}: {}", header.block_index, e);
+                    return decode_error("wavpack: corrupt block");
+                }
+            }
+            if header.flags & FINAL_BLOCK != 0 {
+                break;
+            }
+        }
+
+        let Some((block_index, samples)) = frame else {
+            return Ok(None);
+        };
+        // Canaux annoncés par la première frame : un bloc manquant est complété par du silence
+        channels.resize(self.info.channels, vec![0.0; samples]);
+        let mut pcm = vec![0.0f32; samples * self.info.channels];
+        for (ch, data) in channels.iter().take(self.info.channels).enumerate() {
+            for (frame_index, sample) in data.iter().enumerate() {
+                pcm[frame_index * self.info.channels + ch] = *sample;
+            }
+        }
+        Ok(Some((block_index - self.info.first_index.min(block_index), samples, pcm)))
+    }
+}
+
+impl FormatReader for WavPackFormatReader {
+    fn try_new(source: MediaSourceStream, _options: &FormatOptions) -> SymphoniaResult<Self> {
+        match Self::open(source) {
+            Ok(reader) => Ok(reader),
+            Err(_) => unsupported_error("wavpack: unsupported or invalid WavPack stream"),
+        }
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> SymphoniaResult<SeekedTo> {
+        let required_ts = match to {
+            SeekTo::Time { time, .. } => {
+                ((time.seconds as f64 + time.frac) * self.info.sample_rate as f64) as u64
+            }
+            SeekTo::TimeStamp { ts, .. } => ts,
+        };
+        let target = required_ts + self.info.first_index;
+
+        // Pas d'index dans le format : parcours des en-têtes jusqu'à la frame qui contient la cible
+        let mut offset = self.info.data_offset;
+        let mut actual_ts = self.info.total_samples;
+        loop {
+            self.source.seek(SeekFrom::Start(offset)).map_err(SymphoniaError::IoError)?;
+            let header = match read_block_header(&mut self.source) {
+                Ok(Some(header)) => header,
+                Ok(None) => break,
+                Err(_) => return decode_error("wavpack: invalid block header"),
+            };
+            if header.block_samples > 0 && header.flags & INITIAL_BLOCK != 0
+                && target < header.block_index + header.block_samples as u64
+            {
+                actual_ts = header.block_index.saturating_sub(self.info.first_index);
+                break;
+            }
+            offset += header.block_size as u64;
+        }
+        self.source.seek(SeekFrom::Start(offset)).map_err(SymphoniaError::IoError)?;
+
+        Ok(SeekedTo { track_id: 0, required_ts, actual_ts })
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn next_packet(&mut self) -> SymphoniaResult<Packet> {
+        let Some((ts, samples, pcm)) = self.read_frame()? else {
+            return Err(SymphoniaError::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "end of WavPack stream",
+            )));
+        };
+        let data: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+        Ok(Packet::new_from_boxed_slice(0, ts, samples as u64, data.into_boxed_slice()))
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Bloc mono 16 bits / 44.1kHz de 32 samples (sinusoïde de période 16, amplitude 1000),
+    /// passes 17 et 2, médianes initiales non nulles
+    const MONO_BLOCK: [u8; 184] = [
+        0x77, 0x76, 0x70, 0x6b, 0xb0, 0x00, 0x00, 0x00, 0x10, 0x04, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x05, 0x18, 0xbc, 0x04, 0x7f, 0xff, 0x26, 0x3f,
+        0x02, 0x01, 0x47, 0x56, 0x03, 0x01, 0x18, 0x10, 0x04, 0x04, 0x00, 0x01, 0x00, 0xfe, 0x00, 0x00,
+        0x00, 0x00, 0x05, 0x03, 0x00, 0x06, 0x00, 0x04, 0x00, 0x02, 0x0a, 0x3e, 0xf1, 0xff, 0xef, 0x7f,
+        0xeb, 0xfc, 0xff, 0xfb, 0xbf, 0x58, 0xfc, 0xff, 0xfb, 0xbf, 0xc4, 0xfc, 0xff, 0xfb, 0xdf, 0x33,
+        0xfe, 0xff, 0xfd, 0x6f, 0x4c, 0xff, 0xff, 0xfe, 0xcb, 0xec, 0xff, 0xdf, 0xdf, 0xb6, 0xff, 0x7f,
+        0xbf, 0xdf, 0xff, 0x7f, 0xff, 0x62, 0xfe, 0xff, 0xf7, 0xdf, 0xc5, 0xff, 0xff, 0xfd, 0xb7, 0xe0,
+        0xff, 0x7f, 0xff, 0xba, 0xff, 0xff, 0xef, 0x5f, 0xe7, 0xff, 0xff, 0xfd, 0x5d, 0xfd, 0xff, 0xef,
+        0x8b, 0xfb, 0xff, 0xef, 0x9e, 0xff, 0x7f, 0xbf, 0x55, 0xfe, 0xff, 0xfd, 0xfa, 0xfb, 0xff, 0xf7,
+        0xeb, 0xed, 0xff, 0xdf, 0xef, 0x8a, 0xff, 0x7f, 0xbf, 0x02, 0xfe, 0xff, 0xbd, 0x8f, 0xff, 0xbc,
+        0x3f, 0xee, 0xff, 0xbf, 0x4d, 0xff, 0xff, 0xbd, 0x94, 0xff, 0xff, 0x5e, 0xdc, 0xff, 0xff, 0xae,
+        0xfa, 0xff, 0xbf, 0x86, 0xff, 0xf1, 0xaf, 0x1f,
+    ];
+
+    fn expected_sine() -> Vec<i32> {
+        (0..32).map(|k| (1000.0 * (2.0 * std::f64::consts::PI * k as f64 / 16.0).sin()).round() as i32).collect()
+    }
+
+    #[test]
+    fn test_exp2s_and_weights() {
+        assert_eq!(wp_exp2s(0), 0);
+        assert_eq!(wp_exp2s(0x0900), 256);
+        assert_eq!(wp_exp2s(0x0a00), 512);
+        assert_eq!(wp_exp2s(-0x0a00), -512);
+        assert_eq!(restore_weight(0), 0);
+        assert_eq!(restore_weight(127), 1024);
+        assert_eq!(restore_weight(-128), -1024);
+    }
+
+    #[test]
+    fn test_decode_mono_block() {
+        let header = BlockHeader::parse(MONO_BLOCK[..BLOCK_HEADER_SIZE].try_into().unwrap()).unwrap();
+        assert_eq!(header.block_samples, 32);
+        assert_eq!(header.channels(), 1);
+        assert_eq!(header.bits_per_sample(), 16);
+
+        let decoded = decode_block(&header, &MONO_BLOCK[BLOCK_HEADER_SIZE..]).unwrap();
+        assert_eq!(decoded, vec![expected_sine()]);
+    }
+
+    #[test]
+    fn test_reader_packets_and_seek() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(MONO_BLOCK.to_vec())), Default::default());
+        let mut reader = WavPackFormatReader::open(mss).unwrap();
+        assert_eq!(reader.tracks()[0].codec_params.sample_rate, Some(44100));
+
+        let packet = reader.next_packet().unwrap();
+        assert_eq!(packet.dur, 32);
+        assert_eq!(packet.buf().len(), 32 * 4);
+        let peak = f32::from_le_bytes(packet.buf()[16..20].try_into().unwrap());
+        assert!((peak - 1000.0 / 32768.0).abs() < 1e-6);
+        assert!(reader.next_packet().is_err());
+
+        let seeked = reader.seek(SeekMode::Coarse, SeekTo::TimeStamp { ts: 10, track_id: 0 }).unwrap();
+        assert_eq!(seeked.actual_ts, 0);
+        assert!(reader.next_packet().is_ok());
+    }
+
+    #[test]
+    fn test_hybrid_stream_is_unsupported() {
+        let mut block = MONO_BLOCK.to_vec();
+        block[24] |= HYBRID_FLAG as u8;
+        let info = read_info(&mut Cursor::new(block.clone())).unwrap();
+        assert_eq!(info.unsupported_variant(), Some("hybrid (lossy)"));
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(block)), Default::default());
+        let error = WavPackFormatReader::open(mss).err().unwrap();
+        assert!(error.starts_with(UNSUPPORTED_CODEC_ERROR));
+    }
+}
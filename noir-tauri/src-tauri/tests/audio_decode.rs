@@ -1,9 +1,12 @@
 // =============================================================================
-// Audio Decode Tests (Spec 1.1 - 1.11)
+// Audio Decode Tests (Spec 1.1 - 1.14)
 // Tests probe_audio_file and start_streaming for every supported format.
 // =============================================================================
 
-use noir_tauri_lib::audio_decoder::{probe_audio_file, start_streaming};
+use noir_tauri_lib::audio_decoder::{
+    probe_audio_file, start_streaming, start_streaming_with_speed, BufferConfig, UNSUPPORTED_CODEC_ERROR,
+};
+use ringbuf::traits::Consumer;
 
 /// Helper: absolute path to a fixture file.
 fn fixture_path(name: &str) -> String {
@@ -301,6 +304,93 @@ fn test_1_11_stream_empty_flac_no_panic() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// 1.12  Ogg Vorbis 44.1 kHz
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_1_12_probe_ogg_vorbis() {
+    let path = fixture_path("test_44100_vorbis.ogg");
+    let info = probe_audio_file(&path).expect("probe_audio_file should succeed for Ogg Vorbis");
+
+    assert_eq!(info.sample_rate, 44100, "sample rate should be 44100");
+    assert!(info.duration_seconds > 2.9 && info.duration_seconds < 3.1,
+        "duration should be ~3s, got {}", info.duration_seconds);
+}
+
+#[test]
+fn test_1_12_stream_ogg_vorbis_decodes_pcm() {
+    let path = fixture_path("test_44100_vorbis.ogg");
+    let mut session = start_streaming(&path)
+        .expect("start_streaming should succeed for Ogg Vorbis");
+
+    // start_streaming attend le pre-roll : des samples PCM sont déjà dans le RingBuffer
+    let mut consumer = session.take_consumer().expect("consumer should be available");
+    let mut pcm = vec![0.0f32; 4096];
+    let read = consumer.pop_slice(&mut pcm);
+    assert!(read > 0, "Vorbis decoding should produce PCM samples");
+    session.stop();
+}
+
+// ---------------------------------------------------------------------------
+// 1.13  Opus — no Symphonia decoder: clear "unsupported codec" error
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_1_13_probe_opus() {
+    let path = fixture_path("test_opus.opus");
+    let info = probe_audio_file(&path).expect("probe_audio_file should succeed for Opus (lofty)");
+
+    assert_eq!(info.sample_rate, 48000, "sample rate should be 48000");
+    assert!(info.duration_seconds > 2.9 && info.duration_seconds < 3.1,
+        "duration should be ~3s, got {}", info.duration_seconds);
+}
+
+#[test]
+fn test_1_13_stream_opus_reports_unsupported_codec() {
+    let path = fixture_path("test_opus.opus");
+    let error = start_streaming(&path).err().expect("start_streaming should fail for Opus");
+
+    assert!(error.starts_with(UNSUPPORTED_CODEC_ERROR),
+        "error should flag the codec as unsupported, got: {}", error);
+    assert!(error.contains("Opus"), "error should name the codec, got: {}", error);
+}
+
+// ---------------------------------------------------------------------------
+// 1.14  WavPack lossless 16-bit / 44.1 kHz stereo (decoded by wavpack.rs)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_1_14_probe_wavpack() {
+    let path = fixture_path("test_44100_16.wv");
+    let info = probe_audio_file(&path).expect("probe_audio_file should succeed for WavPack");
+
+    assert_eq!(info.sample_rate, 44100, "sample rate should be 44100");
+    assert_eq!(info.bit_depth, 16, "bit depth should be 16");
+    assert_eq!(info.channels, 2, "WavPack fixture is stereo");
+    assert_eq!(info.total_frames, 132300, "3s at 44.1kHz");
+    assert!(info.duration_seconds > 2.9 && info.duration_seconds < 3.1,
+        "duration should be ~3s, got {}", info.duration_seconds);
+}
+
+#[test]
+fn test_1_14_stream_wavpack_decodes_pcm() {
+    let path = fixture_path("test_44100_16.wv");
+    let mut session = start_streaming(&path)
+        .expect("start_streaming should succeed for WavPack");
+
+    let mut consumer = session.take_consumer().expect("consumer should be available");
+    let mut pcm = vec![0.0f32; 4096];
+    let read = consumer.pop_slice(&mut pcm);
+    assert!(read > 0, "WavPack decoding should produce PCM samples");
+
+    // Sinusoïde 440 Hz : amplitude 1/8 à gauche, 1/16 à droite
+    let peak = |channel: usize| pcm[..read].iter().skip(channel).step_by(2).fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!((peak(0) - 0.125).abs() < 0.005, "left peak should be ~0.125, got {}", peak(0));
+    assert!((peak(1) - 0.0625).abs() < 0.005, "right peak should be ~0.0625, got {}", peak(1));
+    session.stop();
+}
+
 // ---------------------------------------------------------------------------
 // Additional: non-existent file
 // ---------------------------------------------------------------------------
@@ -330,3 +420,23 @@ fn test_audio_info_output_sample_rate_equals_source_when_no_resampling() {
         "output_sample_rate should equal sample_rate when not resampling");
     assert!(!info.is_resampled, "is_resampled should be false for probe");
 }
+
+// ---------------------------------------------------------------------------
+// Additional: configurable RingBuffer size (slow storage / NAS)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_stream_uses_configured_buffer_size() {
+    let path = fixture_path("test_44100_16.flac");
+    let info = probe_audio_file(&path).expect("probe should succeed");
+    let buffer = BufferConfig::new(2.0, 20.0).expect("2s / 20% is a valid buffer config");
+
+    let session = start_streaming_with_speed(&path, 0.0, info.sample_rate, None, 1.0, buffer)
+        .expect("start_streaming_with_speed should succeed");
+    assert_eq!(session.state.ring_capacity, 2 * 44100 * info.channels,
+        "ring buffer should hold 2s at the output rate");
+    session.stop();
+
+    assert!(BufferConfig::new(0.5, 10.0).is_err(), "buffer below 1s should be rejected");
+    assert!(BufferConfig::new(5.0, 60.0).is_err(), "pre-roll above 50% should be rejected");
+}
@@ -181,6 +181,25 @@ ffmpeg -y -f lavfi -i "sine=frequency=440:duration=3:sample_rate=44100" \
     -metadata album="Multi Disc Album" -metadata track="1" -metadata disc="2" \
     "$FIXTURES_DIR/test_multidisc_d2t1.flac" 2>/dev/null
 
+# --- Ogg files ---
+# 17. Ogg Vorbis 44.1kHz
+ffmpeg -y -f lavfi -i "sine=frequency=440:duration=3:sample_rate=44100" \
+    -c:a libvorbis -q:a 4 \
+    -metadata title="Test Vorbis" \
+    "$FIXTURES_DIR/test_44100_vorbis.ogg" 2>/dev/null
+
+# 18. Opus (no Symphonia decoder: used for the unsupported codec error)
+ffmpeg -y -f lavfi -i "sine=frequency=440:duration=3:sample_rate=48000" \
+    -c:a libopus -b:a 96k \
+    -metadata title="Test Opus" \
+    "$FIXTURES_DIR/test_opus.opus" 2>/dev/null
+
+# --- WavPack file ---
+# 19. WavPack lossless 16-bit/44.1kHz stereo (left: sine at 1/8, right: half of it)
+ffmpeg -y -f lavfi -i "sine=frequency=440:duration=3:sample_rate=44100" \
+    -af "pan=stereo|c0=c0|c1=0.5*c0" -c:a wavpack -sample_fmt s16p \
+    "$FIXTURES_DIR/test_44100_16.wv" 2>/dev/null
+
 # Clean up temp files
 rm -f "$FIXTURES_DIR/cover.jpg"
 
@@ -18,7 +18,7 @@ use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Supported audio extensions — mirrors `is_audio_file()` in lib.rs
-const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac", "dsf", "dff", "dsd", "wv", "ape"];
 
 /// Returns the absolute path to a test fixture file.
 fn fixture_path(name: &str) -> String {
@@ -306,7 +306,7 @@ fn test_5_9_sort_by_disc_then_track() {
 #[test]
 fn test_5_10_all_audio_extensions_recognized() {
     // These are the extensions from the real is_audio_file() in lib.rs
-    let expected: &[&str] = &["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac"];
+    let expected: &[&str] = &["mp3", "flac", "wav", "m4a", "aac", "ogg", "aiff", "alac", "dsf", "dff", "dsd", "wv", "ape"];
 
     for ext in expected {
         let fake_path = PathBuf::from(format!("/tmp/test.{}", ext));
@@ -318,7 +318,7 @@ fn test_5_10_all_audio_extensions_recognized() {
     }
 
     // Negative cases: should NOT be recognized
-    let non_audio = &["txt", "jpg", "png", "pdf", "zip", "wma", "dsd", "opus"];
+    let non_audio = &["txt", "jpg", "png", "pdf", "zip", "wma", "opus"];
     for ext in non_audio {
         let fake_path = PathBuf::from(format!("/tmp/test.{}", ext));
         assert!(
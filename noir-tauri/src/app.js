@@ -9,12 +9,15 @@ export const app = {
   togglePlay: null,
   playNextTrack: null,
   playPreviousTrack: null,
+  stopPlayback: null,
   resetPlayerUI: null,
   getCurrentTrackDuration: null,
   triggerGaplessPreload: null,
   loadAudioDevices: null,
   updateVolumeIcon: null,
   updateRepeatButtonUI: null,
+  updateShuffleButtonUI: null,
+  persistPlaybackModes: null,
 
   // === Views ===
   displayCurrentView: null,
@@ -59,6 +62,8 @@ export const app = {
   // === Library ===
   invalidateHomeCache: null,
   groupTracksIntoAlbumsAndArtists: null,
+  loadArtistAliases: null,
+  resolveArtist: null,
   buildTrackLookup: null,
   loadThumbnailAsync: null,
   loadCoverAsync: null,
@@ -30,10 +30,14 @@ const EQ_PRESETS = {
   'Late Night': [3, 2, 0, -2, -2, 0, 1, 2],
 }
 
+// Presets intégrés + presets utilisateur (eq_presets.json), chargés depuis le backend
+let eqPresets = { ...EQ_PRESETS }
+
 // === ÉTAT PRIVÉ ===
 
 let eqGains = new Float32Array(8)
 let eqEnabled = false
+let eqPreamp = 0
 let eqDraggingIndex = -1
 let eqInitialized = false
 let isEqPanelOpen = false
@@ -233,7 +237,7 @@ export function eqUpdateStatusUI() {
 }
 
 function eqFindActivePreset() {
-  for (const [name, preset] of Object.entries(EQ_PRESETS)) {
+  for (const [name, preset] of Object.entries(eqPresets)) {
     if (name === 'Flat' && preset.every((g, i) => Math.abs(g - eqGains[i]) < 0.1)) return 'Flat'
     if (preset.every((g, i) => Math.abs(g - eqGains[i]) < 0.1)) return name
   }
@@ -249,15 +253,71 @@ function eqUpdatePresetLabel() {
   eqUpdatePanelToggleLabel()
 }
 
-function eqApplyPreset(name) {
-  const preset = EQ_PRESETS[name]
+function eqUpdatePreampUI() {
+  const slider = document.getElementById('eq-preamp')
+  const valueEl = document.getElementById('eq-preamp-value')
+  if (slider) slider.value = eqPreamp
+  if (valueEl) valueEl.textContent = `${eqPreamp > 0 ? '+' : ''}${eqPreamp.toFixed(1)} dB`
+}
+
+async function eqSetPreamp(gainDb) {
+  eqPreamp = gainDb
+  eqUpdatePreampUI()
+  try {
+    await invoke('set_eq_preamp', { gainDb })
+  } catch (e) {
+    console.error('[EQ] Error setting preamp:', e)
+  }
+}
+
+function eqFormatFreq(freq) {
+  if (freq >= 1000) {
+    const k = freq / 1000
+    return `${Number.isInteger(k) ? k : k.toFixed(1)}k`
+  }
+  return `${Math.round(freq)}`
+}
+
+// Labels des bandes depuis le backend (les fréquences sont configurables)
+async function eqLoadBandLabels() {
+  const container = document.querySelector('.eq-freq-labels')
+  if (!container) return
+  try {
+    const bands = await invoke('get_eq_bands')
+    container.innerHTML = ''
+    for (const band of bands) {
+      const span = document.createElement('span')
+      span.textContent = eqFormatFreq(band.frequency)
+      container.appendChild(span)
+    }
+  } catch (e) {
+    console.log('[EQ] Could not load EQ bands:', e)
+  }
+}
+
+async function eqLoadPresets() {
+  try {
+    const presets = await invoke('get_eq_presets')
+    eqPresets = Object.fromEntries(presets.map(p => [p.name, p.gains]))
+  } catch (e) {
+    console.log('[EQ] Could not load EQ presets:', e)
+  }
+}
+
+async function eqApplyPreset(name) {
+  const preset = eqPresets[name]
   if (!preset) return
   for (let i = 0; i < 8; i++) {
     eqGains[i] = preset[i]
   }
   eqUpdateCurve()
   eqUpdatePresetLabel()
-  eqSendGains()
+  try {
+    await invoke('load_eq_preset', { name })
+  } catch (e) {
+    console.error('[EQ] Error loading preset:', e)
+    eqSendGains()
+  }
   const dropdown = document.getElementById('eq-preset-dropdown')
   if (dropdown) dropdown.classList.add('hidden')
 }
@@ -266,7 +326,7 @@ function eqBuildPresetDropdown() {
   const container = document.getElementById('eq-preset-dropdown')
   if (!container) return
   container.innerHTML = ''
-  for (const name of Object.keys(EQ_PRESETS)) {
+  for (const name of Object.keys(eqPresets)) {
     const item = document.createElement('button')
     item.className = 'eq-preset-dropdown-item'
     item.dataset.preset = name
@@ -336,6 +396,7 @@ export async function eqInit() {
     for (let i = 0; i < Math.min(state.gains.length, 8); i++) {
       eqGains[i] = state.gains[i]
     }
+    eqPreamp = state.preamp ?? 0
   } catch (e) {
     console.log('[EQ] Could not load EQ state:', e)
   }
@@ -346,6 +407,9 @@ export async function eqInit() {
   eqUpdatePanelToggleLabel()
 
   eqInitSVG()
+  eqUpdatePreampUI()
+  await eqLoadBandLabels()
+  await eqLoadPresets()
   eqBuildPresetDropdown()
 
   const eqInfoBtn = document.getElementById('eq-mode-info-btn')
@@ -363,6 +427,11 @@ export async function eqInit() {
     })
   }
 
+  const preampSlider = document.getElementById('eq-preamp')
+  if (preampSlider) {
+    preampSlider.addEventListener('input', () => eqSetPreamp(parseFloat(preampSlider.value)))
+  }
+
   const flatBtn = document.getElementById('eq-flat-btn')
   if (flatBtn) {
     flatBtn.addEventListener('click', () => eqApplyPreset('Flat'))
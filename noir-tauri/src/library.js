@@ -18,6 +18,24 @@ export function normalizeKey(str) {
   return str.trim().normalize('NFC')
 }
 
+// === ALIAS D'ARTISTES ===
+// Les variantes fusionnées (merge_artists) sont regroupées sous le nom canonique,
+// à l'affichage seulement : les tags des fichiers ne changent pas
+export async function loadArtistAliases() {
+  try {
+    library.artistAliases = await invoke('get_artist_aliases') || {}
+  } catch (e) {
+    console.error('[LIBRARY] Error loading artist aliases:', e)
+    library.artistAliases = {}
+  }
+}
+
+// Nom canonique d'un artiste (lui-même s'il n'a pas d'alias)
+export function resolveArtist(name) {
+  if (!name) return name
+  return library.artistAliases[name.toLowerCase()] || name
+}
+
 // === LAZY LOADING DES POCHETTES (Intersection Observer) ===
 let coverObserver = null
 
@@ -138,10 +156,28 @@ export async function selectFolder() {
 }
 
 async function addFolder(folderPath) {
+  // Dossier déjà couvert par la bibliothèque : rien à scanner
+  let result
+  try {
+    result = await invoke('add_library_path', { path: folderPath })
+  } catch (e) {
+    console.error('[LIBRARY] Error adding folder:', e)
+    showToast('Cannot access folder')
+    return
+  }
+  if (result.status === 'duplicate') {
+    showToast('Folder already in library')
+    return
+  }
+  if (result.status === 'nested') {
+    showToast(`Already included via ${result.parent.split('/').pop()}`)
+    return
+  }
+
   showLoading('Scanning folder...')
 
   try {
-    const tracksWithMetadata = await invoke('scan_folder_with_metadata', { path: folderPath })
+    const tracksWithMetadata = await invoke('scan_folder_with_metadata', { path: result.path })
 
     if (tracksWithMetadata.length === 0) {
       hideLoading()
@@ -151,8 +187,6 @@ async function addFolder(folderPath) {
 
     updateLoading(`${tracksWithMetadata.length} files loaded`)
 
-    await invoke('add_library_path', { path: folderPath })
-
     const existingPaths = new Set(library.tracks.map(t => t.path))
     let newCount = 0
     for (const track of tracksWithMetadata) {
@@ -217,7 +251,7 @@ export function groupTracksIntoAlbumsAndArtists() {
     if (!track.metadata) continue
 
     const albumKey = normalizeKey(track.metadata.album) || 'Unknown Album'
-    const artistKey = normalizeKey(track.metadata.artist) || 'Unknown Artist'
+    const artistKey = resolveArtist(normalizeKey(track.metadata.artist)) || 'Unknown Artist'
 
     // Groupe par album
     if (!library.albums[albumKey]) {
@@ -264,7 +298,7 @@ export function groupTracksIntoAlbumsAndArtists() {
     if (artistsArray.length > 1) {
       const artistCounts = {}
       for (const track of library.albums[albumKey].tracks) {
-        const artist = normalizeKey(track.metadata?.artist) || 'Unknown Artist'
+        const artist = resolveArtist(normalizeKey(track.metadata?.artist)) || 'Unknown Artist'
         artistCounts[artist] = (artistCounts[artist] || 0) + 1
       }
 
@@ -769,6 +803,12 @@ async function reloadLibraryFromCache() {
 // === SCAN LISTENERS ===
 export async function initScanListeners() {
   await listen('scan_progress', (event) => {
+    // Ajout d'un dossier (scan_folder_with_metadata) hors scan d'arrière-plan : overlay de chargement
+    const { phase, current, total } = event.payload
+    if (phase === 'loading_metadata' && !ui.isIndexing) {
+      updateLoading(`Metadata: ${current}/${total}`, total > 0 ? `${Math.round(current / total * 100)}%` : null)
+      return
+    }
     updateIndexationProgress(event.payload)
   })
 
@@ -790,6 +830,22 @@ export async function initScanListeners() {
     }
   })
 
+  // Fichiers illisibles / tags corrompus / durée nulle (émis après scan_complete)
+  await listen('scan_issues', (event) => {
+    library.scanIssues = event.payload
+    if (library.scanIssues.length === 0) return
+    console.warn(`[SCAN] ${library.scanIssues.length} files had problems:`, library.scanIssues)
+    showToast(`${library.scanIssues.length} files had problems during indexing`, 5000)
+  })
+
+  await listen('scan_cancelled', (event) => {
+    const { stats } = event.payload
+    console.log(`Background scan cancelled after ${stats.total_tracks} tracks`)
+    ui.isIndexing = false
+    updateIndexationUI()
+    showToast('Indexing cancelled')
+  })
+
   await listen('genre_enrichment_progress', (event) => {
     const { current, total, enriched } = event.payload
     console.log(`[Genre Enrichment] ${current}/${total} albums (${enriched} enriched)`)
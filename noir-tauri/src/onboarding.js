@@ -427,7 +427,10 @@ function bindAllEvents() {
       try {
         const path = await invoke('select_folder')
         if (!path) return
-        await invoke('add_library_path', { path })
+        const result = await invoke('add_library_path', { path })
+        // Déjà couvert par la bibliothèque : le scan reprend les dossiers existants
+        if (result.status === 'duplicate') showToast('Folder already in library')
+        if (result.status === 'nested') showToast(`Already included via ${result.parent.split('/').pop()}`)
         goToStep(5)
         startLocalScan()
       } catch (e) {
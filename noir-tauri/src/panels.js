@@ -123,6 +123,9 @@ export function showQueueNotification(message) {
 
 // Update the queue panel display (current track + upcoming list)
 export function updateQueueDisplay() {
+  // La session (reprise au démarrage) garde les tracks à venir
+  invoke('set_session_queue', { paths: queue.items.map(t => t.path) }).catch(() => {})
+
   const queueList = document.getElementById('queue-list')
   const queueEmpty = document.getElementById('queue-empty')
   const queueNext = document.getElementById('queue-next')
@@ -976,7 +979,7 @@ export async function showTrackInfoPanel(track) {
 
   if (artistLink) {
     artistLink.addEventListener('click', () => {
-      const artistName = artistLink.dataset.artist
+      const artistName = app.resolveArtist(artistLink.dataset.artist)
       if (artistName && library.artists[artistName]) {
         closeTrackInfoPanel()
         app.navigateToArtistPage(artistName)
@@ -1536,7 +1539,7 @@ export function goToTrackAlbum(track) {
 
 // Navigate to the artist page for a given track
 export function goToTrackArtist(track) {
-  const artistName = track.metadata?.artist
+  const artistName = app.resolveArtist(track.metadata?.artist?.trim())
   if (!artistName || !library.artists[artistName]) return
 
   app.navigateToArtistPage(artistName)
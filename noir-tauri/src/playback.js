@@ -10,6 +10,20 @@ import { isFullscreenOpen, updateFullscreenData, setFullscreenPlayState, setFull
 
 // === FULLSCREEN PLAY/PAUSE ICON SYNC ===
 
+// Now Playing macOS (Centre de contrôle, lock screen, AirPods) : métadonnées + pochette.
+// Seules les pochettes du cache disque (noir://) ont un fichier lisible par le système.
+function publishNowPlaying(track, cover) {
+  invoke('set_now_playing', {
+    metadata: {
+      title: track.metadata?.title || track.name || '',
+      artist: track.metadata?.artist || '',
+      album: track.metadata?.album || '',
+      duration: track.metadata?.duration || null
+    },
+    coverPath: typeof cover === 'string' && cover.startsWith('noir://') ? cover : null
+  }).catch(() => {})
+}
+
 function syncFsPlayPauseIcon(playing) {
   const btn = document.getElementById('fs-play-pause')
   if (!btn) return
@@ -22,6 +36,7 @@ function syncFsPlayPauseIcon(playing) {
 const MAX_INTERPOLATION_DELTA = 0.15  // Max 150ms d'interpolation (évite les sauts)
 const SMOOTHING_FACTOR = 0.3          // Facteur de lissage pour transitions douces
 const DRAG_THRESHOLD = 5              // pixels minimum pour distinguer clic/drag en queue
+const SCRUB_SEEK_DEBOUNCE_MS = 40     // seek en direct pendant le drag de la barre de progression
 
 // === LECTURE D'UN ALBUM ===
 
@@ -118,13 +133,17 @@ export function replenishQueue() {
 
 // === LECTURE D'UN MORCEAU ===
 
-export async function playTrack(index, context) {
+// startPosition : démarre à cette position (secondes) au lieu du début (reprise de session)
+export async function playTrack(index, context, startPosition = null) {
   // Validation des entrées
   if (index < 0 || index >= library.tracks.length) {
     console.error('playTrack: index invalide', index)
     return
   }
 
+  // Un track lancé remplace la session proposée au démarrage
+  playback.pendingResume = null
+
   // Reset complet de l'UI AVANT tout (évite les états incohérents)
   resetPlayerUI()
   playback.gaplessPreloadTriggered = false
@@ -196,28 +215,26 @@ export async function playTrack(index, context) {
   if (isSmb && progressContainer) progressContainer.classList.add('smb-buffering')
 
   // Lancement audio asynchrone (fire-and-forget) — la pochette charge en parallèle
-  const audioPromise = invoke('audio_play', { path: track.path })
-    .then(() => {
+  const audioPromise = invoke('audio_play', { path: track.path, startPosition })
+    .then((queueDriven) => {
+      playback.queueDriven = queueDriven === true
       playback.audioIsPlaying = true
       dom.playPauseBtn.textContent = '⏸'
       dom.durationEl.textContent = estimatedDuration > 0 ? formatTime(estimatedDuration) : '--:--'
       console.log('Streaming started (Rust):', track.path)
-      // Notifie MPRemoteCommandCenter (media keys macOS)
-      invoke('update_media_metadata', {
-        title: track.metadata?.title || track.name || '',
-        artist: track.metadata?.artist || '',
-        album: track.metadata?.album || ''
-      }).catch(() => {})
+      // Notifie MPRemoteCommandCenter (media keys macOS) et le Now Playing
+      publishNowPlaying(track, caches.coverCache.get(track.path))
       invoke('update_media_playback_state', { isPlaying: true }).catch(() => {})
     })
     .catch(e => {
       console.error('Rust audio_play error:', e)
       playback.audioIsPlaying = false
       dom.playPauseBtn.textContent = '▶'
-      const msg = String(e ?? '')
-      if (isSmb && (msg.includes('SMB download failed') || msg.includes('Timeout: SMB') || msg.includes('SMB credentials'))) {
+      // AudioError côté Rust : { code, message }
+      const code = e?.code
+      if (isSmb && code === 'network_unavailable') {
         showToast('NAS injoignable — vérifie que le serveur est allumé')
-      } else if (msg.includes('File not found')) {
+      } else if (code === 'file_not_found') {
         showToast('Fichier introuvable')
       } else {
         showToast('Lecture impossible')
@@ -265,6 +282,7 @@ export async function playTrack(index, context) {
         caches.coverCache.set(track.path, cover)
         // Mise à jour uniquement si c'est toujours le même track en cours
         if (playback.currentTrackIndex === index) {
+          publishNowPlaying(track, cover)
           if (isValidImageSrc(cover)) {
             const img = document.createElement('img')
             img.src = cover
@@ -377,6 +395,8 @@ export function getCurrentTrackPath() {
 export function triggerGaplessPreload() {
   const gaplessEnabled = localStorage.getItem('settings_gapless') !== 'false'
   if (!gaplessEnabled) return
+  // File backend : l'engine précharge lui-même le track suivant de la file
+  if (playback.queueDriven) return
 
   const nextPath = getNextTrackPath()
   if (!nextPath) return
@@ -395,6 +415,14 @@ export async function togglePlay() {
   if (playback.currentTrackIndex < 0 || !library.tracks[playback.currentTrackIndex]) {
     if (library.tracks.length === 0) return  // Pas de musique du tout
 
+    // Session proposée au démarrage : reprend le track là où on s'était arrêté
+    // L'engine démarre directement à la position sauvegardée (pas de seek après coup)
+    const resume = playback.pendingResume
+    if (resume && library.tracks[resume.index]) {
+      await playTrack(resume.index, null, resume.position > 1 ? resume.position : null)
+      return
+    }
+
     try {
       // Essayer de récupérer la dernière track jouée
       const lastPlayed = await invoke('get_last_played')
@@ -414,6 +442,12 @@ export async function togglePlay() {
     return
   }
 
+  // Après un Stop il n'y a plus de stream à reprendre : relance le track
+  if (playback.isStopped) {
+    playTrack(playback.currentTrackIndex)
+    return
+  }
+
   // Évite les appels multiples rapides (debounce strict)
   if (playback.isTogglingPlayState) {
     console.log('[togglePlay] Debounce - ignoring call')
@@ -460,6 +494,26 @@ export async function togglePlay() {
   }, 250)
 }
 
+// Stop (touche média / MPRemoteCommandCenter) : libère le stream, garde le track sélectionné
+export async function stopPlayback() {
+  if (playback.currentTrackIndex < 0 || playback.isStopped) return
+  try {
+    await invoke('audio_stop')
+  } catch (e) {
+    console.error('[stopPlayback] Error:', e)
+    return
+  }
+  playback.audioIsPlaying = false
+  resetPlayerUI()
+  playback.isStopped = true
+  stopPositionInterpolation()
+  dom.playPauseBtn.textContent = '▶'
+  invoke('update_media_playback_state', { isPlaying: false }).catch(() => {})
+  setFullscreenPlayState(false)
+  syncFsPlayPauseIcon(false)
+  app.updateHomeNowPlayingSection()
+}
+
 // Fonction pour jouer le morceau précédent — utilise autoQueueSource pour naviguer en arrière
 export function playPreviousTrack() {
   const currentTrack = library.tracks[playback.currentTrackIndex]
@@ -640,6 +694,9 @@ export function stopPositionInterpolation() {
 // Synchronise immédiatement avec une position Rust (appelé sur événement)
 // IMPORTANT: Ignore les updates pendant un seek pour éviter le "snap back"
 export function syncToRustPosition(position) {
+  // Pendant un drag, la barre suit la souris : les positions des seeks de scrub sont ignorées
+  if (playback.isUserDragging) return
+
   // Si on est en seek, vérifie si la position Rust correspond à notre seek
   if (playback.isSeekingUI) {
     // Compare avec seekTargetPosition (la position DEMANDÉE, pas interpolée)
@@ -686,6 +743,7 @@ export function resetPlayerUI() {
   playback.audioPositionFromRust = 0
   playback.isSeekingUI = false
   playback.isPausedFromRust = false
+  playback.isStopped = false
 
   // Annule le timeout de seek si actif
   if (playback.seekTimeoutId) {
@@ -707,6 +765,20 @@ export function resetPlayerUI() {
 
 // === SEEK ===
 
+let _scrubSeekTimeout = null
+
+// Scrub : seek en direct pendant le drag, après une courte pause du curseur.
+// Le backend fusionne les rafales ; le seek final reste celui de performSeek() au mouseup
+function scheduleScrubSeek(time) {
+  clearTimeout(_scrubSeekTimeout)
+  _scrubSeekTimeout = setTimeout(() => {
+    _scrubSeekTimeout = null
+    if (!playback.isUserDragging) return
+    playback.seekTargetPosition = time
+    invoke('audio_seek', { time }).catch(e => console.error('[Seek] Scrub error:', e))
+  }, SCRUB_SEEK_DEBOUNCE_MS)
+}
+
 // Fonction de seek unique (évite la duplication de code)
 // IMPORTANT: Garde le curseur à la position demandée même si le seek échoue ou prend du temps
 export async function performSeek() {
@@ -1080,6 +1152,9 @@ export async function initRustAudioListeners() {
     // Stoppe la boucle RAF (sera redémarrée par playTrack si nécessaire)
     stopPositionInterpolation()
 
+    // File backend : le suivant arrive par playback_queue_advanced (rien en fin de file)
+    if (playback.queueDriven) return
+
     // Petit délai pour laisser Rust nettoyer son état avant de lancer la suite
     setTimeout(() => {
       // Gère repeat et next track
@@ -1092,17 +1167,40 @@ export async function initRustAudioListeners() {
     }, 50) // 50ms suffisent pour que Rust nettoie
   })
 
+  // Lecture interrompue : fichier devenu illisible (disque débranché, partage perdu).
+  // Le toast vient de playback_error (file_read_error) ; pas d'enchaînement automatique.
+  await listen('playback_interrupted', (event) => {
+    console.warn('Rust: playback_interrupted -', event.payload)
+    playback.audioIsPlaying = false
+    playback.isPausedFromRust = false
+    resetPlayerUI()
+    stopPositionInterpolation()
+    dom.playPauseBtn.textContent = '▶'
+    invoke('update_media_playback_state', { isPlaying: false }).catch(() => {})
+    setFullscreenPlayState(false)
+    syncFsPlayPauseIcon(false)
+  })
+
   // Moniteur de specs audio SOURCE vs OUTPUT
   await listen('playback_audio_specs', (event) => {
     const specs = event.payload
     updateAudioSpecs(specs)
   })
 
+  // File backend : track suivant choisi par Rust (repeat / shuffle), joué via playTrack
+  await listen('playback_queue_advanced', (event) => {
+    const index = library.tracks.findIndex(t => t.path === event.payload)
+    if (index >= 0) playTrack(index)
+  })
+
   // === GAPLESS TRANSITION ===
   await listen('playback_gapless_transition', () => {
     console.log('[Gapless] Seamless transition occurred')
     playback.gaplessPreloadTriggered = false
 
+    // File backend : le track enchaîné arrive par playback_track_switched
+    if (playback.queueDriven) return
+
     // Advance to the next track in the UI (without calling playTrack)
     if (playback.repeatMode === 'one') {
       // Stay on same track, just reset position display
@@ -1118,7 +1216,21 @@ export async function initRustAudioListeners() {
     }
     // else: no queue, playback will stop naturally
 
-    // Update the UI with the new track info
+    showGaplessTrack()
+  })
+
+  // Transition gapless d'une file backend : l'UI suit le chemin préchargé par l'engine
+  await listen('playback_track_switched', (event) => {
+    if (!playback.queueDriven) return
+    const globalIndex = library.tracks.findIndex(t => t.path === event.payload)
+    if (globalIndex !== -1) {
+      playback.currentTrackIndex = globalIndex
+    }
+    showGaplessTrack()
+  })
+
+  // Update the UI with the new track info
+  function showGaplessTrack() {
     const track = library.tracks[playback.currentTrackIndex]
     if (track) {
       if (dom.trackNameEl) dom.trackNameEl.textContent = track.metadata?.title || track.name
@@ -1147,13 +1259,13 @@ export async function initRustAudioListeners() {
         title: track.metadata?.title || track.name
       }).then(() => app.invalidateHomeCache()).catch(() => {})
     }
-  })
+  }
 
   // === ERROR HANDLING ===
   // Erreurs de lecture structurées depuis Rust (debounce 2s par code d'erreur)
   const errorLastShown = {}
   const ERROR_DEBOUNCE_MS = 2000
-  const AUTO_SKIP_ERRORS = new Set(['file_probe_failed', 'decode_failed', 'file_not_found'])
+  const AUTO_SKIP_ERRORS = new Set(['file_probe_failed', 'decode_failed', 'file_not_found', 'unsupported_codec'])
 
   await listen('playback_error', (event) => {
     const { code, message, details } = event.payload
@@ -1190,18 +1302,16 @@ export async function initRustAudioListeners() {
       case 'toggle':
         app.togglePlay()
         break
-      case 'next':
-        app.playNextTrack()
-        break
-      case 'previous':
-        app.playPreviousTrack()
-        break
       case 'stop':
-        if (playback.audioIsPlaying) app.togglePlay()
+        stopPlayback()
         break
     }
   })
 
+  // Piste suivante / précédente : touches média (MPRemoteCommandCenter ou global shortcuts)
+  await listen('media_key_next', () => app.playNextTrack())
+  await listen('media_key_prev', () => app.playPreviousTrack())
+
   // Démarre l'interpolation au chargement
   startPositionInterpolation()
 
@@ -1229,6 +1339,35 @@ export function updateRepeatButtonUI() {
   }
 }
 
+// Met à jour l'UI du bouton shuffle selon le mode actuel
+export function updateShuffleButtonUI() {
+  if (!dom.shuffleBtn) return
+
+  if (playback.shuffleMode === 'album') {
+    dom.shuffleBtn.classList.add('active')
+    dom.shuffleBtn.textContent = '⤮ᴬ'
+    dom.shuffleBtn.title = 'Shuffle (Album)'
+  } else if (playback.shuffleMode === 'library') {
+    dom.shuffleBtn.classList.add('active')
+    dom.shuffleBtn.textContent = '⤮∞'
+    dom.shuffleBtn.title = 'Shuffle (Library)'
+  } else {
+    dom.shuffleBtn.classList.remove('active')
+    dom.shuffleBtn.textContent = '⤮'
+    dom.shuffleBtn.title = 'Shuffle'
+  }
+}
+
+// Persiste shuffle / repeat côté Rust (réglages de lecture + session). Le backend ne
+// connaît que shuffle on/off : la variante album/library reste en localStorage
+export function persistPlaybackModes() {
+  if (playback.shuffleMode !== 'off') localStorage.setItem('shuffle_mode', playback.shuffleMode)
+  invoke('set_shuffle', { enabled: playback.shuffleMode !== 'off' }).catch(() => {})
+  // AudioError { code: 'invalid_argument', message } si le mode est inconnu côté Rust
+  invoke('set_repeat_mode', { mode: playback.repeatMode })
+    .catch(e => console.error('set_repeat_mode:', e?.message ?? e))
+}
+
 // === VOLUME ICON ===
 
 export function updateVolumeIcon(volume) {
@@ -1265,7 +1404,7 @@ function startDevicePolling() {
   }, 3000)
 }
 
-// === SYNC SYSTÈME → NOIR (polling toutes les 5s) ===
+// === SYNC SYSTÈME → NOIR (événement audio_device_changed + polling de secours) ===
 // Détecte quand macOS change le périphérique de sortie par défaut
 // (casque branché, changement dans Préférences Système, etc.)
 // et met à jour Noir en conséquence.
@@ -1289,9 +1428,9 @@ let _audioStreamDeviceId = null
 // macOS reports a "new" default. Without this cooldown, the sync loop misinterprets
 // that Noir-initiated change as an external change, triggering another switch → infinite loop.
 // The 10s window is intentionally generous: macOS audio daemon can take several seconds
-// to stabilize after a device switch, and the sync polls every 5s. Any genuine external
-// change (user plugs headphones) during cooldown will be picked up on the next poll after
-// cooldown expires (max ~15s delay — acceptable trade-off vs. infinite oscillation).
+// to stabilize after a device switch and may report several default changes meanwhile.
+// A genuine external change (user plugs headphones) during cooldown is ignored — acceptable
+// trade-off vs. infinite oscillation.
 let _deviceSwitchCooldownUntil = 0
 
 export function setAudioStreamDeviceId(id) {
@@ -1309,50 +1448,66 @@ let _lastGoodPosition = 0
 // is silently skipped, preventing it from seeking on the newly-opened stream.
 let _seekCancelToken = null
 
-function startSystemDeviceSync() {
-  if (_systemDeviceSyncInterval) return // Déjà démarré
-  _systemDeviceSyncInterval = setInterval(async () => {
-    try {
-      const systemDefaultId = await invoke('get_system_default_device_id')
-      if (!systemDefaultId) return
+// Réagit à un changement du périphérique par défaut du système.
+// Appelé par l'événement Rust 'audio_device_changed' (hot-plug casque/DAC)
+// et par le polling de secours.
+async function handleSystemDefaultDevice(systemDefaultId) {
+  if (!systemDefaultId) return
 
-      // Premier cycle : initialiser sans déclencher de sync
-      if (_lastKnownSystemDefault === null) {
-        _lastKnownSystemDefault = systemDefaultId
-        return
-      }
+  // Premier appel : initialiser sans déclencher de sync
+  if (_lastKnownSystemDefault === null) {
+    _lastKnownSystemDefault = systemDefaultId
+    return
+  }
 
-      // Sync uniquement si le système a changé depuis le dernier cycle
-      if (systemDefaultId !== _lastKnownSystemDefault) {
-        _lastKnownSystemDefault = systemDefaultId
+  // Sync uniquement si le système a changé depuis le dernier appel
+  if (systemDefaultId === _lastKnownSystemDefault) return
+  _lastKnownSystemDefault = systemDefaultId
 
-        // Skip if we're in a cooldown period after a Noir-initiated device switch.
-        // When selectAudioDevice → set_audio_device → set_system_default_device changes
-        // the macOS default, we must NOT react to that change here (it's not external).
-        if (Date.now() < _deviceSwitchCooldownUntil) {
-          console.log('[AUDIO-OUTPUT] System default changed but within cooldown, ignoring (Noir-initiated switch)')
-          return
-        }
+  // Skip if we're in a cooldown period after a Noir-initiated device switch.
+  // When selectAudioDevice → set_audio_device → set_system_default_device changes
+  // the macOS default, we must NOT react to that change here (it's not external).
+  if (Date.now() < _deviceSwitchCooldownUntil) {
+    console.log('[AUDIO-OUTPUT] System default changed but within cooldown, ignoring (Noir-initiated switch)')
+    return
+  }
 
-        console.log('[AUDIO-OUTPUT] System default changed externally:', systemDefaultId)
-
-        // Restart only if audio stream is not already on the new system default.
-        // Use _audioStreamDeviceId (last device passed to audio_play), NOT
-        // playback.currentAudioDeviceId which can be updated by loadAudioDevices
-        // before audio_play is actually called, causing a false "already on device" match.
-        if (systemDefaultId !== _audioStreamDeviceId) {
-          const devices = await invoke('refresh_audio_devices')
-          const newDevice = devices.find(d => d.id === systemDefaultId)
-          if (newDevice) {
-            console.log('[AUDIO-OUTPUT] Synced to system default:', newDevice.name)
-            await selectAudioDevice(systemDefaultId, newDevice.name)
-          }
-        }
-      }
+  // Sortie choisie dans les réglages : Noir ne suit plus la sortie système
+  const preference = await invoke('get_audio_device_preference').catch(() => null)
+  if (preference && !preference.followSystemDefault) return
+
+  console.log('[AUDIO-OUTPUT] System default changed externally:', systemDefaultId)
+
+  // Restart only if audio stream is not already on the new system default.
+  // Use _audioStreamDeviceId (last device passed to audio_play), NOT
+  // playback.currentAudioDeviceId which can be updated by loadAudioDevices
+  // before audio_play is actually called, causing a false "already on device" match.
+  if (systemDefaultId !== _audioStreamDeviceId) {
+    const devices = await invoke('refresh_audio_devices')
+    const newDevice = devices.find(d => d.id === systemDefaultId)
+    if (newDevice) {
+      console.log('[AUDIO-OUTPUT] Synced to system default:', newDevice.name)
+      await selectAudioDevice(systemDefaultId, newDevice.name)
+    }
+  }
+}
+
+function startSystemDeviceSync() {
+  if (_systemDeviceSyncInterval) return // Déjà démarré
+
+  // Push immédiat depuis le listener CoreAudio
+  listen('audio_device_changed', (event) => {
+    handleSystemDefaultDevice(event.payload?.id).catch(() => {})
+  })
+
+  // Polling de secours (backends sans listener, événements manqués)
+  _systemDeviceSyncInterval = setInterval(async () => {
+    try {
+      await handleSystemDefaultDevice(await invoke('get_system_default_device_id'))
     } catch (_) {
       // Non-fatal : le polling reprend au prochain cycle
     }
-  }, 5000)
+  }, 30000)
 }
 
 function stopSystemDeviceSync() {
@@ -1415,15 +1570,23 @@ export async function loadAudioDevices() {
         }
       }
 
+      // Profondeurs entières acceptées par le DAC (ex. "16/24/32-bit")
+      const bitDepthsHtml = device.supported_bit_depths?.length
+        ? ` • ${device.supported_bit_depths.join('/')}-bit`
+        : ''
+
       // Badge bit perfect argenté (style identique au player)
       const bpBadge = canBitPerfect
         ? '<span class="device-bp-badge">BIT PERFECT</span>'
         : ''
 
-      // Badge transport type (AirPlay uniquement pour l'instant)
+      // Badge transport type (USB, Bluetooth, HDMI…) — AirPlay a son propre style
+      const transportTitle = device.manufacturer ? ` title="${escapeHtml(device.manufacturer)}"` : ''
       const transportBadge = device.is_airplay
         ? '<span class="device-transport-badge airplay-badge" title="AirPlay — non bit-perfect, hog mode unavailable">AirPlay</span>'
-        : ''
+        : device.transport
+          ? `<span class="device-transport-badge"${transportTitle}>${escapeHtml(device.transport)}</span>`
+          : ''
       const airplayWarning = device.is_airplay
         ? '<span class="airplay-warning">⚠ +2s latency · non bit-perfect</span>'
         : ''
@@ -1435,7 +1598,7 @@ export async function loadAudioDevices() {
         <div class="audio-output-item-info">
           <div class="audio-output-item-name">${device.name}${transportBadge}</div>
           <div class="audio-output-item-details">
-            ${sampleRate}${supportedRatesHtml}${airplayWarning}${bpBadge}
+            ${sampleRate}${supportedRatesHtml}${bitDepthsHtml}${airplayWarning}${bpBadge}
           </div>
         </div>
       `
@@ -1493,7 +1656,7 @@ export async function selectAudioDevice(deviceId, deviceName) {
     _deviceSwitchCooldownUntil = Date.now() + 10000
 
     console.log('[AUDIO-OUTPUT] Calling set_audio_device...')
-    await invoke('set_audio_device', { deviceId })
+    await invoke('set_audio_device', { deviceId, remember: false })
     console.log('[AUDIO-OUTPUT] Device preference changed successfully')
 
     // Read back the ACTUAL system default after the switch.
@@ -1741,10 +1904,10 @@ export function initPlayback() {
     const duration = getCurrentTrackDuration()
     if (duration > 0) {
       const time = (dom.progressBar.value / 100) * duration
-      // Met à jour UNIQUEMENT l'affichage visuel
       dom.currentTimeEl.textContent = formatTime(time)
       updateProgressBarStyle(dom.progressBar.value)
-      // PAS de seek ici pour éviter le flood !
+      // Pendant un drag : seek en direct (debounce), le seek final part au mouseup
+      if (playback.isUserDragging) scheduleScrubSeek(time)
     }
   })
 
@@ -1754,7 +1917,9 @@ export function initPlayback() {
     if (!playback.isUserDragging) return
     playback.isUserDragging = false
 
-    // Effectue le seek
+    // Effectue le seek final (remplace un seek de scrub encore en attente)
+    clearTimeout(_scrubSeekTimeout)
+    _scrubSeekTimeout = null
     performSeek()
   })
 
@@ -1778,19 +1943,13 @@ export function initPlayback() {
     // Cycle : off → album → library → off
     if (playback.shuffleMode === 'off') {
       playback.shuffleMode = 'album'
-      dom.shuffleBtn.classList.add('active')
-      dom.shuffleBtn.textContent = '⤮ᴬ'
-      dom.shuffleBtn.title = 'Shuffle (Album)'
     } else if (playback.shuffleMode === 'album') {
       playback.shuffleMode = 'library'
-      dom.shuffleBtn.textContent = '⤮∞'
-      dom.shuffleBtn.title = 'Shuffle (Library)'
     } else {
       playback.shuffleMode = 'off'
-      dom.shuffleBtn.classList.remove('active')
-      dom.shuffleBtn.textContent = '⤮'
-      dom.shuffleBtn.title = 'Shuffle'
     }
+    updateShuffleButtonUI()
+    persistPlaybackModes()
   })
 
   // === Repeat ===
@@ -1804,6 +1963,7 @@ export function initPlayback() {
       playback.repeatMode = 'off'
     }
     updateRepeatButtonUI()
+    persistPlaybackModes()
   })
 
   // === Volume ===
@@ -1958,7 +2118,7 @@ export function initPlayback() {
   // === Hog Mode tooltip ===
   initHogModeTooltip()
 
-  // === Sync périphérique système → Noir (polling arrière-plan 5s) ===
+  // === Sync périphérique système → Noir (événement + polling de secours) ===
   // Démarre dès l'init pour détecter les changements de sortie système (casque, etc.)
   startSystemDeviceSync()
 
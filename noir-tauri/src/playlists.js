@@ -1373,10 +1373,12 @@ export async function exportPlaylistM3u(playlistId) {
 
 export async function importPlaylistM3u() {
   try {
-    const playlist = await invoke('import_playlist_m3u')
+    const path = await invoke('pick_playlist_m3u')
+    const { playlist, skippedMissing } = await invoke('import_playlist_m3u', { path })
     playlists.push(playlist)
     updatePlaylistsSidebar()
-    showToast(`Playlist "${escapeHtml(playlist.name)}" imported (${playlist.trackPaths.length} tracks)`)
+    const skipped = skippedMissing > 0 ? `, ${skippedMissing} missing files skipped` : ''
+    showToast(`Playlist "${escapeHtml(playlist.name)}" imported (${playlist.trackPaths.length} tracks${skipped})`)
   } catch (e) {
     if (e === 'Import cancelled') return
     showToast('Import failed')
@@ -54,13 +54,14 @@ import {
   buildTrackLookup, loadCoverAsync, loadThumbnailAsync, loadArtistImageAsync,
   observeCoverLoading, observeArtistLoading, startBackgroundScan, invalidateHomeCache,
   updateIndexationStats, initScanListeners, initLibrary, removeTracksFromLibrary,
+  loadArtistAliases, resolveArtist,
   invalidateDiscoveryMixCache as libInvalidateDiscoveryMixCache
 } from './library.js'
 import {
-  playTrack, playAlbum, togglePlay, playNextTrack, playPreviousTrack,
+  playTrack, playAlbum, togglePlay, playNextTrack, playPreviousTrack, stopPlayback,
   resetPlayerUI, getCurrentTrackDuration, triggerGaplessPreload,
   loadAudioDevices, updateVolumeIcon, updateHogModeStatus,
-  updateRepeatButtonUI, initPlayback, getNextTrackInfo, getCurrentTrackPath,
+  updateRepeatButtonUI, updateShuffleButtonUI, persistPlaybackModes, initPlayback, getNextTrackInfo, getCurrentTrackPath,
   performSeek, populateQueueFromContext, replenishQueue
 } from './playback.js'
 import {
@@ -192,6 +193,8 @@ app.closeSearchPanel = closeSearchPanel
 // Library
 app.invalidateHomeCache = invalidateHomeCache
 app.groupTracksIntoAlbumsAndArtists = groupTracksIntoAlbumsAndArtists
+app.loadArtistAliases = loadArtistAliases
+app.resolveArtist = resolveArtist
 app.buildTrackLookup = buildTrackLookup
 app.loadThumbnailAsync = loadThumbnailAsync
 app.loadCoverAsync = loadCoverAsync
@@ -213,12 +216,15 @@ app.playAlbum = playAlbum
 app.togglePlay = togglePlay
 app.playNextTrack = playNextTrack
 app.playPreviousTrack = playPreviousTrack
+app.stopPlayback = stopPlayback
 app.resetPlayerUI = resetPlayerUI
 app.getCurrentTrackDuration = getCurrentTrackDuration
 app.triggerGaplessPreload = triggerGaplessPreload
 app.loadAudioDevices = loadAudioDevices
 app.updateVolumeIcon = updateVolumeIcon
 app.updateRepeatButtonUI = updateRepeatButtonUI
+app.updateShuffleButtonUI = updateShuffleButtonUI
+app.persistPlaybackModes = persistPlaybackModes
 app.populateQueueFromContext = populateQueueFromContext
 app.replenishQueue = replenishQueue
 
@@ -540,6 +546,16 @@ function resetShortcuts() {
 }
 
 async function populateSettingsValues() {
+  const followToggle = document.getElementById('settings-follow-system-default')
+  if (followToggle) {
+    try {
+      const preference = await invoke('get_audio_device_preference')
+      followToggle.checked = preference.followSystemDefault
+    } catch (e) {
+      console.error('[SETTINGS] Error loading output preference:', e)
+    }
+  }
+
   const hogToggle = document.getElementById('settings-exclusive-mode')
   if (hogToggle) {
     try {
@@ -624,9 +640,12 @@ function initSettingsPanel() {
       const deviceId = audioSelect.value
       if (!deviceId) return
       try {
-        await invoke('set_audio_device', { deviceId })
+        await invoke('set_audio_device', { deviceId, remember: true })
         const selectedName = audioSelect.options[audioSelect.selectedIndex].text
         showToast(`Audio output: ${selectedName}`)
+        // Choix explicite : la sortie est gardée, Noir ne suit plus celle du système
+        const followToggle = document.getElementById('settings-follow-system-default')
+        if (followToggle) followToggle.checked = false
         loadAudioDevices()
       } catch (e) {
         console.error('[SETTINGS] Error changing audio device:', e)
@@ -635,6 +654,23 @@ function initSettingsPanel() {
     })
   }
 
+  const followToggle = document.getElementById('settings-follow-system-default')
+  if (followToggle) {
+    followToggle.addEventListener('change', async () => {
+      const enabled = followToggle.checked
+      try {
+        await invoke('set_follow_system_default', { enabled })
+        showToast(enabled ? 'Following the system audio output' : 'Using the audio output chosen in settings')
+        populateSettingsAudioDevices()
+        loadAudioDevices()
+      } catch (e) {
+        console.error('[SETTINGS] Error changing output preference:', e)
+        followToggle.checked = !enabled
+        showToast('Error changing audio output')
+      }
+    })
+  }
+
   const hogToggle = document.getElementById('settings-exclusive-mode')
   if (hogToggle) {
     hogToggle.addEventListener('change', async () => {
@@ -674,10 +710,18 @@ function initSettingsPanel() {
       try {
         const selected = await invoke('select_folder')
         if (selected) {
-          await invoke('add_library_path', { path: selected })
-          showToast(`Folder added: ${selected.split('/').pop()}`)
+          const result = await invoke('add_library_path', { path: selected })
+          if (result.status === 'duplicate') {
+            showToast('Folder already in library')
+            return
+          }
+          if (result.status === 'nested') {
+            showToast(`Already included via ${result.parent.split('/').pop()}`)
+            return
+          }
+          showToast(`Folder added: ${result.path.split('/').pop()}`)
           populateSettingsLibraryPaths()
-          invoke('scan_folder_with_metadata', { path: selected })
+          invoke('scan_folder_with_metadata', { path: result.path })
         }
       } catch (e) {
         console.error('[SETTINGS] Error adding folder:', e)
@@ -710,6 +754,17 @@ function initSettingsPanel() {
     })
   }
 
+  const restoreRateToggle = document.getElementById('settings-restore-sample-rate')
+  if (restoreRateToggle) {
+    restoreRateToggle.checked = localStorage.getItem('settings_restore_sample_rate') !== 'false'
+    restoreRateToggle.addEventListener('change', () => {
+      const enabled = restoreRateToggle.checked
+      localStorage.setItem('settings_restore_sample_rate', enabled)
+      invoke('set_restore_sample_rate_on_stop', { enabled }).catch(console.error)
+      showToast(enabled ? 'Sample rate restored on stop' : 'DAC keeps its rate on stop')
+    })
+  }
+
   // Privacy — Sentry toggle. État persisté côté Rust (config.json) car la
   // décision doit être lue AVANT que la WebView soit prête au boot.
   const sentryToggle = document.getElementById('settings-sentry-enabled')
@@ -814,19 +869,38 @@ async function handleAutoResume() {
   if (localStorage.getItem('settings_auto_resume') !== 'true') return
 
   try {
-    const lastPlayed = await invoke('get_last_played')
-    if (lastPlayed && lastPlayed.path) {
-      const trackIndex = library.tracks.findIndex(t => t.path === lastPlayed.path)
-      if (trackIndex >= 0) {
-        playback.currentTrackIndex = trackIndex
-        const track = library.tracks[trackIndex]
-        const meta = track.metadata || {}
-        document.getElementById('track-name').textContent = meta.title || track.name || 'Unknown Title'
-        document.getElementById('track-folder').textContent = meta.artist || 'Unknown Artist'
-        const player = document.getElementById('player')
-        if (player) player.classList.remove('hidden')
-      }
+    const session = await invoke('get_last_session')
+    if (!session || !session.path) return
+    // Déjà en lecture entre-temps : rien à proposer
+    if (playback.currentTrackIndex >= 0) return
+
+    const trackIndex = library.tracks.findIndex(t => t.path === session.path)
+    if (trackIndex < 0) return
+
+    // Le track n'est lancé qu'au premier play (togglePlay), à la position sauvegardée
+    playback.pendingResume = { index: trackIndex, position: session.position || 0 }
+
+    // Modes shuffle / repeat et file d'attente (tracks qui suivaient le track courant)
+    const savedShuffle = localStorage.getItem('shuffle_mode') === 'album' ? 'album' : 'library'
+    playback.shuffleMode = session.shuffle ? savedShuffle : 'off'
+    playback.repeatMode = session.repeat || 'off'
+    updateShuffleButtonUI()
+    updateRepeatButtonUI()
+    const upcoming = (session.queue || []).slice((session.queue_index ?? 0) + 1)
+    queue.items.length = 0
+    for (const path of upcoming) {
+      const track = library.tracks.find(t => t.path === path)
+      if (track) queue.items.push(track)
     }
+    app.updateQueueDisplay()
+    app.updateQueueIndicators()
+    const track = library.tracks[trackIndex]
+    const meta = track.metadata || {}
+    document.getElementById('track-name').textContent = meta.title || track.name || 'Unknown Title'
+    document.getElementById('track-folder').textContent = meta.artist || 'Unknown Artist'
+    const player = document.getElementById('player')
+    if (player) player.classList.remove('hidden')
+    showToast('Press play to resume where you left off')
   } catch (e) {
     console.error('[SETTINGS] Error auto-resume:', e)
   }
@@ -885,6 +959,9 @@ async function init() {
     }
     library.trackAddedDates = addedDates
 
+    // Alias d'artistes, appliqués par le groupement
+    await loadArtistAliases()
+
     // Groupe et indexe
     console.log('[INIT] Grouping tracks into albums and artists...')
     groupTracksIntoAlbumsAndArtists()
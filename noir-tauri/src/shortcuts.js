@@ -2,15 +2,58 @@
 // Handles both global (Tauri OS-level) and local (in-app keydown) shortcuts.
 
 import { playback, library, ui, dom, favorites } from './state.js'
-import { invoke } from './state.js'
+import { invoke, listen, emit } from './state.js'
 import { app } from './app.js'
 import { showToast } from './utils.js'
 
 // === GLOBAL SHORTCUTS (OS-level via Tauri) ===
 
+// Each action tries its media key first, then a Cmd/Ctrl+Shift fallback.
+// Note : F7/F8/F9 intentionnellement absents — conflictent avec Apple Music
+// en mode "media keys" macOS. Les touches MediaPreviousTrack/MediaPlayPause/MediaNextTrack
+// ci-dessous couvrent le cas "function keys" désactivé.
+const GLOBAL_SHORTCUTS = {
+  play_pause:  { mediaKey: 'MediaPlayPause',     fallback: 'P',     label: 'Play/Pause',  run: () => app.togglePlay() },
+  next_track:  { mediaKey: 'MediaTrackNext',     fallback: 'Right', label: 'Next',        run: () => emit('media_key_next') },
+  prev_track:  { mediaKey: 'MediaTrackPrevious', fallback: 'Left',  label: 'Previous',    run: () => emit('media_key_prev') },
+  stop:        { mediaKey: 'MediaStop',          fallback: null,    label: 'Stop',        run: () => app.stopPlayback() },
+  volume_up:   { mediaKey: 'MediaVolumeUp',      fallback: 'Up',    label: 'Volume Up',   run: () => adjustVolume(0.1) },
+  volume_down: { mediaKey: 'MediaVolumeDown',    fallback: 'Down',  label: 'Volume Down', run: () => adjustVolume(-0.1) },
+  mute:        { mediaKey: 'MediaMute',          fallback: 'M',     label: 'Mute',        run: () => toggleMute() },
+}
+
 let globalShortcutsRegistered = false
+let focusListenersAttached = false
 
-async function initGlobalShortcuts() {
+// Actions disabled by the user: localStorage 'global_shortcuts_disabled' = ["volume_up", ...]
+function getDisabledGlobalShortcuts() {
+  try {
+    return new Set(JSON.parse(localStorage.getItem('global_shortcuts_disabled') || '[]'))
+  } catch {
+    return new Set()
+  }
+}
+
+export function setGlobalShortcutEnabled(action, enabled) {
+  const disabled = getDisabledGlobalShortcuts()
+  if (enabled) disabled.delete(action)
+  else disabled.add(action)
+  localStorage.setItem('global_shortcuts_disabled', JSON.stringify([...disabled]))
+  refreshGlobalShortcuts()
+}
+
+// Opt-out : settings_global_shortcuts_unfocused = 'false' → keys are only grabbed
+// while the Noir window has focus (released on blur so other apps get them)
+function shortcutsWhenUnfocused() {
+  return localStorage.getItem('settings_global_shortcuts_unfocused') !== 'false'
+}
+
+export function setGlobalShortcutsWhenUnfocused(enabled) {
+  localStorage.setItem('settings_global_shortcuts_unfocused', enabled)
+  refreshGlobalShortcuts()
+}
+
+async function registerGlobalShortcuts() {
   // Avoid registering multiple times
   if (globalShortcutsRegistered) return
 
@@ -20,131 +63,30 @@ async function initGlobalShortcuts() {
     // Unregister all existing shortcuts first
     await unregisterAll()
 
-    // Play/Pause : MediaPlayPause or Cmd/Ctrl+Shift+P
-    try {
-      await register('MediaPlayPause', () => {
-        console.log('[SHORTCUT] MediaPlayPause triggered')
-        app.togglePlay()
-      })
-      console.log('[SHORTCUTS] MediaPlayPause registered')
-    } catch (e) {
-      console.log('[SHORTCUTS] MediaPlayPause not available, trying Ctrl+Shift+P')
-      try {
-        const playPauseKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+P' : 'Ctrl+Shift+P'
-        await register(playPauseKey, () => {
-          console.log('[SHORTCUT] Play/Pause triggered')
-          app.togglePlay()
-        })
-        console.log(`[SHORTCUTS] ${playPauseKey} registered for Play/Pause`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register play/pause shortcut:', e2)
-      }
-    }
-
-    // Next track : MediaTrackNext or Cmd/Ctrl+Shift+Right
-    try {
-      await register('MediaTrackNext', () => {
-        console.log('[SHORTCUT] MediaTrackNext triggered')
-        app.playNextTrack()
-      })
-      console.log('[SHORTCUTS] MediaTrackNext registered')
-    } catch (e) {
-      try {
-        const nextKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+Right' : 'Ctrl+Shift+Right'
-        await register(nextKey, () => {
-          console.log('[SHORTCUT] Next track triggered')
-          app.playNextTrack()
-        })
-        console.log(`[SHORTCUTS] ${nextKey} registered for Next`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register next track shortcut:', e2)
-      }
-    }
-
-    // Previous track : MediaTrackPrevious or Cmd/Ctrl+Shift+Left
-    try {
-      await register('MediaTrackPrevious', () => {
-        console.log('[SHORTCUT] MediaTrackPrevious triggered')
-        app.playPreviousTrack()
-      })
-      console.log('[SHORTCUTS] MediaTrackPrevious registered')
-    } catch (e) {
-      try {
-        const prevKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+Left' : 'Ctrl+Shift+Left'
-        await register(prevKey, () => {
-          console.log('[SHORTCUT] Previous track triggered')
-          app.playPreviousTrack()
-        })
-        console.log(`[SHORTCUTS] ${prevKey} registered for Previous`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register previous track shortcut:', e2)
-      }
-    }
-
-    // Volume Up : MediaVolumeUp or Cmd/Ctrl+Shift+Up
-    try {
-      await register('MediaVolumeUp', () => {
-        console.log('[SHORTCUT] MediaVolumeUp triggered')
-        adjustVolume(0.1)
-      })
-      console.log('[SHORTCUTS] MediaVolumeUp registered')
-    } catch (e) {
-      try {
-        const volUpKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+Up' : 'Ctrl+Shift+Up'
-        await register(volUpKey, () => {
-          console.log('[SHORTCUT] Volume up triggered')
-          adjustVolume(0.1)
-        })
-        console.log(`[SHORTCUTS] ${volUpKey} registered for Volume Up`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register volume up shortcut:', e2)
-      }
-    }
+    const disabled = getDisabledGlobalShortcuts()
+    const modifier = navigator.platform.includes('Mac') ? 'Cmd+Shift+' : 'Ctrl+Shift+'
 
-    // Volume Down : MediaVolumeDown or Cmd/Ctrl+Shift+Down
-    try {
-      await register('MediaVolumeDown', () => {
-        console.log('[SHORTCUT] MediaVolumeDown triggered')
-        adjustVolume(-0.1)
-      })
-      console.log('[SHORTCUTS] MediaVolumeDown registered')
-    } catch (e) {
-      try {
-        const volDownKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+Down' : 'Ctrl+Shift+Down'
-        await register(volDownKey, () => {
-          console.log('[SHORTCUT] Volume down triggered')
-          adjustVolume(-0.1)
-        })
-        console.log(`[SHORTCUTS] ${volDownKey} registered for Volume Down`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register volume down shortcut:', e2)
+    for (const [action, shortcut] of Object.entries(GLOBAL_SHORTCUTS)) {
+      if (disabled.has(action)) continue
+      const handler = () => {
+        console.log(`[SHORTCUT] ${shortcut.label} triggered`)
+        shortcut.run()
       }
-    }
-
-    // Mute : MediaMute or Cmd/Ctrl+Shift+M
-    try {
-      await register('MediaMute', () => {
-        console.log('[SHORTCUT] MediaMute triggered')
-        toggleMute()
-      })
-      console.log('[SHORTCUTS] MediaMute registered')
-    } catch (e) {
       try {
-        const muteKey = navigator.platform.includes('Mac') ? 'Cmd+Shift+M' : 'Ctrl+Shift+M'
-        await register(muteKey, () => {
-          console.log('[SHORTCUT] Mute triggered')
-          toggleMute()
-        })
-        console.log(`[SHORTCUTS] ${muteKey} registered for Mute`)
-      } catch (e2) {
-        console.warn('[SHORTCUTS] Could not register mute shortcut:', e2)
+        await register(shortcut.mediaKey, handler)
+        console.log(`[SHORTCUTS] ${shortcut.mediaKey} registered`)
+      } catch (e) {
+        if (!shortcut.fallback) continue
+        const fallbackKey = modifier + shortcut.fallback
+        try {
+          await register(fallbackKey, handler)
+          console.log(`[SHORTCUTS] ${fallbackKey} registered for ${shortcut.label}`)
+        } catch (e2) {
+          console.warn(`[SHORTCUTS] Could not register ${shortcut.label} shortcut:`, e2)
+        }
       }
     }
 
-    // Note : F7/F8/F9 intentionnellement absents — conflictent avec Apple Music
-    // en mode "media keys" macOS. Les touches MediaPreviousTrack/MediaPlayPause/MediaNextTrack
-    // ci-dessus couvrent le cas "function keys" désactivé.
-
     globalShortcutsRegistered = true
     console.log('[SHORTCUTS] Global shortcuts initialized successfully')
 
@@ -153,6 +95,36 @@ async function initGlobalShortcuts() {
   }
 }
 
+async function unregisterGlobalShortcuts() {
+  try {
+    await window.__TAURI__.globalShortcut.unregisterAll()
+  } catch (error) {
+    console.error('[SHORTCUTS] Error unregistering global shortcuts:', error)
+  }
+  globalShortcutsRegistered = false
+}
+
+// Re-apply the configured set (after a settings change)
+async function refreshGlobalShortcuts() {
+  await unregisterGlobalShortcuts()
+  if (shortcutsWhenUnfocused() || document.hasFocus()) {
+    await registerGlobalShortcuts()
+  }
+}
+
+async function initGlobalShortcuts() {
+  if (!focusListenersAttached) {
+    focusListenersAttached = true
+    await listen('tauri://focus', () => {
+      if (!shortcutsWhenUnfocused()) registerGlobalShortcuts()
+    })
+    await listen('tauri://blur', () => {
+      if (!shortcutsWhenUnfocused()) unregisterGlobalShortcuts()
+    })
+  }
+  await refreshGlobalShortcuts()
+}
+
 // === VOLUME HELPERS ===
 
 // Adjust volume by a relative delta (delta in 0-1 range)
@@ -495,6 +467,8 @@ function cycleRepeatMode() {
     updateRepeatButtonUI()
   }
 
+  app.persistPlaybackModes()
+
   // Feedback
   const labels = { off: 'Repeat off', all: 'Repeat all', one: 'Repeat one' }
   showToast(labels[playback.repeatMode])
@@ -549,6 +523,7 @@ function toggleShuffleMode() {
     }
     showToast('Shuffle disabled')
   }
+  app.persistPlaybackModes()
 }
 
 // === PUBLIC API ===
@@ -567,4 +542,4 @@ export function initShortcuts() {
 }
 
 // Expose for settings UI
-export { DEFAULT_SHORTCUTS, activeShortcuts, loadShortcuts }
+export { DEFAULT_SHORTCUTS, GLOBAL_SHORTCUTS, activeShortcuts, loadShortcuts }
@@ -3,7 +3,7 @@
 
 // === TAURI API ===
 export const { invoke, convertFileSrc } = window.__TAURI__.core;
-export const { listen } = window.__TAURI__.event;
+export const { listen, emit } = window.__TAURI__.event;
 
 // === HELPER ===
 // Vide un objet sans casser les références partagées entre modules
@@ -55,6 +55,13 @@ export const playback = {
   // { type: 'album'|'library'|'playlist'|'mix', id: string|null, tracks: string[] }
   autoQueueSource: null,
   autoQueueIndex: 0,        // index de la track courante dans autoQueueSource.tracks
+  // Reprise de la dernière session : { index, position } jusqu'au premier play
+  pendingResume: null,
+  // Arrêté par Stop : le prochain play relance le track depuis le début
+  isStopped: false,
+  // La file d'attente backend pilote le track en cours (retour de audio_play) : c'est
+  // elle qui choisit le suivant (playback_queue_advanced), la file JS n'enchaîne pas
+  queueDriven: false,
 };
 
 // === LIBRARY DATA ===
@@ -65,6 +72,8 @@ export const library = {
   tracksByPath: new Map(),
   metadataLoaded: false,
   trackAddedDates: {},
+  scanIssues: [],  // Fichiers à problème du dernier scan : { path, reason, details }
+  artistAliases: {},  // Alias d'artistes (minuscules) → nom canonique, cf. merge_artists
 };
 
 // === SEARCH ===
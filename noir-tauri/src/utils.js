@@ -302,6 +302,48 @@ export function showToast(message, type, duration) {
 
 // === HTML HELPERS ===
 
+// Clé de tri des vues de navigation, la même que search::sort_key côté Rust :
+// minuscules, accents et ponctuation typographique normalisés, article initial ignoré
+// ("The Beatles" → "beatles", "L'Impératrice" → "imperatrice"). Le nom affiché reste l'original
+const LEADING_ARTICLES = ['the ', 'an ', 'a ', 'les ', 'le ', 'la ', "l'"]
+
+export function sortKey(text) {
+  const normalized = (text || '')
+    .replace(/[\u2012-\u2015]/g, '-')
+    .replace(/[\u2018\u2019]/g, "'")
+    .replace(/[\u201C\u201D]/g, '"')
+    .toLowerCase()
+    .normalize('NFD')
+    .replace(/[\u0300-\u036f]/g, '')
+    .replace(/ł/g, 'l')
+    .replace(/ø/g, 'o')
+    .replace(/đ/g, 'd')
+    .replace(/ı/g, 'i')
+    .split(/\s+/)
+    .filter(Boolean)
+    .join(' ')
+  const article = LEADING_ARTICLES.find(a => normalized.startsWith(a) && normalized.length > a.length)
+  return article ? normalized.slice(article.length) : normalized
+}
+
+// Comparateur de noms par clé de tri (chaque clé n'est calculée qu'une fois par tri)
+export function compareBySortKey() {
+  const keys = new Map()
+  const keyOf = (name) => {
+    let key = keys.get(name)
+    if (key === undefined) {
+      key = sortKey(name)
+      keys.set(name, key)
+    }
+    return key
+  }
+  return (a, b) => {
+    const keyA = keyOf(a)
+    const keyB = keyOf(b)
+    return keyA < keyB ? -1 : keyA > keyB ? 1 : 0
+  }
+}
+
 export function escapeHtml(text) {
   if (!text) return ''
   const div = document.createElement('div')
@@ -9,7 +9,7 @@ import { isDragging } from './drag.js'
 import {
   formatTime, formatAlbumDuration, formatQuality, escapeHtml, isValidImageSrc,
   loadCachedImage, getCodecFromPath, createParticleCanvas, destroyParticleCanvas,
-  showToast, getResponsiveItemCount
+  showToast, getResponsiveItemCount, sortKey, compareBySortKey
 } from './utils.js'
 import { extractColorsFromBase64, extractColorsFromImg, pickAmbientColor } from './fullscreen-player.js'
 
@@ -397,6 +397,7 @@ export function displayCurrentView() {
 // ============================================================
 
 export function navigateToArtistPage(artistKey) {
+  artistKey = app.resolveArtist(artistKey)
   if (!artistKey || !library.artists[artistKey]) return
 
   // Reset ambient color — will be re-set by displayArtistPage extraction
@@ -2193,7 +2194,7 @@ export async function displayHomeView() {
 
     const carouselItem = e.target.closest('.carousel-item')
     if (carouselItem) {
-      const artistName = carouselItem.dataset.artistName
+      const artistName = app.resolveArtist(carouselItem.dataset.artistName)
       if (artistName && library.artists[artistName]) {
         openArtistFromHome(artistName)
         return
@@ -2248,7 +2249,7 @@ export async function displayHomeView() {
     const carouselItem = e.target.closest('.carousel-item')
     if (carouselItem) {
       // Artist carousel items
-      const artistName = carouselItem.dataset.artistName
+      const artistName = app.resolveArtist(carouselItem.dataset.artistName)
       if (artistName && library.artists[artistName]) {
         app.showArtistContextMenu(e, artistName)
         return
@@ -2390,6 +2391,7 @@ export function updateHomeNowPlayingSection() {
 // ============================================================
 
 export function openArtistFromHome(artistName) {
+  artistName = app.resolveArtist(artistName)
   if (!artistName || !library.artists[artistName]) return
   navigateToArtistPage(artistName)
 }
@@ -2501,14 +2503,16 @@ export function displayAlbumsGrid() {
     library.tracks.forEach((t, i) => trackIndexMap.set(t.path, i))
   }
 
+  // Mêmes clés de tri que le backend : accents et article initial ignorés
+  const byName = compareBySortKey()
   const sortedAlbumKeys = Object.keys(library.albums).sort((a, b) => {
     const albumA = library.albums[a]
     const albumB = library.albums[b]
     switch (sort.albumSortMode) {
-      case 'artist-asc': return albumA.artist.localeCompare(albumB.artist)
-      case 'artist-desc': return albumB.artist.localeCompare(albumA.artist)
-      case 'album-asc': return albumA.album.localeCompare(albumB.album)
-      case 'album-desc': return albumB.album.localeCompare(albumA.album)
+      case 'artist-asc': return byName(albumA.artist, albumB.artist)
+      case 'artist-desc': return byName(albumB.artist, albumA.artist)
+      case 'album-asc': return byName(albumA.album, albumB.album)
+      case 'album-desc': return byName(albumB.album, albumA.album)
       case 'recent':
         const lastTrackA = albumA.tracks.length > 0 ? (trackIndexMap.get(albumA.tracks[albumA.tracks.length - 1].path) ?? -1) : -1
         const lastTrackB = albumB.tracks.length > 0 ? (trackIndexMap.get(albumB.tracks[albumB.tracks.length - 1].path) ?? -1) : -1
@@ -2660,12 +2664,14 @@ export function displayArtistsGrid() {
   const gridContainer = document.createElement('div')
   gridContainer.className = 'albums-grid-container'
 
+  // Mêmes clés de tri que le backend : "The Beatles" est rangé à B
+  const byName = compareBySortKey()
   const sortedArtists = Object.keys(library.artists).sort((a, b) => {
     const artistA = library.artists[a]
     const artistB = library.artists[b]
     switch (artistSortMode) {
-      case 'name-asc': return artistA.name.localeCompare(artistB.name)
-      case 'name-desc': return artistB.name.localeCompare(artistA.name)
+      case 'name-asc': return byName(artistA.name, artistB.name)
+      case 'name-desc': return byName(artistB.name, artistA.name)
       case 'recent':
         const lastTrackA = artistA.tracks.length > 0 ? library.tracks.findIndex(t => t.path === artistA.tracks[artistA.tracks.length - 1].path) : -1
         const lastTrackB = artistB.tracks.length > 0 ? library.tracks.findIndex(t => t.path === artistB.tracks[artistB.tracks.length - 1].path) : -1
@@ -2750,7 +2756,7 @@ export function displayArtistsGrid() {
     createAlphabetScrollbar(
       document.body,
       alphabetItems,
-      item => item.name.charAt(0),
+      item => sortKey(item.name).charAt(0),
       dom.albumsViewDiv
     )
   } else {
@@ -2767,6 +2773,104 @@ export function showArtistAlbums(artistKey) {
 // ARTIST PAGE
 // ============================================================
 
+// Fusion d'artistes : un autre nom tagué (variante, faute de frappe) est affiché sous
+// celui de cette page. Mapping d'affichage seulement (merge_artists), les fichiers ne changent pas
+function showMergeArtistsModal(artistKey) {
+  const artist = library.artists[artistKey]
+  if (!artist) return
+
+  document.getElementById('merge-artists-modal')?.remove()
+
+  const canonicalKey = artist.name.toLowerCase()
+  const merged = Object.entries(library.artistAliases)
+    .filter(([alias, canonical]) => canonical === artist.name && alias !== canonicalKey)
+    .map(([alias]) => alias)
+    .sort()
+  const others = Object.keys(library.artists).filter(name => name !== artistKey).sort()
+
+  const modal = document.createElement('div')
+  modal.id = 'merge-artists-modal'
+  modal.className = 'bulk-edit-overlay'
+  modal.innerHTML = `
+    <div class="bulk-edit-dialog">
+      <div class="bulk-edit-header">
+        <h2 class="bulk-edit-title">Merge into ${escapeHtml(artist.name)}</h2>
+        <button class="bulk-edit-close merge-artist-close" title="Close">
+          <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2"><line x1="18" y1="6" x2="6" y2="18"/><line x1="6" y1="6" x2="18" y2="18"/></svg>
+        </button>
+      </div>
+      <p class="bulk-edit-hint">The chosen artist is shown as ${escapeHtml(artist.name)}. Files are not modified.</p>
+      <div class="track-info-edit-form">
+        <div class="track-info-edit-field">
+          <label class="track-info-edit-label">Artist</label>
+          <input id="merge-artist-input" type="text" list="merge-artist-options" class="track-info-input" autocomplete="off">
+          <datalist id="merge-artist-options">
+            ${others.map(name => `<option value="${escapeHtml(name)}">`).join('')}
+          </datalist>
+        </div>
+        ${merged.length > 0 ? `
+        <div class="track-info-edit-field">
+          <label class="track-info-edit-label">Merged</label>
+          ${merged.map(alias => `
+            <div class="merge-artist-alias">
+              <span>${escapeHtml(alias)}</span>
+              <button class="track-info-cancel-btn merge-artist-unmerge" data-alias="${escapeHtml(alias)}">Unmerge</button>
+            </div>
+          `).join('')}
+        </div>` : ''}
+      </div>
+      <div class="track-info-edit-actions">
+        <button class="track-info-cancel-btn merge-artist-cancel">Cancel</button>
+        <button class="track-info-save-btn merge-artist-save">Merge</button>
+      </div>
+    </div>
+  `
+
+  document.body.appendChild(modal)
+
+  const closeFn = () => modal.remove()
+  // Regroupe la bibliothèque avec la nouvelle table d'alias et réaffiche la page
+  const applyAliases = async () => {
+    closeFn()
+    await app.loadArtistAliases()
+    app.groupTracksIntoAlbumsAndArtists()
+    app.invalidateHomeCache()
+    displayCurrentView()
+  }
+
+  modal.querySelector('.merge-artist-close').addEventListener('click', closeFn)
+  modal.querySelector('.merge-artist-cancel').addEventListener('click', closeFn)
+  modal.addEventListener('click', (e) => { if (e.target === modal) closeFn() })
+
+  modal.querySelector('.merge-artist-save').addEventListener('click', async () => {
+    const alias = modal.querySelector('#merge-artist-input').value.trim()
+    if (!alias || alias === artistKey) return
+    try {
+      await invoke('merge_artists', { canonical: artist.name, aliases: [alias] })
+      showToast(`${alias} merged into ${artist.name}`)
+      await applyAliases()
+    } catch (e) {
+      console.error('[ARTISTS] Merge failed:', e)
+      showToast('Error merging artists')
+    }
+  })
+
+  modal.querySelectorAll('.merge-artist-unmerge').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        await invoke('remove_artist_alias', { alias: btn.dataset.alias })
+        showToast(`${btn.dataset.alias} unmerged`)
+        await applyAliases()
+      } catch (e) {
+        console.error('[ARTISTS] Unmerge failed:', e)
+        showToast('Error unmerging artist')
+      }
+    })
+  })
+
+  modal.querySelector('#merge-artist-input').focus()
+}
+
 export function displayArtistPage(artistKey) {
   const artist = library.artists[artistKey]
   if (!artist) return
@@ -2833,6 +2937,11 @@ export function displayArtistPage(artistKey) {
               <path d="M16 5H3"/><path d="M16 12H3"/><path d="M9 19H3"/><path d="m16 16-3 3 3 3"/><path d="M21 5v12a2 2 0 0 1-2 2h-6"/>
             </svg>
           </button>
+          <button class="btn-add-queue-album merge-artist-btn" title="Merge artists">
+            <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+              <circle cx="18" cy="18" r="3"/><circle cx="6" cy="6" r="3"/><path d="M6 21V9a9 9 0 0 0 9 9"/>
+            </svg>
+          </button>
         </div>
       </div>
     </div>
@@ -2840,6 +2949,7 @@ export function displayArtistPage(artistKey) {
   `
 
   pageContainer.querySelector('.btn-back-nav').addEventListener('click', navigateBack)
+  pageContainer.querySelector('.merge-artist-btn').addEventListener('click', () => showMergeArtistsModal(artistKey))
 
   pageContainer.querySelector('.play-artist-btn').addEventListener('click', () => {
     if (artist.tracks.length > 0) {